@@ -12,6 +12,8 @@ struct BaseEntityConfig {
     id_field: Ident,
     created_at_field: Ident,
     updated_at_field: Ident,
+    archived_at_field: Option<Ident>,
+    deleted_at_field: Option<Ident>,
 }
 
 impl Default for BaseEntityConfig {
@@ -23,6 +25,8 @@ impl Default for BaseEntityConfig {
             id_field: Ident::new("id", proc_macro2::Span::call_site()),
             created_at_field: Ident::new("created_at", proc_macro2::Span::call_site()),
             updated_at_field: Ident::new("updated_at", proc_macro2::Span::call_site()),
+            archived_at_field: None,
+            deleted_at_field: None,
         }
     }
 }
@@ -54,6 +58,31 @@ pub fn base_entity(attr: TokenStream, item: TokenStream) -> TokenStream {
         .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
         .collect();
 
+    let last_seen_setter = fields
+        .named
+        .iter()
+        .find(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == "last_seen")
+        })
+        .map(|field| {
+            let value = if is_option(&field.ty) {
+                quote! { Some(ts) }
+            } else {
+                quote! { ts }
+            };
+            quote! {
+                fn set_last_seen(
+                    &mut self,
+                    ts: sea_orm::entity::prelude::DateTimeWithTimeZone,
+                ) {
+                    self.last_seen = sea_orm::ActiveValue::Set(#value);
+                }
+            }
+        });
+
     let mut new_fields = Punctuated::new();
 
     if !existing.contains(&config.id_field.to_string()) {
@@ -87,6 +116,28 @@ pub fn base_entity(attr: TokenStream, item: TokenStream) -> TokenStream {
         new_fields.push(field);
     }
 
+    if let Some(archived_ident) = config
+        .archived_at_field
+        .as_ref()
+        .filter(|ident| !existing.contains(&ident.to_string()))
+    {
+        let archived_field: syn::Field = syn::parse_quote! {
+            pub #archived_ident: Option<sea_orm::entity::prelude::DateTimeWithTimeZone>
+        };
+        new_fields.push(archived_field);
+    }
+
+    if let Some(deleted_ident) = config
+        .deleted_at_field
+        .as_ref()
+        .filter(|ident| !existing.contains(&ident.to_string()))
+    {
+        let deleted_field: syn::Field = syn::parse_quote! {
+            pub #deleted_ident: Option<sea_orm::entity::prelude::DateTimeWithTimeZone>
+        };
+        new_fields.push(deleted_field);
+    }
+
     fields.named = new_fields;
 
     let traits_path = config.traits_path;
@@ -94,6 +145,47 @@ pub fn base_entity(attr: TokenStream, item: TokenStream) -> TokenStream {
     let id_field = config.id_field;
     let created_at_field = config.created_at_field;
     let updated_at_field = config.updated_at_field;
+    let archived_at_column = config
+        .archived_at_field
+        .as_ref()
+        .map(|ident| Ident::new(&to_pascal_case(&ident.to_string()), ident.span()));
+    let archived_at_override = archived_at_column.as_ref().map(|column| {
+        quote! {
+            fn archived_at_column() -> Option<Column> {
+                Some(Column::#column)
+            }
+        }
+    });
+    let archived_at_impl = archived_at_column.as_ref().map(|column| {
+        quote! {
+            impl #traits_path::HasArchivedAtColumn for Entity {
+                fn archived_at_column() -> Column {
+                    Column::#column
+                }
+            }
+        }
+    });
+
+    let deleted_at_column = config
+        .deleted_at_field
+        .as_ref()
+        .map(|ident| Ident::new(&to_pascal_case(&ident.to_string()), ident.span()));
+    let deleted_at_override = deleted_at_column.as_ref().map(|column| {
+        quote! {
+            fn deleted_at_column() -> Option<Column> {
+                Some(Column::#column)
+            }
+        }
+    });
+    let deleted_at_impl = deleted_at_column.as_ref().map(|column| {
+        quote! {
+            impl #traits_path::HasDeletedAtColumn for Entity {
+                fn deleted_at_column() -> Column {
+                    Column::#column
+                }
+            }
+        }
+    });
 
     let expanded = quote! {
         #input
@@ -118,18 +210,53 @@ pub fn base_entity(attr: TokenStream, item: TokenStream) -> TokenStream {
             ) {
                 self.#updated_at_field = sea_orm::ActiveValue::Set(ts);
             }
+
+            #last_seen_setter
         }
 
         impl #traits_path::HasCreatedAtColumn for Entity {
             fn created_at_column() -> Column {
                 Column::CreatedAt
             }
+
+            #archived_at_override
+
+            #deleted_at_override
         }
+
+        #archived_at_impl
+
+        #deleted_at_impl
     };
 
     expanded.into()
 }
 
+/// Matches the `Column` variant names `DeriveEntityModel` derives from field names.
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let mut chars = segment.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    matches!(
+        ty,
+        syn::Type::Path(path) if path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option")
+    )
+}
+
 fn apply_args(
     config: &mut BaseEntityConfig,
     args: Punctuated<Meta, Token![,]>,
@@ -180,6 +307,12 @@ fn apply_args(
             "updated_at" => {
                 config.updated_at_field = Ident::new(&value.value(), value.span());
             }
+            "archived_at" => {
+                config.archived_at_field = Some(Ident::new(&value.value(), value.span()));
+            }
+            "deleted_at" => {
+                config.deleted_at_field = Some(Ident::new(&value.value(), value.span()));
+            }
             _ => {
                 return Err(syn::Error::new_spanned(
                     ident,
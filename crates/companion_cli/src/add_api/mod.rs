@@ -1,3 +1,5 @@
+pub(crate) mod schema;
+
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -6,6 +8,7 @@ use anyhow::{Context, Result, bail};
 use sha2::{Digest, Sha256};
 
 use crate::cli::AddApiArgs;
+use crate::rcfile;
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 struct Registry {
@@ -22,6 +25,9 @@ struct ApiEntry {
     files: HashMap<String, String>,
     mod_edits: HashMap<String, Vec<String>>,
     dao_context_method: String,
+    /// Template inputs, kept so `oxide update` can re-render the files.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    template_vars: HashMap<String, String>,
 }
 
 const ENTITY_TEMPLATE: &str = include_str!(concat!(
@@ -40,9 +46,45 @@ const ROUTE_TEMPLATE: &str = include_str!(concat!(
     env!("CARGO_MANIFEST_DIR"),
     "/templates/route.rs.tmpl"
 ));
+const DAO_TEST_TEMPLATE: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/templates/dao_test.rs.tmpl"
+));
+
+/// Which pieces `api add` generates, resolved once from the flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AddApiPlan {
+    service: bool,
+    route: bool,
+    auth: bool,
+    tests: bool,
+}
 
-pub fn run(args: AddApiArgs) -> Result<()> {
+/// Single place where flag combinations are checked, so conflicting flags fail
+/// before anything is written.
+fn resolve_plan(args: &AddApiArgs) -> Result<AddApiPlan> {
+    if args.all && args.no_service {
+        bail!("--all generates the service and routes; drop either --all or --no-service");
+    }
+    if args.no_service && args.no_auth {
+        bail!(
+            "--no-auth only applies to generated routes, which --no-service skips; drop --no-auth"
+        );
+    }
+
+    let service = !args.no_service;
+    Ok(AddApiPlan {
+        service,
+        route: service,
+        auth: !args.no_auth,
+        tests: args.with_tests || args.all,
+    })
+}
+
+pub fn run(mut args: AddApiArgs) -> Result<()> {
     let cwd = std::env::current_dir().context("failed to resolve current directory")?;
+    rcfile::load(&cwd)?.add_api.apply(&mut args);
+    let plan = resolve_plan(&args)?;
     let (project_root, server_root) = resolve_roots(&cwd)?;
     let src_root = server_root.join("src");
     if !src_root.exists() {
@@ -92,7 +134,12 @@ pub fn run(args: AddApiArgs) -> Result<()> {
         bail!("base path cannot be empty");
     }
 
-    let fields = parse_fields(args.fields.as_deref())?;
+    let fields = if args.from_schema {
+        let database_url = schema::resolve_database_url(&project_root, &server_root)?;
+        schema::introspect_fields(&database_url, &table)?
+    } else {
+        parse_fields(args.fields.as_deref())?
+    };
     let rendered_fields = render_fields(&fields);
 
     let dao = format!("{entity_pascal}Dao");
@@ -100,18 +147,6 @@ pub fn run(args: AddApiArgs) -> Result<()> {
     let service_module = format!("{entity}_service");
     let route_module = entity.clone();
 
-    let auth_enabled = !args.no_auth;
-    let auth_imports = if auth_enabled {
-        "use axum::middleware;\nuse crate::middleware::jwt_auth;\n"
-    } else {
-        ""
-    };
-    let auth_layer = if auth_enabled {
-        "    let auth_layer = middleware::from_fn_with_state(state.clone(), jwt_auth);\n    let router = router.route_layer(auth_layer);\n"
-    } else {
-        ""
-    };
-
     let mut vars = HashMap::new();
     vars.insert("entity".to_string(), entity.clone());
     vars.insert("Entity".to_string(), entity_pascal.clone());
@@ -123,13 +158,7 @@ pub fn run(args: AddApiArgs) -> Result<()> {
     vars.insert("service_module".to_string(), service_module.clone());
     vars.insert("route_module".to_string(), route_module.clone());
     vars.insert("fields".to_string(), rendered_fields);
-    vars.insert("auth_imports".to_string(), auth_imports.to_string());
-    vars.insert("auth_layer".to_string(), auth_layer.to_string());
-
-    let entity_contents = render_template(ENTITY_TEMPLATE, &vars)?;
-    let dao_contents = render_template(DAO_TEMPLATE, &vars)?;
-    let service_contents = render_template(SERVICE_TEMPLATE, &vars)?;
-    let route_contents = render_template(ROUTE_TEMPLATE, &vars)?;
+    insert_auth_vars(&mut vars, plan.auth);
 
     let entity_path = src_root.join("db/entities").join(format!("{entity}.rs"));
     let dao_path = src_root.join("db/dao").join(format!("{entity}_dao.rs"));
@@ -137,14 +166,22 @@ pub fn run(args: AddApiArgs) -> Result<()> {
         .join("services")
         .join(format!("{entity}_service.rs"));
     let route_path = src_root.join("routes/api").join(format!("{entity}.rs"));
+    let test_path = server_root.join("tests").join(format!("{entity}_dao.rs"));
 
-    let new_files = [
-        entity_path.clone(),
-        dao_path.clone(),
-        service_path.clone(),
-        route_path.clone(),
+    let mut new_files = vec![
+        (entity_path, render_template(ENTITY_TEMPLATE, &vars)?),
+        (dao_path.clone(), render_template(DAO_TEMPLATE, &vars)?),
     ];
-    for path in &new_files {
+    if plan.service {
+        new_files.push((service_path, render_template(SERVICE_TEMPLATE, &vars)?));
+    }
+    if plan.route {
+        new_files.push((route_path.clone(), render_template(ROUTE_TEMPLATE, &vars)?));
+    }
+    if plan.tests {
+        new_files.push((test_path, render_template(DAO_TEST_TEMPLATE, &vars)?));
+    }
+    for (path, _) in &new_files {
         if path.exists() && !args.force {
             bail!(
                 "file already exists (use --force to overwrite): {}",
@@ -169,16 +206,23 @@ pub fn run(args: AddApiArgs) -> Result<()> {
 
     let services_mod = fs::read_to_string(&services_mod_path)
         .with_context(|| format!("failed to read {}", services_mod_path.display()))?;
-    let (services_mod_updated, services_mod_changed) =
-        update_services_mod(&services_mod, &service_module)?;
+    let (services_mod_updated, services_mod_changed) = if plan.service {
+        update_services_mod(&services_mod, &service_module)?
+    } else {
+        (services_mod.clone(), false)
+    };
 
     let routes_mod = fs::read_to_string(&routes_mod_path)
         .with_context(|| format!("failed to read {}", routes_mod_path.display()))?;
-    let (routes_mod_updated, routes_mod_changed) = update_routes_mod(&routes_mod, &route_module)?;
+    let (routes_mod_updated, routes_mod_changed) = if plan.route {
+        update_routes_mod(&routes_mod, &route_module)?
+    } else {
+        (routes_mod.clone(), false)
+    };
 
     if args.dry_run {
         println!("Dry run: would create files:");
-        for path in &new_files {
+        for (path, _) in &new_files {
             println!("  {}", path.display());
         }
         println!("Dry run: would update files:");
@@ -197,10 +241,9 @@ pub fn run(args: AddApiArgs) -> Result<()> {
         return Ok(());
     }
 
-    write_file(&entity_path, &entity_contents)?;
-    write_file(&dao_path, &dao_contents)?;
-    write_file(&service_path, &service_contents)?;
-    write_file(&route_path, &route_contents)?;
+    for (path, contents) in &new_files {
+        write_file(path, contents)?;
+    }
 
     if entities_mod_changed {
         write_file(&entities_mod_path, &entities_mod_updated)?;
@@ -228,24 +271,15 @@ pub fn run(args: AddApiArgs) -> Result<()> {
         registry.apis.remove(idx);
     }
 
-    let files = HashMap::from([
-        (
-            registry_relative_path(&project_root, &entity_path),
-            hash_str(&entity_contents),
-        ),
-        (
-            registry_relative_path(&project_root, &dao_path),
-            hash_str(&dao_contents),
-        ),
-        (
-            registry_relative_path(&project_root, &service_path),
-            hash_str(&service_contents),
-        ),
-        (
-            registry_relative_path(&project_root, &route_path),
-            hash_str(&route_contents),
-        ),
-    ]);
+    let files: HashMap<String, String> = new_files
+        .iter()
+        .map(|(path, contents)| {
+            (
+                registry_relative_path(&project_root, path),
+                hash_str(contents),
+            )
+        })
+        .collect();
 
     let mut mod_edits = HashMap::new();
     let mut entities_edits = Vec::new();
@@ -282,7 +316,7 @@ pub fn run(args: AddApiArgs) -> Result<()> {
 
     let mut services_edits = Vec::new();
     let services_line = format!("pub mod {service_module};");
-    if !line_exists(&services_mod, &services_line) {
+    if plan.service && !line_exists(&services_mod, &services_line) {
         services_edits.push(services_line);
     }
     if !services_edits.is_empty() {
@@ -294,11 +328,11 @@ pub fn run(args: AddApiArgs) -> Result<()> {
 
     let mut routes_edits = Vec::new();
     let routes_mod_line = format!("pub mod {route_module};");
-    if !line_exists(&routes_mod, &routes_mod_line) {
+    if plan.route && !line_exists(&routes_mod, &routes_mod_line) {
         routes_edits.push(routes_mod_line);
     }
     let routes_merge_line = format!("        .merge({route_module}::router(state.clone()))");
-    if !line_exists(&routes_mod, &routes_merge_line) {
+    if plan.route && !line_exists(&routes_mod, &routes_merge_line) {
         routes_edits.push(routes_merge_line);
     }
     if !routes_edits.is_empty() {
@@ -322,16 +356,49 @@ pub fn run(args: AddApiArgs) -> Result<()> {
         files,
         mod_edits,
         dao_context_method,
+        template_vars: vars,
     });
     save_registry(&registry_path, &registry)?;
 
+    let summary_path = if plan.route { &route_path } else { &dao_path };
     println!(
         "Added CRUD API for {entity_pascal} at {}",
-        route_path.display()
+        summary_path.display()
     );
     Ok(())
 }
 
+/// Templates behind the files `api add` can generate, keyed by path
+/// relative to the server crate.
+pub(crate) fn scaffold_templates(entity: &str) -> [(String, &'static str); 5] {
+    [
+        (format!("src/db/entities/{entity}.rs"), ENTITY_TEMPLATE),
+        (format!("src/db/dao/{entity}_dao.rs"), DAO_TEMPLATE),
+        (
+            format!("src/services/{entity}_service.rs"),
+            SERVICE_TEMPLATE,
+        ),
+        (format!("src/routes/api/{entity}.rs"), ROUTE_TEMPLATE),
+        (format!("tests/{entity}_dao.rs"), DAO_TEST_TEMPLATE),
+    ]
+}
+
+/// The route module mounts `CrudApiRouter`, optionally behind `jwt_auth`.
+fn insert_auth_vars(vars: &mut HashMap<String, String>, auth_enabled: bool) {
+    let auth_imports = if auth_enabled {
+        "use axum::middleware;\nuse crate::routes::middleware::jwt_auth;\n"
+    } else {
+        ""
+    };
+    let auth_layer = if auth_enabled {
+        "    let auth_layer = middleware::from_fn_with_state(state.clone(), jwt_auth);\n    let router = router.route_layer(auth_layer);\n"
+    } else {
+        ""
+    };
+    vars.insert("auth_imports".to_string(), auth_imports.to_string());
+    vars.insert("auth_layer".to_string(), auth_layer.to_string());
+}
+
 fn write_file(path: &Path, contents: &str) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
@@ -340,7 +407,7 @@ fn write_file(path: &Path, contents: &str) -> Result<()> {
     fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))
 }
 
-fn resolve_roots(cwd: &Path) -> Result<(PathBuf, PathBuf)> {
+pub(crate) fn resolve_roots(cwd: &Path) -> Result<(PathBuf, PathBuf)> {
     for ancestor in cwd.ancestors() {
         let workspace_server = ancestor.join("crates/server/src");
         if workspace_server.exists() {
@@ -390,7 +457,7 @@ fn registry_relative_path(root: &Path, path: &Path) -> String {
         .to_string()
 }
 
-fn render_template(template: &str, vars: &HashMap<String, String>) -> Result<String> {
+pub(crate) fn render_template(template: &str, vars: &HashMap<String, String>) -> Result<String> {
     let mut output = String::with_capacity(template.len() + 128);
     let bytes = template.as_bytes();
     let mut i = 0;
@@ -571,7 +638,7 @@ fn to_snake_case(input: &str) -> String {
     }
 }
 
-fn to_pascal_case(input: &str) -> String {
+pub(crate) fn to_pascal_case(input: &str) -> String {
     let mut out = String::new();
     for segment in input.split('_').filter(|s| !s.is_empty()) {
         let mut chars = segment.chars();
@@ -616,7 +683,7 @@ fn normalize_base_path(input: &str) -> String {
     }
 }
 
-fn validate_ident(input: &str, label: &str) -> Result<()> {
+pub(crate) fn validate_ident(input: &str, label: &str) -> Result<()> {
     let mut chars = input.chars();
     let Some(first) = chars.next() else {
         bail!("{label} cannot be empty");
@@ -630,7 +697,7 @@ fn validate_ident(input: &str, label: &str) -> Result<()> {
     Ok(())
 }
 
-fn escape_rust_string(input: &str) -> String {
+pub(crate) fn escape_rust_string(input: &str) -> String {
     input.replace('\\', "\\\\").replace('"', "\\\"")
 }
 
@@ -640,7 +707,7 @@ fn line_exists(contents: &str, line: &str) -> bool {
         .any(|existing| existing.trim() == line.trim())
 }
 
-fn hash_str(input: &str) -> String {
+pub(crate) fn hash_str(input: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(input.as_bytes());
     let digest = hasher.finalize();
@@ -707,7 +774,9 @@ fn update_dao_mod(contents: &str, entity: &str, dao: &str) -> Result<(String, bo
 
     let method_line = format!("    pub fn {entity}(&self) -> {dao} {{");
     if !lines.iter().any(|line| line.trim() == method_line.trim()) {
-        insert_method_in_impl(&mut lines, "impl DaoContext {", &method_line)?;
+        let body_line =
+            format!("        {dao}::new(&self.db).with_statement_timeout(self.statement_timeout)");
+        insert_method_in_impl(&mut lines, "impl DaoContext {", &method_line, &body_line)?;
         changed = true;
     }
 
@@ -815,6 +884,7 @@ fn insert_method_in_impl(
     lines: &mut Vec<String>,
     impl_header: &str,
     method_line: &str,
+    body_line: &str,
 ) -> Result<()> {
     let start_idx = lines
         .iter()
@@ -827,7 +897,7 @@ fn insert_method_in_impl(
         depth += count_braces(&lines[idx]);
         if depth == 0 {
             lines.insert(idx, method_line.to_string());
-            lines.insert(idx + 1, "        DaoBase::new(&self.db)".to_string());
+            lines.insert(idx + 1, body_line.to_string());
             lines.insert(idx + 2, "    }".to_string());
             return Ok(());
         }
@@ -855,3 +925,72 @@ fn reconstruct(original: &str, lines: &[String]) -> String {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use clap::Parser;
+
+    use super::{
+        AddApiPlan, ROUTE_TEMPLATE, escape_rust_string, insert_auth_vars, render_template,
+        resolve_plan,
+    };
+    use crate::cli::AddApiArgs;
+
+    fn args(flags: &[&str]) -> AddApiArgs {
+        AddApiArgs::try_parse_from(["add", "invoice"].iter().chain(flags))
+            .expect("flags should parse")
+    }
+
+    #[test]
+    fn route_template_mounts_crud_api_router_at_base_path() {
+        let mut vars = HashMap::new();
+        vars.insert("entity".to_string(), "invoice".to_string());
+        vars.insert("Service".to_string(), "InvoiceService".to_string());
+        vars.insert("service_module".to_string(), "invoice_service".to_string());
+        vars.insert("base_path".to_string(), escape_rust_string("/invoices"));
+        insert_auth_vars(&mut vars, true);
+
+        let route = render_template(ROUTE_TEMPLATE, &vars).expect("route should render");
+
+        assert!(route.contains("const BASE_PATH: &str = \"/invoices\";"));
+        assert!(route.contains("let service = InvoiceService::new(daos.invoice());"));
+        assert!(route.contains("CrudApiRouter::new(service, BASE_PATH)"));
+        assert!(route.contains("routes::CrudApiRouter,"));
+        assert!(route.contains("use crate::routes::middleware::jwt_auth;"));
+    }
+
+    #[test]
+    fn plan_defaults_to_service_and_routes_without_tests() {
+        let plan = resolve_plan(&args(&[])).expect("defaults should resolve");
+
+        assert_eq!(
+            plan,
+            AddApiPlan {
+                service: true,
+                route: true,
+                auth: true,
+                tests: false,
+            }
+        );
+    }
+
+    #[test]
+    fn all_flag_enables_every_generator() {
+        let plan = resolve_plan(&args(&["--all"])).expect("--all should resolve");
+
+        assert!(plan.service && plan.route && plan.tests);
+    }
+
+    #[test]
+    fn conflicting_flags_are_rejected_before_writing() {
+        let err = resolve_plan(&args(&["--all", "--no-service"]))
+            .expect_err("--all with --no-service should fail");
+        assert!(err.to_string().contains("--no-service"));
+
+        let err = resolve_plan(&args(&["--no-service", "--no-auth"]))
+            .expect_err("--no-auth without routes should fail");
+        assert!(err.to_string().contains("--no-auth"));
+    }
+}
@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use sqlx::{AnyConnection, Connection, Row};
+
+use super::{FieldSpec, is_reserved_field, to_snake_case, validate_ident};
+
+const DATABASE_URL_KEYS: [&str; 2] = ["APP_DATABASE__URL", "DATABASE_URL"];
+
+// Nullability is returned as an integer; the Any driver has no bool type shared by both backends.
+const SQLITE_COLUMNS_QUERY: &str = "SELECT name, type AS data_type, \"notnull\" = 0 AS nullable FROM pragma_table_info(?) ORDER BY cid";
+const POSTGRES_COLUMNS_QUERY: &str = "SELECT column_name::text AS name, data_type::text AS data_type, \
+     (is_nullable = 'YES')::int8 AS nullable FROM information_schema.columns \
+     WHERE table_schema = current_schema() AND table_name = $1 ORDER BY ordinal_position";
+
+pub(crate) struct ColumnInfo {
+    pub(crate) name: String,
+    pub(crate) data_type: String,
+    pub(crate) nullable: bool,
+}
+
+/// Resolves the database URL from the environment, then the project/server `.env` files.
+pub(crate) fn resolve_database_url(project_root: &Path, server_root: &Path) -> Result<String> {
+    if let Some(url) = DATABASE_URL_KEYS.iter().find_map(|key| {
+        std::env::var(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }) {
+        return Ok(url);
+    }
+
+    for root in [project_root, server_root] {
+        let env_path = root.join(".env");
+        if !env_path.exists() {
+            continue;
+        }
+        for item in dotenvy::from_path_iter(&env_path)
+            .with_context(|| format!("failed to read {}", env_path.display()))?
+        {
+            let (key, value) = item.context("failed to parse .env")?;
+            if DATABASE_URL_KEYS.contains(&key.as_str()) && !value.trim().is_empty() {
+                return Ok(value.trim().to_string());
+            }
+        }
+    }
+
+    bail!("APP_DATABASE__URL is required (env or .env)")
+}
+
+/// Derives entity fields from the columns of an existing table.
+///
+/// The base `id`/`created_at`/`updated_at` columns are skipped; nullable
+/// columns become optional fields.
+pub(super) fn introspect_fields(database_url: &str, table: &str) -> Result<Vec<FieldSpec>> {
+    let columns = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to start async runtime")?
+        .block_on(load_columns(database_url, table))?;
+    if columns.is_empty() {
+        bail!("table '{table}' was not found or has no columns");
+    }
+
+    let mut fields = Vec::new();
+    for column in columns {
+        let name = to_snake_case(&column.name);
+        if name != column.name {
+            bail!(
+                "column '{}' is not snake_case and cannot be mapped to a field",
+                column.name
+            );
+        }
+        if is_reserved_field(&name) {
+            continue;
+        }
+        validate_ident(&name, "field name")?;
+        let ty = map_sql_type(&column.data_type).ok_or_else(|| {
+            anyhow::anyhow!(
+                "column '{}' has unsupported type '{}'",
+                column.name,
+                column.data_type
+            )
+        })?;
+        fields.push(FieldSpec {
+            name,
+            ty: ty.to_string(),
+            optional: column.nullable,
+        });
+    }
+
+    if fields.is_empty() {
+        bail!("table '{table}' has no columns besides id/created_at/updated_at");
+    }
+    Ok(fields)
+}
+
+pub(crate) async fn load_columns(database_url: &str, table: &str) -> Result<Vec<ColumnInfo>> {
+    sqlx::any::install_default_drivers();
+    let query = if database_url.starts_with("sqlite:") {
+        SQLITE_COLUMNS_QUERY
+    } else if database_url.starts_with("postgres:") || database_url.starts_with("postgresql:") {
+        POSTGRES_COLUMNS_QUERY
+    } else {
+        bail!("only sqlite and postgres database URLs are supported");
+    };
+
+    let mut conn = AnyConnection::connect(database_url)
+        .await
+        .context("failed to connect to database")?;
+    let rows = sqlx::query(query)
+        .bind(table)
+        .fetch_all(&mut conn)
+        .await
+        .with_context(|| format!("failed to introspect table '{table}'"))?;
+    conn.close().await.ok();
+
+    rows.iter()
+        .map(|row| {
+            Ok(ColumnInfo {
+                name: row.try_get("name")?,
+                data_type: row.try_get("data_type")?,
+                nullable: row.try_get::<i64, _>("nullable")? != 0,
+            })
+        })
+        .collect::<Result<_, sqlx::Error>>()
+        .context("failed to read column metadata")
+}
+
+pub(crate) fn map_sql_type(data_type: &str) -> Option<&'static str> {
+    let normalized = data_type.trim().to_lowercase();
+    let base = normalized.split('(').next().unwrap_or_default().trim_end();
+    let ty = match base {
+        "text" | "varchar" | "character varying" | "char" | "character" | "clob" | "string" => {
+            "String"
+        }
+        "bool" | "boolean" => "bool",
+        "integer" | "int" | "int4" | "smallint" | "int2" => "i32",
+        "bigint" | "int8" => "i64",
+        "uuid" | "uuid_text" => "Uuid",
+        "timestamp with time zone"
+        | "timestamp without time zone"
+        | "timestamptz"
+        | "timestamp"
+        | "timestamp_with_timezone_text"
+        | "datetime"
+        | "datetime_text" => "DateTimeWithTimeZone",
+        _ => return None,
+    };
+    Some(ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Connection, SqliteConnection};
+
+    use super::introspect_fields;
+
+    #[test]
+    fn introspect_fields_matches_sqlite_table_columns() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("app.db").display());
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime should start")
+            .block_on(async {
+                let mut conn = SqliteConnection::connect(&url)
+                    .await
+                    .expect("sqlite should open");
+                sqlx::query(
+                    "CREATE TABLE invoices (
+                        id uuid_text NOT NULL PRIMARY KEY,
+                        created_at timestamp_with_timezone_text NOT NULL,
+                        updated_at timestamp_with_timezone_text NOT NULL,
+                        number varchar(32) NOT NULL,
+                        total bigint NOT NULL,
+                        paid boolean NOT NULL,
+                        customer_id uuid_text,
+                        due_at timestamp_with_timezone_text
+                    )",
+                )
+                .execute(&mut conn)
+                .await
+                .expect("table should be created");
+            });
+
+        let fields = introspect_fields(&url, "invoices").expect("introspection should succeed");
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.ty.as_str(), field.optional))
+            .collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                ("number", "String", false),
+                ("total", "i64", false),
+                ("paid", "bool", false),
+                ("customer_id", "Uuid", true),
+                ("due_at", "DateTimeWithTimeZone", true),
+            ]
+        );
+    }
+}
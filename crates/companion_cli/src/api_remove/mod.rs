@@ -22,6 +22,8 @@ struct ApiEntry {
     files: HashMap<String, String>,
     mod_edits: HashMap<String, Vec<String>>,
     dao_context_method: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    template_vars: HashMap<String, String>,
 }
 
 pub fn run(args: RemoveApiArgs) -> Result<()> {
@@ -92,7 +94,7 @@ pub fn run(args: RemoveApiArgs) -> Result<()> {
             modified_files.join(", ")
         );
     }
-    if (!missing_files.is_empty() || !missing_mod_lines.is_empty()) && !(args.force || args.prune) {
+    if (!missing_files.is_empty() || !missing_mod_lines.is_empty()) && !args.force && !args.prune {
         if !missing_files.is_empty() {
             bail!(
                 "refusing to remove: missing files: {}",
@@ -119,7 +121,7 @@ pub fn run(args: RemoveApiArgs) -> Result<()> {
         return Ok(());
     }
 
-    for (path_str, _) in &entry.files {
+    for path_str in entry.files.keys() {
         let path = resolve_registry_path(&project_root, path_str);
         if path.exists() {
             fs::remove_file(&path)
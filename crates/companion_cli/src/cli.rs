@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
 
+pub const MYSQL_DB: &str = "mysql";
 pub const POSTGRES_DB: &str = "postgres";
 pub const SQLITE_DB: &str = "sqlite";
 pub const DEFAULT_DB: &str = SQLITE_DB;
@@ -19,6 +21,12 @@ pub enum Commands {
     Init(InitArgs),
     New(InitArgs),
     Api(ApiArgs),
+    /// Insert generated rows into a local dev database table
+    Seed(SeedArgs),
+    /// Generate a typed reqwest client for the registered CRUD APIs
+    GenClient(GenClientArgs),
+    /// Re-render scaffolded API files from the current templates
+    Update(UpdateArgs),
 }
 
 #[derive(Parser, Clone)]
@@ -28,29 +36,35 @@ pub struct InitArgs {
     /// Output directory (defaults to ./<name>)
     #[arg(long)]
     pub out: Option<PathBuf>,
-    /// Database choice (supported: sqlite, postgres)
-    #[arg(long, default_value = DEFAULT_DB)]
-    pub db: String,
+    /// Database choice (supported: sqlite, postgres, mysql) [default: sqlite]
+    #[arg(long)]
+    pub db: Option<String>,
     /// Database URL (overrides env/default)
     #[arg(long)]
     pub database_url: Option<String>,
     /// Server port (overrides env/default)
     #[arg(long)]
     pub port: Option<u16>,
+    /// Feature preset; explicit feature flags override it [default: full]
+    #[arg(long, value_enum)]
+    pub preset: Option<InitPreset>,
+    /// Print the available presets and the features each enables
+    #[arg(long)]
+    pub list_profiles: bool,
     /// Enable local auth provider scaffolding
-    #[arg(long = "auth-local", default_value_t = true)]
+    #[arg(long = "auth-local")]
     pub auth_local: bool,
     /// Disable local auth provider scaffolding
     #[arg(long = "no-auth-local", default_value_t = false)]
     pub no_auth_local: bool,
     /// Include todo example modules, routes, and tests
-    #[arg(long = "todo-example", default_value_t = true)]
+    #[arg(long = "todo-example")]
     pub todo_example: bool,
     /// Exclude todo example modules, routes, and tests
     #[arg(long = "no-todo-example", default_value_t = false)]
     pub no_todo_example: bool,
     /// Include docs page
-    #[arg(long = "docs", default_value_t = true)]
+    #[arg(long = "docs")]
     pub docs: bool,
     /// Exclude docs page
     #[arg(long = "no-docs", default_value_t = false)]
@@ -66,6 +80,58 @@ pub struct InitArgs {
     pub non_interactive: bool,
 }
 
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InitPreset {
+    /// No auth, no todo example, no docs
+    Minimal,
+    /// Local auth without the todo example or docs views
+    Api,
+    /// Every optional feature
+    #[default]
+    Full,
+}
+
+/// Optional scaffolding features toggled by `init`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InitFeatures {
+    pub auth_local: bool,
+    pub todo_example: bool,
+    pub docs: bool,
+}
+
+impl InitPreset {
+    pub const ALL: [InitPreset; 3] = [InitPreset::Minimal, InitPreset::Api, InitPreset::Full];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InitPreset::Minimal => "minimal",
+            InitPreset::Api => "api",
+            InitPreset::Full => "full",
+        }
+    }
+
+    pub fn features(self) -> InitFeatures {
+        match self {
+            InitPreset::Minimal => InitFeatures {
+                auth_local: false,
+                todo_example: false,
+                docs: false,
+            },
+            InitPreset::Api => InitFeatures {
+                auth_local: true,
+                todo_example: false,
+                docs: false,
+            },
+            InitPreset::Full => InitFeatures {
+                auth_local: true,
+                todo_example: true,
+                docs: true,
+            },
+        }
+    }
+}
+
 #[derive(Parser, Clone)]
 pub struct AddApiArgs {
     /// Resource/entity name (singular)
@@ -82,9 +148,21 @@ pub struct AddApiArgs {
     /// Comma-separated field list (e.g. title:string,done:bool)
     #[arg(long)]
     pub fields: Option<String>,
+    /// Derive fields from the existing table (connects to APP_DATABASE__URL)
+    #[arg(long, conflicts_with = "fields")]
+    pub from_schema: bool,
     /// Disable auth middleware on the CRUD routes
     #[arg(long)]
     pub no_auth: bool,
+    /// Only generate the entity and DAO (no service or routes)
+    #[arg(long)]
+    pub no_service: bool,
+    /// Also generate a DAO integration test under tests/
+    #[arg(long)]
+    pub with_tests: bool,
+    /// Generate the full set: entity, DAO, service, routes and tests
+    #[arg(long)]
+    pub all: bool,
     /// Print planned changes without writing files
     #[arg(long)]
     pub dry_run: bool,
@@ -108,6 +186,38 @@ pub struct RemoveApiArgs {
     pub force: bool,
 }
 
+#[derive(Parser, Clone)]
+pub struct SeedArgs {
+    /// Table to fill (e.g. todo_lists)
+    pub table: String,
+    /// Number of rows to insert
+    #[arg(long, default_value_t = 10)]
+    pub count: usize,
+    /// Database URL (defaults to APP_DATABASE__URL from env or .env)
+    #[arg(long)]
+    pub database_url: Option<String>,
+}
+
+#[derive(Parser, Clone)]
+pub struct GenClientArgs {
+    /// Write the client module here instead of printing it
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+    /// Prefix the server mounts its API routes under
+    #[arg(long, default_value = "/api/v1")]
+    pub api_prefix: String,
+}
+
+#[derive(Parser, Clone)]
+pub struct UpdateArgs {
+    /// Print planned changes without writing files
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Overwrite files even if they were edited since generation
+    #[arg(long)]
+    pub force: bool,
+}
+
 #[derive(Parser, Clone)]
 pub struct ApiArgs {
     #[command(subcommand)]
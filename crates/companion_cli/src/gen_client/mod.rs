@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use crate::add_api::{escape_rust_string, render_template, resolve_roots, to_pascal_case};
+use crate::cli::GenClientArgs;
+
+const CLIENT_TEMPLATE: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/templates/client.rs.tmpl"
+));
+
+#[derive(Debug, serde::Deserialize)]
+struct Registry {
+    apis: Vec<ApiEntry>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ApiEntry {
+    entity: String,
+    plural: String,
+    base_path: String,
+    files: HashMap<String, String>,
+}
+
+/// A resource with routes, as the client sees it.
+#[derive(Debug)]
+struct ClientResource {
+    entity: String,
+    plural: String,
+    base_path: String,
+    fields: Vec<(String, String)>,
+}
+
+pub fn run(args: GenClientArgs) -> Result<()> {
+    let cwd = std::env::current_dir().context("failed to resolve current directory")?;
+    let (project_root, server_root) = resolve_roots(&cwd)?;
+    let client = render_client(&project_root, &server_root, &args.api_prefix)?;
+
+    match args.out {
+        Some(path) => {
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            fs::write(&path, client)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            println!("Wrote API client to {}", path.display());
+        }
+        None => print!("{client}"),
+    }
+    Ok(())
+}
+
+/// Renders the client module for every registered API that has routes.
+///
+/// Field types come from the generated entity files, so hand edits to an
+/// entity's `Model` show up in the client.
+fn render_client(project_root: &Path, server_root: &Path, api_prefix: &str) -> Result<String> {
+    let registry_path = server_root.join(".scaffold/apis.json");
+    let contents = fs::read_to_string(&registry_path)
+        .with_context(|| format!("failed to read {}", registry_path.display()))?;
+    let registry: Registry = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse {}", registry_path.display()))?;
+
+    let mut resources = Vec::new();
+    for entry in registry.apis {
+        let route_suffix = format!("routes/api/{}.rs", entry.entity);
+        if !entry.files.keys().any(|path| path.ends_with(&route_suffix)) {
+            eprintln!("Skipping '{}': it has no generated routes", entry.entity);
+            continue;
+        }
+        let entity_suffix = format!("db/entities/{}.rs", entry.entity);
+        let entity_path = entry
+            .files
+            .keys()
+            .find(|path| path.ends_with(&entity_suffix))
+            .ok_or_else(|| anyhow::anyhow!("no entity file registered for '{}'", entry.entity))?;
+        let entity_path = project_root.join(entity_path);
+        let source = fs::read_to_string(&entity_path)
+            .with_context(|| format!("failed to read {}", entity_path.display()))?;
+        resources.push(ClientResource {
+            fields: parse_model_fields(&source)
+                .with_context(|| format!("failed to parse {}", entity_path.display()))?,
+            entity: entry.entity,
+            plural: entry.plural,
+            base_path: entry.base_path,
+        });
+    }
+
+    let mut vars = HashMap::new();
+    vars.insert("api_prefix".to_string(), escape_rust_string(api_prefix));
+    vars.insert(
+        "resources".to_string(),
+        resources.iter().map(render_resource).collect(),
+    );
+    render_template(CLIENT_TEMPLATE, &vars)
+}
+
+/// Reads `pub name: Type,` lines from the entity's `Model` struct.
+fn parse_model_fields(source: &str) -> Result<Vec<(String, String)>> {
+    let Some(start) = source.find("pub struct Model {") else {
+        bail!("no `pub struct Model` found");
+    };
+    let body = &source[start + "pub struct Model {".len()..];
+    let Some(end) = body.find("\n}") else {
+        bail!("`pub struct Model` is not closed");
+    };
+
+    let mut fields = Vec::new();
+    for line in body[..end].lines() {
+        let Some(field) = line.trim().strip_prefix("pub ") else {
+            continue;
+        };
+        let Some((name, ty)) = field.split_once(':') else {
+            continue;
+        };
+        let ty = ty.trim().trim_end_matches(',').trim();
+        fields.push((name.trim().to_string(), ty.to_string()));
+    }
+    Ok(fields)
+}
+
+fn render_resource(resource: &ClientResource) -> String {
+    let ClientResource {
+        entity,
+        plural,
+        base_path,
+        fields,
+    } = resource;
+    let pascal = to_pascal_case(entity);
+    let base_path = escape_rust_string(base_path);
+
+    let mut model_fields = String::from(
+        "    pub id: Uuid,\n    pub created_at: DateTimeWithTimeZone,\n    pub updated_at: DateTimeWithTimeZone,\n",
+    );
+    let mut input_fields = String::new();
+    let mut patch_fields = String::new();
+    for (name, ty) in fields {
+        model_fields.push_str(&format!("    pub {name}: {ty},\n"));
+        input_fields.push_str(&format!("    pub {name}: {ty},\n"));
+        patch_fields.push_str(&format!(
+            "    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub {name}: Option<{ty}>,\n"
+        ));
+    }
+
+    format!(
+        r#"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct {pascal} {{
+{model_fields}}}
+
+/// Body for `create_{entity}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct {pascal}Input {{
+{input_fields}}}
+
+/// Body for `update_{entity}`; `None` leaves a field unchanged and
+/// `Some(None)` clears an optional one.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct {pascal}Patch {{
+{patch_fields}}}
+
+impl ApiClient {{
+    pub async fn create_{entity}(&self, input: &{pascal}Input) -> reqwest::Result<{pascal}> {{
+        Self::send(self.request(Method::POST, "{base_path}").json(input)).await
+    }}
+
+    pub async fn get_{entity}(&self, id: Uuid) -> reqwest::Result<{pascal}> {{
+        Self::send(self.request(Method::GET, &format!("{base_path}/{{id}}"))).await
+    }}
+
+    pub async fn list_{plural}(&self, page: u64, page_size: u64) -> reqwest::Result<Page<{pascal}>> {{
+        let request = self
+            .request(Method::GET, "{base_path}")
+            .query(&[("page", page), ("page_size", page_size)]);
+        Self::send(request).await
+    }}
+
+    pub async fn update_{entity}(&self, id: Uuid, patch: &{pascal}Patch) -> reqwest::Result<{pascal}> {{
+        let request = self
+            .request(Method::PATCH, &format!("{base_path}/{{id}}"))
+            .json(patch);
+        Self::send(request).await
+    }}
+
+    pub async fn delete_{entity}(&self, id: Uuid) -> reqwest::Result<()> {{
+        Self::send_empty(self.request(Method::DELETE, &format!("{base_path}/{{id}}"))).await
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::render_client;
+
+    fn write(path: &std::path::Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).expect("dir should be created");
+        fs::write(path, contents).expect("file should be written");
+    }
+
+    #[test]
+    fn generated_client_has_methods_per_registered_resource() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let root = dir.path();
+        let server = root.join("crates/server");
+        write(
+            &server.join(".scaffold/apis.json"),
+            r#"{
+  "version": 1,
+  "apis": [
+    {
+      "name": "todo_item", "entity": "todo_item", "plural": "todo_items",
+      "base_path": "/todo-item",
+      "files": {
+        "crates/server/src/db/entities/todo_item.rs": "h1",
+        "crates/server/src/routes/api/todo_item.rs": "h2"
+      },
+      "mod_edits": {}, "dao_context_method": ""
+    },
+    {
+      "name": "invoice", "entity": "invoice", "plural": "invoices",
+      "base_path": "/billing/invoices",
+      "files": {
+        "crates/server/src/db/entities/invoice.rs": "h3",
+        "crates/server/src/routes/api/invoice.rs": "h4"
+      },
+      "mod_edits": {}, "dao_context_method": ""
+    },
+    {
+      "name": "audit_note", "entity": "audit_note", "plural": "audit_notes",
+      "base_path": "/audit-note",
+      "files": { "crates/server/src/db/entities/audit_note.rs": "h5" },
+      "mod_edits": {}, "dao_context_method": ""
+    }
+  ]
+}"#,
+        );
+        write(
+            &server.join("src/db/entities/todo_item.rs"),
+            "#[sea_orm(table_name = \"todo_items\")]\npub struct Model {\n    pub title: String,\n    pub done: bool,\n}\n",
+        );
+        write(
+            &server.join("src/db/entities/invoice.rs"),
+            "pub struct Model {\n    pub total: i64,\n    pub due_at: Option<DateTimeWithTimeZone>,\n}\n",
+        );
+
+        let client = render_client(root, &server, "/api/v1").expect("client should render");
+
+        assert!(client.contains("const API_PREFIX: &str = \"/api/v1\";"));
+        for (entity, plural, base) in [
+            ("todo_item", "todo_items", "/todo-item"),
+            ("invoice", "invoices", "/billing/invoices"),
+        ] {
+            assert!(client.contains(&format!(
+                "self.request(Method::POST, \"{base}\").json(input)"
+            )));
+            assert!(client.contains(&format!("pub async fn create_{entity}(")));
+            assert!(client.contains(&format!("pub async fn get_{entity}(")));
+            assert!(client.contains(&format!("pub async fn list_{plural}(")));
+            assert!(client.contains(&format!("pub async fn update_{entity}(")));
+            assert!(client.contains(&format!("pub async fn delete_{entity}(")));
+            assert!(client.contains(&format!("&format!(\"{base}/{{id}}\")")));
+        }
+        assert!(client.contains(
+            "pub struct TodoItemInput {\n    pub title: String,\n    pub done: bool,\n}"
+        ));
+        assert!(client.contains("pub due_at: Option<Option<DateTimeWithTimeZone>>,"));
+        assert!(!client.contains("audit_note"));
+    }
+}
@@ -187,7 +187,7 @@ pub(super) fn run_tui(args: InitArgs, repo: String) -> Result<TuiOutcome> {
         port: resolve_default_port(args.port),
         input: String::new(),
         error: None,
-        db_index: first_enabled_index(DB_OPTIONS),
+        db_index: db_option_index(args.db.as_deref()),
         auth_index: auth_option_index(args.auth_local),
         todo_index: todo_option_index(args.todo_example),
         docs_index: docs_option_index(args.docs),
@@ -240,12 +240,11 @@ pub(super) fn run_tui(args: InitArgs, repo: String) -> Result<TuiOutcome> {
 
         terminal.draw(|frame| draw_ui(frame, &state))?;
 
-        if event::poll(Duration::from_millis(120))? {
-            if let Event::Key(key) = event::read()? {
-                if handle_key(&mut state, key)? {
-                    break;
-                }
-            }
+        if event::poll(Duration::from_millis(120))?
+            && let Event::Key(key) = event::read()?
+            && handle_key(&mut state, key)?
+        {
+            break;
         }
     }
 
@@ -256,7 +255,9 @@ fn build_args(state: &UiState, args: &InitArgs) -> InitArgs {
     InitArgs {
         name: Some(state.name.clone()),
         out: Some(PathBuf::from(state.out_dir.clone())),
-        db: DB_OPTIONS[state.db_index].label.to_string(),
+        db: Some(DB_OPTIONS[state.db_index].label.to_string()),
+        preset: args.preset,
+        list_profiles: false,
         auth_local: AUTH_LOCAL_VALUES[state.auth_index],
         todo_example: TODO_EXAMPLE_VALUES[state.todo_index],
         docs: DOCS_VALUES[state.docs_index],
@@ -355,7 +356,7 @@ fn adjust_choice_index(current: usize, options: &[ChoiceOption], delta: isize) -
 fn shift_index(current: usize, max: usize, delta: isize) -> usize {
     let next = current as isize + delta;
     if next < 0 {
-        (max - 1) as usize
+        max - 1
     } else {
         (next as usize) % max
     }
@@ -365,6 +366,15 @@ fn first_enabled_index(options: &[ChoiceOption]) -> usize {
     options.iter().position(|opt| opt.enabled).unwrap_or(0)
 }
 
+fn db_option_index(db: Option<&str>) -> usize {
+    db.and_then(|db| {
+        DB_OPTIONS
+            .iter()
+            .position(|opt| opt.enabled && opt.label.eq_ignore_ascii_case(db.trim()))
+    })
+    .unwrap_or_else(|| first_enabled_index(DB_OPTIONS))
+}
+
 fn auth_option_index(auth_local: bool) -> usize {
     let target = if auth_local { 0 } else { 1 };
     if AUTH_OPTIONS
@@ -527,10 +537,10 @@ fn resolve_default_port(port: Option<u16>) -> String {
     if let Some(port) = port {
         return port.to_string();
     }
-    if let Some(env_port) = first_non_empty_env(&["APP_GENERAL__PORT", "PORT"]) {
-        if let Ok(parsed) = env_port.trim().parse::<u16>() {
-            return parsed.to_string();
-        }
+    if let Some(env_port) = first_non_empty_env(&["APP_GENERAL__PORT", "PORT"])
+        && let Ok(parsed) = env_port.trim().parse::<u16>()
+    {
+        return parsed.to_string();
     }
     DEFAULT_PORT.to_string()
 }
@@ -951,7 +961,7 @@ const DB_OPTIONS: &[ChoiceOption] = &[
     },
     ChoiceOption {
         label: "mysql",
-        enabled: false,
+        enabled: true,
     },
 ];
 
@@ -1,7 +1,11 @@
 mod add_api;
 mod api_remove;
 mod cli;
+mod gen_client;
 mod init;
+mod rcfile;
+mod seed;
+mod update;
 
 use anyhow::Result;
 use clap::Parser;
@@ -14,5 +18,8 @@ fn main() -> Result<()> {
             cli::ApiCommands::Add(args) => add_api::run(args),
             cli::ApiCommands::Remove(args) => api_remove::run(args),
         },
+        cli::Commands::Seed(args) => seed::run(args),
+        cli::Commands::GenClient(args) => gen_client::run(args),
+        cli::Commands::Update(args) => update::run(args),
     }
 }
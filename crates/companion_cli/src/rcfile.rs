@@ -0,0 +1,211 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::cli::{AddApiArgs, InitArgs, InitPreset};
+
+pub const RC_FILE_NAME: &str = ".sampleserverrc";
+
+/// Defaults read from `.sampleserverrc` (TOML).
+///
+/// Precedence is CLI flags, then this file, then the built-in defaults: a value
+/// from the file only fills in options the command line left unset.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RcFile {
+    pub init: InitDefaults,
+    pub add_api: AddApiDefaults,
+}
+
+/// `[init]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InitDefaults {
+    pub db: Option<String>,
+    pub database_url: Option<String>,
+    pub port: Option<u16>,
+    pub preset: Option<InitPreset>,
+    pub repo: Option<String>,
+    pub auth_local: Option<bool>,
+    pub todo_example: Option<bool>,
+    pub docs: Option<bool>,
+}
+
+/// `[add_api]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AddApiDefaults {
+    pub no_auth: Option<bool>,
+    pub no_service: Option<bool>,
+    pub with_tests: Option<bool>,
+    pub all: Option<bool>,
+}
+
+/// Nearest `.sampleserverrc` in `cwd` or one of its ancestors, falling back to
+/// `$HOME/.sampleserverrc`.
+pub fn find(cwd: &Path) -> Option<PathBuf> {
+    cwd.ancestors()
+        .map(|dir| dir.join(RC_FILE_NAME))
+        .chain(std::env::var_os("HOME").map(|home| PathBuf::from(home).join(RC_FILE_NAME)))
+        .find(|path| path.is_file())
+}
+
+/// Loads the nearest rc file; no file means no defaults.
+pub fn load(cwd: &Path) -> Result<RcFile> {
+    match find(cwd) {
+        Some(path) => parse_file(&path),
+        None => Ok(RcFile::default()),
+    }
+}
+
+fn parse_file(path: &Path) -> Result<RcFile> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    toml::from_str(&contents).with_context(|| format!("invalid {}", path.display()))
+}
+
+impl InitDefaults {
+    pub fn apply(&self, args: &mut InitArgs) {
+        let cli_preset = args.preset.is_some();
+        fill(&mut args.db, &self.db);
+        fill(&mut args.database_url, &self.database_url);
+        fill(&mut args.port, &self.port);
+        fill(&mut args.preset, &self.preset);
+        fill(&mut args.repo, &self.repo);
+
+        // A preset or feature flag on the command line settles the feature set;
+        // the file only decides features nobody mentioned.
+        if cli_preset {
+            return;
+        }
+        apply_toggle(
+            &mut args.auth_local,
+            &mut args.no_auth_local,
+            self.auth_local,
+        );
+        apply_toggle(
+            &mut args.todo_example,
+            &mut args.no_todo_example,
+            self.todo_example,
+        );
+        apply_toggle(&mut args.docs, &mut args.no_docs, self.docs);
+    }
+}
+
+impl AddApiDefaults {
+    pub fn apply(&self, args: &mut AddApiArgs) {
+        // Skip file values that would conflict with a flag given on the command line.
+        if !args.all {
+            args.no_service |= self.no_service.unwrap_or(false);
+        }
+        if !args.no_service {
+            args.all |= self.all.unwrap_or(false);
+            args.no_auth |= self.no_auth.unwrap_or(false);
+        }
+        args.with_tests |= self.with_tests.unwrap_or(false);
+    }
+}
+
+fn fill<T: Clone>(arg: &mut Option<T>, default: &Option<T>) {
+    if arg.is_none() {
+        arg.clone_from(default);
+    }
+}
+
+fn apply_toggle(on: &mut bool, off: &mut bool, default: Option<bool>) {
+    if *on || *off {
+        return;
+    }
+    match default {
+        Some(true) => *on = true,
+        Some(false) => *off = true,
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::Cli;
+
+    fn init_args(argv: &[&str]) -> InitArgs {
+        let argv = ["oxide", "init"].iter().chain(argv).copied();
+        match Cli::try_parse_from(argv)
+            .expect("args should parse")
+            .command
+        {
+            crate::cli::Commands::Init(args) => args,
+            _ => unreachable!("parsed init"),
+        }
+    }
+
+    fn write_rc(contents: &str) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        fs::write(dir.path().join(RC_FILE_NAME), contents).expect("write rc file");
+        let nested = dir.path().join("projects").join("demo");
+        fs::create_dir_all(&nested).expect("nested dir");
+        (dir, nested)
+    }
+
+    const RC: &str = r#"
+[init]
+db = "postgres"
+port = 8080
+todo_example = false
+
+[add_api]
+with_tests = true
+no_auth = true
+"#;
+
+    #[test]
+    fn file_values_fill_omitted_flags() {
+        let (_dir, cwd) = write_rc(RC);
+        let rc = load(&cwd).expect("rc file should load");
+
+        let mut args = init_args(&["demo"]);
+        rc.init.apply(&mut args);
+        assert_eq!(args.db.as_deref(), Some("postgres"));
+        assert_eq!(args.port, Some(8080));
+        assert!(args.no_todo_example);
+
+        let mut args = AddApiArgs::try_parse_from(["add", "invoice"]).expect("args should parse");
+        rc.add_api.apply(&mut args);
+        assert!(args.with_tests);
+        assert!(args.no_auth);
+    }
+
+    #[test]
+    fn cli_flags_override_file_values() {
+        let (_dir, cwd) = write_rc(RC);
+        let rc = load(&cwd).expect("rc file should load");
+
+        let mut args = init_args(&["demo", "--db", "sqlite", "--port", "4000", "--todo-example"]);
+        rc.init.apply(&mut args);
+        assert_eq!(args.db.as_deref(), Some("sqlite"));
+        assert_eq!(args.port, Some(4000));
+        assert!(args.todo_example);
+        assert!(!args.no_todo_example);
+
+        let mut args = init_args(&["demo", "--preset", "full"]);
+        rc.init.apply(&mut args);
+        assert!(!args.no_todo_example);
+
+        let mut args = AddApiArgs::try_parse_from(["add", "invoice", "--no-service"])
+            .expect("args should parse");
+        rc.add_api.apply(&mut args);
+        assert!(!args.no_auth);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let (_dir, cwd) = write_rc("[init]\ndatabase = \"postgres\"\n");
+        assert!(load(&cwd).is_err());
+    }
+}
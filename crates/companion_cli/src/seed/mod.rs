@@ -0,0 +1,195 @@
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use sqlx::{AnyConnection, Connection};
+use uuid::Uuid;
+
+use crate::add_api::schema::{ColumnInfo, load_columns, map_sql_type, resolve_database_url};
+use crate::add_api::{resolve_roots, validate_ident};
+use crate::cli::SeedArgs;
+
+enum SeedValue {
+    Text(String),
+    Blob(Vec<u8>),
+    Null,
+}
+
+pub fn run(args: SeedArgs) -> Result<()> {
+    validate_ident(&args.table, "table name")?;
+    let database_url = match args.database_url {
+        Some(url) => url,
+        None => {
+            let cwd = std::env::current_dir().context("failed to resolve current directory")?;
+            let (project_root, server_root) = resolve_roots(&cwd)?;
+            resolve_database_url(&project_root, &server_root)?
+        }
+    };
+
+    let inserted = seed_table(&database_url, &args.table, args.count)?;
+    println!("Inserted {inserted} rows into {}.", args.table);
+    Ok(())
+}
+
+/// Inserts `count` generated rows into `table`.
+///
+/// Values are derived from the column types: NOT NULL columns always get a
+/// value, and strings/integers carry a per-run tag plus the row index so
+/// unique columns do not collide with each other or with earlier runs.
+pub(crate) fn seed_table(database_url: &str, table: &str, count: usize) -> Result<usize> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to start async runtime")?
+        .block_on(seed_table_async(database_url, table, count))
+}
+
+async fn seed_table_async(database_url: &str, table: &str, count: usize) -> Result<usize> {
+    let columns = load_columns(database_url, table).await?;
+    if columns.is_empty() {
+        bail!("table '{table}' was not found or has no columns");
+    }
+    let sqlite = database_url.starts_with("sqlite:");
+    let statement = insert_statement(table, &columns, sqlite);
+
+    let tag = Uuid::new_v4().simple().to_string()[..8].to_string();
+    let number_base = i64::from(u32::from_str_radix(&tag[..6], 16).unwrap_or_default()) * 1000;
+    let now = Utc::now().to_rfc3339();
+
+    let mut conn = AnyConnection::connect(database_url)
+        .await
+        .context("failed to connect to database")?;
+    let mut tx = conn.begin().await.context("failed to start transaction")?;
+    for index in 0..count {
+        let mut query = sqlx::query(&statement);
+        for column in &columns {
+            let value = seed_value(column, sqlite, &tag, number_base, index, &now)?;
+            query = match value {
+                SeedValue::Text(value) => query.bind(value),
+                SeedValue::Blob(value) => query.bind(value),
+                SeedValue::Null => query.bind(None::<String>),
+            };
+        }
+        query
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("failed to insert row {} into '{table}'", index + 1))?;
+    }
+    tx.commit().await.context("failed to commit seeded rows")?;
+    conn.close().await.ok();
+    Ok(count)
+}
+
+fn insert_statement(table: &str, columns: &[ColumnInfo], sqlite: bool) -> String {
+    let names: Vec<String> = columns
+        .iter()
+        .map(|column| format!("\"{}\"", column.name.replace('"', "\"\"")))
+        .collect();
+    let placeholders: Vec<String> = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            if sqlite {
+                "?".to_string()
+            } else {
+                format!("CAST(${} AS {})", idx + 1, column.data_type)
+            }
+        })
+        .collect();
+    format!(
+        "INSERT INTO \"{table}\" ({}) VALUES ({})",
+        names.join(", "),
+        placeholders.join(", ")
+    )
+}
+
+fn seed_value(
+    column: &ColumnInfo,
+    sqlite: bool,
+    tag: &str,
+    number_base: i64,
+    index: usize,
+    now: &str,
+) -> Result<SeedValue> {
+    let Some(ty) = map_sql_type(&column.data_type) else {
+        if column.nullable {
+            return Ok(SeedValue::Null);
+        }
+        bail!(
+            "column '{}' has unsupported type '{}' and is NOT NULL",
+            column.name,
+            column.data_type
+        );
+    };
+
+    let value = match ty {
+        "String" if column.name.contains("email") => {
+            SeedValue::Text(format!("{}-{tag}-{index}@example.com", column.name))
+        }
+        "String" => SeedValue::Text(format!("{}-{tag}-{index}", column.name)),
+        "bool" if sqlite => {
+            SeedValue::Text(if index.is_multiple_of(2) { "1" } else { "0" }.to_string())
+        }
+        "bool" => SeedValue::Text(index.is_multiple_of(2).to_string()),
+        "i32" => SeedValue::Text(((number_base % 1_000_000) + index as i64).to_string()),
+        "i64" => SeedValue::Text((number_base + index as i64).to_string()),
+        // sea-orm stores sqlite UUIDs as 16-byte blobs.
+        "Uuid" if sqlite => SeedValue::Blob(Uuid::new_v4().as_bytes().to_vec()),
+        "Uuid" => SeedValue::Text(Uuid::new_v4().to_string()),
+        "DateTimeWithTimeZone" => SeedValue::Text(now.to_string()),
+        _ => SeedValue::Null,
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Connection, Row, SqliteConnection};
+
+    use super::seed_table;
+
+    #[test]
+    fn seed_table_inserts_rows_with_distinct_values() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("app.db").display());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime should start");
+        runtime.block_on(async {
+            let mut conn = SqliteConnection::connect(&url)
+                .await
+                .expect("sqlite should open");
+            sqlx::query(
+                "CREATE TABLE invoices (
+                    id uuid_text NOT NULL PRIMARY KEY,
+                    created_at timestamp_with_timezone_text NOT NULL,
+                    updated_at timestamp_with_timezone_text NOT NULL,
+                    number varchar(32) NOT NULL UNIQUE,
+                    total bigint NOT NULL,
+                    paid boolean NOT NULL,
+                    customer_id uuid_text,
+                    due_at timestamp_with_timezone_text
+                )",
+            )
+            .execute(&mut conn)
+            .await
+            .expect("table should be created");
+        });
+
+        let inserted = seed_table(&url, "invoices", 10).expect("seeding should succeed");
+        assert_eq!(inserted, 10);
+
+        runtime.block_on(async {
+            let mut conn = SqliteConnection::connect(&url)
+                .await
+                .expect("sqlite should open");
+            let row = sqlx::query(
+                "SELECT COUNT(*) AS total, COUNT(DISTINCT number) AS numbers FROM invoices",
+            )
+            .fetch_one(&mut conn)
+            .await
+            .expect("count should succeed");
+            assert_eq!(row.get::<i64, _>("total"), 10);
+            assert_eq!(row.get::<i64, _>("numbers"), 10);
+        });
+    }
+}
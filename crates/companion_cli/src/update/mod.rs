@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::add_api::{hash_str, render_template, resolve_roots, scaffold_templates};
+use crate::cli::UpdateArgs;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct Registry {
+    version: u32,
+    apis: Vec<ApiEntry>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct ApiEntry {
+    name: String,
+    entity: String,
+    plural: String,
+    base_path: String,
+    files: HashMap<String, String>,
+    mod_edits: HashMap<String, Vec<String>>,
+    dao_context_method: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    template_vars: HashMap<String, String>,
+}
+
+pub fn run(args: UpdateArgs) -> Result<()> {
+    let cwd = std::env::current_dir().context("failed to resolve current directory")?;
+    let (project_root, server_root) = resolve_roots(&cwd)?;
+    update(&project_root, &server_root, &args)
+}
+
+/// Re-renders every registered file whose contents still match the hash
+/// recorded at generation time. Edited files are reported and left alone
+/// unless `--force` is set.
+fn update(project_root: &Path, server_root: &Path, args: &UpdateArgs) -> Result<()> {
+    let registry_path = server_root.join(".scaffold/apis.json");
+    let contents = fs::read_to_string(&registry_path)
+        .with_context(|| format!("failed to read {}", registry_path.display()))?;
+    let mut registry: Registry = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse {}", registry_path.display()))?;
+
+    let mut updated = Vec::new();
+    let mut drifted = Vec::new();
+    for entry in &mut registry.apis {
+        if entry.template_vars.is_empty() {
+            eprintln!(
+                "Skipping '{}': registered before template inputs were recorded",
+                entry.name
+            );
+            continue;
+        }
+        let templates = scaffold_templates(&entry.entity);
+        let paths: Vec<String> = entry.files.keys().cloned().collect();
+        for path_str in paths {
+            let Some((_, template)) = templates
+                .iter()
+                .find(|(suffix, _)| path_str.ends_with(suffix.as_str()))
+            else {
+                continue;
+            };
+            let rendered = render_template(template, &entry.template_vars)?;
+            let new_hash = hash_str(&rendered);
+            let recorded_hash = &entry.files[&path_str];
+            if &new_hash == recorded_hash {
+                continue;
+            }
+
+            let path = resolve_registry_path(project_root, &path_str);
+            if !path.exists() {
+                eprintln!("Skipping {path_str}: file is missing");
+                continue;
+            }
+            let current = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            if &hash_str(&current) != recorded_hash && !args.force {
+                drifted.push(path_str);
+                continue;
+            }
+
+            if !args.dry_run {
+                fs::write(&path, &rendered)
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+                entry.files.insert(path_str.clone(), new_hash);
+            }
+            updated.push(path_str);
+        }
+    }
+
+    if args.dry_run {
+        println!("Dry run: would update files:");
+    } else {
+        let contents =
+            serde_json::to_string_pretty(&registry).context("failed to serialize registry")?;
+        fs::write(&registry_path, contents)
+            .with_context(|| format!("failed to write {}", registry_path.display()))?;
+        println!("Updated files:");
+    }
+    for path in &updated {
+        println!("  {path}");
+    }
+    if !drifted.is_empty() {
+        println!("Skipped modified files (use --force to overwrite):");
+        for path in &drifted {
+            println!("  {path}");
+        }
+    }
+    Ok(())
+}
+
+fn resolve_registry_path(root: &Path, stored: &str) -> PathBuf {
+    let stored_path = Path::new(stored);
+    if stored_path.is_absolute() {
+        stored_path.to_path_buf()
+    } else {
+        root.join(stored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::update;
+    use crate::add_api::hash_str;
+    use crate::cli::UpdateArgs;
+
+    fn write(path: &std::path::Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).expect("dir should be created");
+        fs::write(path, contents).expect("file should be written");
+    }
+
+    #[test]
+    fn unmodified_files_are_re_rendered_and_edited_files_are_kept() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let root = dir.path();
+        let server = root.join("crates/server");
+
+        let entity_file = "crates/server/src/db/entities/widget.rs";
+        let dao_file = "crates/server/src/db/dao/widget_dao.rs";
+        let stale = "// generated by an older template\n";
+        let edited = "// generated by an older template\n// hand edit\n";
+        write(&root.join(entity_file), stale);
+        write(&root.join(dao_file), edited);
+
+        let vars = serde_json::json!({
+            "entity": "widget", "Entity": "Widget", "entity_plural": "widgets",
+            "table": "widgets", "base_path": "/widget", "Dao": "WidgetDao",
+            "Service": "WidgetService", "service_module": "widget_service",
+            "route_module": "widget", "fields": "    pub name: String,\n",
+            "auth_imports": "", "auth_layer": ""
+        });
+        let registry = serde_json::json!({
+            "version": 1,
+            "apis": [{
+                "name": "widget", "entity": "widget", "plural": "widgets",
+                "base_path": "/widget",
+                "files": { entity_file: hash_str(stale), dao_file: hash_str(stale) },
+                "mod_edits": {}, "dao_context_method": "",
+                "template_vars": vars
+            }]
+        });
+        write(
+            &server.join(".scaffold/apis.json"),
+            &serde_json::to_string_pretty(&registry).unwrap(),
+        );
+
+        let args = UpdateArgs {
+            dry_run: false,
+            force: false,
+        };
+        update(root, &server, &args).expect("update should succeed");
+
+        let entity = fs::read_to_string(root.join(entity_file)).unwrap();
+        assert!(entity.contains("table_name = \"widgets\""));
+        assert!(entity.contains("pub name: String,"));
+        assert_eq!(fs::read_to_string(root.join(dao_file)).unwrap(), edited);
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(server.join(".scaffold/apis.json")).unwrap())
+                .unwrap();
+        let files = &saved["apis"][0]["files"];
+        assert_eq!(files[entity_file], hash_str(&entity));
+        assert_eq!(files[dao_file], hash_str(stale));
+        assert_eq!(saved["apis"][0]["template_vars"]["table"], "widgets");
+    }
+}
@@ -0,0 +1 @@
+//! Derive macros shared by rust_oxide entities.
@@ -0,0 +1 @@
+//! Renders docs sections at build time.
@@ -0,0 +1 @@
+//! Builds the local auth provider.
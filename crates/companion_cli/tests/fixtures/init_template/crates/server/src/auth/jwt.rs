@@ -0,0 +1 @@
+//! JWT minting and verification.
@@ -0,0 +1,4 @@
+pub mod bootstrap;
+pub mod jwt;
+pub mod password;
+pub mod providers;
@@ -0,0 +1 @@
+//! Email/password provider.
@@ -0,0 +1,26 @@
+use sea_orm::DatabaseConnection;
+
+use super::{DaoBase, InviteDao, RefreshTokenDao, TodoDao, UserDao};
+
+#[derive(Clone)]
+pub struct DaoContext {
+    db: DatabaseConnection,
+}
+
+impl DaoContext {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    pub fn user(&self) -> UserDao {
+        DaoBase::new(&self.db)
+    }
+
+    pub fn todo(&self) -> TodoDao {
+        DaoBase::new(&self.db)
+    }
+
+    pub fn invite(&self) -> InviteDao {
+        DaoBase::new(&self.db)
+    }
+}
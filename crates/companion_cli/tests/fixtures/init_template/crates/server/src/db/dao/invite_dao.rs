@@ -0,0 +1 @@
+//! `invite_dao` queries.
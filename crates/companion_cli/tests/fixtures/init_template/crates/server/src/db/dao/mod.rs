@@ -0,0 +1,11 @@
+mod context;
+pub mod invite_dao;
+pub mod refresh_token_dao;
+pub mod todo_dao;
+pub mod user_dao;
+
+pub use context::DaoContext;
+pub use invite_dao::InviteDao;
+pub use refresh_token_dao::RefreshTokenDao;
+pub use todo_dao::TodoDao;
+pub use user_dao::UserDao;
@@ -0,0 +1 @@
+//! `todo_dao` queries.
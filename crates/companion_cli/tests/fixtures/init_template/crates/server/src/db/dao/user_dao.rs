@@ -0,0 +1 @@
+//! `user_dao` queries.
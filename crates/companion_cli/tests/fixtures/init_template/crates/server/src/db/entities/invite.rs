@@ -0,0 +1 @@
+//! `invite` entity.
@@ -0,0 +1,7 @@
+pub mod prelude;
+
+pub mod invite;
+pub mod refresh_token;
+pub mod todo_item;
+pub mod todo_list;
+pub mod user;
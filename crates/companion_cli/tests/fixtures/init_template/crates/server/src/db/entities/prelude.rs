@@ -0,0 +1,5 @@
+pub use super::invite::Entity as Invite;
+pub use super::refresh_token::Entity as RefreshToken;
+pub use super::todo_item::Entity as TodoItem;
+pub use super::todo_list::Entity as TodoList;
+pub use super::user::Entity as User;
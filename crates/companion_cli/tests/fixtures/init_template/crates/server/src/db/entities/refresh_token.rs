@@ -0,0 +1 @@
+//! `refresh_token` entity.
@@ -0,0 +1 @@
+//! `todo_item` entity.
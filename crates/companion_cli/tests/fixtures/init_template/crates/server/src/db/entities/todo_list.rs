@@ -0,0 +1 @@
+//! `todo_list` entity.
@@ -0,0 +1 @@
+//! `admin` API routes.
@@ -0,0 +1 @@
+//! `auth` API routes.
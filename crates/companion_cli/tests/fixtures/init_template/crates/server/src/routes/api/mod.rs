@@ -0,0 +1,8 @@
+pub mod admin;
+pub mod auth;
+pub mod protected;
+pub mod public;
+mod router;
+pub mod todo_crud;
+
+pub use router::router;
@@ -0,0 +1 @@
+//! `protected` API routes.
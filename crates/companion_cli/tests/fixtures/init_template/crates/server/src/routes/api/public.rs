@@ -0,0 +1 @@
+//! `public` API routes.
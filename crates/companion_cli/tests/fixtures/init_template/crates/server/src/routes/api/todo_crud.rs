@@ -0,0 +1 @@
+//! `todo_crud` API routes.
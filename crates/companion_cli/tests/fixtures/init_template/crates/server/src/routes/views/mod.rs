@@ -0,0 +1,5 @@
+pub mod public;
+mod router;
+pub mod todo;
+
+pub use router::router;
@@ -0,0 +1 @@
+//! Home and docs pages.
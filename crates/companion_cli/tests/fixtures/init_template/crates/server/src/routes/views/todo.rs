@@ -0,0 +1 @@
+//! Todo UI page.
@@ -0,0 +1 @@
+//! `auth_service` business logic.
@@ -0,0 +1,6 @@
+pub mod auth_service;
+pub mod context;
+pub mod todo_service;
+pub mod user_service;
+
+pub use context::ServiceContext;
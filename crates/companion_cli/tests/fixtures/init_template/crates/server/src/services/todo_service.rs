@@ -0,0 +1 @@
+//! `todo_service` business logic.
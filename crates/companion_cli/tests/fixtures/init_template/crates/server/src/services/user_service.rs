@@ -0,0 +1 @@
+//! `user_service` business logic.
@@ -0,0 +1 @@
+//! `auth_flow` integration tests.
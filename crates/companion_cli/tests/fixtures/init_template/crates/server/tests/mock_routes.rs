@@ -0,0 +1 @@
+//! `mock_routes` integration tests.
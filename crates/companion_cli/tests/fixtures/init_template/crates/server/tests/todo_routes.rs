@@ -0,0 +1 @@
+//! `todo_routes` integration tests.
@@ -1,5 +1,6 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    future::Future,
     sync::{
         Arc,
         atomic::{AtomicU64, Ordering},
@@ -7,17 +8,17 @@ use std::{
     time::Duration,
 };
 
-use futures_util::{
-    SinkExt, StreamExt,
-    stream::{SplitSink, SplitStream},
-};
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
 use serde_json::Value;
 use tokio::net::TcpStream;
 use tokio::{
     sync::{Mutex, mpsc, oneshot},
-    time::{interval, timeout},
+    time::{Instant, MissedTickBehavior, interval, sleep, sleep_until, timeout},
+};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{Error as WsError, Message},
 };
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
 use uuid::Uuid;
 
 use crate::protocol::{ClientFrame, DEFAULT_EVENT, ErrorPayload, ServerFrame};
@@ -38,8 +39,50 @@ type ChannelEventHandlers =
     Arc<std::sync::Mutex<HashMap<String, HashMap<SubscriptionId, ChannelEventHandler>>>>;
 type GlobalEventHandlers = Arc<std::sync::Mutex<HashMap<SubscriptionId, GlobalEventHandler>>>;
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
-type WsWriter = SplitSink<WsStream, Message>;
-type WsReader = SplitStream<WsStream>;
+type StateHandler = Arc<dyn Fn(ConnectionState) + Send + Sync>;
+type StateHandlers = Arc<std::sync::Mutex<HashMap<SubscriptionId, StateHandler>>>;
+type ResubscribedHandler = Arc<dyn Fn(Vec<String>) + Send + Sync>;
+type ResubscribedHandlers = Arc<std::sync::Mutex<HashMap<SubscriptionId, ResubscribedHandler>>>;
+type JoinedChannels = Arc<std::sync::Mutex<BTreeSet<String>>>;
+
+/// Error given to requests still waiting for an ack when the socket drops.
+const CONNECTION_CLOSED: &str = "websocket connection closed";
+
+/// Socket lifecycle reported to [`RealtimeClient::on_connection_state`] handlers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// A new socket is open after a reconnect.
+    Connected,
+    /// The socket closed, failed, or missed too many pongs.
+    Disconnected,
+    /// A reconnect attempt is starting.
+    Reconnecting,
+}
+
+/// Handler registries shared by the client handles and the connection task.
+#[derive(Clone)]
+struct SessionHandlers {
+    pending_acks: PendingAcks,
+    channel_handlers: ChannelHandlers,
+    global_handlers: GlobalHandlers,
+    channel_event_handlers: ChannelEventHandlers,
+    global_event_handlers: GlobalEventHandlers,
+    state_handlers: StateHandlers,
+    resubscribed_handlers: ResubscribedHandlers,
+    joined_channels: JoinedChannels,
+}
+
+enum SessionEnd {
+    ClientDropped,
+    SocketClosed,
+    MissedPongs,
+}
+
+enum Incoming {
+    Continue,
+    Pong(String),
+    Close,
+}
 
 #[derive(Clone)]
 pub struct RealtimeClient {
@@ -49,6 +92,9 @@ pub struct RealtimeClient {
     global_handlers: GlobalHandlers,
     channel_event_handlers: ChannelEventHandlers,
     global_event_handlers: GlobalEventHandlers,
+    state_handlers: StateHandlers,
+    resubscribed_handlers: ResubscribedHandlers,
+    joined_channels: JoinedChannels,
     next_subscription_id: Arc<AtomicU64>,
     cfg: ClientConfig,
 }
@@ -63,8 +109,33 @@ impl RealtimeClient {
         token: &str,
         cfg: ClientConfig,
     ) -> ClientResult<Self> {
-        let ws = Self::open_socket(base_url, token).await?;
-        let (write, read) = ws.split();
+        let base_url = base_url.to_string();
+        let token = token.to_string();
+        let connect = move || {
+            let base_url = base_url.clone();
+            let token = token.clone();
+            async move {
+                let ws = Self::open_socket(&base_url, &token).await?;
+                Ok(ws.split())
+            }
+        };
+        Self::connect_with_transport(connect, cfg).await
+    }
+
+    /// Opens the first connection with `connect` and keeps calling it to
+    /// reconnect. Split out so tests can drive the client over an in-memory
+    /// transport.
+    async fn connect_with_transport<C, Fut, W, R>(
+        connect: C,
+        cfg: ClientConfig,
+    ) -> ClientResult<Self>
+    where
+        C: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ClientResult<(W, R)>> + Send + 'static,
+        W: Sink<Message> + Unpin + Send + 'static,
+        R: Stream<Item = Result<Message, WsError>> + Unpin + Send + 'static,
+    {
+        let (write, read) = connect().await?;
         let (outbound_tx, outbound_rx) = mpsc::channel::<ClientFrame>(cfg.outbound_buffer);
 
         let pending_acks: PendingAcks = Arc::new(Mutex::new(HashMap::new()));
@@ -74,17 +145,29 @@ impl RealtimeClient {
             Arc::new(std::sync::Mutex::new(HashMap::new()));
         let global_event_handlers: GlobalEventHandlers =
             Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let state_handlers: StateHandlers = Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let resubscribed_handlers: ResubscribedHandlers =
+            Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let joined_channels: JoinedChannels = Arc::new(std::sync::Mutex::new(BTreeSet::new()));
 
-        Self::spawn_writer_task(write, outbound_rx);
-        Self::spawn_reader_task(
+        let handlers = SessionHandlers {
+            pending_acks: Arc::clone(&pending_acks),
+            channel_handlers: Arc::clone(&channel_handlers),
+            global_handlers: Arc::clone(&global_handlers),
+            channel_event_handlers: Arc::clone(&channel_event_handlers),
+            global_event_handlers: Arc::clone(&global_event_handlers),
+            state_handlers: Arc::clone(&state_handlers),
+            resubscribed_handlers: Arc::clone(&resubscribed_handlers),
+            joined_channels: Arc::clone(&joined_channels),
+        };
+        tokio::spawn(Self::run_connection(
+            connect,
+            write,
             read,
-            Arc::clone(&pending_acks),
-            Arc::clone(&channel_handlers),
-            Arc::clone(&global_handlers),
-            Arc::clone(&channel_event_handlers),
-            Arc::clone(&global_event_handlers),
-        );
-        Self::spawn_ping_task(outbound_tx.clone(), cfg.ping_interval);
+            outbound_rx,
+            handlers,
+            cfg.clone(),
+        ));
 
         Ok(Self {
             outbound_tx,
@@ -93,11 +176,16 @@ impl RealtimeClient {
             global_handlers,
             channel_event_handlers,
             global_event_handlers,
+            state_handlers,
+            resubscribed_handlers,
+            joined_channels,
             next_subscription_id: Arc::new(AtomicU64::new(1)),
             cfg,
         })
     }
 
+    /// Joins `channel`. Joined channels are remembered and rejoined after a
+    /// reconnect while `ClientConfig::resubscribe` is set.
     pub async fn join(&self, channel: &str) -> ClientResult<()> {
         self.request_ack(
             ClientFrame::ChannelJoin {
@@ -107,7 +195,12 @@ impl RealtimeClient {
             },
             self.cfg.request_timeout,
         )
-        .await
+        .await?;
+        self.joined_channels
+            .lock()
+            .expect("joined channels mutex poisoned")
+            .insert(channel.to_string());
+        Ok(())
     }
 
     pub async fn leave(&self, channel: &str) -> ClientResult<()> {
@@ -119,7 +212,12 @@ impl RealtimeClient {
             },
             self.cfg.request_timeout,
         )
-        .await
+        .await?;
+        self.joined_channels
+            .lock()
+            .expect("joined channels mutex poisoned")
+            .remove(channel);
+        Ok(())
     }
 
     pub async fn send(&self, channel: &str, message: Value) -> ClientResult<()> {
@@ -127,12 +225,100 @@ impl RealtimeClient {
     }
 
     pub async fn send_event(&self, channel: &str, event: &str, message: Value) -> ClientResult<()> {
+        self.emit(channel, event, message, None).await
+    }
+
+    /// Like `send_event`, but the server stops replaying the event to new
+    /// joiners once `ttl` has passed.
+    pub async fn send_event_with_ttl(
+        &self,
+        channel: &str,
+        event: &str,
+        message: Value,
+        ttl: Duration,
+    ) -> ClientResult<()> {
+        self.emit(channel, event, message, Some(ttl)).await
+    }
+
+    async fn emit(
+        &self,
+        channel: &str,
+        event: &str,
+        message: Value,
+        ttl: Option<Duration>,
+    ) -> ClientResult<()> {
         self.request_ack(
             ClientFrame::ChannelEmit {
                 id: Uuid::new_v4().to_string(),
                 channel: channel.to_string(),
                 event: event.to_string(),
                 data: message,
+                ttl_ms: ttl.map(|ttl| ttl.as_millis() as u64),
+                ts: None,
+            },
+            self.cfg.request_timeout,
+        )
+        .await
+    }
+
+    /// Emits one event to several joined channels with a single frame. Fails
+    /// unless the server accepted the event on every channel.
+    pub async fn send_event_multi(
+        &self,
+        channels: &[&str],
+        event: &str,
+        message: Value,
+    ) -> ClientResult<()> {
+        self.request_ack(
+            ClientFrame::ChannelEmitMulti {
+                id: Uuid::new_v4().to_string(),
+                channels: channels.iter().map(|channel| channel.to_string()).collect(),
+                event: event.to_string(),
+                data: message,
+                ts: None,
+            },
+            self.cfg.request_timeout,
+        )
+        .await
+    }
+
+    /// Sets this connection's presence state on a joined channel; other
+    /// members receive it as a `presence_update` frame. `Value::Null` clears it.
+    pub async fn set_presence(&self, channel: &str, state: Value) -> ClientResult<()> {
+        self.request_ack(
+            ClientFrame::SetPresence {
+                id: Uuid::new_v4().to_string(),
+                channel: channel.to_string(),
+                state,
+                ts: None,
+            },
+            self.cfg.request_timeout,
+        )
+        .await
+    }
+
+    /// Replaces the shared state document of a joined channel; every member,
+    /// this one included, receives it as a `state` frame. `Value::Null` clears it.
+    pub async fn set_state(&self, channel: &str, state: Value) -> ClientResult<()> {
+        self.request_ack(
+            ClientFrame::SetState {
+                id: Uuid::new_v4().to_string(),
+                channel: channel.to_string(),
+                state,
+                ts: None,
+            },
+            self.cfg.request_timeout,
+        )
+        .await
+    }
+
+    /// Merges key/value pairs into this connection's metadata, which the
+    /// server's channel policy can read. An empty value removes the key.
+    pub async fn set_meta(&self, meta: BTreeMap<String, String>) -> ClientResult<()> {
+        self.request_ack(
+            ClientFrame::SetMeta {
+                id: Uuid::new_v4().to_string(),
+                meta,
                 ts: None,
             },
             self.cfg.request_timeout,
@@ -196,9 +382,58 @@ impl RealtimeClient {
         id
     }
 
+    /// Called on every disconnect, reconnect attempt, and successful
+    /// reconnect.
+    pub fn on_connection_state<F>(&self, handler: F) -> SubscriptionId
+    where
+        F: Fn(ConnectionState) + Send + Sync + 'static,
+    {
+        let id = self.next_subscription_id.fetch_add(1, Ordering::Relaxed);
+        self.state_handlers
+            .lock()
+            .expect("state handler mutex poisoned")
+            .insert(id, Arc::new(handler));
+        id
+    }
+
+    /// Called after a reconnect once the server has acked the rejoins, with
+    /// the channels that were rejoined. Events published while disconnected
+    /// beyond the server's replay buffer are lost, so refetch state here.
+    pub fn on_resubscribed<F>(&self, handler: F) -> SubscriptionId
+    where
+        F: Fn(Vec<String>) + Send + Sync + 'static,
+    {
+        let id = self.next_subscription_id.fetch_add(1, Ordering::Relaxed);
+        self.resubscribed_handlers
+            .lock()
+            .expect("resubscribed handler mutex poisoned")
+            .insert(id, Arc::new(handler));
+        id
+    }
+
     pub fn off(&self, id: SubscriptionId) -> bool {
         let mut removed = false;
 
+        if self
+            .resubscribed_handlers
+            .lock()
+            .expect("resubscribed handler mutex poisoned")
+            .remove(&id)
+            .is_some()
+        {
+            removed = true;
+        }
+
+        if self
+            .state_handlers
+            .lock()
+            .expect("state handler mutex poisoned")
+            .remove(&id)
+            .is_some()
+        {
+            removed = true;
+        }
+
         let mut global = self
             .global_handlers
             .lock()
@@ -248,102 +483,232 @@ impl RealtimeClient {
         Ok(ws)
     }
 
-    fn spawn_writer_task(mut write: WsWriter, mut outbound_rx: mpsc::Receiver<ClientFrame>) {
-        tokio::spawn(async move {
-            while let Some(frame) = outbound_rx.recv().await {
-                let text = match serde_json::to_string(&frame) {
-                    Ok(text) => text,
-                    Err(err) => {
-                        eprintln!("failed to serialize outbound frame: {err}");
-                        continue;
-                    }
-                };
+    /// Drives the socket until it drops, then reconnects while any client
+    /// handle is still alive and `cfg.reconnect` is set.
+    async fn run_connection<C, Fut, W, R>(
+        connect: C,
+        mut write: W,
+        mut read: R,
+        mut outbound_rx: mpsc::Receiver<ClientFrame>,
+        handlers: SessionHandlers,
+        cfg: ClientConfig,
+    ) where
+        C: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ClientResult<(W, R)>> + Send + 'static,
+        W: Sink<Message> + Unpin + Send + 'static,
+        R: Stream<Item = Result<Message, WsError>> + Unpin + Send + 'static,
+    {
+        loop {
+            let end =
+                Self::drive_session(&mut write, &mut read, &mut outbound_rx, &handlers, &cfg).await;
+            let _ = write.close().await;
+            Self::fail_pending_acks(&handlers.pending_acks).await;
+            match end {
+                SessionEnd::ClientDropped => return,
+                SessionEnd::MissedPongs => {
+                    eprintln!("missed {} pongs; dropping connection", cfg.max_missed_pongs);
+                }
+                SessionEnd::SocketClosed => {}
+            }
+            dispatch_state_handlers(&handlers.state_handlers, ConnectionState::Disconnected);
+            if !cfg.reconnect {
+                return;
+            }
 
-                if write.send(Message::Text(text.into())).await.is_err() {
-                    break;
+            loop {
+                sleep(cfg.reconnect_delay).await;
+                if outbound_rx.is_closed() {
+                    return;
+                }
+                dispatch_state_handlers(&handlers.state_handlers, ConnectionState::Reconnecting);
+                match connect().await {
+                    Ok((next_write, next_read)) => {
+                        write = next_write;
+                        read = next_read;
+                        break;
+                    }
+                    Err(err) => eprintln!("reconnect failed: {err}"),
                 }
             }
-        });
+            dispatch_state_handlers(&handlers.state_handlers, ConnectionState::Connected);
+            if cfg.resubscribe {
+                Self::rejoin_channels(&mut write, &handlers, &cfg).await;
+            }
+        }
     }
 
-    fn spawn_reader_task(
-        mut read: WsReader,
-        pending_acks: PendingAcks,
-        channel_handlers: ChannelHandlers,
-        global_handlers: GlobalHandlers,
-        channel_event_handlers: ChannelEventHandlers,
-        global_event_handlers: GlobalEventHandlers,
-    ) {
+    /// Sends a join for every remembered channel on the new socket, then
+    /// reports the ones the server accepted to `on_resubscribed` handlers.
+    /// Rejected channels are forgotten.
+    async fn rejoin_channels<W>(write: &mut W, handlers: &SessionHandlers, cfg: &ClientConfig)
+    where
+        W: Sink<Message> + Unpin,
+    {
+        let channels: Vec<String> = handlers
+            .joined_channels
+            .lock()
+            .expect("joined channels mutex poisoned")
+            .iter()
+            .cloned()
+            .collect();
+        if channels.is_empty() {
+            return;
+        }
+
+        let mut acks = Vec::with_capacity(channels.len());
+        for channel in channels {
+            let id = Uuid::new_v4().to_string();
+            let (tx, rx) = oneshot::channel();
+            handlers.pending_acks.lock().await.insert(id.clone(), tx);
+            let frame = ClientFrame::ChannelJoin {
+                id: id.clone(),
+                channel: channel.clone(),
+                ts: None,
+            };
+            if !Self::write_frame(write, &frame).await {
+                // The session loop notices the dead socket and reconnects again.
+                handlers.pending_acks.lock().await.remove(&id);
+                return;
+            }
+            acks.push((channel, id, rx));
+        }
+
+        // Acks arrive through the session loop, so wait for them off this task.
+        let handlers = handlers.clone();
+        let request_timeout = cfg.request_timeout;
         tokio::spawn(async move {
-            while let Some(next) = read.next().await {
-                let msg = match next {
-                    Ok(msg) => msg,
-                    Err(err) => {
-                        eprintln!("websocket read error: {err}");
-                        break;
+            let mut rejoined = Vec::with_capacity(acks.len());
+            for (channel, id, rx) in acks {
+                match timeout(request_timeout, rx).await {
+                    Ok(Ok(Ok(()))) => rejoined.push(channel),
+                    // Kept for the next reconnect.
+                    Ok(Ok(Err(err))) if err == CONNECTION_CLOSED => {}
+                    Ok(Ok(Err(err))) => {
+                        eprintln!("rejoin failed for channel={channel}: {err}");
+                        handlers
+                            .joined_channels
+                            .lock()
+                            .expect("joined channels mutex poisoned")
+                            .remove(&channel);
                     }
-                };
+                    Ok(Err(_)) => {}
+                    Err(_) => {
+                        handlers.pending_acks.lock().await.remove(&id);
+                        eprintln!("rejoin timed out for channel={channel}");
+                    }
+                }
+            }
+            dispatch_resubscribed_handlers(&handlers.resubscribed_handlers, rejoined);
+        });
+    }
+
+    /// Pumps outbound frames and inbound messages for one socket and sends
+    /// keepalive pings. Only one ping is in flight at a time; a ping without a
+    /// matching pong within `pong_timeout` counts as missed.
+    async fn drive_session<W, R>(
+        write: &mut W,
+        read: &mut R,
+        outbound_rx: &mut mpsc::Receiver<ClientFrame>,
+        handlers: &SessionHandlers,
+        cfg: &ClientConfig,
+    ) -> SessionEnd
+    where
+        W: Sink<Message> + Unpin,
+        R: Stream<Item = Result<Message, WsError>> + Unpin,
+    {
+        let mut ticker = interval(cfg.ping_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut awaiting_pong: Option<(String, Instant)> = None;
+        let mut missed_pongs = 0u32;
 
-                let keep_reading = Self::handle_incoming_message(
-                    msg,
-                    &pending_acks,
-                    &channel_handlers,
-                    &global_handlers,
-                    &channel_event_handlers,
-                    &global_event_handlers,
-                )
-                .await;
-                if !keep_reading {
-                    break;
+        loop {
+            let pong_deadline = awaiting_pong.as_ref().map(|(_, deadline)| *deadline);
+            tokio::select! {
+                frame = outbound_rx.recv() => {
+                    let Some(frame) = frame else {
+                        return SessionEnd::ClientDropped;
+                    };
+                    if !Self::write_frame(write, &frame).await {
+                        return SessionEnd::SocketClosed;
+                    }
+                }
+                next = read.next() => {
+                    let msg = match next {
+                        Some(Ok(msg)) => msg,
+                        Some(Err(err)) => {
+                            eprintln!("websocket read error: {err}");
+                            return SessionEnd::SocketClosed;
+                        }
+                        None => return SessionEnd::SocketClosed,
+                    };
+                    match Self::handle_incoming_message(msg, handlers).await {
+                        Incoming::Continue => {}
+                        Incoming::Pong(id) => {
+                            if awaiting_pong.as_ref().is_some_and(|(expected, _)| *expected == id) {
+                                awaiting_pong = None;
+                                missed_pongs = 0;
+                            }
+                        }
+                        Incoming::Close => return SessionEnd::SocketClosed,
+                    }
+                }
+                _ = ticker.tick(), if awaiting_pong.is_none() => {
+                    let id = Uuid::new_v4().to_string();
+                    let ping = ClientFrame::Ping { id: id.clone(), ts: None };
+                    if !Self::write_frame(write, &ping).await {
+                        return SessionEnd::SocketClosed;
+                    }
+                    awaiting_pong = Some((id, Instant::now() + cfg.pong_timeout));
+                }
+                _ = sleep_until(pong_deadline.unwrap_or_else(Instant::now)), if pong_deadline.is_some() => {
+                    awaiting_pong = None;
+                    missed_pongs += 1;
+                    if cfg.max_missed_pongs > 0 && missed_pongs >= cfg.max_missed_pongs {
+                        return SessionEnd::MissedPongs;
+                    }
                 }
             }
+        }
+    }
 
-            Self::fail_pending_acks(&pending_acks).await;
-        });
+    /// Returns `false` once the socket can no longer be written to.
+    async fn write_frame<W>(write: &mut W, frame: &ClientFrame) -> bool
+    where
+        W: Sink<Message> + Unpin,
+    {
+        let text = match serde_json::to_string(frame) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("failed to serialize outbound frame: {err}");
+                return true;
+            }
+        };
+        write.send(Message::Text(text.into())).await.is_ok()
     }
 
-    async fn handle_incoming_message(
-        msg: Message,
-        pending_acks: &PendingAcks,
-        channel_handlers: &ChannelHandlers,
-        global_handlers: &GlobalHandlers,
-        channel_event_handlers: &ChannelEventHandlers,
-        global_event_handlers: &GlobalEventHandlers,
-    ) -> bool {
+    async fn handle_incoming_message(msg: Message, handlers: &SessionHandlers) -> Incoming {
         let text = match msg {
             Message::Text(text) => text,
-            Message::Close(_) => return false,
-            _ => return true,
+            Message::Close(_) => return Incoming::Close,
+            _ => return Incoming::Continue,
         };
 
         let frame = match serde_json::from_str::<ServerFrame>(&text) {
             Ok(frame) => frame,
             Err(err) => {
                 eprintln!("invalid server frame: {err}");
-                return true;
+                return Incoming::Continue;
             }
         };
 
-        Self::handle_server_frame(
-            frame,
-            pending_acks,
-            channel_handlers,
-            global_handlers,
-            channel_event_handlers,
-            global_event_handlers,
-        )
-        .await;
-        true
+        match Self::handle_server_frame(frame, handlers).await {
+            Some(pong_id) => Incoming::Pong(pong_id),
+            None => Incoming::Continue,
+        }
     }
 
-    async fn handle_server_frame(
-        frame: ServerFrame,
-        pending_acks: &PendingAcks,
-        channel_handlers: &ChannelHandlers,
-        global_handlers: &GlobalHandlers,
-        channel_event_handlers: &ChannelEventHandlers,
-        global_event_handlers: &GlobalEventHandlers,
-    ) {
+    /// Dispatches a server frame and returns the id when it is a pong.
+    async fn handle_server_frame(frame: ServerFrame, handlers: &SessionHandlers) -> Option<String> {
         match frame {
             ServerFrame::Connected {
                 conn_id, user_id, ..
@@ -353,32 +718,71 @@ impl RealtimeClient {
             ServerFrame::Joined { channel, .. } => {
                 println!("joined channel={channel}");
             }
-            ServerFrame::Left { channel, .. } => {
-                println!("left channel={channel}");
-            }
+            ServerFrame::Left {
+                channel, reason, ..
+            } => match reason {
+                Some(reason) => {
+                    // Closed or kicked by the server; do not rejoin on reconnect.
+                    handlers
+                        .joined_channels
+                        .lock()
+                        .expect("joined channels mutex poisoned")
+                        .remove(&channel);
+                    println!("left channel={channel} reason={reason}");
+                }
+                None => println!("left channel={channel}"),
+            },
             ServerFrame::Event {
                 channel,
                 event,
                 data,
                 ..
             } => {
-                dispatch_channel_handlers(channel_handlers, &channel, &data);
-                dispatch_global_handlers(global_handlers, &channel, &data);
-                dispatch_channel_event_handlers(channel_event_handlers, &channel, &event, &data);
-                dispatch_global_event_handlers(global_event_handlers, &channel, &event, &data);
+                dispatch_channel_handlers(&handlers.channel_handlers, &channel, &data);
+                dispatch_global_handlers(&handlers.global_handlers, &channel, &data);
+                dispatch_channel_event_handlers(
+                    &handlers.channel_event_handlers,
+                    &channel,
+                    &event,
+                    &data,
+                );
+                dispatch_global_event_handlers(
+                    &handlers.global_event_handlers,
+                    &channel,
+                    &event,
+                    &data,
+                );
             }
             ServerFrame::Ack {
                 for_id, ok, error, ..
             } => {
-                Self::resolve_ack(pending_acks, for_id, ok, error).await;
+                Self::resolve_ack(&handlers.pending_acks, for_id, ok, error).await;
+            }
+            ServerFrame::PresenceUpdate {
+                channel,
+                user_id,
+                state,
+                ..
+            } => {
+                println!("presence channel={channel} user_id={user_id} state={state}");
             }
-            ServerFrame::Pong { id, .. } => {
-                println!("pong id={id}");
+            ServerFrame::State {
+                channel,
+                state,
+                seq,
+                ..
+            } => {
+                println!("state channel={channel} seq={seq} state={state}");
             }
+            ServerFrame::Pong { id, .. } => return Some(id),
             ServerFrame::Error { error, .. } => {
                 eprintln!("server error {}: {}", error.code, error.message);
             }
+            ServerFrame::Disconnecting { reason, .. } => {
+                println!("server disconnecting reason={reason}");
+            }
         }
+        None
     }
 
     async fn resolve_ack(
@@ -405,29 +809,10 @@ impl RealtimeClient {
     async fn fail_pending_acks(pending_acks: &PendingAcks) {
         let mut pending = pending_acks.lock().await;
         for (_, tx) in pending.drain() {
-            let _ = tx.send(Err("websocket connection closed".to_string()));
+            let _ = tx.send(Err(CONNECTION_CLOSED.to_string()));
         }
     }
 
-    fn spawn_ping_task(outbound_tx: mpsc::Sender<ClientFrame>, ping_interval: Duration) {
-        tokio::spawn(async move {
-            let mut ticker = interval(ping_interval);
-            loop {
-                ticker.tick().await;
-                if outbound_tx
-                    .send(ClientFrame::Ping {
-                        id: Uuid::new_v4().to_string(),
-                        ts: None,
-                    })
-                    .await
-                    .is_err()
-                {
-                    break;
-                }
-            }
-        });
-    }
-
     async fn request_ack(&self, frame: ClientFrame, timeout_dur: Duration) -> ClientResult<()> {
         let req_id = frame_id(&frame).to_string();
         let (tx, rx) = oneshot::channel();
@@ -513,11 +898,39 @@ fn dispatch_global_event_handlers(
     }
 }
 
+fn dispatch_state_handlers(handlers: &StateHandlers, state: ConnectionState) {
+    let callbacks: Vec<StateHandler> = {
+        let guard = handlers.lock().expect("state handler mutex poisoned");
+        guard.values().cloned().collect()
+    };
+
+    for callback in callbacks {
+        callback(state);
+    }
+}
+
+fn dispatch_resubscribed_handlers(handlers: &ResubscribedHandlers, channels: Vec<String>) {
+    let callbacks: Vec<ResubscribedHandler> = {
+        let guard = handlers
+            .lock()
+            .expect("resubscribed handler mutex poisoned");
+        guard.values().cloned().collect()
+    };
+
+    for callback in callbacks {
+        callback(channels.clone());
+    }
+}
+
 fn frame_id(frame: &ClientFrame) -> &str {
     match frame {
         ClientFrame::ChannelJoin { id, .. } => id,
         ClientFrame::ChannelLeave { id, .. } => id,
         ClientFrame::ChannelEmit { id, .. } => id,
+        ClientFrame::ChannelEmitMulti { id, .. } => id,
+        ClientFrame::SetPresence { id, .. } => id,
+        ClientFrame::SetState { id, .. } => id,
+        ClientFrame::SetMeta { id, .. } => id,
         ClientFrame::Ping { id, .. } => id,
     }
 }
@@ -529,3 +942,160 @@ fn with_query_token(base_url: &str, token: &str) -> String {
         format!("{base_url}?token={token}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use futures_util::{sink, stream};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn missed_pongs_trigger_disconnect_and_reconnect() {
+        let connects = Arc::new(AtomicUsize::new(0));
+        let (sent_tx, mut sent_rx) = mpsc::unbounded_channel::<Message>();
+        let connect = {
+            let connects = Arc::clone(&connects);
+            move || {
+                connects.fetch_add(1, Ordering::SeqCst);
+                let sent_tx = sent_tx.clone();
+                async move {
+                    // The mock server accepts frames but never answers pings.
+                    let write = sink::unfold(sent_tx, |tx, msg: Message| async move {
+                        let _ = tx.send(msg);
+                        Ok::<_, WsError>(tx)
+                    });
+                    let read = stream::pending::<Result<Message, WsError>>();
+                    Ok((Box::pin(write), read))
+                }
+            }
+        };
+        let cfg = ClientConfig {
+            ping_interval: Duration::from_millis(10),
+            pong_timeout: Duration::from_millis(10),
+            max_missed_pongs: 2,
+            reconnect_delay: Duration::from_millis(10),
+            ..ClientConfig::default()
+        };
+
+        let client = RealtimeClient::connect_with_transport(connect, cfg)
+            .await
+            .expect("mock transport should connect");
+        let (state_tx, mut state_rx) = mpsc::unbounded_channel();
+        client.on_connection_state(move |state| {
+            let _ = state_tx.send(state);
+        });
+
+        let mut states = Vec::new();
+        while states.len() < 3 {
+            let state = timeout(Duration::from_secs(2), state_rx.recv())
+                .await
+                .expect("state change should arrive")
+                .expect("state channel open");
+            states.push(state);
+        }
+
+        assert_eq!(
+            states,
+            vec![
+                ConnectionState::Disconnected,
+                ConnectionState::Reconnecting,
+                ConnectionState::Connected,
+            ]
+        );
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+        let first = sent_rx.recv().await.expect("ping should be sent");
+        let Message::Text(text) = first else {
+            panic!("expected text frame, got {first:?}");
+        };
+        assert!(matches!(
+            serde_json::from_str::<ClientFrame>(&text),
+            Ok(ClientFrame::Ping { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn reconnect_rejoins_previously_joined_channels() {
+        type Inbound = mpsc::UnboundedSender<Message>;
+        // Inbound senders per connection, so the test can drop a socket.
+        let sockets: Arc<std::sync::Mutex<Vec<Inbound>>> = Arc::default();
+        let (sent_tx, mut sent_rx) = mpsc::unbounded_channel::<(usize, ClientFrame)>();
+        let connect = {
+            let sockets = Arc::clone(&sockets);
+            move || {
+                let (in_tx, in_rx) = mpsc::unbounded_channel::<Message>();
+                let conn = {
+                    let mut sockets = sockets.lock().expect("sockets mutex poisoned");
+                    sockets.push(in_tx.clone());
+                    sockets.len() - 1
+                };
+                let sent_tx = sent_tx.clone();
+                async move {
+                    // The mock server acks every request it receives.
+                    let write = sink::unfold(
+                        (in_tx, sent_tx),
+                        move |(in_tx, sent_tx), msg: Message| async move {
+                            if let Message::Text(text) = msg
+                                && let Ok(frame) = serde_json::from_str::<ClientFrame>(&text)
+                            {
+                                let ack = ServerFrame::ack_ok(frame_id(&frame));
+                                let ack = serde_json::to_string(&ack).expect("ack serializes");
+                                let _ = in_tx.send(Message::Text(ack.into()));
+                                let _ = sent_tx.send((conn, frame));
+                            }
+                            Ok::<_, WsError>((in_tx, sent_tx))
+                        },
+                    );
+                    let read = stream::unfold(in_rx, |mut rx| async move {
+                        rx.recv().await.map(|msg| (Ok(msg), rx))
+                    });
+                    Ok((Box::pin(write), Box::pin(read)))
+                }
+            }
+        };
+        let cfg = ClientConfig {
+            ping_interval: Duration::from_secs(60),
+            reconnect_delay: Duration::from_millis(10),
+            ..ClientConfig::default()
+        };
+
+        let client = RealtimeClient::connect_with_transport(connect, cfg)
+            .await
+            .expect("mock transport should connect");
+        for channel in ["alpha", "beta", "gamma"] {
+            client.join(channel).await.expect("join should be acked");
+        }
+        client.leave("gamma").await.expect("leave should be acked");
+        let (resub_tx, mut resub_rx) = mpsc::unbounded_channel();
+        client.on_resubscribed(move |channels| {
+            let _ = resub_tx.send(channels);
+        });
+
+        let first = sockets.lock().expect("sockets mutex poisoned")[0].clone();
+        first
+            .send(Message::Close(None))
+            .expect("first socket is open");
+
+        let rejoined = timeout(Duration::from_secs(2), resub_rx.recv())
+            .await
+            .expect("resubscribe should be reported")
+            .expect("resubscribe channel open");
+        assert_eq!(rejoined, vec!["alpha".to_string(), "beta".to_string()]);
+
+        let mut rejoin_frames = Vec::new();
+        while let Ok((conn, frame)) = sent_rx.try_recv() {
+            if conn == 1 {
+                rejoin_frames.push(frame);
+            }
+        }
+        let rejoined_channels: Vec<&str> = rejoin_frames
+            .iter()
+            .filter_map(|frame| match frame {
+                ClientFrame::ChannelJoin { channel, .. } => Some(channel.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rejoined_channels, vec!["alpha", "beta"]);
+    }
+}
@@ -3,7 +3,18 @@ use std::time::Duration;
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
     pub request_timeout: Duration,
+    /// How often the client sends a `ping` frame while connected.
     pub ping_interval: Duration,
+    /// How long to wait for the matching `pong` before counting a ping as missed.
+    pub pong_timeout: Duration,
+    /// Consecutive missed pongs that drop the connection. `0` disables the check.
+    pub max_missed_pongs: u32,
+    /// Reopen the socket after it drops instead of staying disconnected.
+    pub reconnect: bool,
+    /// Pause between reconnect attempts.
+    pub reconnect_delay: Duration,
+    /// Rejoin the channels joined before a reconnect once the new socket opens.
+    pub resubscribe: bool,
     pub outbound_buffer: usize,
 }
 
@@ -12,6 +23,11 @@ impl Default for ClientConfig {
         Self {
             request_timeout: Duration::from_secs(5),
             ping_interval: Duration::from_secs(20),
+            pong_timeout: Duration::from_secs(10),
+            max_missed_pongs: 2,
+            reconnect: true,
+            reconnect_delay: Duration::from_secs(1),
+            resubscribe: true,
             outbound_buffer: 256,
         }
     }
@@ -2,5 +2,5 @@ mod api;
 mod config;
 
 pub use api::RealtimeClient;
-pub use api::{ClientResult, SubscriptionId};
+pub use api::{ClientResult, ConnectionState, SubscriptionId};
 pub use config::ClientConfig;
@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, fmt};
+
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -5,21 +7,114 @@ use uuid::Uuid;
 
 pub const DEFAULT_EVENT: &str = "message";
 
+/// Stable error codes carried in `ErrorPayload::code`, sent as snake_case strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RealtimeErrorCode {
+    CapacityExceeded,
+    ChannelLimitExceeded,
+    ChannelNotJoined,
+    EmitFailed,
+    ForbiddenChannel,
+    InvalidChannel,
+    InvalidPayload,
+    MessageTooLarge,
+    PartialFailure,
+    RateLimited,
+    UnknownChannel,
+    /// Any code this build does not know, e.g. from a newer server.
+    #[serde(other)]
+    Unknown,
+}
+
+impl RealtimeErrorCode {
+    pub const ALL: [RealtimeErrorCode; 12] = [
+        RealtimeErrorCode::CapacityExceeded,
+        RealtimeErrorCode::ChannelLimitExceeded,
+        RealtimeErrorCode::ChannelNotJoined,
+        RealtimeErrorCode::EmitFailed,
+        RealtimeErrorCode::ForbiddenChannel,
+        RealtimeErrorCode::InvalidChannel,
+        RealtimeErrorCode::InvalidPayload,
+        RealtimeErrorCode::MessageTooLarge,
+        RealtimeErrorCode::PartialFailure,
+        RealtimeErrorCode::RateLimited,
+        RealtimeErrorCode::UnknownChannel,
+        RealtimeErrorCode::Unknown,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RealtimeErrorCode::CapacityExceeded => "capacity_exceeded",
+            RealtimeErrorCode::ChannelLimitExceeded => "channel_limit_exceeded",
+            RealtimeErrorCode::ChannelNotJoined => "channel_not_joined",
+            RealtimeErrorCode::EmitFailed => "emit_failed",
+            RealtimeErrorCode::ForbiddenChannel => "forbidden_channel",
+            RealtimeErrorCode::InvalidChannel => "invalid_channel",
+            RealtimeErrorCode::InvalidPayload => "invalid_payload",
+            RealtimeErrorCode::MessageTooLarge => "message_too_large",
+            RealtimeErrorCode::PartialFailure => "partial_failure",
+            RealtimeErrorCode::RateLimited => "rate_limited",
+            RealtimeErrorCode::UnknownChannel => "unknown_channel",
+            RealtimeErrorCode::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for RealtimeErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorPayload {
-    pub code: String,
+    pub code: RealtimeErrorCode,
     pub message: String,
 }
 
 impl ErrorPayload {
-    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn new(code: RealtimeErrorCode, message: impl Into<String>) -> Self {
         Self {
-            code: code.into(),
+            code,
             message: message.into(),
         }
     }
 }
 
+/// One connection on a channel, listed in `joined` when the policy allows it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelMember {
+    pub user_id: String,
+    pub conn_id: String,
+}
+
+/// Per-channel outcome reported in the ack for a `channel_emit_multi` frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelEmitResult {
+    pub channel: String,
+    pub ok: bool,
+    pub error: Option<ErrorPayload>,
+}
+
+impl ChannelEmitResult {
+    pub fn ok(channel: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            ok: true,
+            error: None,
+        }
+    }
+
+    pub fn err(channel: impl Into<String>, error: ErrorPayload) -> Self {
+        Self {
+            channel: channel.into(),
+            ok: false,
+            error: Some(error),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "op", rename_all = "snake_case")]
 pub enum ClientFrame {
@@ -41,6 +136,47 @@ pub enum ClientFrame {
         event: String,
         #[serde(default)]
         data: Value,
+        /// Milliseconds after which the event is no longer replayed to new
+        /// joiners. Live delivery is unaffected.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ttl_ms: Option<u64>,
+        #[serde(default)]
+        ts: Option<i64>,
+    },
+    ChannelEmitMulti {
+        id: String,
+        channels: Vec<String>,
+        event: String,
+        #[serde(default)]
+        data: Value,
+        #[serde(default)]
+        ts: Option<i64>,
+    },
+    /// Replaces this connection's presence state on a joined channel. `null`
+    /// clears it.
+    SetPresence {
+        id: String,
+        channel: String,
+        #[serde(default)]
+        state: Value,
+        #[serde(default)]
+        ts: Option<i64>,
+    },
+    /// Replaces the channel's shared state document for every member. `null`
+    /// clears it.
+    SetState {
+        id: String,
+        channel: String,
+        #[serde(default)]
+        state: Value,
+        #[serde(default)]
+        ts: Option<i64>,
+    },
+    /// Merges key/value pairs into this connection's metadata. An empty
+    /// value removes the key.
+    SetMeta {
+        id: String,
+        meta: BTreeMap<String, String>,
         #[serde(default)]
         ts: Option<i64>,
     },
@@ -60,14 +196,31 @@ pub enum ServerFrame {
         user_id: String,
         ts: i64,
     },
+    /// Acknowledges a join with a snapshot of the channel so the client needs
+    /// no follow-up query. `members` is only sent when the channel policy's
+    /// `can_list_members` allows it; `state` and `state_seq` are absent when
+    /// the channel has no shared state.
     Joined {
         id: String,
         channel: String,
+        /// Connections on the channel, the joiner included.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        member_count: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        members: Option<Vec<ChannelMember>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        state: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        state_seq: Option<u64>,
         ts: i64,
     },
     Left {
         id: String,
         channel: String,
+        /// Set when the server removed the connection (channel closed or user
+        /// kicked); absent for a client-requested leave.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
         ts: i64,
     },
     Event {
@@ -76,6 +229,10 @@ pub enum ServerFrame {
         event: String,
         data: Value,
         from_user: Option<String>,
+        /// Per-channel sequence number assigned by the hub, starting at 1.
+        /// A jump means events were missed; `0` means unsequenced.
+        #[serde(default)]
+        seq: u64,
         ts: i64,
     },
     Ack {
@@ -83,6 +240,26 @@ pub enum ServerFrame {
         for_id: String,
         ok: bool,
         error: Option<ErrorPayload>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        results: Option<Vec<ChannelEmitResult>>,
+        ts: i64,
+    },
+    /// Presence state of one connection on `channel`. `state` is `null` once
+    /// the connection clears it, leaves or disconnects.
+    PresenceUpdate {
+        channel: String,
+        user_id: String,
+        conn_id: String,
+        state: Value,
+        ts: i64,
+    },
+    /// Shared state document of `channel`, sent to members on every change
+    /// and to new joiners. `seq` grows by one per write on the channel, so a
+    /// client keeps the state with the highest `seq` it has seen.
+    State {
+        channel: String,
+        state: Value,
+        seq: u64,
         ts: i64,
     },
     Pong {
@@ -94,6 +271,13 @@ pub enum ServerFrame {
         error: ErrorPayload,
         ts: i64,
     },
+    /// The server is about to close the connection, e.g. `server_shutdown`.
+    /// Clients should reconnect later rather than straight away.
+    Disconnecting {
+        id: String,
+        reason: String,
+        ts: i64,
+    },
 }
 
 impl ServerFrame {
@@ -118,6 +302,56 @@ impl ServerFrame {
             event: event.into(),
             data,
             from_user,
+            seq: 0,
+            ts: now_unix_i64(),
+        }
+    }
+
+    /// Sets the sequence number on an `Event` frame; other frames are unchanged.
+    pub fn with_seq(mut self, value: u64) -> Self {
+        if let Self::Event { seq, .. } = &mut self {
+            *seq = value;
+        }
+        self
+    }
+
+    pub fn presence_update(
+        channel: impl Into<String>,
+        user_id: impl Into<String>,
+        conn_id: impl Into<String>,
+        state: Value,
+    ) -> Self {
+        Self::PresenceUpdate {
+            channel: channel.into(),
+            user_id: user_id.into(),
+            conn_id: conn_id.into(),
+            state,
+            ts: now_unix_i64(),
+        }
+    }
+
+    pub fn state(channel: impl Into<String>, state: Value, seq: u64) -> Self {
+        Self::State {
+            channel: channel.into(),
+            state,
+            seq,
+            ts: now_unix_i64(),
+        }
+    }
+
+    pub fn left(channel: impl Into<String>, reason: Option<String>) -> Self {
+        Self::Left {
+            id: random_id(),
+            channel: channel.into(),
+            reason,
+            ts: now_unix_i64(),
+        }
+    }
+
+    pub fn disconnecting(reason: impl Into<String>) -> Self {
+        Self::Disconnecting {
+            id: random_id(),
+            reason: reason.into(),
             ts: now_unix_i64(),
         }
     }
@@ -128,16 +362,45 @@ impl ServerFrame {
             for_id: for_id.into(),
             ok: true,
             error: None,
+            results: None,
             ts: now_unix_i64(),
         }
     }
 
-    pub fn ack_err(for_id: impl Into<String>, code: &str, message: &str) -> Self {
+    pub fn ack_err(for_id: impl Into<String>, code: RealtimeErrorCode, message: &str) -> Self {
         Self::Ack {
             id: random_id(),
             for_id: for_id.into(),
             ok: false,
             error: Some(ErrorPayload::new(code, message)),
+            results: None,
+            ts: now_unix_i64(),
+        }
+    }
+
+    /// Summarizes a multi-channel emit. `ok` is true only when every channel
+    /// succeeded; otherwise `error` tells partial from total failure.
+    pub fn ack_multi(for_id: impl Into<String>, results: Vec<ChannelEmitResult>) -> Self {
+        let failed = results.iter().filter(|result| !result.ok).count();
+        let error = if failed == 0 {
+            None
+        } else if failed < results.len() {
+            Some(ErrorPayload::new(
+                RealtimeErrorCode::PartialFailure,
+                format!("Emit failed for {failed} of {} channels", results.len()),
+            ))
+        } else {
+            Some(ErrorPayload::new(
+                RealtimeErrorCode::EmitFailed,
+                "Emit failed for all channels",
+            ))
+        };
+        Self::Ack {
+            id: random_id(),
+            for_id: for_id.into(),
+            ok: failed == 0,
+            error,
+            results: Some(results),
             ts: now_unix_i64(),
         }
     }
@@ -149,7 +412,7 @@ impl ServerFrame {
         }
     }
 
-    pub fn error(code: &str, message: &str) -> Self {
+    pub fn error(code: RealtimeErrorCode, message: &str) -> Self {
         Self::Error {
             id: random_id(),
             error: ErrorPayload::new(code, message),
@@ -165,3 +428,32 @@ fn random_id() -> String {
 fn now_unix_i64() -> i64 {
     Utc::now().timestamp()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorPayload, RealtimeErrorCode};
+
+    #[test]
+    fn error_codes_serialize_as_snake_case_strings_and_round_trip() {
+        for code in RealtimeErrorCode::ALL {
+            let json = serde_json::to_string(&code).expect("code should serialize");
+            assert_eq!(json, format!("\"{}\"", code.as_str()));
+            let parsed: RealtimeErrorCode =
+                serde_json::from_str(&json).expect("code should deserialize");
+            assert_eq!(parsed, code);
+        }
+        assert_eq!(
+            serde_json::to_string(&RealtimeErrorCode::ChannelLimitExceeded).unwrap(),
+            "\"channel_limit_exceeded\""
+        );
+
+        let payload: ErrorPayload =
+            serde_json::from_str(r#"{"code":"rate_limited","message":"slow down"}"#)
+                .expect("payload should deserialize");
+        assert_eq!(payload.code, RealtimeErrorCode::RateLimited);
+        let payload: ErrorPayload =
+            serde_json::from_str(r#"{"code":"brand_new_code","message":"?"}"#)
+                .expect("unknown codes should still deserialize");
+        assert_eq!(payload.code, RealtimeErrorCode::Unknown);
+    }
+}
@@ -19,6 +19,10 @@ pub struct RealtimeRouteOptions {
     pub path: &'static str,
     pub allow_query_token: bool,
     pub strict_header_precedence: bool,
+    /// Browser origins allowed to open a socket, e.g. `https://app.example.com`.
+    /// Empty allows every origin; requests without an `Origin` header (non-browser
+    /// clients) are always allowed.
+    pub allowed_origins: Vec<String>,
 }
 
 impl Default for RealtimeRouteOptions {
@@ -27,6 +31,7 @@ impl Default for RealtimeRouteOptions {
             path: "/realtime/socket",
             allow_query_token: true,
             strict_header_precedence: true,
+            allowed_origins: Vec::new(),
         }
     }
 }
@@ -56,6 +61,7 @@ enum RealtimeHttpError {
     InvalidToken,
     UpgradeRequired,
     RealtimeDisabled,
+    OriginNotAllowed,
     VerifyFailed(RealtimeError),
 }
 
@@ -65,6 +71,7 @@ impl RealtimeHttpError {
             Self::MissingToken | Self::InvalidToken => StatusCode::UNAUTHORIZED,
             Self::UpgradeRequired => StatusCode::BAD_REQUEST,
             Self::RealtimeDisabled => StatusCode::NOT_FOUND,
+            Self::OriginNotAllowed => StatusCode::FORBIDDEN,
             Self::VerifyFailed(err) => match err {
                 RealtimeError::BadRequest(_) => StatusCode::BAD_REQUEST,
                 RealtimeError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
@@ -83,6 +90,7 @@ impl RealtimeHttpError {
             Self::InvalidToken => "Missing/invalid Authorization header".to_string(),
             Self::UpgradeRequired => "WebSocket upgrade required".to_string(),
             Self::RealtimeDisabled => "Realtime is disabled".to_string(),
+            Self::OriginNotAllowed => "Origin not allowed".to_string(),
             Self::VerifyFailed(err) => err.message().to_string(),
         }
     }
@@ -123,6 +131,10 @@ async fn socket_handler(
         return RealtimeHttpError::RealtimeDisabled.into_response();
     }
 
+    if !origin_allowed(&headers, &handler_state.options) {
+        return RealtimeHttpError::OriginNotAllowed.into_response();
+    }
+
     let upgrade = match upgrade {
         Ok(upgrade) => upgrade,
         Err(_) => return RealtimeHttpError::UpgradeRequired.into_response(),
@@ -152,6 +164,25 @@ async fn socket_handler(
         .into_response()
 }
 
+fn origin_allowed(headers: &HeaderMap, options: &RealtimeRouteOptions) -> bool {
+    if options.allowed_origins.is_empty() {
+        return true;
+    }
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    let origin = origin.trim().trim_end_matches('/');
+    options.allowed_origins.iter().any(|allowed| {
+        allowed
+            .trim()
+            .trim_end_matches('/')
+            .eq_ignore_ascii_case(origin)
+    })
+}
+
 fn extract_access_token(
     headers: &HeaderMap,
     query: &SocketQuery,
@@ -192,8 +223,78 @@ fn extract_access_token(
 #[cfg(test)]
 mod tests {
     use axum::http::header;
+    use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest};
 
     use super::*;
+    use crate::server::{RealtimeConfig, RealtimeTokenVerifier, SessionAuth, SocketServerHandle};
+
+    struct AcceptAll;
+
+    #[async_trait::async_trait]
+    impl RealtimeTokenVerifier for AcceptAll {
+        async fn verify_token(&self, _token: &str) -> Result<SessionAuth, RealtimeError> {
+            Ok(SessionAuth {
+                user_id: "user-1".to_string(),
+                roles: Vec::new(),
+            })
+        }
+    }
+
+    async fn serve(allowed_origins: &[&str]) -> String {
+        let state = SocketAppState::new(
+            SocketServerHandle::spawn(RealtimeConfig::default()),
+            AcceptAll,
+        );
+        let options = RealtimeRouteOptions {
+            allowed_origins: allowed_origins.iter().map(|o| o.to_string()).collect(),
+            ..RealtimeRouteOptions::default()
+        };
+        let app = router_with_options(Arc::new(state), options);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener.local_addr().expect("listener has an address");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("server should run");
+        });
+        format!("ws://{addr}/realtime/socket?token=t")
+    }
+
+    async fn connect(url: &str, origin: &str) -> Result<StatusCode, tungstenite::Error> {
+        let mut request = url.into_client_request().expect("valid request");
+        request
+            .headers_mut()
+            .insert(header::ORIGIN, origin.parse().expect("valid origin"));
+        let (_socket, response) = tokio_tungstenite::connect_async(request).await?;
+        Ok(response.status())
+    }
+
+    #[tokio::test]
+    async fn socket_upgrade_accepts_allowed_origin_and_rejects_others() {
+        let url = serve(&["https://app.example.com"]).await;
+
+        let status = connect(&url, "https://app.example.com")
+            .await
+            .expect("allowed origin should upgrade");
+        assert_eq!(status, StatusCode::SWITCHING_PROTOCOLS);
+
+        match connect(&url, "https://evil.example.com").await {
+            Err(tungstenite::Error::Http(response)) => {
+                assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            }
+            other => panic!("expected 403, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn socket_upgrade_allows_any_origin_when_list_is_empty() {
+        let url = serve(&[]).await;
+
+        let status = connect(&url, "https://anywhere.example.com")
+            .await
+            .expect("any origin should upgrade");
+        assert_eq!(status, StatusCode::SWITCHING_PROTOCOLS);
+    }
 
     #[test]
     fn extract_access_token_prefers_authorization_header() {
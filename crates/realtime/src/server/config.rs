@@ -12,6 +12,16 @@ pub struct RealtimeConfig {
     pub outbound_queue_size: usize,
     pub emit_rate_per_sec: u32,
     pub join_rate_per_sec: u32,
+    /// Recent channel events kept and replayed to clients when they join.
+    /// `0` disables replay.
+    pub replay_buffer_size: usize,
+    /// Most metadata entries one connection may hold via `set_meta`.
+    pub max_meta_entries: usize,
+    /// Most bytes one metadata entry may use, key and value combined.
+    pub max_meta_entry_bytes: usize,
+    /// Seconds `SocketServerHandle::shutdown` waits for clients to disconnect
+    /// before closing the remaining sessions.
+    pub shutdown_grace_secs: u64,
 }
 
 impl Default for RealtimeConfig {
@@ -26,6 +36,10 @@ impl Default for RealtimeConfig {
             outbound_queue_size: 256,
             emit_rate_per_sec: 100,
             join_rate_per_sec: 50,
+            replay_buffer_size: 0,
+            max_meta_entries: 16,
+            max_meta_entry_bytes: 256,
+            shutdown_grace_secs: 5,
         }
     }
 }
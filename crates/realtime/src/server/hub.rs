@@ -1,26 +1,38 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     sync::{
         Arc,
         atomic::{AtomicU64, Ordering},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use chrono::Utc;
-use tokio::sync::mpsc;
+use futures_util::{Stream, stream};
+use tokio::sync::{broadcast, mpsc, oneshot};
 
-use crate::protocol::{DEFAULT_EVENT, ServerFrame};
+use crate::protocol::{
+    ChannelEmitResult, ChannelMember, DEFAULT_EVENT, ErrorPayload, RealtimeErrorCode, ServerFrame,
+};
 
 use super::{
-    Channel, ChannelName, ConnectionId, ConnectionMeta, DisconnectReason, Event, Payload,
-    RealtimeConfig, RealtimeError, SessionAuth, UserId,
+    Channel, ChannelEvent, ChannelName, ConnectionId, ConnectionMeta, DisconnectReason, Event,
+    Payload, PublishOutcome, RealtimeConfig, RealtimeError, RealtimeStats, SessionAuth, UserId,
     policy::{ChannelPolicy, DefaultChannelPolicy},
     session,
 };
 
 const HUB_QUEUE_SIZE: usize = 4096;
 const INBOUND_QUEUE_SIZE: usize = 4096;
+const SUBSCRIBER_QUEUE_SIZE: usize = 1024;
+// How often the hub drops memberless channels whose replay buffer expired.
+const IDLE_CHANNEL_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+/// Event name presence changes are checked against in `ChannelPolicy::can_publish`.
+pub const PRESENCE_EVENT: &str = "presence";
+/// Event name shared state writes are checked against in `ChannelPolicy::can_publish`.
+pub const STATE_EVENT: &str = "state";
+/// `reason` of the `disconnecting` frame sent by `SocketServerHandle::shutdown`.
+pub const SERVER_SHUTDOWN_REASON: &str = "server_shutdown";
 
 pub type SubscriptionId = u64;
 type ChannelHandler = Arc<dyn Fn(Payload) + Send + Sync>;
@@ -33,6 +45,7 @@ type GlobalHandlers = Arc<std::sync::Mutex<HashMap<SubscriptionId, GlobalHandler
 type ChannelEventHandlers =
     Arc<std::sync::Mutex<HashMap<Channel, HashMap<SubscriptionId, ChannelEventHandler>>>>;
 type GlobalEventHandlers = Arc<std::sync::Mutex<HashMap<SubscriptionId, GlobalEventHandler>>>;
+type ChannelSubscribers = Arc<std::sync::Mutex<HashMap<Channel, broadcast::Sender<ChannelEvent>>>>;
 
 #[derive(Clone)]
 pub(crate) struct InboundMessage {
@@ -49,6 +62,7 @@ pub struct SocketServerHandle {
     global_handlers: GlobalHandlers,
     channel_event_handlers: ChannelEventHandlers,
     global_event_handlers: GlobalEventHandlers,
+    subscribers: ChannelSubscribers,
     next_subscription_id: Arc<AtomicU64>,
 }
 
@@ -64,6 +78,7 @@ impl SocketServerHandle {
             Arc::new(std::sync::Mutex::new(HashMap::new()));
         let global_event_handlers: GlobalEventHandlers =
             Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let subscribers: ChannelSubscribers = Arc::new(std::sync::Mutex::new(HashMap::new()));
         let next_subscription_id = Arc::new(AtomicU64::new(1));
 
         if !config.enabled {
@@ -74,13 +89,20 @@ impl SocketServerHandle {
                 global_handlers,
                 channel_event_handlers,
                 global_event_handlers,
+                subscribers,
                 next_subscription_id,
             };
         }
 
         let (tx, rx) = mpsc::channel(HUB_QUEUE_SIZE);
         let (inbound_tx, inbound_rx) = mpsc::channel(INBOUND_QUEUE_SIZE);
-        let mut hub = SocketServer::new(config.clone(), rx, policy, Some(inbound_tx));
+        let mut hub = SocketServer::new(
+            config.clone(),
+            rx,
+            policy,
+            Some(inbound_tx),
+            Arc::clone(&subscribers),
+        );
         tokio::spawn(async move {
             hub.run().await;
         });
@@ -99,6 +121,7 @@ impl SocketServerHandle {
             global_handlers,
             channel_event_handlers,
             global_event_handlers,
+            subscribers,
             next_subscription_id,
         }
     }
@@ -111,6 +134,7 @@ impl SocketServerHandle {
             global_handlers: Arc::new(std::sync::Mutex::new(HashMap::new())),
             channel_event_handlers: Arc::new(std::sync::Mutex::new(HashMap::new())),
             global_event_handlers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            subscribers: Arc::new(std::sync::Mutex::new(HashMap::new())),
             next_subscription_id: Arc::new(AtomicU64::new(1)),
         }
     }
@@ -152,19 +176,95 @@ impl SocketServerHandle {
         channel_name: impl Into<Channel>,
         event: impl Into<Event>,
         payload: Payload,
+    ) -> Result<(), RealtimeError> {
+        self.send_to_channel(channel_name.into(), event.into(), payload, None)
+            .await
+    }
+
+    /// Broadcasts an app-originated event. Frames carry `from_user: null`, so
+    /// clients can tell it apart from emits by a connected user.
+    pub async fn emit_system(
+        &self,
+        channel_name: impl Into<Channel>,
+        event: impl Into<Event>,
+        payload: Payload,
+    ) -> Result<(), RealtimeError> {
+        self.send_to_channel(channel_name.into(), event.into(), payload, None)
+            .await
+    }
+
+    /// Like `send_event`, but the event is dropped from the replay buffer once
+    /// `ttl` has passed, so late joiners do not see it. Live delivery is the same.
+    pub async fn send_event_with_ttl(
+        &self,
+        channel_name: impl Into<Channel>,
+        event: impl Into<Event>,
+        payload: Payload,
+        ttl: Duration,
+    ) -> Result<(), RealtimeError> {
+        self.send_to_channel(channel_name.into(), event.into(), payload, Some(ttl))
+            .await
+    }
+
+    async fn send_to_channel(
+        &self,
+        channel_name: Channel,
+        event: Event,
+        payload: Payload,
+        ttl: Option<Duration>,
     ) -> Result<(), RealtimeError> {
         let Some(tx) = &self.tx else {
             return Ok(());
         };
+        let channel = ChannelName::parse(&channel_name)?;
+        tx.send(HubCommand::SendToChannel {
+            channel,
+            event,
+            payload,
+            ttl,
+            reply: None,
+        })
+        .await
+        .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))
+    }
+
+    /// Like `send`, but waits for the hub and reports how many subscribers got the message.
+    pub async fn publish(
+        &self,
+        channel_name: impl Into<Channel>,
+        message: Payload,
+    ) -> Result<PublishOutcome, RealtimeError> {
+        self.publish_event(channel_name, DEFAULT_EVENT, message)
+            .await
+    }
+
+    /// Like `send_event`, but waits for the hub and reports how many subscribers got the message.
+    ///
+    /// Returns a zero outcome when realtime is disabled.
+    pub async fn publish_event(
+        &self,
+        channel_name: impl Into<Channel>,
+        event: impl Into<Event>,
+        payload: Payload,
+    ) -> Result<PublishOutcome, RealtimeError> {
+        let Some(tx) = &self.tx else {
+            return Ok(PublishOutcome::default());
+        };
         let channel_name = channel_name.into();
         let channel = ChannelName::parse(&channel_name)?;
+        let (reply_tx, reply_rx) = oneshot::channel();
         tx.send(HubCommand::SendToChannel {
             channel,
             event: event.into(),
             payload,
+            ttl: None,
+            reply: Some(reply_tx),
         })
         .await
-        .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))
+        .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))?;
+        reply_rx
+            .await
+            .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))
     }
 
     pub async fn send_event_to_user(
@@ -194,6 +294,111 @@ impl SocketServerHandle {
         self.send_event_to_user(user_id, event, payload).await
     }
 
+    /// Removes every member from `channel`; each gets a `left` frame carrying
+    /// `reason`. Returns how many connections were removed, `0` when realtime
+    /// is disabled.
+    pub async fn close_channel(
+        &self,
+        channel_name: impl Into<Channel>,
+        reason: impl Into<String>,
+    ) -> Result<usize, RealtimeError> {
+        let Some(tx) = &self.tx else {
+            return Ok(0);
+        };
+        let channel = ChannelName::parse(&channel_name.into())?;
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(HubCommand::CloseChannel {
+            channel,
+            reason: reason.into(),
+            reply: reply_tx,
+        })
+        .await
+        .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))?;
+        reply_rx
+            .await
+            .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))
+    }
+
+    /// Removes every connection of `user_id` from `channel`; each gets a
+    /// `left` frame carrying `reason`. The user may join again unless the
+    /// `ChannelPolicy` stops them. Returns how many connections were removed.
+    pub async fn kick(
+        &self,
+        channel_name: impl Into<Channel>,
+        user_id: impl Into<UserId>,
+        reason: impl Into<String>,
+    ) -> Result<usize, RealtimeError> {
+        let Some(tx) = &self.tx else {
+            return Ok(0);
+        };
+        let channel = ChannelName::parse(&channel_name.into())?;
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(HubCommand::Kick {
+            channel,
+            user_id: user_id.into(),
+            reason: reason.into(),
+            reply: reply_tx,
+        })
+        .await
+        .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))?;
+        reply_rx
+            .await
+            .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))
+    }
+
+    /// Snapshot of connection and channel counts, with connections tallied
+    /// per metadata key and value. Empty when realtime is disabled.
+    pub async fn stats(&self) -> Result<RealtimeStats, RealtimeError> {
+        let Some(tx) = &self.tx else {
+            return Ok(RealtimeStats::default());
+        };
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(HubCommand::Stats { reply: reply_tx })
+            .await
+            .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))?;
+        reply_rx
+            .await
+            .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))
+    }
+
+    /// Drains the hub: sends every connection a `disconnecting` frame with
+    /// reason `server_shutdown`, refuses new connections, and waits up to
+    /// `shutdown_grace_secs` for clients to disconnect before closing the
+    /// remaining sessions. Resolves at once when realtime is disabled.
+    pub async fn shutdown(&self) -> Result<(), RealtimeError> {
+        self.shutdown_within(Duration::from_secs(self.config.shutdown_grace_secs))
+            .await
+    }
+
+    async fn shutdown_within(&self, grace: Duration) -> Result<(), RealtimeError> {
+        let Some(tx) = &self.tx else {
+            return Ok(());
+        };
+        let (drained_tx, drained_rx) = oneshot::channel();
+        tx.send(HubCommand::Shutdown {
+            reason: SERVER_SHUTDOWN_REASON.to_string(),
+            drained: drained_tx,
+        })
+        .await
+        .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))?;
+        if tokio::time::timeout(grace, drained_rx).await.is_ok() {
+            return Ok(());
+        }
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(HubCommand::Terminate { reply: reply_tx })
+            .await
+            .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))?;
+        let closed = reply_rx
+            .await
+            .map_err(|_| RealtimeError::internal("realtime hub is unavailable"))?;
+        tracing::info!(
+            connections = closed,
+            "realtime shutdown grace period elapsed; closed remaining sessions"
+        );
+        Ok(())
+    }
+
     pub fn on_message<F>(&self, channel: &str, handler: F) -> SubscriptionId
     where
         F: Fn(Payload) + Send + Sync + 'static,
@@ -250,6 +455,39 @@ impl SocketServerHandle {
         id
     }
 
+    /// Streams every event fanned out on `channel`, bypassing `ChannelPolicy`.
+    ///
+    /// Intended for trusted server-side consumers. A consumer that falls more
+    /// than the queue size behind skips the missed events. The stream ends
+    /// immediately when realtime is disabled.
+    pub fn subscribe(
+        &self,
+        channel_name: impl Into<Channel>,
+    ) -> Result<impl Stream<Item = ChannelEvent> + Send + 'static, RealtimeError> {
+        let channel = ChannelName::parse(&channel_name.into())?;
+        let receiver = self.is_enabled().then(|| {
+            self.subscribers
+                .lock()
+                .expect("channel subscriber mutex poisoned")
+                .entry(channel.to_string())
+                .or_insert_with(|| broadcast::channel(SUBSCRIBER_QUEUE_SIZE).0)
+                .subscribe()
+        });
+
+        Ok(stream::unfold(receiver, |receiver| async move {
+            let mut receiver = receiver?;
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, Some(receiver))),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "realtime subscriber lagged; skipping events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+
     pub fn off(&self, id: SubscriptionId) -> bool {
         let mut removed = false;
 
@@ -319,22 +557,72 @@ pub(crate) enum HubCommand {
         channel: ChannelName,
         event: Event,
         payload: Payload,
+        ttl: Option<Duration>,
+        req_id: String,
+    },
+    EmitMulti {
+        conn_id: ConnectionId,
+        channels: Vec<String>,
+        event: Event,
+        payload: Payload,
+        req_id: String,
+    },
+    SetPresence {
+        conn_id: ConnectionId,
+        channel: ChannelName,
+        state: Payload,
+        req_id: String,
+    },
+    SetState {
+        conn_id: ConnectionId,
+        channel: ChannelName,
+        state: Payload,
+        req_id: String,
+    },
+    SetMeta {
+        conn_id: ConnectionId,
+        meta: BTreeMap<String, String>,
         req_id: String,
     },
     Ping {
         conn_id: ConnectionId,
         req_id: String,
     },
+    Stats {
+        reply: oneshot::Sender<RealtimeStats>,
+    },
     SendToChannel {
         channel: ChannelName,
         event: Event,
         payload: Payload,
+        ttl: Option<Duration>,
+        reply: Option<oneshot::Sender<PublishOutcome>>,
     },
     SendToUser {
         user_id: UserId,
         event: Event,
         payload: Payload,
     },
+    CloseChannel {
+        channel: ChannelName,
+        reason: String,
+        reply: oneshot::Sender<usize>,
+    },
+    Kick {
+        channel: ChannelName,
+        user_id: UserId,
+        reason: String,
+        reply: oneshot::Sender<usize>,
+    },
+    /// Sends `disconnecting` to every connection and refuses new ones;
+    /// `drained` fires once no connections remain.
+    Shutdown {
+        reason: String,
+        drained: oneshot::Sender<()>,
+    },
+    /// Drops every remaining connection, ending its session; replies with
+    /// how many there were.
+    Terminate { reply: oneshot::Sender<usize> },
 }
 
 struct SocketServer {
@@ -342,10 +630,44 @@ struct SocketServer {
     rx: mpsc::Receiver<HubCommand>,
     policy: Arc<dyn ChannelPolicy>,
     inbound_tx: Option<mpsc::Sender<InboundMessage>>,
+    subscribers: ChannelSubscribers,
     connections: HashMap<ConnectionId, ConnectionState>,
     users: HashMap<UserId, HashSet<ConnectionId>>,
     channels: HashMap<ChannelName, HashSet<ConnectionId>>,
     connection_channels: HashMap<ConnectionId, HashSet<ChannelName>>,
+    // Last event sequence per channel. Kept while the channel has members or
+    // replayable events, so a reconnecting client never sees the numbering
+    // restart mid-stream.
+    channel_seqs: HashMap<String, u64>,
+    // Recent events per channel, replayed to new joiners when
+    // `replay_buffer_size` is non-zero.
+    replay: HashMap<String, VecDeque<ReplayEntry>>,
+    // Presence state per channel member; absent means none set.
+    presence: HashMap<ChannelName, HashMap<ConnectionId, Payload>>,
+    // Shared state document per channel with the seq of its last write. Kept
+    // after a clear so the numbering never restarts; dropped with the
+    // channel's seq once it is idle.
+    states: HashMap<ChannelName, ChannelState>,
+    // Set by `Shutdown`: the `disconnecting` reason sent to late registrations.
+    shutdown_reason: Option<String>,
+    // Fired once the last connection goes after `Shutdown`.
+    drained: Option<oneshot::Sender<()>>,
+}
+
+struct ChannelState {
+    seq: u64,
+    state: Payload,
+}
+
+struct ReplayEntry {
+    frame: ServerFrame,
+    expires_at: Option<Instant>,
+}
+
+impl ReplayEntry {
+    fn is_live(&self, now: Instant) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
 }
 
 struct ConnectionState {
@@ -367,22 +689,38 @@ impl SocketServer {
         rx: mpsc::Receiver<HubCommand>,
         policy: Arc<dyn ChannelPolicy>,
         inbound_tx: Option<mpsc::Sender<InboundMessage>>,
+        subscribers: ChannelSubscribers,
     ) -> Self {
         Self {
             config,
             rx,
             policy,
             inbound_tx,
+            subscribers,
             connections: HashMap::new(),
             users: HashMap::new(),
             channels: HashMap::new(),
             connection_channels: HashMap::new(),
+            channel_seqs: HashMap::new(),
+            replay: HashMap::new(),
+            presence: HashMap::new(),
+            states: HashMap::new(),
+            shutdown_reason: None,
+            drained: None,
         }
     }
 
     async fn run(&mut self) {
-        while let Some(command) = self.rx.recv().await {
-            self.handle_command(command);
+        let mut sweep = tokio::time::interval(IDLE_CHANNEL_SWEEP_INTERVAL);
+        sweep.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                command = self.rx.recv() => match command {
+                    Some(command) => self.handle_command(command),
+                    None => break,
+                },
+                _ = sweep.tick() => self.sweep_idle_channels(),
+            }
         }
     }
 
@@ -405,26 +743,84 @@ impl SocketServer {
                 channel,
                 event,
                 payload,
+                ttl,
+                req_id,
+            } => self.handle_emit(conn_id, channel, event, payload, ttl, req_id),
+            HubCommand::EmitMulti {
+                conn_id,
+                channels,
+                event,
+                payload,
+                req_id,
+            } => self.handle_emit_multi(conn_id, channels, event, payload, req_id),
+            HubCommand::SetPresence {
+                conn_id,
+                channel,
+                state,
+                req_id,
+            } => self.handle_set_presence(conn_id, channel, state, req_id),
+            HubCommand::SetState {
+                conn_id,
+                channel,
+                state,
+                req_id,
+            } => self.handle_set_state(conn_id, channel, state, req_id),
+            HubCommand::SetMeta {
+                conn_id,
+                meta,
                 req_id,
-            } => self.handle_emit(conn_id, channel, event, payload, req_id),
+            } => self.handle_set_meta(conn_id, meta, req_id),
             HubCommand::Ping { conn_id, req_id } => self.handle_ping(conn_id, req_id),
+            HubCommand::Stats { reply } => {
+                let _ = reply.send(self.stats());
+            }
             HubCommand::SendToChannel {
                 channel,
                 event,
                 payload,
-            } => self.handle_send_to_channel(channel, event, payload),
+                ttl,
+                reply,
+            } => {
+                let outcome = self.handle_send_to_channel(channel, event, payload, ttl);
+                if let Some(reply) = reply {
+                    let _ = reply.send(outcome);
+                }
+            }
             HubCommand::SendToUser {
                 user_id,
                 event,
                 payload,
             } => self.handle_send_to_user(user_id, event, payload),
+            HubCommand::CloseChannel {
+                channel,
+                reason,
+                reply,
+            } => {
+                let _ = reply.send(self.handle_close_channel(channel, reason));
+            }
+            HubCommand::Kick {
+                channel,
+                user_id,
+                reason,
+                reply,
+            } => {
+                let _ = reply.send(self.handle_kick(channel, user_id, reason));
+            }
+            HubCommand::Shutdown { reason, drained } => self.handle_shutdown(reason, drained),
+            HubCommand::Terminate { reply } => {
+                let _ = reply.send(self.handle_terminate());
+            }
         }
     }
 
     fn register(&mut self, meta: ConnectionMeta, outbound_tx: mpsc::Sender<ServerFrame>) {
+        if let Some(reason) = &self.shutdown_reason {
+            let _ = outbound_tx.try_send(ServerFrame::disconnecting(reason.clone()));
+            return;
+        }
         if self.connections.len() >= self.config.max_connections {
             let _ = outbound_tx.try_send(ServerFrame::error(
-                "capacity_exceeded",
+                RealtimeErrorCode::CapacityExceeded,
                 "Realtime server is at capacity",
             ));
             return;
@@ -460,14 +856,8 @@ impl SocketServer {
             self.unregister(conn_id, reason);
             return;
         }
-        self.send_frame(
-            conn_id,
-            ServerFrame::Joined {
-                id: uuid::Uuid::new_v4().to_string(),
-                channel: private_channel.to_string(),
-                ts: Utc::now().timestamp(),
-            },
-        );
+        let joined = self.joined_frame(conn_id, &private_channel);
+        self.send_frame(conn_id, joined);
     }
 
     fn unregister(&mut self, conn_id: ConnectionId, reason: DisconnectReason) {
@@ -497,11 +887,47 @@ impl SocketServer {
                         self.channels.remove(&channel);
                     }
                 }
+                self.clear_presence(conn_id, &existing.meta.user_id, &channel);
+                self.forget_if_idle(&channel);
             }
         }
+
+        if self.connections.is_empty()
+            && let Some(drained) = self.drained.take()
+        {
+            let _ = drained.send(());
+        }
+    }
+
+    fn handle_shutdown(&mut self, reason: String, drained: oneshot::Sender<()>) {
+        tracing::info!(
+            connections = self.connections.len(),
+            "realtime hub shutting down"
+        );
+        self.shutdown_reason = Some(reason.clone());
+        let conn_ids: Vec<ConnectionId> = self.connections.keys().copied().collect();
+        for conn_id in conn_ids {
+            self.send_frame(conn_id, ServerFrame::disconnecting(reason.clone()));
+        }
+        if self.connections.is_empty() {
+            let _ = drained.send(());
+        } else {
+            self.drained = Some(drained);
+        }
+    }
+
+    fn handle_terminate(&mut self) -> usize {
+        let conn_ids: Vec<ConnectionId> = self.connections.keys().copied().collect();
+        for conn_id in &conn_ids {
+            self.unregister(*conn_id, DisconnectReason::ServerShutdown);
+        }
+        conn_ids.len()
     }
 
     fn handle_join(&mut self, conn_id: ConnectionId, channel: ChannelName, req_id: String) {
+        let Some(channel) = self.normalize_or_ack(conn_id, &channel, &req_id) else {
+            return;
+        };
         tracing::debug!(
             conn_id = %conn_id,
             channel = %channel,
@@ -518,7 +944,11 @@ impl SocketServer {
             );
             self.send_frame(
                 conn_id,
-                ServerFrame::ack_err(req_id, "rate_limited", "Join rate limit exceeded"),
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::RateLimited,
+                    "Join rate limit exceeded",
+                ),
             );
             return;
         }
@@ -538,7 +968,25 @@ impl SocketServer {
             );
             self.send_frame(
                 conn_id,
-                ServerFrame::ack_err(req_id, "forbidden_channel", err.message()),
+                ServerFrame::ack_err(req_id, RealtimeErrorCode::ForbiddenChannel, err.message()),
+            );
+            return;
+        }
+
+        if !self.policy.channel_exists(&channel) {
+            tracing::debug!(
+                conn_id = %conn_id,
+                channel = %channel,
+                req_id = %req_id,
+                "realtime join denied: unknown channel"
+            );
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::UnknownChannel,
+                    &format!("Unknown channel: {channel}"),
+                ),
             );
             return;
         }
@@ -575,7 +1023,7 @@ impl SocketServer {
                 conn_id,
                 ServerFrame::ack_err(
                     req_id,
-                    "channel_limit_exceeded",
+                    RealtimeErrorCode::ChannelLimitExceeded,
                     "Maximum channels per connection reached",
                 ),
             );
@@ -602,17 +1050,55 @@ impl SocketServer {
         );
 
         self.send_frame(conn_id, ServerFrame::ack_ok(req_id));
-        self.send_frame(
-            conn_id,
-            ServerFrame::Joined {
-                id: uuid::Uuid::new_v4().to_string(),
-                channel: channel.to_string(),
-                ts: Utc::now().timestamp(),
-            },
-        );
+        let joined = self.joined_frame(conn_id, &channel);
+        self.send_frame(conn_id, joined);
+        self.replay_to(conn_id, channel.as_str());
+        self.presence_to(conn_id, &channel);
+        self.state_to(conn_id, &channel);
+    }
+
+    /// The `joined` acknowledgement for `conn_id`, carrying the member count,
+    /// the member list when the policy allows it, and the shared state.
+    fn joined_frame(&self, conn_id: ConnectionId, channel: &ChannelName) -> ServerFrame {
+        let member_ids = self.channels.get(channel);
+        let members = self
+            .connections
+            .get(&conn_id)
+            .filter(|conn| self.policy.can_list_members(&conn.meta, channel))
+            .map(|_| {
+                let mut members: Vec<ChannelMember> = member_ids
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|member_id| {
+                        let conn = self.connections.get(member_id)?;
+                        Some(ChannelMember {
+                            user_id: conn.meta.user_id.clone(),
+                            conn_id: member_id.to_string(),
+                        })
+                    })
+                    .collect();
+                members.sort_by(|a, b| (&a.user_id, &a.conn_id).cmp(&(&b.user_id, &b.conn_id)));
+                members
+            });
+        let state = self
+            .states
+            .get(channel)
+            .filter(|current| !current.state.is_null());
+        ServerFrame::Joined {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel: channel.to_string(),
+            member_count: Some(member_ids.map_or(0, HashSet::len)),
+            members,
+            state: state.map(|current| current.state.clone()),
+            state_seq: state.map(|current| current.seq),
+            ts: Utc::now().timestamp(),
+        }
     }
 
     fn handle_leave(&mut self, conn_id: ConnectionId, channel: ChannelName, req_id: String) {
+        let Some(channel) = self.normalize_or_ack(conn_id, &channel, &req_id) else {
+            return;
+        };
         tracing::debug!(
             conn_id = %conn_id,
             channel = %channel,
@@ -632,12 +1118,23 @@ impl SocketServer {
             );
             self.send_frame(
                 conn_id,
-                ServerFrame::ack_err(req_id, "channel_not_joined", "Not a member of channel"),
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::ChannelNotJoined,
+                    "Not a member of channel",
+                ),
             );
             return;
         }
 
         self.leave_internal(conn_id, &channel);
+        if let Some(user_id) = self
+            .connections
+            .get(&conn_id)
+            .map(|conn| conn.meta.user_id.clone())
+        {
+            self.clear_presence(conn_id, &user_id, &channel);
+        }
         tracing::debug!(
             conn_id = %conn_id,
             channel = %channel,
@@ -645,13 +1142,61 @@ impl SocketServer {
             "realtime leave succeeded"
         );
         self.send_frame(conn_id, ServerFrame::ack_ok(req_id));
+        self.send_frame(conn_id, ServerFrame::left(channel.to_string(), None));
+    }
+
+    /// Removes every member from `channel`, sending each a `left` frame with
+    /// `reason`. Returns how many connections were removed.
+    fn handle_close_channel(&mut self, channel: ChannelName, reason: String) -> usize {
+        let Ok(channel) = self.policy.normalize_channel(channel.as_str()) else {
+            return 0;
+        };
+        let members = self.channels.get(&channel).cloned().unwrap_or_default();
+        // Everyone is leaving, so there is nobody left to tell about presence.
+        self.presence.remove(&channel);
+        for conn_id in &members {
+            self.remove_member(*conn_id, &channel, &reason);
+        }
+        tracing::debug!(
+            channel = %channel,
+            removed = members.len(),
+            reason = %reason,
+            "realtime channel closed"
+        );
+        members.len()
+    }
+
+    /// Removes every connection of `user_id` from `channel`, sending each a
+    /// `left` frame with `reason`. Returns how many connections were removed.
+    fn handle_kick(&mut self, channel: ChannelName, user_id: UserId, reason: String) -> usize {
+        let Ok(channel) = self.policy.normalize_channel(channel.as_str()) else {
+            return 0;
+        };
+        let members = self.channels.get(&channel).cloned().unwrap_or_default();
+        let kicked: Vec<ConnectionId> = self
+            .users
+            .get(&user_id)
+            .map(|conns| conns.intersection(&members).copied().collect())
+            .unwrap_or_default();
+        for conn_id in &kicked {
+            self.remove_member(*conn_id, &channel, &reason);
+            self.clear_presence(*conn_id, &user_id, &channel);
+        }
+        tracing::debug!(
+            channel = %channel,
+            user_id = %user_id,
+            removed = kicked.len(),
+            reason = %reason,
+            "realtime user kicked from channel"
+        );
+        kicked.len()
+    }
+
+    fn remove_member(&mut self, conn_id: ConnectionId, channel: &ChannelName, reason: &str) {
+        self.leave_internal(conn_id, channel);
         self.send_frame(
             conn_id,
-            ServerFrame::Left {
-                id: uuid::Uuid::new_v4().to_string(),
-                channel: channel.to_string(),
-                ts: Utc::now().timestamp(),
-            },
+            ServerFrame::left(channel.to_string(), Some(reason.to_string())),
         );
     }
 
@@ -661,90 +1206,526 @@ impl SocketServer {
         channel: ChannelName,
         event: Event,
         payload: Payload,
+        ttl: Option<Duration>,
         req_id: String,
     ) {
-        if !self.check_emit_rate(conn_id) {
-            self.send_frame(
-                conn_id,
-                ServerFrame::ack_err(req_id, "rate_limited", "Emit rate limit exceeded"),
-            );
+        let Some(meta) = self.connections.get(&conn_id).map(|conn| conn.meta.clone()) else {
             return;
-        }
+        };
+        let Some(channel) = self.normalize_or_ack(conn_id, &channel, &req_id) else {
+            return;
+        };
+
+        let ack = match self.emit_to_channel(&meta, &channel, &event, payload, ttl) {
+            Ok(()) => ServerFrame::ack_ok(req_id),
+            Err(err) => ServerFrame::ack_err(req_id, err.code, &err.message),
+        };
+        self.send_frame(conn_id, ack);
+    }
 
+    fn handle_emit_multi(
+        &mut self,
+        conn_id: ConnectionId,
+        channels: Vec<String>,
+        event: Event,
+        payload: Payload,
+        req_id: String,
+    ) {
         let Some(meta) = self.connections.get(&conn_id).map(|conn| conn.meta.clone()) else {
             return;
         };
 
-        if let Err(err) = self.policy.can_publish(&meta, &channel, &event) {
+        if channels.is_empty() {
             self.send_frame(
                 conn_id,
-                ServerFrame::ack_err(req_id, "forbidden_channel", err.message()),
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::InvalidChannel,
+                    "At least one channel is required",
+                ),
             );
             return;
         }
-
-        let sender_is_member = self
-            .connection_channels
-            .get(&conn_id)
-            .is_some_and(|set| set.contains(&channel));
-        if !sender_is_member {
+        if channels.len() > self.config.max_channels_per_connection {
             self.send_frame(
                 conn_id,
-                ServerFrame::ack_err(req_id, "channel_not_joined", "Join channel before emitting"),
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::ChannelLimitExceeded,
+                    "Too many channels in one emit",
+                ),
             );
             return;
         }
 
-        let recipients = self.channels.get(&channel).cloned().unwrap_or_default();
-        let include_sender = should_echo_to_sender(&channel);
+        let mut seen = HashSet::new();
+        let mut results = Vec::with_capacity(channels.len());
+        for raw in channels {
+            let channel = match self.policy.normalize_channel(&raw) {
+                Ok(channel) => channel,
+                Err(err) => {
+                    results.push(ChannelEmitResult::err(
+                        raw,
+                        ErrorPayload::new(RealtimeErrorCode::InvalidChannel, err.message()),
+                    ));
+                    continue;
+                }
+            };
+            if !seen.insert(channel.clone()) {
+                continue;
+            }
+            let result = match self.emit_to_channel(&meta, &channel, &event, payload.clone(), None)
+            {
+                Ok(()) => ChannelEmitResult::ok(channel.to_string()),
+                Err(err) => ChannelEmitResult::err(channel.to_string(), err),
+            };
+            results.push(result);
+        }
+
+        self.send_frame(conn_id, ServerFrame::ack_multi(req_id, results));
+    }
+
+    // Shared by single and multi-channel emits: rate limit, policy and
+    // membership checks, then fan-out to channel members.
+    fn emit_to_channel(
+        &mut self,
+        meta: &ConnectionMeta,
+        channel: &ChannelName,
+        event: &str,
+        payload: Payload,
+        ttl: Option<Duration>,
+    ) -> Result<(), ErrorPayload> {
+        let conn_id = meta.id;
+        if !self.check_emit_rate(conn_id) {
+            return Err(ErrorPayload::new(
+                RealtimeErrorCode::RateLimited,
+                "Emit rate limit exceeded",
+            ));
+        }
+
+        if let Err(err) = self.policy.can_publish(meta, channel, event) {
+            return Err(ErrorPayload::new(
+                RealtimeErrorCode::ForbiddenChannel,
+                err.message(),
+            ));
+        }
+
+        let sender_is_member = self
+            .connection_channels
+            .get(&conn_id)
+            .is_some_and(|set| set.contains(channel));
+        if !sender_is_member {
+            return Err(ErrorPayload::new(
+                RealtimeErrorCode::ChannelNotJoined,
+                "Join channel before emitting",
+            ));
+        }
+
+        let recipients = self.channels.get(channel).cloned().unwrap_or_default();
+        let include_sender = should_echo_to_sender(channel);
         self.publish_inbound(InboundMessage {
             channel: channel.to_string(),
-            event: event.clone(),
+            event: event.to_string(),
             payload: payload.clone(),
         });
+        self.publish_subscribers(channel.as_str(), event, &payload, Some(&meta.user_id));
+        let seq = self.next_seq(channel.as_str());
         let event_frame = ServerFrame::event(
             channel.to_string(),
             event,
             payload,
             Some(meta.user_id.clone()),
-        );
+        )
+        .with_seq(seq);
+        self.buffer_for_replay(channel.as_str(), &event_frame, ttl);
         for recipient_id in recipients {
             if recipient_id == conn_id && !include_sender {
                 continue;
             }
             self.send_frame(recipient_id, event_frame.clone());
         }
+        Ok(())
+    }
+
+    // Applies `ChannelPolicy::normalize_channel`, acking `invalid_channel` on rejection.
+    fn normalize_or_ack(
+        &mut self,
+        conn_id: ConnectionId,
+        channel: &ChannelName,
+        req_id: &str,
+    ) -> Option<ChannelName> {
+        match self.policy.normalize_channel(channel.as_str()) {
+            Ok(channel) => Some(channel),
+            Err(err) => {
+                self.send_frame(
+                    conn_id,
+                    ServerFrame::ack_err(req_id, RealtimeErrorCode::InvalidChannel, err.message()),
+                );
+                None
+            }
+        }
+    }
+
+    fn handle_set_presence(
+        &mut self,
+        conn_id: ConnectionId,
+        channel: ChannelName,
+        state: Payload,
+        req_id: String,
+    ) {
+        let Some(meta) = self.connections.get(&conn_id).map(|conn| conn.meta.clone()) else {
+            return;
+        };
+        let Some(channel) = self.normalize_or_ack(conn_id, &channel, &req_id) else {
+            return;
+        };
+
+        if !self.check_emit_rate(conn_id) {
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::RateLimited,
+                    "Emit rate limit exceeded",
+                ),
+            );
+            return;
+        }
+        if let Err(err) = self.policy.can_publish(&meta, &channel, PRESENCE_EVENT) {
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(req_id, RealtimeErrorCode::ForbiddenChannel, err.message()),
+            );
+            return;
+        }
+        let is_member = self
+            .connection_channels
+            .get(&conn_id)
+            .is_some_and(|set| set.contains(&channel));
+        if !is_member {
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::ChannelNotJoined,
+                    "Join channel before setting presence",
+                ),
+            );
+            return;
+        }
+
+        let members = self.presence.entry(channel.clone()).or_default();
+        if state.is_null() {
+            members.remove(&conn_id);
+            if members.is_empty() {
+                self.presence.remove(&channel);
+            }
+        } else {
+            members.insert(conn_id, state.clone());
+        }
+        self.send_frame(conn_id, ServerFrame::ack_ok(req_id));
+        self.broadcast_presence(conn_id, &meta.user_id, &channel, state);
+    }
+
+    /// Drops the presence `conn_id` holds on `channel`, telling the remaining
+    /// members if there was any.
+    fn clear_presence(&mut self, conn_id: ConnectionId, user_id: &UserId, channel: &ChannelName) {
+        let Some(members) = self.presence.get_mut(channel) else {
+            return;
+        };
+        if members.remove(&conn_id).is_none() {
+            return;
+        }
+        if members.is_empty() {
+            self.presence.remove(channel);
+        }
+        self.broadcast_presence(conn_id, user_id, channel, Payload::Null);
+    }
+
+    fn broadcast_presence(
+        &mut self,
+        conn_id: ConnectionId,
+        user_id: &UserId,
+        channel: &ChannelName,
+        state: Payload,
+    ) {
+        let frame =
+            ServerFrame::presence_update(channel.to_string(), user_id, conn_id.to_string(), state);
+        let recipients = self.channels.get(channel).cloned().unwrap_or_default();
+        for recipient_id in recipients {
+            if recipient_id != conn_id {
+                self.send_frame(recipient_id, frame.clone());
+            }
+        }
+    }
+
+    /// Sends the current presence of every other member to a new joiner.
+    fn presence_to(&mut self, conn_id: ConnectionId, channel: &ChannelName) {
+        let Some(members) = self.presence.get(channel) else {
+            return;
+        };
+        let frames: Vec<ServerFrame> = members
+            .iter()
+            .filter(|(member_id, _)| **member_id != conn_id)
+            .filter_map(|(member_id, state)| {
+                let user_id = &self.connections.get(member_id)?.meta.user_id;
+                Some(ServerFrame::presence_update(
+                    channel.to_string(),
+                    user_id,
+                    member_id.to_string(),
+                    state.clone(),
+                ))
+            })
+            .collect();
+        for frame in frames {
+            self.send_frame(conn_id, frame);
+        }
+    }
+
+    fn handle_set_state(
+        &mut self,
+        conn_id: ConnectionId,
+        channel: ChannelName,
+        state: Payload,
+        req_id: String,
+    ) {
+        let Some(meta) = self.connections.get(&conn_id).map(|conn| conn.meta.clone()) else {
+            return;
+        };
+        let Some(channel) = self.normalize_or_ack(conn_id, &channel, &req_id) else {
+            return;
+        };
+
+        if !self.check_emit_rate(conn_id) {
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::RateLimited,
+                    "Emit rate limit exceeded",
+                ),
+            );
+            return;
+        }
+        if let Err(err) = self.policy.can_publish(&meta, &channel, STATE_EVENT) {
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(req_id, RealtimeErrorCode::ForbiddenChannel, err.message()),
+            );
+            return;
+        }
+        let is_member = self
+            .connection_channels
+            .get(&conn_id)
+            .is_some_and(|set| set.contains(&channel));
+        if !is_member {
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(
+                    req_id,
+                    RealtimeErrorCode::ChannelNotJoined,
+                    "Join channel before setting state",
+                ),
+            );
+            return;
+        }
+
+        // The hub applies writes one at a time, so the highest seq is the
+        // last write and wins.
+        let entry = self.states.entry(channel.clone()).or_insert(ChannelState {
+            seq: 0,
+            state: Payload::Null,
+        });
+        entry.seq += 1;
+        entry.state = state.clone();
+        let frame = ServerFrame::state(channel.to_string(), state, entry.seq);
+        self.send_frame(conn_id, ServerFrame::ack_ok(req_id));
+        let recipients = self.channels.get(&channel).cloned().unwrap_or_default();
+        for recipient_id in recipients {
+            self.send_frame(recipient_id, frame.clone());
+        }
+    }
+
+    /// Sends the channel's shared state, if any, to a new joiner.
+    fn state_to(&mut self, conn_id: ConnectionId, channel: &ChannelName) {
+        let Some(current) = self.states.get(channel) else {
+            return;
+        };
+        if current.state.is_null() {
+            return;
+        }
+        let frame = ServerFrame::state(channel.to_string(), current.state.clone(), current.seq);
+        self.send_frame(conn_id, frame);
+    }
+
+    /// Merges `meta` into the connection's metadata, rejecting the whole
+    /// frame if any entry or the resulting map exceeds the configured caps.
+    fn handle_set_meta(
+        &mut self,
+        conn_id: ConnectionId,
+        meta: BTreeMap<String, String>,
+        req_id: String,
+    ) {
+        let max_entries = self.config.max_meta_entries;
+        let max_entry_bytes = self.config.max_meta_entry_bytes;
+        let Some(conn) = self.connections.get_mut(&conn_id) else {
+            return;
+        };
+
+        let oversized = meta
+            .iter()
+            .find(|(key, value)| key.is_empty() || key.len() + value.len() > max_entry_bytes);
+        if let Some((key, _)) = oversized {
+            let message = if key.is_empty() {
+                "Metadata keys must not be empty".to_string()
+            } else {
+                format!("Metadata entry `{key}` exceeds {max_entry_bytes} bytes")
+            };
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(req_id, RealtimeErrorCode::InvalidPayload, &message),
+            );
+            return;
+        }
 
+        let mut merged = conn.meta.metadata.clone();
+        for (key, value) in meta {
+            if value.is_empty() {
+                merged.remove(&key);
+            } else {
+                merged.insert(key, value);
+            }
+        }
+        if merged.len() > max_entries {
+            let message = format!("Metadata is limited to {max_entries} entries");
+            self.send_frame(
+                conn_id,
+                ServerFrame::ack_err(req_id, RealtimeErrorCode::InvalidPayload, &message),
+            );
+            return;
+        }
+
+        conn.meta.metadata = merged;
         self.send_frame(conn_id, ServerFrame::ack_ok(req_id));
     }
 
+    fn stats(&self) -> RealtimeStats {
+        let mut metadata: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        for conn in self.connections.values() {
+            for (key, value) in &conn.meta.metadata {
+                *metadata
+                    .entry(key.clone())
+                    .or_default()
+                    .entry(value.clone())
+                    .or_default() += 1;
+            }
+        }
+        RealtimeStats {
+            connections: self.connections.len(),
+            channels: self.channels.len(),
+            metadata,
+        }
+    }
+
     fn handle_ping(&mut self, conn_id: ConnectionId, req_id: String) {
         self.send_frame(conn_id, ServerFrame::pong(req_id));
     }
 
-    fn handle_send_to_channel(&mut self, channel: ChannelName, event: Event, payload: Payload) {
-        let Some(conn_ids) = self.channels.get(&channel).cloned() else {
-            return;
+    fn handle_send_to_channel(
+        &mut self,
+        channel: ChannelName,
+        event: Event,
+        payload: Payload,
+        ttl: Option<Duration>,
+    ) -> PublishOutcome {
+        let channel = match self.policy.normalize_channel(channel.as_str()) {
+            Ok(channel) => channel,
+            Err(err) => {
+                tracing::debug!(
+                    channel = %channel,
+                    reason = %err,
+                    "realtime server send dropped: channel rejected by policy"
+                );
+                return PublishOutcome::default();
+            }
         };
+        self.publish_subscribers(channel.as_str(), &event, &payload, None);
+        let conn_ids = self.channels.get(&channel).cloned();
+        if conn_ids.is_none() && self.config.replay_buffer_size == 0 {
+            return PublishOutcome::default();
+        }
 
-        let frame = ServerFrame::event(channel.to_string(), event, payload, None);
-        for conn_id in conn_ids {
-            self.send_frame(conn_id, frame.clone());
+        let seq = self.next_seq(channel.as_str());
+        let frame = ServerFrame::event(channel.to_string(), event, payload, None).with_seq(seq);
+        self.buffer_for_replay(channel.as_str(), &frame, ttl);
+        let Some(conn_ids) = conn_ids else {
+            return PublishOutcome::default();
+        };
+        let delivered = conn_ids
+            .into_iter()
+            .filter(|conn_id| self.send_frame(*conn_id, frame.clone()))
+            .count();
+        PublishOutcome {
+            delivered,
+            channel_existed: true,
         }
     }
 
     fn handle_send_to_user(&mut self, user_id: UserId, event: Event, payload: Payload) {
+        let channel = format!("user:{user_id}");
+        self.publish_subscribers(&channel, &event, &payload, None);
         let Some(conn_ids) = self.users.get(&user_id).cloned() else {
             return;
         };
 
-        let channel = format!("user:{user_id}");
-        let frame = ServerFrame::event(channel, event, payload, None);
+        let seq = self.next_seq(&channel);
+        let frame = ServerFrame::event(channel, event, payload, None).with_seq(seq);
         for conn_id in conn_ids {
             self.send_frame(conn_id, frame.clone());
         }
     }
 
+    /// Hands out the next sequence number for `channel`. The hub processes
+    /// commands one at a time, so numbers are unique and gap-free per channel.
+    fn next_seq(&mut self, channel: &str) -> u64 {
+        let seq = self.channel_seqs.entry(channel.to_string()).or_default();
+        *seq += 1;
+        *seq
+    }
+
+    /// Keeps `frame` for later joiners of `channel`, evicting expired entries
+    /// and the oldest ones beyond `replay_buffer_size`.
+    fn buffer_for_replay(&mut self, channel: &str, frame: &ServerFrame, ttl: Option<Duration>) {
+        let capacity = self.config.replay_buffer_size;
+        if capacity == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let buffer = self.replay.entry(channel.to_string()).or_default();
+        buffer.retain(|entry| entry.is_live(now));
+        buffer.push_back(ReplayEntry {
+            frame: frame.clone(),
+            expires_at: ttl.map(|ttl| now + ttl),
+        });
+        while buffer.len() > capacity {
+            buffer.pop_front();
+        }
+    }
+
+    /// Sends the unexpired buffered events of `channel` to a connection that
+    /// just joined it, oldest first and with their original `seq`.
+    fn replay_to(&mut self, conn_id: ConnectionId, channel: &str) {
+        let now = Instant::now();
+        let Some(buffer) = self.replay.get_mut(channel) else {
+            return;
+        };
+        buffer.retain(|entry| entry.is_live(now));
+        let frames: Vec<ServerFrame> = buffer.iter().map(|entry| entry.frame.clone()).collect();
+        for frame in frames {
+            self.send_frame(conn_id, frame);
+        }
+    }
+
     fn publish_inbound(&mut self, message: InboundMessage) {
         let Some(tx) = &self.inbound_tx else {
             return;
@@ -761,6 +1742,32 @@ impl SocketServer {
         }
     }
 
+    fn publish_subscribers(
+        &self,
+        channel: &str,
+        event: &str,
+        payload: &Payload,
+        from: Option<&UserId>,
+    ) {
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .expect("channel subscriber mutex poisoned");
+        let Some(sender) = subscribers.get(channel) else {
+            return;
+        };
+        let event = ChannelEvent {
+            channel: channel.to_string(),
+            event: event.to_string(),
+            payload: payload.clone(),
+            from: from.cloned(),
+        };
+        // Sending only fails once every receiver is gone; drop the idle sender.
+        if sender.send(event).is_err() {
+            subscribers.remove(channel);
+        }
+    }
+
     fn check_join_rate(&mut self, conn_id: ConnectionId) -> bool {
         let Some(state) = self.connections.get_mut(&conn_id) else {
             return false;
@@ -810,24 +1817,67 @@ impl SocketServer {
                 self.channels.remove(channel);
             }
         }
+        self.forget_if_idle(channel);
     }
 
-    fn send_frame(&mut self, conn_id: ConnectionId, frame: ServerFrame) {
+    /// Drops the seq, replay buffer and shared state of `channel` once it has
+    /// no members and nothing left to replay. A later join starts it afresh.
+    fn forget_if_idle(&mut self, channel: &ChannelName) {
+        if self.channels.contains_key(channel) {
+            return;
+        }
+        let now = Instant::now();
+        if let Some(buffer) = self.replay.get_mut(channel.as_str()) {
+            buffer.retain(|entry| entry.is_live(now));
+            if !buffer.is_empty() {
+                return;
+            }
+        }
+        self.replay.remove(channel.as_str());
+        self.channel_seqs.remove(channel.as_str());
+        self.states.remove(channel);
+    }
+
+    /// Applies `forget_if_idle` to every tracked channel, reclaiming
+    /// memberless channels whose buffered events expired after the last
+    /// member left, or that only ever received server sends.
+    fn sweep_idle_channels(&mut self) {
+        let now = Instant::now();
+        self.replay.retain(|_, buffer| {
+            buffer.retain(|entry| entry.is_live(now));
+            !buffer.is_empty()
+        });
+        let active: HashSet<&str> = self
+            .channels
+            .keys()
+            .map(ChannelName::as_str)
+            .chain(self.replay.keys().map(String::as_str))
+            .collect();
+        self.channel_seqs
+            .retain(|channel, _| active.contains(channel.as_str()));
+        self.states
+            .retain(|channel, _| active.contains(channel.as_str()));
+    }
+
+    /// Queues `frame` for `conn_id`, returning whether the connection accepted it.
+    fn send_frame(&mut self, conn_id: ConnectionId, frame: ServerFrame) -> bool {
         let Some(outbound_tx) = self
             .connections
             .get(&conn_id)
             .map(|connection| connection.outbound_tx.clone())
         else {
-            return;
+            return false;
         };
 
         match outbound_tx.try_send(frame) {
-            Ok(_) => {}
+            Ok(_) => true,
             Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
                 self.unregister(conn_id, DisconnectReason::SlowConsumer);
+                false
             }
             Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
                 self.unregister(conn_id, DisconnectReason::SocketError);
+                false
             }
         }
     }
@@ -944,21 +1994,892 @@ fn dispatch_global_event_handlers(
 #[cfg(test)]
 mod tests {
     use std::{
-        collections::HashMap,
+        collections::{BTreeMap, HashMap},
         sync::{
             Arc,
             atomic::{AtomicUsize, Ordering},
         },
+        time::Duration,
     };
 
+    use futures_util::StreamExt;
     use serde_json::json;
+    use tokio::sync::mpsc;
+    use uuid::Uuid;
 
     use super::{
-        ChannelEventHandlers, ChannelHandlers, GlobalEventHandlers, GlobalHandlers,
-        dispatch_channel_event_handlers, dispatch_channel_handlers, dispatch_global_event_handlers,
-        dispatch_global_handlers, should_echo_to_sender,
+        ChannelEventHandlers, ChannelHandlers, GlobalEventHandlers, GlobalHandlers, HubCommand,
+        SocketServer, dispatch_channel_event_handlers, dispatch_channel_handlers,
+        dispatch_global_event_handlers, dispatch_global_handlers, should_echo_to_sender,
     };
-    use crate::server::ChannelName;
+    use crate::protocol::{RealtimeErrorCode, ServerFrame};
+    use crate::server::{
+        ChannelEvent, ChannelName, ChannelPolicy, ConnectionId, ConnectionMeta,
+        DefaultChannelPolicy, DisconnectReason, PublishOutcome, RealtimeConfig, RealtimeError,
+        RegisteredChannelPolicy, SocketServerHandle,
+    };
+
+    /// Lowercases names and only allows the `room:` namespace.
+    struct RoomOnlyPolicy;
+
+    impl ChannelPolicy for RoomOnlyPolicy {
+        fn normalize_channel(&self, raw: &str) -> Result<ChannelName, RealtimeError> {
+            let channel = ChannelName::parse(&raw.to_ascii_lowercase())?;
+            if !channel.as_str().starts_with("room:") {
+                return Err(RealtimeError::bad_request(
+                    "Channel must start with 'room:'",
+                ));
+            }
+            Ok(channel)
+        }
+
+        fn can_join(&self, _: &ConnectionMeta, _: &ChannelName) -> Result<(), RealtimeError> {
+            Ok(())
+        }
+
+        fn can_publish(
+            &self,
+            _: &ConnectionMeta,
+            _: &ChannelName,
+            _: &str,
+        ) -> Result<(), RealtimeError> {
+            Ok(())
+        }
+    }
+
+    fn test_server() -> SocketServer {
+        test_server_with_policy(Arc::new(DefaultChannelPolicy))
+    }
+
+    fn test_server_with_policy(policy: Arc<dyn ChannelPolicy>) -> SocketServer {
+        let (_tx, rx) = mpsc::channel(1);
+        SocketServer::new(
+            RealtimeConfig::default(),
+            rx,
+            policy,
+            None,
+            Arc::new(std::sync::Mutex::new(HashMap::new())),
+        )
+    }
+
+    fn register_user(
+        server: &mut SocketServer,
+        user_id: &str,
+    ) -> (ConnectionId, mpsc::Receiver<ServerFrame>) {
+        let conn_id = ConnectionId(Uuid::new_v4());
+        let (outbound_tx, outbound_rx) = mpsc::channel(64);
+        server.register(
+            ConnectionMeta {
+                id: conn_id,
+                user_id: user_id.to_string(),
+                roles: vec!["user".to_string()],
+                joined_at_unix: 0,
+                metadata: BTreeMap::new(),
+            },
+            outbound_tx,
+        );
+        (conn_id, outbound_rx)
+    }
+
+    fn drain(rx: &mut mpsc::Receiver<ServerFrame>) -> Vec<ServerFrame> {
+        let mut frames = Vec::new();
+        while let Ok(frame) = rx.try_recv() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn send_to_channel_reports_delivered_subscriber_count() {
+        let mut server = test_server();
+        let (first, mut first_rx) = register_user(&mut server, "u1");
+        let (second, mut second_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(first, lobby.clone(), "join-1".to_string());
+        server.handle_join(second, lobby.clone(), "join-2".to_string());
+        drain(&mut first_rx);
+        drain(&mut second_rx);
+
+        let outcome =
+            server.handle_send_to_channel(lobby, "status".to_string(), json!({"ok": true}), None);
+
+        assert_eq!(
+            outcome,
+            PublishOutcome {
+                delivered: 2,
+                channel_existed: true,
+            }
+        );
+        assert_eq!(drain(&mut first_rx).len(), 1);
+        assert_eq!(drain(&mut second_rx).len(), 1);
+    }
+
+    #[test]
+    fn policy_normalizes_channel_names_before_join_and_send() {
+        let mut server = test_server_with_policy(Arc::new(RoomOnlyPolicy));
+        let (first, mut first_rx) = register_user(&mut server, "u1");
+        let (second, mut second_rx) = register_user(&mut server, "u2");
+        let mixed = ChannelName::parse("Room:Lobby").expect("channel should parse");
+        let lower = ChannelName::parse("room:lobby").expect("channel should parse");
+        drain(&mut first_rx);
+        server.handle_join(first, mixed.clone(), "join-1".to_string());
+        server.handle_join(second, lower.clone(), "join-2".to_string());
+        assert!(matches!(
+            drain(&mut first_rx).as_slice(),
+            [ServerFrame::Ack { ok: true, .. }, ServerFrame::Joined { channel, .. }]
+                if channel == "room:lobby"
+        ));
+        drain(&mut second_rx);
+
+        let outcome = server.handle_send_to_channel(mixed, "status".to_string(), json!({}), None);
+
+        assert_eq!(outcome.delivered, 2);
+        assert_eq!(server.channels.get(&lower).map(|set| set.len()), Some(2));
+    }
+
+    #[test]
+    fn policy_rejects_invalid_channel_name_on_join() {
+        let mut server = test_server_with_policy(Arc::new(RoomOnlyPolicy));
+        let (conn, mut rx) = register_user(&mut server, "u1");
+        let other = ChannelName::parse("chat:general").expect("channel should parse");
+        drain(&mut rx);
+
+        server.handle_join(conn, other.clone(), "join-1".to_string());
+
+        let frames = drain(&mut rx);
+        let [ServerFrame::Ack { ok, error, .. }] = frames.as_slice() else {
+            panic!("join should receive exactly one ack, got {frames:?}");
+        };
+        assert!(!ok);
+        let error = error.as_ref().expect("ack should carry an error");
+        assert_eq!(error.code, RealtimeErrorCode::InvalidChannel);
+        assert_eq!(error.message, "Channel must start with 'room:'");
+        assert!(!server.channels.contains_key(&other));
+    }
+
+    #[test]
+    fn registered_policy_rejects_joins_to_unknown_channels() {
+        let policy = Arc::new(RegisteredChannelPolicy::new(DefaultChannelPolicy));
+        let lobby = policy
+            .register("room:lobby")
+            .expect("channel should register");
+        let mut server = test_server_with_policy(policy);
+        let (conn, mut rx) = register_user(&mut server, "u1");
+        drain(&mut rx);
+
+        server.handle_join(conn, lobby.clone(), "join-1".to_string());
+        let frames = drain(&mut rx);
+        assert!(matches!(
+            frames.as_slice(),
+            [
+                ServerFrame::Ack { ok: true, .. },
+                ServerFrame::Joined { .. }
+            ]
+        ));
+
+        let typo = ChannelName::parse("room:lobyy").expect("channel should parse");
+        server.handle_join(conn, typo.clone(), "join-2".to_string());
+        let frames = drain(&mut rx);
+        let [ServerFrame::Ack { ok, error, .. }] = frames.as_slice() else {
+            panic!("join should receive exactly one ack, got {frames:?}");
+        };
+        assert!(!ok);
+        let error = error.as_ref().expect("ack should carry an error");
+        assert_eq!(error.code, RealtimeErrorCode::UnknownChannel);
+        assert!(server.channels.contains_key(&lobby));
+        assert!(!server.channels.contains_key(&typo));
+    }
+
+    #[test]
+    fn sequential_emits_carry_increasing_channel_seq() {
+        let mut server = test_server();
+        let (sender, mut sender_rx) = register_user(&mut server, "u1");
+        let (listener, mut listener_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(sender, lobby.clone(), "join-1".to_string());
+        server.handle_join(listener, lobby.clone(), "join-2".to_string());
+        drain(&mut sender_rx);
+        drain(&mut listener_rx);
+
+        for req_id in ["emit-1", "emit-2"] {
+            server.handle_emit(
+                sender,
+                lobby.clone(),
+                "status".to_string(),
+                json!({}),
+                None,
+                req_id.to_string(),
+            );
+        }
+        server.handle_send_to_channel(lobby, "status".to_string(), json!({}), None);
+
+        let seqs: Vec<u64> = drain(&mut listener_rx)
+            .into_iter()
+            .filter_map(|frame| match frame {
+                ServerFrame::Event { seq, .. } => Some(seq),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(seqs, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn replay_skips_expired_events_for_new_joiners() {
+        let (_tx, rx) = mpsc::channel(1);
+        let mut server = SocketServer::new(
+            RealtimeConfig {
+                replay_buffer_size: 8,
+                ..RealtimeConfig::default()
+            },
+            rx,
+            Arc::new(DefaultChannelPolicy),
+            None,
+            Arc::new(std::sync::Mutex::new(HashMap::new())),
+        );
+        let (sender, _sender_rx) = register_user(&mut server, "u1");
+        let (listener, mut listener_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(sender, lobby.clone(), "join-1".to_string());
+        server.handle_join(listener, lobby.clone(), "join-2".to_string());
+
+        server.handle_emit(
+            sender,
+            lobby.clone(),
+            "typing".to_string(),
+            json!({}),
+            Some(Duration::ZERO),
+            "emit-1".to_string(),
+        );
+        server.handle_emit(
+            sender,
+            lobby.clone(),
+            "chat.message".to_string(),
+            json!({"text": "hi"}),
+            Some(Duration::from_secs(60)),
+            "emit-2".to_string(),
+        );
+        let live: Vec<String> = drain(&mut listener_rx)
+            .into_iter()
+            .filter_map(|frame| match frame {
+                ServerFrame::Event { event, .. } => Some(event),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(live, vec!["typing", "chat.message"]);
+
+        let (late, mut late_rx) = register_user(&mut server, "u3");
+        drain(&mut late_rx);
+        server.handle_join(late, lobby, "join-3".to_string());
+
+        let replayed: Vec<(String, u64)> = drain(&mut late_rx)
+            .into_iter()
+            .filter_map(|frame| match frame {
+                ServerFrame::Event { event, seq, .. } => Some((event, seq)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(replayed, vec![("chat.message".to_string(), 2)]);
+    }
+
+    fn presence_updates(frames: Vec<ServerFrame>) -> Vec<(String, serde_json::Value)> {
+        frames
+            .into_iter()
+            .filter_map(|frame| match frame {
+                ServerFrame::PresenceUpdate { user_id, state, .. } => Some((user_id, state)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn presence_is_broadcast_to_members_and_cleared_on_disconnect() {
+        let mut server = test_server();
+        let (setter, mut setter_rx) = register_user(&mut server, "u1");
+        let (listener, mut listener_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(setter, lobby.clone(), "join-1".to_string());
+        server.handle_join(listener, lobby.clone(), "join-2".to_string());
+        drain(&mut setter_rx);
+        drain(&mut listener_rx);
+
+        server.handle_set_presence(
+            setter,
+            lobby.clone(),
+            json!({ "status": "away" }),
+            "presence-1".to_string(),
+        );
+
+        assert!(matches!(
+            drain(&mut setter_rx).as_slice(),
+            [ServerFrame::Ack { ok: true, .. }]
+        ));
+        assert_eq!(
+            presence_updates(drain(&mut listener_rx)),
+            vec![("u1".to_string(), json!({ "status": "away" }))]
+        );
+
+        let (late, mut late_rx) = register_user(&mut server, "u3");
+        server.handle_join(late, lobby.clone(), "join-3".to_string());
+        assert_eq!(
+            presence_updates(drain(&mut late_rx)),
+            vec![("u1".to_string(), json!({ "status": "away" }))]
+        );
+
+        server.unregister(setter, DisconnectReason::ClientClosed);
+
+        assert_eq!(
+            presence_updates(drain(&mut listener_rx)),
+            vec![("u1".to_string(), serde_json::Value::Null)]
+        );
+        assert!(!server.presence.contains_key(&lobby));
+    }
+
+    #[test]
+    fn presence_requires_channel_membership() {
+        let mut server = test_server();
+        let (conn, mut rx) = register_user(&mut server, "u1");
+        drain(&mut rx);
+
+        server.handle_set_presence(
+            conn,
+            ChannelName::parse("room:lobby").expect("channel should parse"),
+            json!({ "status": "away" }),
+            "presence-1".to_string(),
+        );
+
+        match drain(&mut rx).as_slice() {
+            [ServerFrame::Ack { ok, error, .. }] => {
+                assert!(!ok);
+                assert_eq!(
+                    error.as_ref().map(|error| error.code.as_str()),
+                    Some("channel_not_joined")
+                );
+            }
+            frames => panic!("unexpected frames: {frames:?}"),
+        }
+    }
+
+    fn states(frames: Vec<ServerFrame>) -> Vec<(serde_json::Value, u64)> {
+        frames
+            .into_iter()
+            .filter_map(|frame| match frame {
+                ServerFrame::State { state, seq, .. } => Some((state, seq)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn joiners_get_latest_shared_state_and_newer_writes_supersede_it() {
+        let mut server = test_server();
+        let (writer, mut writer_rx) = register_user(&mut server, "u1");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(writer, lobby.clone(), "join-1".to_string());
+        drain(&mut writer_rx);
+
+        server.handle_set_state(
+            writer,
+            lobby.clone(),
+            json!({ "slide": 1 }),
+            "state-1".to_string(),
+        );
+        server.handle_set_state(
+            writer,
+            lobby.clone(),
+            json!({ "slide": 2 }),
+            "state-2".to_string(),
+        );
+        assert_eq!(
+            states(drain(&mut writer_rx)),
+            vec![(json!({ "slide": 1 }), 1), (json!({ "slide": 2 }), 2)]
+        );
+
+        let (late, mut late_rx) = register_user(&mut server, "u2");
+        server.handle_join(late, lobby.clone(), "join-2".to_string());
+        assert_eq!(
+            states(drain(&mut late_rx)),
+            vec![(json!({ "slide": 2 }), 2)]
+        );
+
+        server.handle_set_state(
+            late,
+            lobby.clone(),
+            json!({ "slide": 3 }),
+            "state-3".to_string(),
+        );
+        assert_eq!(
+            states(drain(&mut writer_rx)),
+            vec![(json!({ "slide": 3 }), 3)]
+        );
+        assert_eq!(
+            states(drain(&mut late_rx)),
+            vec![(json!({ "slide": 3 }), 3)]
+        );
+    }
+
+    #[test]
+    fn channels_without_members_or_replay_drop_their_seq_and_state() {
+        let mut server = test_server();
+        let (writer, mut writer_rx) = register_user(&mut server, "u1");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(writer, lobby.clone(), "join-1".to_string());
+        server.handle_set_state(
+            writer,
+            lobby.clone(),
+            json!({ "slide": 1 }),
+            "state-1".to_string(),
+        );
+        server.handle_send_to_channel(lobby.clone(), "status".to_string(), json!({}), None);
+        drain(&mut writer_rx);
+
+        server.handle_leave(writer, lobby.clone(), "leave-1".to_string());
+
+        assert!(!server.channel_seqs.contains_key(lobby.as_str()));
+        assert!(!server.states.contains_key(&lobby));
+
+        server.unregister(writer, DisconnectReason::ClientClosed);
+        assert!(server.channel_seqs.is_empty());
+        assert!(server.states.is_empty());
+    }
+
+    #[test]
+    fn idle_channels_keep_their_seq_until_the_replay_buffer_expires() {
+        let (_tx, rx) = mpsc::channel(1);
+        let mut server = SocketServer::new(
+            RealtimeConfig {
+                replay_buffer_size: 8,
+                ..RealtimeConfig::default()
+            },
+            rx,
+            Arc::new(DefaultChannelPolicy),
+            None,
+            Arc::new(std::sync::Mutex::new(HashMap::new())),
+        );
+        let kept = ChannelName::parse("room:kept").expect("channel should parse");
+        let stale = ChannelName::parse("room:stale").expect("channel should parse");
+        server.handle_send_to_channel(kept.clone(), "status".to_string(), json!({}), None);
+        server.handle_send_to_channel(
+            stale.clone(),
+            "typing".to_string(),
+            json!({}),
+            Some(Duration::ZERO),
+        );
+
+        server.sweep_idle_channels();
+
+        assert_eq!(server.channel_seqs.get(kept.as_str()), Some(&1));
+        assert!(server.replay.contains_key(kept.as_str()));
+        assert!(!server.channel_seqs.contains_key(stale.as_str()));
+        assert!(!server.replay.contains_key(stale.as_str()));
+    }
+
+    struct ListMembersPolicy;
+
+    impl ChannelPolicy for ListMembersPolicy {
+        fn can_join(
+            &self,
+            meta: &ConnectionMeta,
+            channel: &ChannelName,
+        ) -> Result<(), RealtimeError> {
+            DefaultChannelPolicy.can_join(meta, channel)
+        }
+
+        fn can_list_members(&self, _: &ConnectionMeta, channel: &ChannelName) -> bool {
+            channel.as_str().starts_with("room:")
+        }
+
+        fn can_publish(
+            &self,
+            meta: &ConnectionMeta,
+            channel: &ChannelName,
+            event: &str,
+        ) -> Result<(), RealtimeError> {
+            DefaultChannelPolicy.can_publish(meta, channel, event)
+        }
+    }
+
+    fn joined(frames: Vec<ServerFrame>) -> ServerFrame {
+        frames
+            .into_iter()
+            .find(|frame| matches!(frame, ServerFrame::Joined { .. }))
+            .expect("a joined frame should be sent")
+    }
+
+    #[test]
+    fn join_to_populated_channel_acks_with_member_count_and_state() {
+        let mut server = test_server_with_policy(Arc::new(ListMembersPolicy));
+        let (first, mut first_rx) = register_user(&mut server, "u1");
+        let (second, _second_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(first, lobby.clone(), "join-1".to_string());
+        server.handle_join(second, lobby.clone(), "join-2".to_string());
+        server.handle_set_state(
+            first,
+            lobby.clone(),
+            json!({ "slide": 4 }),
+            "state-1".to_string(),
+        );
+        drain(&mut first_rx);
+
+        let (late, mut late_rx) = register_user(&mut server, "u3");
+        let ServerFrame::Joined {
+            member_count,
+            members,
+            ..
+        } = joined(drain(&mut late_rx))
+        else {
+            unreachable!();
+        };
+        assert_eq!(
+            member_count,
+            Some(1),
+            "private channel holds only the joiner"
+        );
+        assert_eq!(members, None, "user channels are not listed by this policy");
+
+        server.handle_join(late, lobby.clone(), "join-3".to_string());
+        let ServerFrame::Joined {
+            channel,
+            member_count,
+            members,
+            state,
+            state_seq,
+            ..
+        } = joined(drain(&mut late_rx))
+        else {
+            unreachable!();
+        };
+        assert_eq!(channel, "room:lobby");
+        assert_eq!(member_count, Some(3));
+        assert_eq!(state, Some(json!({ "slide": 4 })));
+        assert_eq!(state_seq, Some(1));
+        let members = members.expect("policy allows the member list");
+        let user_ids: Vec<_> = members
+            .iter()
+            .map(|member| member.user_id.as_str())
+            .collect();
+        assert_eq!(user_ids, ["u1", "u2", "u3"]);
+        assert!(
+            members
+                .iter()
+                .any(|member| member.conn_id == late.to_string())
+        );
+    }
+
+    #[test]
+    fn join_ack_omits_members_by_default_and_state_when_unset() {
+        let mut server = test_server();
+        let (first, _first_rx) = register_user(&mut server, "u1");
+        let (second, mut second_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(first, lobby.clone(), "join-1".to_string());
+        drain(&mut second_rx);
+
+        server.handle_join(second, lobby, "join-2".to_string());
+
+        let frame = joined(drain(&mut second_rx));
+        let wire = serde_json::to_value(&frame).expect("frame should serialize");
+        assert_eq!(wire["member_count"], 2);
+        assert!(wire.get("members").is_none());
+        assert!(wire.get("state").is_none());
+        assert!(wire.get("state_seq").is_none());
+    }
+
+    fn left_reasons(frames: Vec<ServerFrame>) -> Vec<Option<String>> {
+        frames
+            .into_iter()
+            .filter_map(|frame| match frame {
+                ServerFrame::Left { reason, .. } => Some(reason),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn close_channel_unsubscribes_every_member() {
+        let mut server = test_server();
+        let (first, mut first_rx) = register_user(&mut server, "u1");
+        let (second, mut second_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(first, lobby.clone(), "join-1".to_string());
+        server.handle_join(second, lobby.clone(), "join-2".to_string());
+        drain(&mut first_rx);
+        drain(&mut second_rx);
+
+        let removed = server.handle_close_channel(lobby.clone(), "room closed".to_string());
+
+        assert_eq!(removed, 2);
+        assert!(!server.channels.contains_key(&lobby));
+        for rx in [&mut first_rx, &mut second_rx] {
+            assert_eq!(
+                left_reasons(drain(rx)),
+                vec![Some("room closed".to_string())]
+            );
+        }
+        let outcome = server.handle_send_to_channel(lobby, "status".to_string(), json!({}), None);
+        assert_eq!(outcome.delivered, 0);
+    }
+
+    #[test]
+    fn kick_removes_only_the_targeted_user() {
+        let mut server = test_server();
+        let (target, mut target_rx) = register_user(&mut server, "u1");
+        let (other, mut other_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(target, lobby.clone(), "join-1".to_string());
+        server.handle_join(other, lobby.clone(), "join-2".to_string());
+        drain(&mut target_rx);
+        drain(&mut other_rx);
+
+        let removed = server.handle_kick(lobby.clone(), "u1".to_string(), "spam".to_string());
+
+        assert_eq!(removed, 1);
+        assert_eq!(
+            left_reasons(drain(&mut target_rx)),
+            vec![Some("spam".to_string())]
+        );
+        assert!(drain(&mut other_rx).is_empty());
+        let members = server
+            .channels
+            .get(&lobby)
+            .expect("channel still has members");
+        assert!(members.contains(&other) && !members.contains(&target));
+        assert!(
+            server
+                .connection_channels
+                .get(&target)
+                .is_some_and(|channels| !channels.contains(&lobby))
+        );
+    }
+
+    #[test]
+    fn system_emits_have_null_sender_and_user_emits_carry_user_id() {
+        let mut server = test_server();
+        let (sender, mut sender_rx) = register_user(&mut server, "u1");
+        let (listener, mut listener_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(sender, lobby.clone(), "join-1".to_string());
+        server.handle_join(listener, lobby.clone(), "join-2".to_string());
+        drain(&mut sender_rx);
+        drain(&mut listener_rx);
+
+        server.handle_send_to_channel(lobby.clone(), "announce".to_string(), json!({}), None);
+        server.handle_emit(
+            sender,
+            lobby,
+            "chat.message".to_string(),
+            json!({}),
+            None,
+            "emit-1".to_string(),
+        );
+
+        let senders: Vec<(String, serde_json::Value)> = drain(&mut listener_rx)
+            .into_iter()
+            .map(|frame| serde_json::to_value(frame).expect("frame should serialize"))
+            .filter(|frame| frame["op"] == "event")
+            .map(|frame| {
+                (
+                    frame["event"].as_str().unwrap_or_default().to_string(),
+                    frame["from_user"].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            senders,
+            vec![
+                ("announce".to_string(), serde_json::Value::Null),
+                ("chat.message".to_string(), json!("u1")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn emit_system_reaches_subscribers_without_sender() {
+        let handle = SocketServerHandle::spawn(RealtimeConfig::default());
+        let events = handle
+            .subscribe("ops:alerts")
+            .expect("channel should parse");
+        tokio::pin!(events);
+
+        handle
+            .emit_system("ops:alerts", "maintenance", json!({"in": 5}))
+            .await
+            .expect("emit should succeed");
+        let event = tokio::time::timeout(std::time::Duration::from_secs(1), events.next())
+            .await
+            .expect("event should arrive")
+            .expect("stream should stay open");
+
+        assert_eq!(event.event, "maintenance");
+        assert_eq!(event.from, None);
+    }
+
+    #[test]
+    fn send_to_channel_without_subscribers_reports_nothing_delivered() {
+        let mut server = test_server();
+        let empty = ChannelName::parse("room:empty").expect("channel should parse");
+
+        let outcome = server.handle_send_to_channel(empty, "status".to_string(), json!({}), None);
+
+        assert_eq!(outcome.delivered, 0);
+        assert!(!outcome.channel_existed);
+    }
+
+    #[tokio::test]
+    async fn subscribe_observes_server_published_events() {
+        let handle = SocketServerHandle::spawn(RealtimeConfig::default());
+        let events = handle
+            .subscribe("metrics:orders")
+            .expect("channel should parse");
+        tokio::pin!(events);
+
+        handle
+            .publish_event("metrics:orders", "order.created", json!({"id": 7}))
+            .await
+            .expect("publish should succeed");
+        let event = tokio::time::timeout(std::time::Duration::from_secs(1), events.next())
+            .await
+            .expect("event should arrive")
+            .expect("stream should stay open");
+
+        assert_eq!(
+            event,
+            ChannelEvent {
+                channel: "metrics:orders".to_string(),
+                event: "order.created".to_string(),
+                payload: json!({"id": 7}),
+                from: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn subscribe_on_disabled_handle_ends_immediately() {
+        let handle = SocketServerHandle::disabled(RealtimeConfig {
+            enabled: false,
+            ..RealtimeConfig::default()
+        });
+
+        let mut events = Box::pin(
+            handle
+                .subscribe("metrics:orders")
+                .expect("channel should parse"),
+        );
+
+        assert!(events.next().await.is_none());
+    }
+
+    #[test]
+    fn client_emits_reach_server_subscribers_with_sender() {
+        let mut server = test_server();
+        let (sender, mut receiver) = tokio::sync::broadcast::channel(8);
+        server
+            .subscribers
+            .lock()
+            .expect("subscribers lock")
+            .insert("room:lobby".to_string(), sender);
+        let (emitter, mut emitter_rx) = register_user(&mut server, "u1");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(emitter, lobby.clone(), "join-1".to_string());
+        drain(&mut emitter_rx);
+
+        server.handle_emit(
+            emitter,
+            lobby,
+            "chat.message".to_string(),
+            json!({"text": "hi"}),
+            None,
+            "emit-1".to_string(),
+        );
+
+        let event = receiver.try_recv().expect("subscriber should see the emit");
+        assert_eq!(event.event, "chat.message");
+        assert_eq!(event.from.as_deref(), Some("u1"));
+    }
+
+    #[test]
+    fn emit_multi_acks_partial_success_when_one_channel_is_forbidden() {
+        let mut server = test_server();
+        let (sender, mut sender_rx) = register_user(&mut server, "u1");
+        let (listener, mut listener_rx) = register_user(&mut server, "u2");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(sender, lobby.clone(), "join-1".to_string());
+        server.handle_join(listener, lobby, "join-2".to_string());
+        drain(&mut sender_rx);
+        drain(&mut listener_rx);
+
+        server.handle_emit_multi(
+            sender,
+            vec!["room:lobby".to_string(), "admin:ops".to_string()],
+            "status.updated".to_string(),
+            json!({"ok": true}),
+            "emit-1".to_string(),
+        );
+
+        let frames = drain(&mut sender_rx);
+        let [
+            ServerFrame::Ack {
+                for_id,
+                ok,
+                error,
+                results,
+                ..
+            },
+        ] = frames.as_slice()
+        else {
+            panic!("sender should receive exactly one ack, got {frames:?}");
+        };
+        assert_eq!(for_id, "emit-1");
+        assert!(!ok);
+        assert_eq!(
+            error.as_ref().map(|err| err.code.as_str()),
+            Some("partial_failure")
+        );
+        let results = results
+            .as_ref()
+            .expect("ack should carry per-channel results");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].channel, "room:lobby");
+        assert!(results[0].ok);
+        assert_eq!(results[1].channel, "admin:ops");
+        assert!(!results[1].ok);
+        assert_eq!(
+            results[1].error.as_ref().map(|err| err.code.as_str()),
+            Some("forbidden_channel")
+        );
+
+        let delivered = drain(&mut listener_rx);
+        assert!(matches!(
+            delivered.as_slice(),
+            [ServerFrame::Event { channel, event, .. }]
+                if channel == "room:lobby" && event == "status.updated"
+        ));
+    }
+
+    #[test]
+    fn emit_multi_rejects_empty_channel_list() {
+        let mut server = test_server();
+        let (sender, mut sender_rx) = register_user(&mut server, "u1");
+        drain(&mut sender_rx);
+
+        server.handle_emit_multi(
+            sender,
+            Vec::new(),
+            "status.updated".to_string(),
+            json!({}),
+            "emit-1".to_string(),
+        );
+
+        let frames = drain(&mut sender_rx);
+        assert!(matches!(
+            frames.as_slice(),
+            [ServerFrame::Ack {
+                ok: false,
+                results: None,
+                ..
+            }]
+        ));
+    }
 
     #[test]
     fn echo_channel_includes_sender() {
@@ -1067,4 +2988,219 @@ mod tests {
 
         assert_eq!(count.load(Ordering::Relaxed), 1);
     }
+
+    /// Lets a connection publish only once it has set `tier=pro` metadata.
+    struct ProTierPolicy;
+
+    impl ChannelPolicy for ProTierPolicy {
+        fn can_join(&self, _: &ConnectionMeta, _: &ChannelName) -> Result<(), RealtimeError> {
+            Ok(())
+        }
+
+        fn can_publish(
+            &self,
+            meta: &ConnectionMeta,
+            _: &ChannelName,
+            _: &str,
+        ) -> Result<(), RealtimeError> {
+            match meta.metadata.get("tier").map(String::as_str) {
+                Some("pro") => Ok(()),
+                _ => Err(RealtimeError::forbidden("Pro tier required")),
+            }
+        }
+    }
+
+    #[test]
+    fn set_meta_is_visible_to_policy_and_stats() {
+        let mut server = test_server_with_policy(Arc::new(ProTierPolicy));
+        let (conn, mut rx) = register_user(&mut server, "u1");
+        let lobby = ChannelName::parse("room:lobby").expect("channel should parse");
+        server.handle_join(conn, lobby.clone(), "join-1".to_string());
+        drain(&mut rx);
+
+        server.handle_emit(
+            conn,
+            lobby.clone(),
+            "chat.message".to_string(),
+            json!({}),
+            None,
+            "emit-1".to_string(),
+        );
+        assert!(matches!(
+            drain(&mut rx).as_slice(),
+            [ServerFrame::Ack { ok: false, .. }]
+        ));
+
+        server.handle_set_meta(
+            conn,
+            BTreeMap::from([
+                ("tier".to_string(), "pro".to_string()),
+                ("client".to_string(), "ios".to_string()),
+            ]),
+            "meta-1".to_string(),
+        );
+        assert!(matches!(
+            drain(&mut rx).as_slice(),
+            [ServerFrame::Ack { ok: true, .. }]
+        ));
+
+        server.handle_emit(
+            conn,
+            lobby,
+            "chat.message".to_string(),
+            json!({}),
+            None,
+            "emit-2".to_string(),
+        );
+        assert!(matches!(
+            drain(&mut rx).first(),
+            Some(ServerFrame::Ack { ok: true, .. })
+        ));
+
+        let stats = server.stats();
+        assert_eq!(stats.connections, 1);
+        assert_eq!(
+            stats.metadata.get("tier"),
+            Some(&BTreeMap::from([("pro".to_string(), 1)]))
+        );
+        assert_eq!(
+            stats.metadata.get("client"),
+            Some(&BTreeMap::from([("ios".to_string(), 1)]))
+        );
+    }
+
+    #[test]
+    fn set_meta_rejects_entries_over_the_caps() {
+        let mut server = test_server();
+        server.config.max_meta_entries = 1;
+        server.config.max_meta_entry_bytes = 8;
+        let (conn, mut rx) = register_user(&mut server, "u1");
+        drain(&mut rx);
+
+        server.handle_set_meta(
+            conn,
+            BTreeMap::from([("tier".to_string(), "enterprise".to_string())]),
+            "meta-1".to_string(),
+        );
+        server.handle_set_meta(
+            conn,
+            BTreeMap::from([
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "2".to_string()),
+            ]),
+            "meta-2".to_string(),
+        );
+
+        let frames = drain(&mut rx);
+        let [
+            ServerFrame::Ack {
+                ok: false,
+                error: Some(too_long),
+                ..
+            },
+            ServerFrame::Ack {
+                ok: false,
+                error: Some(too_many),
+                ..
+            },
+        ] = frames.as_slice()
+        else {
+            panic!("both frames should be rejected, got {frames:?}");
+        };
+        assert_eq!(too_long.code, RealtimeErrorCode::InvalidPayload);
+        assert_eq!(too_long.message, "Metadata entry `tier` exceeds 8 bytes");
+        assert_eq!(too_many.message, "Metadata is limited to 1 entries");
+        assert!(server.stats().metadata.is_empty());
+    }
+
+    /// Registers a connection on a spawned hub, returning its id and outbound frames.
+    async fn connect(
+        handle: &SocketServerHandle,
+        user_id: &str,
+    ) -> (ConnectionId, mpsc::Receiver<ServerFrame>) {
+        let conn_id = ConnectionId(Uuid::new_v4());
+        let (outbound_tx, outbound_rx) = mpsc::channel(64);
+        handle
+            .tx
+            .as_ref()
+            .expect("hub should be running")
+            .send(HubCommand::Register {
+                meta: ConnectionMeta {
+                    id: conn_id,
+                    user_id: user_id.to_string(),
+                    roles: vec!["user".to_string()],
+                    joined_at_unix: 0,
+                    metadata: BTreeMap::new(),
+                },
+                outbound_tx,
+            })
+            .await
+            .expect("hub should accept the connection");
+        (conn_id, outbound_rx)
+    }
+
+    /// Next `disconnecting` reason on `rx`, skipping the frames sent on connect.
+    async fn next_disconnecting(rx: &mut mpsc::Receiver<ServerFrame>) -> String {
+        loop {
+            let frame = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+                .await
+                .expect("frame should arrive")
+                .expect("connection should stay open");
+            if let ServerFrame::Disconnecting { reason, .. } = frame {
+                return reason;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_sends_disconnecting_and_resolves_once_clients_leave() {
+        let handle = SocketServerHandle::spawn(RealtimeConfig::default());
+        let (conn_id, mut outbound_rx) = connect(&handle, "u1").await;
+        let shutdown = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.shutdown_within(Duration::from_secs(30)).await }
+        });
+
+        assert_eq!(
+            next_disconnecting(&mut outbound_rx).await,
+            "server_shutdown"
+        );
+        let (_late, mut late_rx) = connect(&handle, "u2").await;
+        assert_eq!(next_disconnecting(&mut late_rx).await, "server_shutdown");
+        assert!(!shutdown.is_finished());
+
+        handle
+            .tx
+            .as_ref()
+            .expect("hub should be running")
+            .send(HubCommand::Unregister {
+                conn_id,
+                reason: DisconnectReason::ClientClosed,
+            })
+            .await
+            .expect("hub should accept the unregister");
+        tokio::time::timeout(Duration::from_secs(1), shutdown)
+            .await
+            .expect("shutdown should not wait for the grace period")
+            .expect("shutdown task should join")
+            .expect("shutdown should succeed");
+        assert_eq!(handle.stats().await.expect("stats").connections, 0);
+    }
+
+    #[tokio::test]
+    async fn shutdown_closes_lingering_sessions_after_the_grace_period() {
+        let handle = SocketServerHandle::spawn(RealtimeConfig::default());
+        let (_conn_id, mut outbound_rx) = connect(&handle, "u1").await;
+
+        handle
+            .shutdown_within(Duration::from_millis(50))
+            .await
+            .expect("shutdown should succeed");
+
+        assert_eq!(
+            next_disconnecting(&mut outbound_rx).await,
+            "server_shutdown"
+        );
+        assert!(outbound_rx.recv().await.is_none());
+    }
 }
@@ -10,10 +10,12 @@ mod types;
 pub use axum::RealtimeRouteOptions;
 pub use config::RealtimeConfig;
 pub use error::RealtimeError;
-pub use hub::{SocketServerHandle, SubscriptionId};
-pub use policy::{ChannelPolicy, DefaultChannelPolicy};
+pub use hub::{
+    PRESENCE_EVENT, SERVER_SHUTDOWN_REASON, STATE_EVENT, SocketServerHandle, SubscriptionId,
+};
+pub use policy::{ChannelPolicy, DefaultChannelPolicy, RegisteredChannelPolicy};
 pub use runtime::{RealtimeTokenVerifier, SocketAppState};
 pub use types::{
-    Channel, ChannelName, ConnectionId, ConnectionMeta, DisconnectReason, Event, Payload,
-    SessionAuth, UserId,
+    Channel, ChannelEvent, ChannelName, ConnectionId, ConnectionMeta, DisconnectReason, Event,
+    Payload, PublishOutcome, RealtimeStats, SessionAuth, UserId,
 };
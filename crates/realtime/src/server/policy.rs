@@ -1,7 +1,33 @@
+use std::{
+    collections::HashSet,
+    sync::{PoisonError, RwLock},
+};
+
 use super::{ChannelName, ConnectionMeta, RealtimeError};
 
 pub trait ChannelPolicy: Send + Sync {
+    /// Maps a client-supplied channel name to its canonical form before join, leave or emit.
+    ///
+    /// Override to lowercase names, narrow the charset or reject names outside the app's
+    /// naming convention. The default accepts any name `ChannelName::parse` accepts.
+    fn normalize_channel(&self, raw: &str) -> Result<ChannelName, RealtimeError> {
+        ChannelName::parse(raw)
+    }
+
+    /// Whether `channel` is part of the app's namespace. Joins to a channel that does
+    /// not exist are acked `unknown_channel`; the default treats every name as open.
+    fn channel_exists(&self, _channel: &ChannelName) -> bool {
+        true
+    }
+
     fn can_join(&self, meta: &ConnectionMeta, channel: &ChannelName) -> Result<(), RealtimeError>;
+
+    /// Whether a joiner gets the channel's member list in its `joined` frame.
+    /// Off by default so user ids are not exposed to everyone in a channel.
+    fn can_list_members(&self, _meta: &ConnectionMeta, _channel: &ChannelName) -> bool {
+        false
+    }
+
     fn can_publish(
         &self,
         meta: &ConnectionMeta,
@@ -59,6 +85,80 @@ impl ChannelPolicy for DefaultChannelPolicy {
     }
 }
 
+/// Only channels registered up front can be joined, so typos and ad-hoc rooms are
+/// rejected instead of silently created. Private `user:` channels always exist.
+///
+/// Name normalization and access checks are delegated to the wrapped policy.
+#[derive(Debug, Default)]
+pub struct RegisteredChannelPolicy<P = DefaultChannelPolicy> {
+    inner: P,
+    channels: RwLock<HashSet<ChannelName>>,
+}
+
+impl<P: ChannelPolicy> RegisteredChannelPolicy<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            channels: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Registers `raw` in its normalized form and returns that form.
+    pub fn register(&self, raw: &str) -> Result<ChannelName, RealtimeError> {
+        let channel = self.inner.normalize_channel(raw)?;
+        self.channels
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(channel.clone());
+        Ok(channel)
+    }
+
+    /// Removes `raw` from the registry. Members already joined stay joined.
+    pub fn unregister(&self, raw: &str) -> bool {
+        let Ok(channel) = self.inner.normalize_channel(raw) else {
+            return false;
+        };
+        self.channels
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&channel)
+    }
+
+    pub fn is_registered(&self, channel: &ChannelName) -> bool {
+        self.channels
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(channel)
+    }
+}
+
+impl<P: ChannelPolicy> ChannelPolicy for RegisteredChannelPolicy<P> {
+    fn normalize_channel(&self, raw: &str) -> Result<ChannelName, RealtimeError> {
+        self.inner.normalize_channel(raw)
+    }
+
+    fn channel_exists(&self, channel: &ChannelName) -> bool {
+        channel.as_str().starts_with("user:") || self.is_registered(channel)
+    }
+
+    fn can_join(&self, meta: &ConnectionMeta, channel: &ChannelName) -> Result<(), RealtimeError> {
+        self.inner.can_join(meta, channel)
+    }
+
+    fn can_list_members(&self, meta: &ConnectionMeta, channel: &ChannelName) -> bool {
+        self.inner.can_list_members(meta, channel)
+    }
+
+    fn can_publish(
+        &self,
+        meta: &ConnectionMeta,
+        channel: &ChannelName,
+        event: &str,
+    ) -> Result<(), RealtimeError> {
+        self.inner.can_publish(meta, channel, event)
+    }
+}
+
 fn is_admin(meta: &ConnectionMeta) -> bool {
     meta.roles.iter().any(|role| role == "admin")
 }
@@ -76,9 +176,25 @@ mod tests {
             user_id: user_id.to_string(),
             roles,
             joined_at_unix: 0,
+            metadata: Default::default(),
         }
     }
 
+    #[test]
+    fn default_normalize_keeps_name_and_rejects_empty() {
+        let policy = DefaultChannelPolicy;
+
+        let channel = policy
+            .normalize_channel(" Room:Lobby ")
+            .expect("channel should normalize");
+        assert_eq!(channel.as_str(), "Room:Lobby");
+
+        let err = policy
+            .normalize_channel("   ")
+            .expect_err("empty name should be rejected");
+        assert_eq!(err.message(), "Channel name is required");
+    }
+
     #[test]
     fn user_cannot_join_another_private_channel() {
         let policy = DefaultChannelPolicy;
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use super::{RealtimeError, SessionAuth, SocketServerHandle};
+use super::{Channel, Event, Payload, RealtimeError, SessionAuth, SocketServerHandle};
 
 #[async_trait]
 pub trait RealtimeTokenVerifier: Send + Sync + 'static {
@@ -44,4 +44,14 @@ impl SocketAppState {
     ) -> Self {
         Self { handle, verifier }
     }
+
+    /// Broadcast a system event with no `from_user`; see [`SocketServerHandle::emit_system`].
+    pub async fn emit_system(
+        &self,
+        channel_name: impl Into<Channel>,
+        event: impl Into<Event>,
+        payload: Payload,
+    ) -> Result<(), RealtimeError> {
+        self.handle.emit_system(channel_name, event, payload).await
+    }
 }
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
 use axum::extract::ws::{Message, WebSocket};
 use chrono::Utc;
@@ -6,7 +6,7 @@ use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
 use tokio::time::{Instant, MissedTickBehavior, interval};
 
-use crate::protocol::{ClientFrame, ServerFrame};
+use crate::protocol::{ClientFrame, RealtimeErrorCode, ServerFrame};
 
 use super::{
     RealtimeConfig, SessionAuth,
@@ -29,6 +29,7 @@ pub async fn run_socket_session(
         user_id: auth.user_id,
         roles: auth.roles,
         joined_at_unix: Utc::now().timestamp(),
+        metadata: BTreeMap::new(),
     };
 
     if hub_tx
@@ -52,6 +53,8 @@ pub async fn run_socket_session(
         tokio::select! {
             outbound = outbound_rx.recv() => {
                 let Some(frame) = outbound else {
+                    // The hub dropped this connection, e.g. on shutdown.
+                    let _ = ws_sender.send(Message::Close(None)).await;
                     break DisconnectReason::HubUnavailable;
                 };
 
@@ -74,7 +77,7 @@ pub async fn run_socket_session(
                         if text.len() > cfg.max_message_bytes {
                             let _ = send_direct_error(
                                 &mut ws_sender,
-                                "message_too_large",
+                                RealtimeErrorCode::MessageTooLarge,
                                 "Message exceeds realtime.max_message_bytes",
                             ).await;
                             continue;
@@ -85,7 +88,7 @@ pub async fn run_socket_session(
                             Err(_) => {
                                 let _ = send_direct_error(
                                     &mut ws_sender,
-                                    "invalid_payload",
+                                    RealtimeErrorCode::InvalidPayload,
                                     "Invalid websocket payload",
                                 )
                                 .await;
@@ -103,7 +106,7 @@ pub async fn run_socket_session(
                     Ok(Message::Binary(_)) => {
                         let _ = send_direct_error(
                             &mut ws_sender,
-                            "invalid_payload",
+                            RealtimeErrorCode::InvalidPayload,
                             "Binary websocket payloads are not supported",
                         )
                         .await;
@@ -168,7 +171,9 @@ async fn dispatch_client_frame(
                 Ok(channel) => channel,
                 Err(err) => {
                     let message = err.message().to_string();
-                    let _ = send_direct_error(ws_sender, "invalid_channel", &message).await;
+                    let _ =
+                        send_direct_error(ws_sender, RealtimeErrorCode::InvalidChannel, &message)
+                            .await;
                     return Ok(());
                 }
             };
@@ -183,7 +188,9 @@ async fn dispatch_client_frame(
                 Ok(channel) => channel,
                 Err(err) => {
                     let message = err.message().to_string();
-                    let _ = send_direct_error(ws_sender, "invalid_channel", &message).await;
+                    let _ =
+                        send_direct_error(ws_sender, RealtimeErrorCode::InvalidChannel, &message)
+                            .await;
                     return Ok(());
                 }
             };
@@ -198,13 +205,16 @@ async fn dispatch_client_frame(
             channel,
             event,
             data,
+            ttl_ms,
             ..
         } => {
             let channel = match ChannelName::parse(&channel) {
                 Ok(channel) => channel,
                 Err(err) => {
                     let message = err.message().to_string();
-                    let _ = send_direct_error(ws_sender, "invalid_channel", &message).await;
+                    let _ =
+                        send_direct_error(ws_sender, RealtimeErrorCode::InvalidChannel, &message)
+                            .await;
                     return Ok(());
                 }
             };
@@ -213,9 +223,68 @@ async fn dispatch_client_frame(
                 channel,
                 event,
                 payload: data,
+                ttl: ttl_ms.map(Duration::from_millis),
                 req_id: id,
             }
         }
+        ClientFrame::ChannelEmitMulti {
+            id,
+            channels,
+            event,
+            data,
+            ..
+        } => HubCommand::EmitMulti {
+            conn_id,
+            channels,
+            event,
+            payload: data,
+            req_id: id,
+        },
+        ClientFrame::SetPresence {
+            id, channel, state, ..
+        } => {
+            let channel = match ChannelName::parse(&channel) {
+                Ok(channel) => channel,
+                Err(err) => {
+                    let message = err.message().to_string();
+                    let _ =
+                        send_direct_error(ws_sender, RealtimeErrorCode::InvalidChannel, &message)
+                            .await;
+                    return Ok(());
+                }
+            };
+            HubCommand::SetPresence {
+                conn_id,
+                channel,
+                state,
+                req_id: id,
+            }
+        }
+        ClientFrame::SetState {
+            id, channel, state, ..
+        } => {
+            let channel = match ChannelName::parse(&channel) {
+                Ok(channel) => channel,
+                Err(err) => {
+                    let message = err.message().to_string();
+                    let _ =
+                        send_direct_error(ws_sender, RealtimeErrorCode::InvalidChannel, &message)
+                            .await;
+                    return Ok(());
+                }
+            };
+            HubCommand::SetState {
+                conn_id,
+                channel,
+                state,
+                req_id: id,
+            }
+        }
+        ClientFrame::SetMeta { id, meta, .. } => HubCommand::SetMeta {
+            conn_id,
+            meta,
+            req_id: id,
+        },
         ClientFrame::Ping { id, .. } => HubCommand::Ping {
             conn_id,
             req_id: id,
@@ -227,7 +296,7 @@ async fn dispatch_client_frame(
 
 async fn send_direct_error(
     ws_sender: &mut futures_util::stream::SplitSink<WebSocket, Message>,
-    code: &str,
+    code: RealtimeErrorCode,
     message: &str,
 ) -> Result<(), ()> {
     let frame = ServerFrame::error(code, message);
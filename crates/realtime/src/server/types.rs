@@ -1,4 +1,7 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -80,6 +83,40 @@ pub struct ConnectionMeta {
     pub user_id: UserId,
     pub roles: Vec<String>,
     pub joined_at_unix: i64,
+    /// Client-set key/value pairs from `set_meta` frames, visible to the
+    /// `ChannelPolicy`. Capped by `max_meta_entries` and `max_meta_entry_bytes`.
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Point-in-time counts from the hub.
+///
+/// `metadata` maps each connection metadata key to the number of
+/// connections holding each value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RealtimeStats {
+    pub connections: usize,
+    pub channels: usize,
+    pub metadata: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+/// Result of publishing to a channel from the server side.
+///
+/// `delivered` counts subscribers whose outbound queue accepted the frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PublishOutcome {
+    pub delivered: usize,
+    pub channel_existed: bool,
+}
+
+/// An event fanned out on a channel, as seen by server-side subscribers.
+///
+/// `from` is the emitting user, or `None` for server-originated sends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelEvent {
+    pub channel: Channel,
+    pub event: Event,
+    pub payload: Payload,
+    pub from: Option<UserId>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -90,6 +127,7 @@ pub enum DisconnectReason {
     SlowConsumer,
     IdleTimeout,
     ProtocolError,
+    ServerShutdown,
 }
 
 #[cfg(test)]
@@ -1,9 +1,18 @@
-use std::{collections::HashSet, env, path::Path};
+use std::{
+    collections::HashSet,
+    env,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 #[path = "build/docs.rs"]
 mod docs;
 #[path = "build/entities.rs"]
 mod entities;
+#[path = "build/openapi.rs"]
+mod openapi;
+#[path = "build/postman.rs"]
+mod postman;
 #[path = "build/routes.rs"]
 mod routes;
 #[path = "build/utils.rs"]
@@ -26,13 +35,43 @@ fn strict_build_mode() -> bool {
     profile_is_strict()
 }
 
+fn postman_enabled() -> bool {
+    match env::var("RUST_OXIDE_BUILD_POSTMAN") {
+        Ok(raw) => match raw.trim().to_ascii_lowercase().as_str() {
+            "0" | "false" | "no" | "off" => false,
+            "1" | "true" | "yes" | "on" => true,
+            invalid => {
+                println!(
+                    "cargo:warning=invalid RUST_OXIDE_BUILD_POSTMAN value '{}'; expected one of [1,true,yes,on,0,false,no,off], emitting the collection",
+                    invalid
+                );
+                true
+            }
+        },
+        Err(_) => true,
+    }
+}
+
 fn profile_is_strict() -> bool {
     matches!(env::var("PROFILE").as_deref(), Ok("release") | Ok("bench"))
 }
 
+/// Exposes the build time as `RUST_OXIDE_BUILD_TIMESTAMP` (unix seconds); view
+/// routes use it as their `Last-Modified` value.
+fn emit_build_timestamp() {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=RUST_OXIDE_BUILD_TIMESTAMP={secs}");
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-env-changed=RUST_OXIDE_BUILD_STRICT");
+    println!("cargo:rerun-if-env-changed=RUST_OXIDE_BUILD_POSTMAN");
+    println!("cargo:rerun-if-changed=views");
+    emit_build_timestamp();
 
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("missing CARGO_MANIFEST_DIR");
     let manifest_path = Path::new(&manifest_dir);
@@ -75,6 +114,8 @@ fn main() {
         let empty_relations: Vec<entities::EntityRelationEntry> = Vec::new();
 
         routes::write_routes(out_path, &empty_routes);
+        postman::write_postman_collection(out_path, &empty_routes);
+        openapi::write_openapi_spec(out_path, &empty_routes);
         entities::write_entities(out_path, &empty_entities, &empty_relations);
         docs::write_docs_sections(manifest_path, out_path);
         return;
@@ -88,6 +129,8 @@ fn main() {
         routes::collect_type_docs(&parsed, &module_path, &mut registry);
         routes::collect_crud_service_impls(&parsed, &mut crud_context.service_to_dao);
         routes::collect_dao_base_impls(&parsed, &mut crud_context.dao_to_entity);
+        routes::collect_bare_list_services(&parsed, &mut crud_context.bare_list_services);
+        routes::collect_ok_create_services(&parsed, &mut crud_context.ok_create_services);
     }
     crud_context.entity_to_model =
         routes::collect_entity_model_map(&src_dir.join("db/entities"), &src_dir);
@@ -150,6 +193,12 @@ fn main() {
     relations.dedup();
 
     routes::write_routes(out_path, &routes_list);
+    if postman_enabled() {
+        postman::write_postman_collection(out_path, &routes_list);
+    } else {
+        postman::write_postman_collection(out_path, &[]);
+    }
+    openapi::write_openapi_spec(out_path, &routes_list);
     entities::write_entities(out_path, &entities_list, &relations);
     docs::write_docs_sections(manifest_path, out_path);
 }
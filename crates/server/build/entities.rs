@@ -118,10 +118,8 @@ pub(crate) fn collect_entity_relations(
             continue;
         }
         match item {
-            Item::Struct(item_struct) => {
-                if has_derive_entity_model(&item_struct.attrs) {
-                    out.extend(build_entity_relations(item_struct, module_path));
-                }
+            Item::Struct(item_struct) if has_derive_entity_model(&item_struct.attrs) => {
+                out.extend(build_entity_relations(item_struct, module_path));
             }
             Item::Mod(item_mod) => {
                 if let Some((_, nested)) = &item_mod.content {
@@ -388,10 +386,11 @@ fn field_has_relation_attr(attrs: &[Attribute]) -> bool {
             if meta.path.is_ident("has_many")
                 || meta.path.is_ident("has_one")
                 || meta.path.is_ident("belongs_to")
+                || meta.path.is_ident("self_ref")
             {
                 is_relation = true;
             }
-            Ok(())
+            skip_meta_value(&meta)
         });
         if is_relation {
             return true;
@@ -429,10 +428,19 @@ fn parse_fk_column_variants(value: &str) -> Vec<String> {
     parts
         .into_iter()
         .filter(|part| !part.is_empty())
-        .map(|part| part.rsplit("::").next().unwrap_or(part).trim().to_string())
+        .map(|part| to_pascal_case(part.rsplit("::").next().unwrap_or(part).trim()))
         .collect()
 }
 
+/// Consumes the `= value` of a nested meta item the caller does not read, so
+/// `parse_nested_meta` keeps going past it (e.g. `relation_enum = "Parent"`).
+fn skip_meta_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    }
+    Ok(())
+}
+
 fn collect_fk_columns(items: &[Item]) -> HashSet<String> {
     let mut out = HashSet::new();
     for item in items {
@@ -463,11 +471,14 @@ fn collect_fk_columns_from_enum(item_enum: &ItemEnum, out: &mut HashSet<String>)
             let mut belongs_to = false;
             let mut from_value: Option<String> = None;
             let _ = attr.parse_nested_meta(|meta| {
-                if meta.path.is_ident("belongs_to") {
+                if meta.path.is_ident("belongs_to") || meta.path.is_ident("self_ref") {
                     belongs_to = true;
+                    skip_meta_value(&meta)?;
                 } else if meta.path.is_ident("from") {
                     let value: LitStr = meta.value()?.parse()?;
                     from_value = Some(value.value());
+                } else {
+                    skip_meta_value(&meta)?;
                 }
                 Ok(())
             });
@@ -500,11 +511,14 @@ fn extract_belongs_to_from_field(attrs: &[Attribute]) -> Vec<String> {
         let mut belongs_to = false;
         let mut from_value: Option<String> = None;
         let _ = attr.parse_nested_meta(|meta| {
-            if meta.path.is_ident("belongs_to") {
+            if meta.path.is_ident("belongs_to") || meta.path.is_ident("self_ref") {
                 belongs_to = true;
+                skip_meta_value(&meta)?;
             } else if meta.path.is_ident("from") {
                 let value: LitStr = meta.value()?.parse()?;
                 from_value = Some(value.value());
+            } else {
+                skip_meta_value(&meta)?;
             }
             Ok(())
         });
@@ -640,7 +654,7 @@ fn build_entity_relations(item_struct: &ItemStruct, module_path: &str) -> Vec<En
         let Some(kind) = kind else {
             continue;
         };
-        let Some(target) = relation_target_entity(&field.ty) else {
+        let Some(target) = relation_target_entity(&field.ty, &entity) else {
             continue;
         };
         let mut label = field
@@ -667,6 +681,8 @@ fn relation_kind_from_attrs(attrs: &[Attribute]) -> Option<RelationKind> {
             continue;
         }
         let mut kind = None;
+        let mut self_ref = false;
+        let mut has_from = false;
         let _ = attr.parse_nested_meta(|meta| {
             if meta.path.is_ident("has_many") {
                 kind = Some(RelationKind::HasMany);
@@ -674,9 +690,18 @@ fn relation_kind_from_attrs(attrs: &[Attribute]) -> Option<RelationKind> {
                 kind = Some(RelationKind::HasOne);
             } else if meta.path.is_ident("belongs_to") {
                 kind = Some(RelationKind::BelongsTo);
+            } else if meta.path.is_ident("self_ref") {
+                self_ref = true;
+            } else if meta.path.is_ident("from") {
+                has_from = true;
             }
-            Ok(())
+            skip_meta_value(&meta)
         });
+        // A `self_ref` side that owns the foreign key is the belongs-to end;
+        // the other side takes its kind from the `HasOne`/`HasMany` type.
+        if kind.is_none() && self_ref && has_from {
+            kind = Some(RelationKind::BelongsTo);
+        }
         if kind.is_some() {
             return kind;
         }
@@ -700,15 +725,21 @@ fn relation_kind_from_type(ty: &Type) -> Option<RelationKind> {
     None
 }
 
-fn relation_target_entity(ty: &Type) -> Option<String> {
-    if let Some(inner) = extract_generic_inner(ty, "HasMany")
+/// Entity a relation field points at. A bare `Entity` (the self-referential
+/// `HasMany<Entity>` form) resolves to `entity`, the entity being parsed.
+fn relation_target_entity(ty: &Type, entity: &str) -> Option<String> {
+    let inner = extract_generic_inner(ty, "HasMany")
         .or_else(|| extract_generic_inner(ty, "HasOne"))
         .or_else(|| extract_generic_inner(ty, "Vec"))
         .or_else(|| extract_generic_inner(ty, "Option"))
-    {
-        return entity_name_from_type(inner);
-    }
-    entity_name_from_type(ty)
+        .unwrap_or(ty);
+    entity_name_from_type(inner).map(|target| {
+        if target == "Entity" {
+            entity.to_string()
+        } else {
+            target
+        }
+    })
 }
 
 fn mermaid_relation_marker(kind: RelationKind) -> &'static str {
@@ -790,3 +821,101 @@ fn mermaid_sanitize_word(value: &str) -> String {
         out
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATEGORY_FIXTURE: &str = r#"
+        #[base_entity]
+        #[sea_orm::model]
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "categories")]
+        pub struct Model {
+            pub name: String,
+            #[sea_orm(indexed)]
+            pub parent_id: Option<Uuid>,
+            #[sea_orm(self_ref, relation_enum = "Parent", from = "parent_id", to = "id")]
+            pub parent: HasOne<Entity>,
+            #[sea_orm(self_ref, relation_reverse = "Parent")]
+            pub children: HasMany<Entity>,
+        }
+    "#;
+
+    fn parse_category() -> (Vec<EntityEntry>, Vec<EntityRelationEntry>) {
+        let file = syn::parse_file(CATEGORY_FIXTURE).expect("fixture should parse");
+        let (mut entities, mut relations) = (Vec::new(), Vec::new());
+        collect_entity_entries(&file.items, "db::entities::category", &mut entities);
+        collect_entity_relations(&file.items, "db::entities::category", &mut relations);
+        (entities, relations)
+    }
+
+    #[test]
+    fn self_referential_relations_target_the_same_entity() {
+        let (entities, relations) = parse_category();
+
+        let parent_id = entities[0]
+            .columns
+            .iter()
+            .find(|column| column.name == "parent_id")
+            .expect("parent_id should be a column");
+        assert_eq!(parent_id.attributes, ["Foreign Key", "Indexed", "Nullable"]);
+        assert!(
+            entities[0]
+                .columns
+                .iter()
+                .all(|column| column.name != "parent" && column.name != "children")
+        );
+
+        let edges: Vec<(&str, &str, RelationKind, &str)> = relations
+            .iter()
+            .map(|relation| {
+                (
+                    relation.from.as_str(),
+                    relation.to.as_str(),
+                    relation.kind,
+                    relation.label.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("category", "category", RelationKind::BelongsTo, "parent"),
+                ("category", "category", RelationKind::HasMany, "children"),
+            ]
+        );
+    }
+
+    #[test]
+    fn erd_draws_a_self_reference_as_one_loop() {
+        let (entities, relations) = parse_category();
+
+        let erd = render_mermaid_er_diagram(&entities, &relations);
+
+        assert!(
+            erd.contains("  category ||--o{ category : children\n"),
+            "{erd}"
+        );
+        assert!(!erd.contains(": parent\n"), "{erd}");
+    }
+
+    #[test]
+    fn snake_case_from_marks_the_foreign_key_column() {
+        let file = syn::parse_file(
+            r#"
+            #[derive(DeriveEntityModel)]
+            pub struct Model {
+                pub list_id: Uuid,
+                #[sea_orm(belongs_to, from = "list_id", to = "id", on_delete = "Cascade")]
+                pub list: HasOne<super::todo_list::Entity>,
+            }
+            "#,
+        )
+        .expect("fixture should parse");
+        let mut entities = Vec::new();
+        collect_entity_entries(&file.items, "db::entities::todo_item", &mut entities);
+
+        assert_eq!(entities[0].columns[0].attributes, ["Foreign Key"]);
+    }
+}
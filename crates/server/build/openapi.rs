@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    routes::{RouteEntry, sample_method, sample_request},
+    utils::json_string,
+};
+
+const OPENAPI_VERSION: &str = "3.0.3";
+const API_TITLE: &str = "rust-oxide";
+const API_SOURCE_PREFIX: &str = "src/routes/api/";
+
+/// Renders the API routes of the catalog as an OpenAPI 3.0 document.
+///
+/// Only routes declared under `src/routes/api` are included; view routes serve
+/// HTML. The catalog only knows type summaries, so bodies use a loose `object`
+/// schema and the summaries go into the descriptions. Routes that need a bearer
+/// token reference the `bearerAuth` security scheme.
+pub(crate) fn build_openapi_spec(routes: &[RouteEntry]) -> String {
+    let mut paths: BTreeMap<&str, BTreeMap<String, String>> = BTreeMap::new();
+    for route in routes
+        .iter()
+        .filter(|route| route.source.starts_with(API_SOURCE_PREFIX))
+    {
+        paths
+            .entry(route.path.as_str())
+            .or_default()
+            .entry(sample_method(&route.method).to_ascii_lowercase())
+            .or_insert_with(|| operation(route));
+    }
+
+    let paths: Vec<String> = paths
+        .into_iter()
+        .map(|(path, operations)| {
+            let operations: Vec<String> = operations
+                .into_iter()
+                .map(|(method, operation)| format!("{}:{}", json_string(&method), operation))
+                .collect();
+            format!("{}:{{{}}}", json_string(path), operations.join(","))
+        })
+        .collect();
+
+    format!(
+        "{{\"openapi\":{},\"info\":{{\"title\":{},\"version\":{}}},\"paths\":{{{}}},\"components\":{{\"securitySchemes\":{{\"bearerAuth\":{{\"type\":\"http\",\"scheme\":\"bearer\",\"bearerFormat\":\"JWT\"}}}}}}}}\n",
+        json_string(OPENAPI_VERSION),
+        json_string(API_TITLE),
+        json_string(env!("CARGO_PKG_VERSION")),
+        paths.join(",")
+    )
+}
+
+pub(crate) fn write_openapi_spec(out_dir: &Path, routes: &[RouteEntry]) {
+    let out_path = out_dir.join("openapi.json");
+    std::fs::write(&out_path, build_openapi_spec(routes))
+        .unwrap_or_else(|err| panic!("failed to write {}: {}", out_path.display(), err));
+}
+
+fn operation(route: &RouteEntry) -> String {
+    let method = sample_method(&route.method);
+    let (query, body) = sample_request(&route.request);
+
+    let mut parameters: Vec<String> = path_params(&route.path)
+        .map(|name| parameter(name, "path", true))
+        .collect();
+    if let Some(query) = query {
+        parameters.extend(
+            query
+                .split('&')
+                .filter_map(|pair| pair.split('=').next())
+                .filter(|name| !name.is_empty())
+                .map(|name| parameter(name, "query", false)),
+        );
+    }
+
+    let mut fields = vec![
+        format!("\"tags\":[{}]", json_string(&tag_for_source(&route.source))),
+        format!(
+            "\"summary\":{}",
+            json_string(&format!("{} {}", method, route.path))
+        ),
+    ];
+    if !parameters.is_empty() {
+        fields.push(format!("\"parameters\":[{}]", parameters.join(",")));
+    }
+    if body.is_some() {
+        fields.push(format!(
+            "\"requestBody\":{{\"required\":true,\"description\":{},\"content\":{{\"application/json\":{{\"schema\":{{\"type\":\"object\"}}}}}}}}",
+            json_string(&route.request)
+        ));
+    }
+    let response = if route.response.trim().is_empty() {
+        "OK"
+    } else {
+        route.response.as_str()
+    };
+    fields.push(format!(
+        "\"responses\":{{\"200\":{{\"description\":{}}}}}",
+        json_string(response)
+    ));
+    if route.required_headers.contains("Authorization") {
+        fields.push("\"security\":[{\"bearerAuth\":[]}]".to_string());
+    }
+
+    format!("{{{}}}", fields.join(","))
+}
+
+fn parameter(name: &str, location: &str, required: bool) -> String {
+    format!(
+        "{{\"name\":{},\"in\":{},\"required\":{},\"schema\":{{\"type\":\"string\"}}}}",
+        json_string(name),
+        json_string(location),
+        required
+    )
+}
+
+/// `{id}` and `{*rest}` segments of an axum path.
+fn path_params(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter_map(|segment| {
+        segment
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .map(|name| name.trim_start_matches('*'))
+    })
+}
+
+/// `src/routes/api/todo_crud.rs` -> `todo_crud`.
+fn tag_for_source(source: &str) -> String {
+    let trimmed = source.strip_prefix(API_SOURCE_PREFIX).unwrap_or(source);
+    let trimmed = trimmed.strip_suffix(".rs").unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix("/mod").unwrap_or(trimmed);
+    trimmed.replace('/', "::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(method: &str, path: &str, source: &str, request: &str, headers: &str) -> RouteEntry {
+        RouteEntry {
+            method: method.to_string(),
+            path: path.to_string(),
+            source: source.to_string(),
+            request: request.to_string(),
+            response: "JSON".to_string(),
+            required_headers: headers.to_string(),
+            curl: String::new(),
+        }
+    }
+
+    #[test]
+    fn spec_groups_api_routes_by_path() {
+        let routes = vec![
+            route(
+                "POST",
+                "/api/v1/login",
+                "src/routes/api/auth.rs",
+                "{ \"email\": String, \"password\": String }",
+                "Content-Type: application/json",
+            ),
+            route(
+                "GET",
+                "/api/v1/todo-crud/{id}",
+                "src/routes/api/todo_crud.rs",
+                "path: Uuid",
+                "Authorization: Bearer $ACCESS_TOKEN",
+            ),
+            route(
+                "DELETE",
+                "/api/v1/todo-crud/{id}",
+                "src/routes/api/todo_crud.rs",
+                "path: Uuid",
+                "Authorization: Bearer $ACCESS_TOKEN",
+            ),
+            route("GET", "/docs", "src/routes/views/public.rs", "None", "None"),
+        ];
+
+        let spec: serde_json::Value =
+            serde_json::from_str(&build_openapi_spec(&routes)).expect("spec should be valid JSON");
+
+        assert_eq!(spec["openapi"], OPENAPI_VERSION);
+        let paths = spec["paths"].as_object().expect("paths object");
+        assert_eq!(paths.len(), 2);
+        assert!(!paths.contains_key("/docs"));
+
+        let login = &spec["paths"]["/api/v1/login"]["post"];
+        assert_eq!(login["tags"][0], "auth");
+        assert!(login["requestBody"]["content"]["application/json"].is_object());
+        assert!(login.get("security").is_none());
+
+        let item = &spec["paths"]["/api/v1/todo-crud/{id}"];
+        assert!(item["get"].is_object() && item["delete"].is_object());
+        assert_eq!(item["get"]["parameters"][0]["name"], "id");
+        assert_eq!(item["get"]["parameters"][0]["in"], "path");
+        assert_eq!(
+            item["get"]["security"][0]["bearerAuth"],
+            serde_json::json!([])
+        );
+    }
+}
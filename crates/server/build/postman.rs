@@ -0,0 +1,190 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    routes::{RouteEntry, sample_method, sample_request},
+    utils::json_string,
+};
+
+const POSTMAN_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+const COLLECTION_NAME: &str = "rust-oxide";
+const DEFAULT_BASE_URL: &str = "http://localhost:3000";
+
+/// Renders the route catalog as a Postman v2.1 collection (also importable by Insomnia).
+///
+/// Requests are grouped into one folder per source module. `{BASE_URL}` and
+/// `{ACCESS_TOKEN}` become collection variables, and `{id}` path segments
+/// become Postman `:id` path variables.
+pub(crate) fn build_postman_collection(routes: &[RouteEntry]) -> String {
+    let mut folders: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for route in routes {
+        folders
+            .entry(module_for_source(&route.source))
+            .or_default()
+            .push(postman_item(route));
+    }
+
+    let folders: Vec<String> = folders
+        .into_iter()
+        .map(|(name, items)| {
+            format!(
+                "{{\"name\":{},\"item\":[{}]}}",
+                json_string(&name),
+                items.join(",")
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"info\":{{\"name\":{},\"schema\":{}}},\"variable\":[{{\"key\":\"BASE_URL\",\"value\":{}}},{{\"key\":\"ACCESS_TOKEN\",\"value\":\"\"}}],\"item\":[{}]}}\n",
+        json_string(COLLECTION_NAME),
+        json_string(POSTMAN_SCHEMA),
+        json_string(DEFAULT_BASE_URL),
+        folders.join(",")
+    )
+}
+
+pub(crate) fn write_postman_collection(out_dir: &Path, routes: &[RouteEntry]) {
+    let out_path = out_dir.join("postman_collection.json");
+    std::fs::write(&out_path, build_postman_collection(routes))
+        .unwrap_or_else(|err| panic!("failed to write {}: {}", out_path.display(), err));
+}
+
+fn postman_item(route: &RouteEntry) -> String {
+    let method = sample_method(&route.method);
+    let (query, body) = sample_request(&route.request);
+
+    let mut url = format!("{{{{BASE_URL}}}}{}", postman_path(&route.path));
+    if let Some(query) = query.filter(|query| !query.is_empty()) {
+        url.push('?');
+        url.push_str(&query);
+    }
+
+    let mut headers = Vec::new();
+    if route.required_headers.contains("Authorization") {
+        headers.push(header("Authorization", "Bearer {{ACCESS_TOKEN}}"));
+    }
+    let body = body.map(|raw| {
+        headers.push(header("Content-Type", "application/json"));
+        format!(
+            ",\"body\":{{\"mode\":\"raw\",\"raw\":{},\"options\":{{\"raw\":{{\"language\":\"json\"}}}}}}",
+            json_string(&raw)
+        )
+    });
+
+    format!(
+        "{{\"name\":{},\"request\":{{\"method\":{},\"header\":[{}],\"url\":{{\"raw\":{}}}{}}}}}",
+        json_string(&format!("{} {}", method, route.path)),
+        json_string(method),
+        headers.join(","),
+        json_string(&url),
+        body.unwrap_or_default()
+    )
+}
+
+fn header(key: &str, value: &str) -> String {
+    format!(
+        "{{\"key\":{},\"value\":{}}}",
+        json_string(key),
+        json_string(value)
+    )
+}
+
+fn postman_path(path: &str) -> String {
+    path.split('/')
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => format!(":{name}"),
+                None => segment.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// `src/routes/api/admin.rs` -> `routes::api::admin`.
+fn module_for_source(source: &str) -> String {
+    let trimmed = source.strip_prefix("src/").unwrap_or(source);
+    let trimmed = trimmed.strip_suffix(".rs").unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix("/mod").unwrap_or(trimmed);
+    trimmed.replace('/', "::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(method: &str, path: &str, source: &str, request: &str, headers: &str) -> RouteEntry {
+        RouteEntry {
+            method: method.to_string(),
+            path: path.to_string(),
+            source: source.to_string(),
+            request: request.to_string(),
+            response: "JSON".to_string(),
+            required_headers: headers.to_string(),
+            curl: String::new(),
+        }
+    }
+
+    #[test]
+    fn collection_parses_with_one_item_per_route() {
+        let routes = vec![
+            route(
+                "POST",
+                "/api/v1/login",
+                "src/routes/api/auth.rs",
+                "{ \"email\": String, \"password\": String }",
+                "Content-Type: application/json",
+            ),
+            route(
+                "GET",
+                "/api/v1/me",
+                "src/routes/api/protected.rs",
+                "None",
+                "Authorization: Bearer $ACCESS_TOKEN",
+            ),
+            route(
+                "DELETE",
+                "/api/v1/todo-crud/{id}",
+                "src/routes/api/todo_crud.rs",
+                "path: Uuid",
+                "None",
+            ),
+        ];
+
+        let collection: serde_json::Value =
+            serde_json::from_str(&build_postman_collection(&routes))
+                .expect("collection should be valid JSON");
+
+        assert_eq!(collection["info"]["schema"], POSTMAN_SCHEMA);
+        assert_eq!(collection["variable"][0]["key"], "BASE_URL");
+        let requests: Vec<&serde_json::Value> = collection["item"]
+            .as_array()
+            .expect("folders")
+            .iter()
+            .flat_map(|folder| folder["item"].as_array().expect("items"))
+            .map(|item| &item["request"])
+            .collect();
+        assert_eq!(requests.len(), routes.len());
+
+        let find = |raw: &str| {
+            requests
+                .iter()
+                .find(|request| request["url"]["raw"] == raw)
+                .unwrap_or_else(|| panic!("missing request for {raw}"))
+        };
+        let login = find("{{BASE_URL}}/api/v1/login");
+        assert_eq!(login["method"], "POST");
+        assert_eq!(
+            login["body"]["raw"],
+            "{ \"email\": \"string\", \"password\": \"string\" }"
+        );
+        let me = find("{{BASE_URL}}/api/v1/me");
+        assert_eq!(me["method"], "GET");
+        assert_eq!(me["header"][0]["value"], "Bearer {{ACCESS_TOKEN}}");
+        assert_eq!(
+            find("{{BASE_URL}}/api/v1/todo-crud/:id")["method"],
+            "DELETE"
+        );
+        assert_eq!(collection["item"][0]["name"], "routes::api::auth");
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use quote::ToTokens;
 use syn::{
     Attribute, Block, Expr, ExprCall, ExprLit, ExprMethodCall, ExprStruct, File, FnArg, ImplItem,
     Item, ItemFn, ItemImpl, ItemStruct, Lit, Pat, PatType, Path as SynPath, ReturnType, Stmt,
@@ -32,12 +33,32 @@ struct HandlerInfo {
     response: String,
     auth_required: bool,
     required_headers: String,
+    // Request/response types the registry could not describe.
+    unresolved: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 struct FieldDoc {
     name: String,
     ty: String,
+    // `#[serde(default)]`: may be omitted even when the type is not `Option`.
+    has_default: bool,
+    flatten: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QueryField {
+    name: String,
+    ty: String,
+    optional: bool,
+}
+
+#[derive(Debug, Default)]
+struct SerdeFieldAttrs {
+    rename: Option<String>,
+    default: bool,
+    flatten: bool,
+    skip: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -69,6 +90,61 @@ impl ExtractorKind {
 
 const CURL_BASE_URL_PLACEHOLDER: &str = "{BASE_URL}";
 const API_PREFIX: &str = "/api/v1";
+// Key used in query docs for a flattened map that accepts arbitrary keys.
+const QUERY_CATCH_ALL_KEY: &str = "*";
+const MAX_QUERY_FLATTEN_DEPTH: usize = 4;
+// Types whose docs are their own names; documenting them is not expected.
+const SCALAR_TYPES: &[&str] = &[
+    "String",
+    "str",
+    "bool",
+    "char",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "isize",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "usize",
+    "f32",
+    "f64",
+    "Uuid",
+    "Value",
+    "DateTime",
+    "DateTimeWithTimeZone",
+    "NaiveDate",
+    "NaiveDateTime",
+    "NaiveTime",
+];
+// Response types with no serde shape to document, e.g. HTML pages and raw
+// bodies; they never trigger an unresolved-type warning.
+const OPAQUE_TYPES: &[&str] = &[
+    "Html",
+    "Response",
+    "Redirect",
+    "StatusCode",
+    "Body",
+    "Bytes",
+    "HeaderMap",
+];
+// Wrappers documented through their type arguments. `Result`-like wrappers
+// only have their first argument checked; errors render via `IntoResponse`.
+const TRANSPARENT_TYPES: &[&str] = &[
+    "Option",
+    "Vec",
+    "Arc",
+    "Box",
+    "HashMap",
+    "BTreeMap",
+    "Json",
+    "Query",
+    "Path",
+    "JsonApiResponse",
+];
+const RESULT_TYPES: &[&str] = &["Result", "ApiResult"];
 
 impl TypeDoc {
     fn render_with<F>(&self, expand: F) -> String
@@ -98,6 +174,7 @@ struct RouteVisitor<'a> {
     route_bindings: Option<&'a HashMap<String, Vec<RouteHandler>>>,
     api_prefix: Option<&'a str>,
     routes: Vec<RouteEntry>,
+    used_handlers: HashSet<String>,
 }
 
 impl<'a, 'ast> Visit<'ast> for RouteVisitor<'a> {
@@ -148,6 +225,9 @@ impl<'a, 'ast> Visit<'ast> for RouteVisitor<'a> {
                     });
                 }
                 for handler in handlers {
+                    if let Some(name) = &handler.handler {
+                        self.used_handlers.insert(name.clone());
+                    }
                     let (request, response, auth_required, required_headers) = handler
                         .handler
                         .as_ref()
@@ -161,6 +241,13 @@ impl<'a, 'ast> Visit<'ast> for RouteVisitor<'a> {
                             )
                         })
                         .unwrap_or_else(|| {
+                            println!(
+                                "cargo:warning=Route {} {} in {} uses handler `{}` that is not defined in that file; request and response are documented as Unknown",
+                                handler.method,
+                                path,
+                                self.source,
+                                handler.handler.as_deref().unwrap_or("<inline>")
+                            );
                             (
                                 "Unknown".to_string(),
                                 "Unknown".to_string(),
@@ -606,6 +693,37 @@ fn is_relation_field(ty: &Type) -> bool {
     matches!(last.as_deref(), Some("HasOne") | Some("HasMany"))
 }
 
+fn serde_field_attrs(attrs: &[Attribute]) -> SerdeFieldAttrs {
+    let mut out = SerdeFieldAttrs::default();
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                if let Ok(value) = meta.value()
+                    && let Ok(Lit::Str(lit)) = value.parse::<Lit>()
+                {
+                    out.rename = Some(lit.value());
+                }
+            } else if meta.path.is_ident("default") {
+                out.default = true;
+                if let Ok(value) = meta.value() {
+                    let _ = value.parse::<Lit>();
+                }
+            } else if meta.path.is_ident("flatten") {
+                out.flatten = true;
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                out.skip = true;
+            } else if let Ok(value) = meta.value() {
+                let _ = value.parse::<Expr>();
+            }
+            Ok(())
+        });
+    }
+    out
+}
+
 fn build_struct_doc(item_struct: &ItemStruct) -> TypeDoc {
     let mut fields = Vec::new();
     match &item_struct.fields {
@@ -614,6 +732,10 @@ fn build_struct_doc(item_struct: &ItemStruct) -> TypeDoc {
                 if is_relation_field(&field.ty) {
                     continue;
                 }
+                let serde = serde_field_attrs(&field.attrs);
+                if serde.skip {
+                    continue;
+                }
                 let mut name = field
                     .ident
                     .as_ref()
@@ -622,8 +744,16 @@ fn build_struct_doc(item_struct: &ItemStruct) -> TypeDoc {
                 if let Some(stripped) = name.strip_prefix("r#") {
                     name = stripped.to_string();
                 }
+                if let Some(rename) = serde.rename {
+                    name = rename;
+                }
                 let ty = type_to_string(&field.ty);
-                fields.push(FieldDoc { name, ty });
+                fields.push(FieldDoc {
+                    name,
+                    ty,
+                    has_default: serde.default,
+                    flatten: serde.flatten,
+                });
             }
         }
         syn::Fields::Unnamed(unnamed) => {
@@ -633,7 +763,12 @@ fn build_struct_doc(item_struct: &ItemStruct) -> TypeDoc {
                 }
                 let name = index.to_string();
                 let ty = type_to_string(&field.ty);
-                fields.push(FieldDoc { name, ty });
+                fields.push(FieldDoc {
+                    name,
+                    ty,
+                    has_default: false,
+                    flatten: false,
+                });
             }
         }
         syn::Fields::Unit => {}
@@ -812,6 +947,115 @@ fn describe_type(
     type_to_string(ty)
 }
 
+/// Describes a `Query<T>` payload as a flat object, since serde reads query
+/// strings as a single level of keys. Flattened structs are inlined, and
+/// `Option`/`#[serde(default)]` fields are marked optional with `?`.
+fn describe_query_type(
+    ty: &Type,
+    module_path: &str,
+    registry: &TypeRegistry,
+    context: &CrudTypeContext,
+) -> String {
+    match resolve_type_doc(registry, ty, module_path) {
+        Some(doc) => {
+            render_query_fields(&collect_query_fields(doc, module_path, registry, None, 0))
+        }
+        None => describe_type(ty, module_path, registry, context),
+    }
+}
+
+/// `catch_all` replaces a flattened map (for example CRUD list filters) with
+/// concrete optional keys taken from another type, such as the entity model.
+fn collect_query_fields(
+    doc: &TypeDoc,
+    module_path: &str,
+    registry: &TypeRegistry,
+    catch_all: Option<&TypeDoc>,
+    depth: usize,
+) -> Vec<QueryField> {
+    let mut out = Vec::new();
+    for field in &doc.fields {
+        let Ok(parsed) = syn::parse_str::<Type>(&field.ty) else {
+            out.push(QueryField {
+                name: field.name.clone(),
+                ty: field.ty.clone(),
+                optional: field.has_default,
+            });
+            continue;
+        };
+        if field.flatten {
+            if depth < MAX_QUERY_FLATTEN_DEPTH
+                && let Some(nested) = resolve_type_doc(registry, &parsed, module_path)
+            {
+                out.extend(collect_query_fields(
+                    nested,
+                    module_path,
+                    registry,
+                    catch_all,
+                    depth + 1,
+                ));
+                continue;
+            }
+            match catch_all {
+                Some(columns) => {
+                    out.extend(columns.fields.iter().map(|column| QueryField {
+                        name: column.name.clone(),
+                        ty: unwrap_option_type(&column.ty),
+                        optional: true,
+                    }));
+                }
+                None => {
+                    let value_ty = extract_generic_types(&parsed, "HashMap")
+                        .get(1)
+                        .map(|ty| type_to_string(ty))
+                        .unwrap_or_else(|| "String".to_string());
+                    out.push(QueryField {
+                        name: QUERY_CATCH_ALL_KEY.to_string(),
+                        ty: value_ty,
+                        optional: true,
+                    });
+                }
+            }
+            continue;
+        }
+        let (ty, optional) = match extract_generic_inner(&parsed, "Option") {
+            Some(inner) => (type_to_string(inner), true),
+            None => (field.ty.clone(), field.has_default),
+        };
+        out.push(QueryField {
+            name: field.name.clone(),
+            ty,
+            optional,
+        });
+    }
+    let mut seen = HashSet::new();
+    out.retain(|field| seen.insert(field.name.clone()));
+    out
+}
+
+fn render_query_fields(fields: &[QueryField]) -> String {
+    if fields.is_empty() {
+        return "{}".to_string();
+    }
+    let parts: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            let marker = if field.optional { "?" } else { "" };
+            format!("\"{}\"{}: {}", field.name, marker, field.ty)
+        })
+        .collect();
+    format!("{{ {} }}", parts.join(", "))
+}
+
+fn unwrap_option_type(ty: &str) -> String {
+    let trimmed = ty.trim();
+    trimmed
+        .strip_prefix("Option<")
+        .and_then(|rest| rest.strip_suffix('>'))
+        .map(|inner| inner.trim().to_string())
+        .unwrap_or_else(|| trimmed.to_string())
+}
+
 fn extract_request_extractor(ty: &Type) -> Option<(ExtractorKind, &Type)> {
     if let Some(inner) = extract_generic_inner(ty, "Json") {
         return Some((ExtractorKind::Json, inner));
@@ -867,12 +1111,12 @@ fn build_required_headers(auth_required: bool, has_json_body: bool) -> String {
     }
 }
 
-fn build_curl(method: &str, path: &str, request: &str, auth_required: bool) -> String {
-    let parts = split_request_parts(request);
+/// Sample query string and JSON body for a request description.
+pub(crate) fn sample_request(request: &str) -> (Option<String>, Option<String>) {
     let mut query = None;
     let mut json_body = None;
 
-    for part in parts {
+    for part in split_request_parts(request) {
         let trimmed = part.trim();
         if let Some(rest) = trimmed.strip_prefix("query:") {
             if let Some(built) = build_query(rest.trim()) {
@@ -896,11 +1140,20 @@ fn build_curl(method: &str, path: &str, request: &str, auth_required: bool) -> S
             json_body = Some(sample_json_value(trimmed));
         }
     }
+    (query, json_body)
+}
 
-    let method = match method {
+/// Catalog pseudo-methods (`ROUTE`, `SERVICE`) are sampled as GET requests.
+pub(crate) fn sample_method(method: &str) -> &str {
+    match method {
         "ROUTE" | "SERVICE" => "GET",
         _ => method,
-    };
+    }
+}
+
+fn build_curl(method: &str, path: &str, request: &str, auth_required: bool) -> String {
+    let (query, json_body) = sample_request(request);
+    let method = sample_method(method);
 
     let mut url = format!("{}{}", CURL_BASE_URL_PLACEHOLDER, path);
     if let Some(query) = query
@@ -938,8 +1191,12 @@ fn build_query(desc: &str) -> Option<String> {
     }
     let pairs: Vec<String> = fields
         .into_iter()
+        .filter(|(key, _)| key != QUERY_CATCH_ALL_KEY)
         .map(|(key, ty)| format!("{}={}", key, sample_query_value(&ty)))
         .collect();
+    if pairs.is_empty() {
+        return None;
+    }
     Some(pairs.join("&"))
 }
 
@@ -991,7 +1248,8 @@ fn parse_object_fields(desc: &str) -> Vec<(String, String)> {
         if key.is_empty() || ty.is_empty() {
             continue;
         }
-        let key = key.trim_matches('"').to_string();
+        // Optional query keys render as `"name"?`.
+        let key = key.trim_end_matches('?').trim_matches('"').to_string();
         out.push((key, ty.to_string()));
     }
     out
@@ -1022,7 +1280,7 @@ fn sample_json_value(ty: &str) -> String {
 fn sample_query_value(ty: &str) -> String {
     let cleaned = ty.trim();
     if cleaned.starts_with("Option<") {
-        return "1".to_string();
+        return sample_query_value(&unwrap_option_type(cleaned));
     }
     if cleaned.starts_with("Vec<") {
         return "value".to_string();
@@ -1036,6 +1294,7 @@ fn sample_query_value(ty: &str) -> String {
         "Uuid" => "00000000-0000-0000-0000-000000000000".to_string(),
         "JSON" => "value".to_string(),
         "i16" | "i32" | "i64" | "isize" | "u16" | "u32" | "u64" | "usize" => "1".to_string(),
+        "f32" | "f64" => "1.0".to_string(),
         "DateTimeWithTimeZone" => "2024-01-01T00:00:00Z".to_string(),
         _ => "value".to_string(),
     }
@@ -1106,6 +1365,7 @@ fn build_handler_info(
     context: &CrudTypeContext,
 ) -> HandlerInfo {
     let mut request_parts = Vec::new();
+    let mut unresolved = Vec::new();
     let mut auth_required = false;
     let mut has_json_body = false;
     for input in &item_fn.sig.inputs {
@@ -1115,10 +1375,16 @@ fn build_handler_info(
                 continue;
             }
             if let Some((kind, inner)) = extract_request_extractor(ty) {
-                let desc = describe_type(inner, module_path, registry, context);
+                let desc = match kind {
+                    ExtractorKind::Query => {
+                        describe_query_type(inner, module_path, registry, context)
+                    }
+                    _ => describe_type(inner, module_path, registry, context),
+                };
                 if matches!(kind, ExtractorKind::Json) {
                     has_json_body = true;
                 }
+                collect_unresolved_types(inner, module_path, registry, &mut unresolved);
                 request_parts.push((kind, desc));
             }
         }
@@ -1126,17 +1392,95 @@ fn build_handler_info(
     let request = format_request(request_parts);
     let response = match &item_fn.sig.output {
         ReturnType::Default => "None".to_string(),
-        ReturnType::Type(_, ty) => describe_response_type(ty, module_path, registry, context),
+        ReturnType::Type(_, ty) => {
+            collect_unresolved_types(ty, module_path, registry, &mut unresolved);
+            describe_response_type(ty, module_path, registry, context)
+        }
     };
     let required_headers = build_required_headers(auth_required, has_json_body);
+    unresolved.dedup();
     HandlerInfo {
         request,
         response,
         auth_required,
         required_headers,
+        unresolved,
+    }
+}
+
+/// Records named types in `ty` that neither the registry nor the built-in
+/// scalar/opaque lists can describe, typically a struct missing its serde
+/// derive or one defined outside this crate.
+fn collect_unresolved_types(
+    ty: &Type,
+    module_path: &str,
+    registry: &TypeRegistry,
+    out: &mut Vec<String>,
+) {
+    match ty {
+        Type::Reference(reference) => {
+            collect_unresolved_types(&reference.elem, module_path, registry, out)
+        }
+        Type::Paren(paren) => collect_unresolved_types(&paren.elem, module_path, registry, out),
+        Type::Tuple(tuple) => {
+            for elem in &tuple.elems {
+                collect_unresolved_types(elem, module_path, registry, out);
+            }
+        }
+        Type::Slice(slice) => collect_unresolved_types(&slice.elem, module_path, registry, out),
+        Type::Path(_) => {
+            let (_, Some(last)) = type_path_parts(ty) else {
+                return;
+            };
+            let args = extract_generic_types(ty, &last);
+            if RESULT_TYPES.contains(&last.as_str()) {
+                if let Some(ok) = args.first() {
+                    collect_unresolved_types(ok, module_path, registry, out);
+                }
+                return;
+            }
+            if SCALAR_TYPES.contains(&last.as_str()) || OPAQUE_TYPES.contains(&last.as_str()) {
+                return;
+            }
+            if !TRANSPARENT_TYPES.contains(&last.as_str())
+                && resolve_type_doc(registry, ty, module_path).is_none()
+            {
+                out.push(type_to_string(ty));
+                return;
+            }
+            for arg in args {
+                collect_unresolved_types(arg, module_path, registry, out);
+            }
+        }
+        // `impl IntoResponse` and friends have no nameable shape.
+        _ => {}
     }
 }
 
+/// One `cargo:warning` line per type a routed handler documents as its bare
+/// name. Helpers that never back a route are ignored.
+fn unresolved_type_warnings(
+    handlers: &HashMap<String, HandlerInfo>,
+    used: &HashSet<String>,
+    source: &str,
+) -> Vec<String> {
+    let mut names: Vec<&String> = used.iter().collect();
+    names.sort();
+    let mut out = Vec::new();
+    for name in names {
+        let Some(info) = handlers.get(name) else {
+            continue;
+        };
+        for ty in &info.unresolved {
+            out.push(format!(
+                "cargo:warning=Handler `{}` in {} uses unresolved type `{}`; derive Serialize/Deserialize on it to document its fields",
+                name, source, ty
+            ));
+        }
+    }
+    out
+}
+
 fn collect_handlers(
     file: &File,
     module_path: &str,
@@ -1176,6 +1520,7 @@ pub(crate) fn parse_routes_file(
         None
     };
     let mut routes = Vec::new();
+    let mut used_handlers = HashSet::new();
     for item in &parsed.items {
         match item {
             Item::Fn(item_fn) => {
@@ -1186,8 +1531,10 @@ pub(crate) fn parse_routes_file(
                     route_bindings: Some(&route_bindings),
                     api_prefix,
                     routes: Vec::new(),
+                    used_handlers: HashSet::new(),
                 };
                 visitor.visit_block(&item_fn.block);
+                used_handlers.extend(visitor.used_handlers);
                 routes.extend(visitor.routes);
             }
             Item::Impl(item_impl) => {
@@ -1202,14 +1549,19 @@ pub(crate) fn parse_routes_file(
                         route_bindings: Some(&route_bindings),
                         api_prefix,
                         routes: Vec::new(),
+                        used_handlers: HashSet::new(),
                     };
                     visitor.visit_block(&item_fn.block);
+                    used_handlers.extend(visitor.used_handlers);
                     routes.extend(visitor.routes);
                 }
             }
             _ => {}
         }
     }
+    for warning in unresolved_type_warnings(&handlers, &used_handlers, &source) {
+        println!("{warning}");
+    }
     routes
 }
 
@@ -1257,7 +1609,17 @@ fn crud_route_entries(
         .as_deref()
         .map(|name| describe_type_name(name, registry, context))
         .unwrap_or_else(|| "JSON".to_string());
-    let list_query_desc = describe_type_name("ListQuery", registry, context);
+    let model_doc = model_type
+        .as_deref()
+        .and_then(|name| registry.docs.get(name));
+    let list_query_desc = describe_query_type_name("ListQuery", registry, model_doc);
+    let detail_request = match registry.docs.get("DetailQuery") {
+        Some(_) => format!(
+            "path: Uuid | query: {}",
+            describe_query_type_name("DetailQuery", registry, None)
+        ),
+        None => "path: Uuid".to_string(),
+    };
     let list_response = replace_paginated_generic(
         &describe_type_name("PaginatedResponse", registry, context),
         &model_desc,
@@ -1266,18 +1628,40 @@ fn crud_route_entries(
         "{{ \"status\": u16, \"message\": String, \"data\": {} }}",
         model_desc
     );
-    let list_response = format!(
-        "{{ \"status\": u16, \"message\": String, \"data\": {} }}",
-        list_response
+    let bare_list = service.is_some_and(|name| context.bare_list_services.contains(name));
+    let list_response = if bare_list {
+        format!(
+            "Vec<{}> | headers: X-Total-Count: u64, Link: rel=\"next\"/\"prev\"",
+            model_desc
+        )
+    } else {
+        format!(
+            "{{ \"status\": u16, \"message\": String, \"data\": {} }}",
+            list_response
+        )
+    };
+    let create_status = if service.is_some_and(|name| context.ok_create_services.contains(name)) {
+        200
+    } else {
+        201
+    };
+    let create_response = format!(
+        "{} | status: {} | headers: Location: {}/{{id}}",
+        model_response, create_status, base
     );
     let delete_response = "{ \"status\": u16, \"message\": String, \"data\": JSON }".to_string();
+    let schema_path = format!("{}/schema", base);
+    let schema_response = format!(
+        "{{ \"status\": u16, \"message\": String, \"data\": Vec<{}> }}",
+        describe_type_name("ColumnSchema", registry, context)
+    );
     vec![
         RouteEntry {
             method: "POST".to_string(),
             path: base.to_string(),
             source: source.to_string(),
             request: model_desc.clone(),
-            response: model_response.clone(),
+            response: create_response,
             required_headers: build_required_headers(false, true),
             curl: build_curl("POST", base, &model_desc, false),
         },
@@ -1290,14 +1674,23 @@ fn crud_route_entries(
             required_headers: "None".to_string(),
             curl: build_curl("GET", base, &format!("query: {}", list_query_desc), false),
         },
+        RouteEntry {
+            method: "GET".to_string(),
+            path: schema_path.clone(),
+            source: source.to_string(),
+            request: "None".to_string(),
+            response: schema_response,
+            required_headers: "None".to_string(),
+            curl: build_curl("GET", &schema_path, "None", false),
+        },
         RouteEntry {
             method: "GET".to_string(),
             path: id_path.clone(),
             source: source.to_string(),
-            request: "path: Uuid".to_string(),
+            curl: build_curl("GET", &id_path, &detail_request, false),
+            request: detail_request,
             response: model_response.clone(),
             required_headers: "None".to_string(),
-            curl: build_curl("GET", &id_path, "path: Uuid", false),
         },
         RouteEntry {
             method: "PATCH".to_string(),
@@ -1406,6 +1799,8 @@ pub(crate) struct CrudTypeContext {
     pub(crate) service_to_dao: HashMap<String, String>,
     pub(crate) dao_to_entity: HashMap<String, String>,
     pub(crate) entity_to_model: HashMap<String, String>,
+    pub(crate) bare_list_services: HashSet<String>,
+    pub(crate) ok_create_services: HashSet<String>,
 }
 
 pub(crate) fn collect_crud_service_impls(file: &File, out: &mut HashMap<String, String>) {
@@ -1426,6 +1821,50 @@ pub(crate) fn collect_crud_service_impls(file: &File, out: &mut HashMap<String,
     }
 }
 
+/// Records services whose `list_envelope` returns `ListEnvelope::Bare`.
+pub(crate) fn collect_bare_list_services(file: &File, out: &mut HashSet<String>) {
+    collect_crud_service_overrides(file, "list_envelope", "ListEnvelope::Bare", out);
+}
+
+pub(crate) fn collect_ok_create_services(file: &File, out: &mut HashSet<String>) {
+    collect_crud_service_overrides(file, "create_status", "CreateStatus::Ok", out);
+}
+
+/// Collects `CrudService` impls whose `method` override mentions `variant`.
+fn collect_crud_service_overrides(
+    file: &File,
+    method_name: &str,
+    variant: &str,
+    out: &mut HashSet<String>,
+) {
+    for item in &file.items {
+        let Item::Impl(item_impl) = item else {
+            continue;
+        };
+        if !impl_uses_trait(item_impl, "CrudService") {
+            continue;
+        }
+        let Some(service) = type_from_type(&item_impl.self_ty) else {
+            continue;
+        };
+        let overridden = item_impl.items.iter().any(|item| {
+            let ImplItem::Fn(method) = item else {
+                return false;
+            };
+            method.sig.ident == method_name
+                && method
+                    .block
+                    .to_token_stream()
+                    .to_string()
+                    .replace(' ', "")
+                    .contains(variant)
+        });
+        if overridden {
+            out.insert(service);
+        }
+    }
+}
+
 pub(crate) fn collect_dao_base_impls(file: &File, out: &mut HashMap<String, String>) {
     for item in &file.items {
         let Item::Impl(item_impl) = item else {
@@ -1498,6 +1937,18 @@ fn describe_type_name(name: &str, registry: &TypeRegistry, context: &CrudTypeCon
         .unwrap_or_else(|| name.to_string())
 }
 
+fn describe_query_type_name(
+    name: &str,
+    registry: &TypeRegistry,
+    catch_all: Option<&TypeDoc>,
+) -> String {
+    registry
+        .docs
+        .get(name)
+        .map(|doc| render_query_fields(&collect_query_fields(doc, "", registry, catch_all, 0)))
+        .unwrap_or_else(|| name.to_string())
+}
+
 fn resolve_model_type(service: Option<&str>, context: &CrudTypeContext) -> Option<String> {
     let service = service?;
     let dao = context.service_to_dao.get(service)?;
@@ -1532,3 +1983,250 @@ pub(crate) fn write_routes(out_dir: &Path, routes: &[RouteEntry]) {
     std::fs::write(&out_path, output)
         .unwrap_or_else(|err| panic!("failed to write {}: {}", out_path.display(), err));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY_FIXTURE: &str = r#"
+        #[derive(Deserialize)]
+        pub struct Pagination {
+            pub page: Option<u64>,
+            #[serde(default)]
+            pub page_size: u64,
+        }
+
+        #[derive(Deserialize)]
+        pub struct ListParams {
+            #[serde(flatten)]
+            pub pagination: Pagination,
+            pub status: Option<String>,
+            #[serde(rename = "tag")]
+            pub tag_name: String,
+            #[serde(skip)]
+            pub internal: bool,
+        }
+
+        async fn list(Query(params): Query<ListParams>) -> ApiResult<Vec<String>> {
+            todo!()
+        }
+
+        pub fn router() -> Router {
+            Router::new().route("/items", get(list))
+        }
+    "#;
+
+    fn fixture_route() -> RouteEntry {
+        let file = syn::parse_file(QUERY_FIXTURE).expect("fixture should parse");
+        let mut registry = TypeRegistry::default();
+        collect_type_docs(&file, "routes::fixture", &mut registry);
+        let context = CrudTypeContext::default();
+        let handlers = collect_handlers(&file, "routes::fixture", &registry, &context);
+        let bindings = HashMap::new();
+        let mut visitor = RouteVisitor {
+            source: "fixture.rs".to_string(),
+            handlers: &handlers,
+            route_bindings: Some(&bindings),
+            api_prefix: Some(API_PREFIX),
+            routes: Vec::new(),
+            used_handlers: HashSet::new(),
+        };
+        for item in &file.items {
+            if let Item::Fn(item_fn) = item {
+                visitor.visit_block(&item_fn.block);
+            }
+        }
+        visitor.routes.pop().expect("fixture should define a route")
+    }
+
+    #[test]
+    fn query_struct_fields_are_expanded_in_docs() {
+        let route = fixture_route();
+
+        assert_eq!(
+            route.request,
+            "query: { \"page\"?: u64, \"page_size\"?: u64, \"status\"?: String, \"tag\": String }"
+        );
+    }
+
+    #[test]
+    fn query_struct_fields_are_included_in_curl() {
+        let route = fixture_route();
+
+        assert_eq!(
+            route.curl,
+            "curl -sS -X GET \"{BASE_URL}/api/v1/items?page=1&page_size=1&status=string&tag=string\""
+        );
+    }
+
+    #[test]
+    fn unresolved_handler_types_produce_warnings() {
+        let file = syn::parse_file(
+            r#"
+            #[derive(Serialize)]
+            pub struct Known {
+                pub id: Uuid,
+            }
+
+            pub struct NoSerde {
+                pub id: Uuid,
+            }
+
+            async fn create(Json(body): Json<NoSerde>) -> ApiResult<Known> {
+                todo!()
+            }
+
+            async fn page() -> Result<Html<String>, HtmlError> {
+                todo!()
+            }
+
+            fn helper() -> NoSerde {
+                todo!()
+            }
+            "#,
+        )
+        .expect("fixture should parse");
+        let mut registry = TypeRegistry::default();
+        collect_type_docs(&file, "routes::fixture", &mut registry);
+        let context = CrudTypeContext::default();
+        let handlers = collect_handlers(&file, "routes::fixture", &registry, &context);
+        let used = HashSet::from(["create".to_string(), "page".to_string()]);
+
+        let warnings = unresolved_type_warnings(&handlers, &used, "fixture.rs");
+
+        assert_eq!(
+            warnings,
+            vec![
+                "cargo:warning=Handler `create` in fixture.rs uses unresolved type `NoSerde`; derive Serialize/Deserialize on it to document its fields"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_list_services_document_array_response_with_headers() {
+        let file = syn::parse_file(
+            r#"
+            impl CrudService for TodoService {
+                type Dao = TodoDao;
+
+                fn list_envelope(&self) -> ListEnvelope {
+                    ListEnvelope::Bare
+                }
+            }
+
+            impl CrudService for NoteService {
+                type Dao = NoteDao;
+            }
+            "#,
+        )
+        .expect("fixture should parse");
+        let registry = TypeRegistry::default();
+        let mut context = CrudTypeContext::default();
+        collect_bare_list_services(&file, &mut context.bare_list_services);
+
+        let bare = crud_route_entries(
+            "/todos",
+            "todo.rs",
+            Some("TodoService"),
+            &registry,
+            &context,
+        );
+        let wrapped = crud_route_entries(
+            "/notes",
+            "note.rs",
+            Some("NoteService"),
+            &registry,
+            &context,
+        );
+
+        assert_eq!(
+            context.bare_list_services,
+            HashSet::from(["TodoService".to_string()])
+        );
+        assert_eq!(
+            bare[1].response,
+            "Vec<JSON> | headers: X-Total-Count: u64, Link: rel=\"next\"/\"prev\""
+        );
+        assert!(wrapped[1].response.starts_with("{ \"status\": u16"));
+    }
+
+    #[test]
+    fn create_route_documents_status_and_location_header() {
+        let file = syn::parse_file(
+            r#"
+            impl CrudService for TodoService {
+                type Dao = TodoDao;
+
+                fn create_status(&self) -> CreateStatus {
+                    CreateStatus::Ok
+                }
+            }
+            "#,
+        )
+        .expect("fixture should parse");
+        let registry = TypeRegistry::default();
+        let mut context = CrudTypeContext::default();
+        collect_ok_create_services(&file, &mut context.ok_create_services);
+
+        let ok = crud_route_entries(
+            "/todos",
+            "todo.rs",
+            Some("TodoService"),
+            &registry,
+            &context,
+        );
+        let created = crud_route_entries("/notes", "note.rs", None, &registry, &context);
+
+        assert!(
+            ok[0]
+                .response
+                .ends_with("| status: 200 | headers: Location: /todos/{id}")
+        );
+        assert!(
+            created[0]
+                .response
+                .ends_with("| status: 201 | headers: Location: /notes/{id}")
+        );
+    }
+
+    #[test]
+    fn flattened_filter_map_expands_to_catch_all_columns() {
+        let file = syn::parse_file(
+            r#"
+            #[derive(Deserialize)]
+            pub struct ListQuery {
+                pub page: Option<u64>,
+                #[serde(flatten, default)]
+                pub filters: HashMap<String, String>,
+            }
+
+            #[derive(Serialize)]
+            pub struct Model {
+                pub id: Uuid,
+                pub title: String,
+                pub due: Option<DateTimeWithTimeZone>,
+            }
+            "#,
+        )
+        .expect("fixture should parse");
+        let mut registry = TypeRegistry::default();
+        collect_type_docs(&file, "", &mut registry);
+
+        let without_columns = describe_query_type_name("ListQuery", &registry, None);
+        let with_columns =
+            describe_query_type_name("ListQuery", &registry, registry.docs.get("Model"));
+
+        assert_eq!(without_columns, "{ \"page\"?: u64, \"*\"?: String }");
+        assert_eq!(
+            with_columns,
+            "{ \"page\"?: u64, \"id\"?: Uuid, \"title\"?: String, \"due\"?: DateTimeWithTimeZone }"
+        );
+        assert_eq!(
+            build_query(&with_columns).as_deref(),
+            Some(
+                "page=1&id=00000000-0000-0000-0000-000000000000&title=string&due=2024-01-01T00:00:00Z"
+            )
+        );
+    }
+}
@@ -65,6 +65,24 @@ pub(crate) fn escape_rust_string(value: &str) -> String {
         .replace('\t', "\\t")
 }
 
+pub(crate) fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
 pub(crate) fn try_parse_rust_file(path: &Path) -> Result<File, ParseRustFileError> {
     let content = fs::read_to_string(path).map_err(|err| ParseRustFileError::read(path, err))?;
     syn::parse_file(&content).map_err(|err| ParseRustFileError::parse(path, err))
@@ -154,6 +172,7 @@ pub(crate) fn compact_type_string(mut value: String) -> String {
         (" ) ", ")"),
         (" [ ", "["),
         (" ] ", "]"),
+        (" >", ">"),
     ] {
         value = value.replace(from, to);
     }
@@ -0,0 +1,148 @@
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::db::{
+    dao::{AuthEventDao, DaoBase},
+    entities::auth_event,
+};
+
+const MAX_USER_AGENT_LEN: usize = 512;
+/// Request header with the client-chosen nonce hashed into its fingerprint.
+pub const CLIENT_NONCE_HEADER: &str = "x-client-nonce";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthEventType {
+    Login,
+    LoginFailed,
+    Register,
+    RegisterFailed,
+    Refresh,
+    RefreshFailed,
+    Logout,
+    Impersonate,
+    RoleChange,
+    PasswordChange,
+    PasswordChangeFailed,
+}
+
+impl AuthEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthEventType::Login => "login",
+            AuthEventType::LoginFailed => "login_failed",
+            AuthEventType::Register => "register",
+            AuthEventType::RegisterFailed => "register_failed",
+            AuthEventType::Refresh => "refresh",
+            AuthEventType::RefreshFailed => "refresh_failed",
+            AuthEventType::Logout => "logout",
+            AuthEventType::Impersonate => "impersonate",
+            AuthEventType::RoleChange => "role_change",
+            AuthEventType::PasswordChange => "password_change",
+            AuthEventType::PasswordChangeFailed => "password_change_failed",
+        }
+    }
+}
+
+/// Where an auth request came from, as recorded on audit rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthRequestMeta {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// Value of `X-Client-Nonce`, never recorded on audit rows.
+    pub client_nonce: Option<String>,
+}
+
+impl AuthRequestMeta {
+    pub fn new(ip: Option<String>, user_agent: Option<String>) -> Self {
+        let user_agent = user_agent.map(|value| value.chars().take(MAX_USER_AGENT_LEN).collect());
+        Self {
+            ip,
+            user_agent,
+            client_nonce: None,
+        }
+    }
+
+    pub fn with_client_nonce(mut self, nonce: Option<String>) -> Self {
+        self.client_nonce = nonce.filter(|nonce| !nonce.is_empty());
+        self
+    }
+
+    /// Hex SHA-256 of the user agent and client nonce, used to bind refresh
+    /// tokens to the client they were issued to. `None` without a nonce.
+    pub fn fingerprint(&self) -> Option<String> {
+        let nonce = self.client_nonce.as_deref()?;
+        let mut hasher = Sha256::new();
+        hasher.update(self.user_agent.as_deref().unwrap_or_default());
+        hasher.update(b"\n");
+        hasher.update(nonce);
+        Some(
+            hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+        )
+    }
+}
+
+/// Writes `auth_events` rows for a single request.
+///
+/// Recording is best-effort: a failed insert is logged and never changes the
+/// outcome of the auth operation being audited.
+#[derive(Clone)]
+pub struct AuthAuditLog {
+    dao: AuthEventDao,
+    meta: AuthRequestMeta,
+}
+
+impl AuthAuditLog {
+    pub fn new(dao: AuthEventDao, meta: AuthRequestMeta) -> Self {
+        Self { dao, meta }
+    }
+
+    pub async fn record(
+        &self,
+        event_type: AuthEventType,
+        user_id: Option<Uuid>,
+        subject: Option<&str>,
+        detail: Option<&str>,
+    ) {
+        let model = auth_event::ActiveModel {
+            event_type: Set(event_type.as_str().to_string()),
+            user_id: Set(user_id),
+            subject: Set(subject.map(str::to_string)),
+            ip: Set(self.meta.ip.clone()),
+            user_agent: Set(self.meta.user_agent.clone()),
+            detail: Set(detail.map(str::to_string)),
+            ..Default::default()
+        };
+        if let Err(err) = self.dao.create(model).await {
+            tracing::warn!(
+                event_type = event_type.as_str(),
+                error = %err,
+                "failed to record auth event"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AuthRequestMeta;
+
+    #[test]
+    fn fingerprint_requires_nonce_and_depends_on_user_agent() {
+        let meta = AuthRequestMeta::new(None, Some("agent-a".to_string()));
+        assert_eq!(meta.fingerprint(), None);
+
+        let first = meta.clone().with_client_nonce(Some("nonce".to_string()));
+        let other_agent = AuthRequestMeta::new(None, Some("agent-b".to_string()))
+            .with_client_nonce(Some("nonce".to_string()));
+        assert_eq!(first.fingerprint(), first.clone().fingerprint());
+        assert_ne!(first.fingerprint(), other_agent.fingerprint());
+        assert_eq!(first.fingerprint().map(|hash| hash.len()), Some(64));
+    }
+}
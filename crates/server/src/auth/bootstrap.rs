@@ -7,12 +7,27 @@ use super::{
     providers::{AuthProviders, LocalAuthProvider},
 };
 
+/// Returns an empty registry when auth is disabled, so auth routes and guards fail
+/// instead of verifying against an unset secret.
 pub fn build_providers(
     cfg: &AuthConfig,
     services: &ServiceContext,
 ) -> Result<AuthProviders, AppError> {
-    let jwt = JwtKeys::from_secret(cfg.jwt_secret.as_bytes());
-    let local_provider = LocalAuthProvider::new(services.user(), services.refresh_token_dao(), jwt);
+    if !cfg.enabled {
+        return Ok(AuthProviders::new(cfg.provider));
+    }
+    let jwt = JwtKeys::from_config(cfg);
+    let local_provider = LocalAuthProvider::new(
+        services.user(),
+        services.refresh_token_dao(),
+        services.invite_dao(),
+        jwt,
+    )
+    .with_registration_mode(cfg.registration_mode)
+    .with_single_session(cfg.single_session)
+    .with_refresh_token_binding(cfg.bind_refresh_tokens)
+    .with_password_kdf(cfg.password_kdf)
+    .with_password_history(services.password_history_dao(), cfg.password_history);
     let mut providers = AuthProviders::new(cfg.provider).with_provider(Arc::new(local_provider))?;
     providers.set_active(cfg.provider)?;
     Ok(providers)
@@ -23,6 +38,33 @@ pub async fn init_providers(
     services: &ServiceContext,
 ) -> anyhow::Result<AuthProviders> {
     let providers = build_providers(auth_cfg, services)?;
+    if !auth_cfg.enabled {
+        tracing::warn!("auth is disabled; skipping admin seed");
+        return Ok(providers);
+    }
     services.auth(&providers).seed_admin(auth_cfg).await?;
     Ok(providers)
 }
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    use super::init_providers;
+    use crate::{config::AuthConfig, services::ServiceContext};
+
+    #[tokio::test]
+    async fn disabled_auth_starts_without_secret_or_admin_seed() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let services = ServiceContext::new(&db);
+        let cfg: AuthConfig =
+            serde_json::from_value(serde_json::json!({ "enabled": false })).expect("auth config");
+
+        let providers = init_providers(&cfg, &services)
+            .await
+            .expect("disabled auth should start");
+
+        assert!(providers.active().is_err());
+        assert!(db.into_transaction_log().is_empty());
+    }
+}
@@ -1,14 +1,23 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, encode};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation, decode, decode_header,
+    encode,
+};
 
 use super::{Claims, Role};
-use crate::error::AppError;
+use crate::{config::AuthConfig, error::AppError};
 
+/// Signing key plus the keyset accepted during verification.
+///
+/// Tokens carrying a `kid` header are verified with the matching key only;
+/// tokens without one fall back to the current key.
 #[derive(Clone)]
 pub struct JwtKeys {
     pub enc: EncodingKey,
     pub dec: DecodingKey,
+    pub kid: Option<String>,
+    verification_keys: Vec<(String, DecodingKey)>,
 }
 
 impl JwtKeys {
@@ -16,8 +25,46 @@ impl JwtKeys {
         Self {
             enc: EncodingKey::from_secret(secret),
             dec: DecodingKey::from_secret(secret),
+            kid: None,
+            verification_keys: Vec::new(),
         }
     }
+
+    pub fn from_config(cfg: &AuthConfig) -> Self {
+        let keys = Self::from_secret(cfg.jwt_secret.as_bytes());
+        let keys = match &cfg.jwt_kid {
+            Some(kid) => keys.with_kid(kid.clone()),
+            None => keys,
+        };
+        cfg.jwt_verification_keys.iter().fold(keys, |keys, key| {
+            keys.with_verification_key(key.kid.clone(), key.secret.as_bytes())
+        })
+    }
+
+    pub fn with_kid(mut self, kid: impl Into<String>) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    pub fn with_verification_key(mut self, kid: impl Into<String>, secret: &[u8]) -> Self {
+        self.verification_keys
+            .push((kid.into(), DecodingKey::from_secret(secret)));
+        self
+    }
+
+    pub fn decoding_key(&self, kid: Option<&str>) -> Result<&DecodingKey, AppError> {
+        let Some(kid) = kid else {
+            return Ok(&self.dec);
+        };
+        if self.kid.as_deref() == Some(kid) {
+            return Ok(&self.dec);
+        }
+        self.verification_keys
+            .iter()
+            .find(|(candidate, _)| candidate == kid)
+            .map(|(_, key)| key)
+            .ok_or_else(|| AppError::bad_request("Invalid or expired token: unknown key id"))
+    }
 }
 
 pub fn now_unix() -> usize {
@@ -30,19 +77,32 @@ pub fn now_unix() -> usize {
 pub fn encode_token(keys: &JwtKeys, claims: &Claims) -> Result<String, AppError> {
     let mut header = Header::new(Algorithm::HS256);
     header.typ = Some("JWT".into());
+    header.kid = keys.kid.clone();
 
     encode(&header, claims, &keys.enc)
         .map_err(|err| AppError::bad_request(format!("Token encoding failed: {err}")))
 }
 
+pub fn decode_token(
+    keys: &JwtKeys,
+    token: &str,
+    validation: &Validation,
+) -> Result<TokenData<Claims>, AppError> {
+    let header = decode_header(token)?;
+    let key = keys.decoding_key(header.kid.as_deref())?;
+    Ok(decode::<Claims>(token, key, validation)?)
+}
+
 pub fn make_access_claims(user_id: &uuid::Uuid, roles: Vec<Role>, ttl_secs: usize) -> Claims {
     let iat = now_unix();
     let exp = iat + ttl_secs;
     Claims {
         sub: user_id.to_string(),
         roles,
+        scopes: Vec::new(),
         iat,
         exp,
+        act: None,
     }
 }
 
@@ -59,7 +119,7 @@ mod tests {
 
     use crate::auth::Claims;
 
-    use super::{JwtKeys, Role, encode_token, make_access_claims};
+    use super::{JwtKeys, Role, decode_token, encode_token, make_access_claims};
 
     #[test]
     fn makes_claims_with_expected_subject_roles_and_ttl() {
@@ -88,6 +148,37 @@ mod tests {
         assert_eq!(decoded.claims.exp, claims.exp);
     }
 
+    #[test]
+    fn decode_token_accepts_retired_key_still_in_keyset() {
+        let retired = JwtKeys::from_secret(b"old-secret").with_kid("2025-12");
+        let current = JwtKeys::from_secret(b"new-secret")
+            .with_kid("2026-01")
+            .with_verification_key("2025-12", b"old-secret");
+        let claims = make_access_claims(&Uuid::new_v4(), vec![Role::User], 600);
+        let token = encode_token(&retired, &claims).expect("token should encode");
+
+        let decoded = decode_token(&current, &token, &Validation::new(Algorithm::HS256))
+            .expect("retired key should still verify");
+
+        assert_eq!(decoded.header.kid.as_deref(), Some("2025-12"));
+        assert_eq!(decoded.claims.sub, claims.sub);
+    }
+
+    #[test]
+    fn decode_token_rejects_unknown_kid() {
+        let unknown = JwtKeys::from_secret(b"new-secret").with_kid("rogue");
+        let current = JwtKeys::from_secret(b"new-secret")
+            .with_kid("2026-01")
+            .with_verification_key("2025-12", b"old-secret");
+        let claims = make_access_claims(&Uuid::new_v4(), vec![Role::User], 600);
+        let token = encode_token(&unknown, &claims).expect("token should encode");
+
+        let err = decode_token(&current, &token, &Validation::new(Algorithm::HS256))
+            .expect_err("unknown kid should be rejected");
+
+        assert_eq!(err.message(), "Invalid or expired token: unknown key id");
+    }
+
     #[test]
     fn decode_error_maps_to_bad_request() {
         let err = crate::error::AppError::from(
@@ -0,0 +1,275 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::AuthConfig,
+    db::dao::{DaoContext, LoginAttemptDao},
+    error::AppError,
+    window_counter::FixedWindowCounter,
+};
+
+/// Where failed login counts are kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockoutBackend {
+    /// Per process; lost on restart and not shared between instances.
+    #[default]
+    Memory,
+    /// The `login_attempts` table, shared by every instance on the database.
+    Database,
+}
+
+/// Failed login counters keyed by normalized email.
+#[async_trait]
+pub trait LockoutStore: Send + Sync {
+    /// Failures recorded for `key` in its current, unexpired window.
+    async fn failures(&self, key: &str) -> Result<u32, AppError>;
+
+    /// Counts one failure and returns the new total. The first failure after
+    /// the previous window lapsed starts a new one of length `window`.
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, AppError>;
+
+    async fn reset(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Per-process failure counts.
+#[derive(Default)]
+pub struct MemoryLockoutStore {
+    counter: FixedWindowCounter,
+}
+
+impl MemoryLockoutStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl LockoutStore for MemoryLockoutStore {
+    async fn failures(&self, key: &str) -> Result<u32, AppError> {
+        Ok(self.counter.hits(key))
+    }
+
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, AppError> {
+        Ok(self.counter.hit(key, window, u32::MAX).unwrap_or(u32::MAX))
+    }
+
+    async fn reset(&self, key: &str) -> Result<(), AppError> {
+        self.counter.reset(key);
+        Ok(())
+    }
+}
+
+pub struct DbLockoutStore {
+    dao: LoginAttemptDao,
+}
+
+impl DbLockoutStore {
+    pub fn new(dao: LoginAttemptDao) -> Self {
+        Self { dao }
+    }
+}
+
+#[async_trait]
+impl LockoutStore for DbLockoutStore {
+    async fn failures(&self, key: &str) -> Result<u32, AppError> {
+        Ok(self.dao.failures(key).await?)
+    }
+
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, AppError> {
+        Ok(self.dao.record_failure(key, window).await?)
+    }
+
+    async fn reset(&self, key: &str) -> Result<(), AppError> {
+        Ok(self.dao.clear(key).await?)
+    }
+}
+
+/// Refuses logins for an email once `max_attempts` failed within `window`.
+///
+/// Only wrong credentials count. The lockout lifts when the window that the
+/// first failure opened runs out, or on the next successful login before that.
+#[derive(Clone)]
+pub struct LoginLockout {
+    store: Arc<dyn LockoutStore>,
+    max_attempts: u32,
+    window: Duration,
+}
+
+impl LoginLockout {
+    pub fn new(store: Arc<dyn LockoutStore>, max_attempts: u32, window: Duration) -> Self {
+        Self {
+            store,
+            max_attempts,
+            window,
+        }
+    }
+
+    /// `None` when auth is disabled or `lockout.max_attempts` is `0`.
+    pub fn from_config(cfg: &AuthConfig, daos: &DaoContext) -> Option<Self> {
+        let lockout = &cfg.lockout;
+        if !cfg.enabled || lockout.max_attempts == 0 {
+            return None;
+        }
+        let store: Arc<dyn LockoutStore> = match lockout.backend {
+            LockoutBackend::Memory => Arc::new(MemoryLockoutStore::new()),
+            LockoutBackend::Database => Arc::new(DbLockoutStore::new(daos.login_attempt())),
+        };
+        Some(Self::new(
+            store,
+            lockout.max_attempts,
+            Duration::from_secs(lockout.window_secs),
+        ))
+    }
+
+    pub async fn check(&self, email: &str) -> Result<(), AppError> {
+        if self.store.failures(&lockout_key(email)).await? >= self.max_attempts {
+            return Err(AppError::too_many_requests(
+                "Too many failed login attempts; try again later",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Counts a wrong-credentials failure or clears the count after a success.
+    /// Store errors are logged rather than changing the login outcome.
+    pub async fn record<T>(&self, email: &str, result: &Result<T, AppError>) {
+        let key = lockout_key(email);
+        let outcome = match result {
+            Ok(_) => self.store.reset(&key).await,
+            Err(AppError::Unauthorized(_)) => self
+                .store
+                .record_failure(&key, self.window)
+                .await
+                .map(|_| ()),
+            Err(_) => return,
+        };
+        if let Err(err) = outcome {
+            tracing::warn!(error = %err, "failed to update login lockout");
+        }
+    }
+}
+
+fn lockout_key(email: &str) -> String {
+    email.trim().to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use sea_orm::{ConnectOptions, Database};
+
+    use super::{DbLockoutStore, LockoutStore, LoginLockout, MemoryLockoutStore};
+    use crate::{
+        db::{
+            dao::{DaoBase, LoginAttemptDao},
+            entities::login_attempt,
+        },
+        error::AppError,
+    };
+
+    const WINDOW: Duration = Duration::from_millis(200);
+
+    fn wrong_password() -> Result<(), AppError> {
+        Err(AppError::unauthorized("Invalid credentials"))
+    }
+
+    async fn db_store() -> Arc<dyn LockoutStore> {
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+        let db = Database::connect(options)
+            .await
+            .expect("sqlite should connect");
+        db.get_schema_builder()
+            .register(login_attempt::Entity)
+            .sync(&db)
+            .await
+            .expect("schema should sync");
+        Arc::new(DbLockoutStore::new(LoginAttemptDao::new(&db)))
+    }
+
+    async fn locks_at_threshold_then_recovers(store: Arc<dyn LockoutStore>) {
+        let lockout = LoginLockout::new(store, 3, WINDOW);
+
+        for _ in 0..3 {
+            lockout
+                .check("Ada@Example.com")
+                .await
+                .expect("not locked yet");
+            lockout.record("Ada@Example.com", &wrong_password()).await;
+        }
+        let err = lockout
+            .check(" ada@example.com ")
+            .await
+            .expect_err("email should be locked");
+        assert!(matches!(err, AppError::TooManyRequests(_)));
+        lockout
+            .check("bob@example.com")
+            .await
+            .expect("other emails stay open");
+
+        tokio::time::sleep(WINDOW + Duration::from_millis(50)).await;
+        lockout
+            .check("ada@example.com")
+            .await
+            .expect("lockout should lift after the window");
+    }
+
+    async fn success_resets_failures(store: Arc<dyn LockoutStore>) {
+        let lockout = LoginLockout::new(store, 2, WINDOW);
+
+        lockout.record("ada@example.com", &wrong_password()).await;
+        lockout.record("ada@example.com", &Ok(())).await;
+        lockout.record("ada@example.com", &wrong_password()).await;
+        lockout
+            .check("ada@example.com")
+            .await
+            .expect("success should have cleared earlier failures");
+
+        lockout
+            .record(
+                "ada@example.com",
+                &Err::<(), _>(AppError::internal("database down")),
+            )
+            .await;
+        lockout
+            .check("ada@example.com")
+            .await
+            .expect("non-credential errors are not counted");
+    }
+
+    #[tokio::test]
+    async fn memory_store_locks_and_recovers() {
+        locks_at_threshold_then_recovers(Arc::new(MemoryLockoutStore::new())).await;
+        success_resets_failures(Arc::new(MemoryLockoutStore::new())).await;
+    }
+
+    #[tokio::test]
+    async fn database_store_locks_and_recovers() {
+        locks_at_threshold_then_recovers(db_store().await).await;
+        success_resets_failures(db_store().await).await;
+    }
+
+    #[tokio::test]
+    async fn memory_store_restarts_lapsed_windows_and_prunes_on_the_next_sweep() {
+        let store = MemoryLockoutStore::new();
+
+        assert_eq!(
+            store.record_failure("ada", Duration::ZERO).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            store.record_failure("ada", Duration::ZERO).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            store.record_failure("bob", Duration::ZERO).await.unwrap(),
+            1
+        );
+
+        assert_eq!(store.counter.tracked_keys(), 1);
+    }
+}
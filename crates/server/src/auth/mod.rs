@@ -1,7 +1,15 @@
+pub mod audit;
 pub mod bootstrap;
 pub mod jwt;
+pub mod lockout;
 pub mod password;
 pub mod providers;
+mod token;
 mod types;
 
-pub use types::{AdminRole, Claims, RequiredRole, Role, TokenBundle, UserRole};
+pub use audit::{AuthAuditLog, AuthEventType, AuthRequestMeta, CLIENT_NONCE_HEADER};
+pub use token::{download_token, request_token};
+pub use types::{
+    Actor, AdminRole, Claims, ImpersonationToken, RegistrationMode, RequiredRole, RequiredScope,
+    Role, TokenBundle, UserRole,
+};
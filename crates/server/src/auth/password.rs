@@ -3,25 +3,60 @@ use argon2::{
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
 };
 use rand::thread_rng;
+use serde::{Deserialize, Serialize};
 
 use crate::error::AppError;
 
 const MIN_PASSWORD_LEN: usize = 8;
+const BCRYPT_PREFIXES: [&str; 3] = ["$2a$", "$2b$", "$2y$"];
 
+/// Key derivation function used for new password hashes.
+///
+/// Verification picks the KDF from the stored hash prefix, so switching this only
+/// affects hashes written afterwards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordKdf {
+    #[default]
+    Argon2id,
+    Bcrypt,
+}
+
+/// Hashes with the default KDF ([`PasswordKdf::Argon2id`]).
 pub fn hash_password(password: &str) -> Result<String, AppError> {
+    hash_password_with(password, PasswordKdf::default())
+}
+
+pub fn hash_password_with(password: &str, kdf: PasswordKdf) -> Result<String, AppError> {
     if password.len() < MIN_PASSWORD_LEN {
         return Err(AppError::bad_request("Password too short"));
     }
 
-    let salt = SaltString::generate(&mut thread_rng());
-    let hash = Argon2::default()
-        .hash_password(password.as_bytes(), &salt)
-        .map_err(|err| AppError::bad_request(format!("Password hashing failed: {err}")))?
-        .to_string();
-    Ok(hash)
+    match kdf {
+        PasswordKdf::Argon2id => {
+            let salt = SaltString::generate(&mut thread_rng());
+            let hash = Argon2::default()
+                .hash_password(password.as_bytes(), &salt)
+                .map_err(|err| AppError::bad_request(format!("Password hashing failed: {err}")))?
+                .to_string();
+            Ok(hash)
+        }
+        PasswordKdf::Bcrypt => bcrypt::hash(password, bcrypt::DEFAULT_COST)
+            .map_err(|err| AppError::bad_request(format!("Password hashing failed: {err}"))),
+    }
 }
 
+/// Checks `password` against an argon2 PHC string or a `$2a$`/`$2b$`/`$2y$`
+/// bcrypt hash.
 pub fn verify_password(password: &str, hash: &str) -> Result<bool, AppError> {
+    if BCRYPT_PREFIXES
+        .iter()
+        .any(|prefix| hash.starts_with(prefix))
+    {
+        return bcrypt::verify(password, hash)
+            .map_err(|err| AppError::bad_request(format!("Invalid password hash: {err}")));
+    }
+
     let parsed = PasswordHash::new(hash)
         .map_err(|err| AppError::bad_request(format!("Invalid password hash: {err}")))?;
 
@@ -32,7 +67,7 @@ pub fn verify_password(password: &str, hash: &str) -> Result<bool, AppError> {
 
 #[cfg(test)]
 mod tests {
-    use super::{hash_password, verify_password};
+    use super::{PasswordKdf, hash_password, hash_password_with, verify_password};
 
     #[test]
     fn hash_password_rejects_password_shorter_than_min_len() {
@@ -72,4 +107,23 @@ mod tests {
             err.message()
         );
     }
+
+    #[test]
+    fn verify_password_accepts_bcrypt_hash() {
+        let hash = bcrypt::hash("correct-horse-battery-staple", 4).expect("bcrypt hash");
+
+        assert!(verify_password("correct-horse-battery-staple", &hash).expect("verify"));
+        assert!(!verify_password("wrong-password", &hash).expect("verify"));
+    }
+
+    #[test]
+    fn new_hashes_use_the_configured_kdf() {
+        let default = hash_password("correct-horse-battery-staple").expect("hash should succeed");
+        assert!(default.starts_with("$argon2id$"));
+
+        let bcrypt = hash_password_with("correct-horse-battery-staple", PasswordKdf::Bcrypt)
+            .expect("hash should succeed");
+        assert!(bcrypt.starts_with("$2b$"));
+        assert!(verify_password("correct-horse-battery-staple", &bcrypt).expect("verify"));
+    }
 }
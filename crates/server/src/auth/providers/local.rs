@@ -1,13 +1,17 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use sea_orm::{TransactionError, TransactionTrait};
+use uuid::Uuid;
 
 use crate::{
     auth::{
-        Claims, Role, TokenBundle,
-        jwt::{JwtKeys, encode_token, make_access_claims},
-        password::{hash_password, verify_password},
+        Actor, Claims, ImpersonationToken, RegistrationMode, Role, TokenBundle,
+        jwt::{JwtKeys, decode_token, encode_token, make_access_claims},
+        password::{PasswordKdf, hash_password_with, verify_password},
     },
     config::AuthConfig,
-    db::dao::RefreshTokenDao,
+    db::dao::{DaoBase, DaoLayerError, InviteDao, PasswordHistoryDao, RefreshTokenDao},
     db::entities,
     error::AppError,
     services::user_service::UserService,
@@ -17,39 +21,147 @@ use super::{AuthProvider, AuthProviderId};
 
 const ACCESS_TTL_SECS: usize = 15 * 60; // 15 minutes
 const REFRESH_TTL_DAYS: i64 = 30;
+const IMPERSONATION_TTL_SECS: usize = 10 * 60; // 10 minutes
+
+/// Scopes to put in a user's access tokens.
+pub type ScopeResolver = Arc<dyn Fn(&entities::user::Model) -> Vec<String> + Send + Sync>;
 
 #[derive(Clone)]
 pub struct LocalAuthProvider {
     user_service: UserService,
     refresh_token_dao: RefreshTokenDao,
+    invite_dao: InviteDao,
     jwt: JwtKeys,
+    registration_mode: RegistrationMode,
+    single_session: bool,
+    bind_refresh_tokens: bool,
+    password_kdf: PasswordKdf,
+    password_history: Option<(PasswordHistoryDao, u64)>,
+    scope_resolver: Option<ScopeResolver>,
 }
 
 impl LocalAuthProvider {
     pub fn new(
         user_service: UserService,
         refresh_token_dao: RefreshTokenDao,
+        invite_dao: InviteDao,
         jwt: JwtKeys,
     ) -> Self {
         Self {
             user_service,
             refresh_token_dao,
+            invite_dao,
             jwt,
+            registration_mode: RegistrationMode::Open,
+            single_session: false,
+            bind_refresh_tokens: false,
+            password_kdf: PasswordKdf::default(),
+            password_history: None,
+            scope_resolver: None,
         }
     }
 
-    async fn issue_tokens(&self, user: &entities::user::Model) -> Result<TokenBundle, AppError> {
-        let primary_role = Role::try_from(user.role.as_str()).unwrap_or(Role::User);
-        let mut roles = vec![primary_role.clone()];
-        if matches!(primary_role, Role::Admin) {
-            roles.push(Role::User);
-        }
-        let claims = make_access_claims(&user.id, roles, ACCESS_TTL_SECS);
+    pub fn with_registration_mode(mut self, mode: RegistrationMode) -> Self {
+        self.registration_mode = mode;
+        self
+    }
+
+    /// Revoke the user's existing refresh tokens on each successful login.
+    pub fn with_single_session(mut self, enabled: bool) -> Self {
+        self.single_session = enabled;
+        self
+    }
+
+    /// Bind refresh tokens to the client fingerprint they were issued to and
+    /// refuse to refresh them from any other.
+    pub fn with_refresh_token_binding(mut self, enabled: bool) -> Self {
+        self.bind_refresh_tokens = enabled;
+        self
+    }
+
+    /// KDF for hashes written on register and admin seed.
+    pub fn with_password_kdf(mut self, kdf: PasswordKdf) -> Self {
+        self.password_kdf = kdf;
+        self
+    }
+
+    /// Refuses password changes that reuse the current password or any of the
+    /// `keep` before it. `0` turns the check off.
+    pub fn with_password_history(mut self, dao: PasswordHistoryDao, keep: u32) -> Self {
+        self.password_history = (keep > 0).then_some((dao, u64::from(keep)));
+        self
+    }
+
+    /// Grants scopes per user at token issue. Without a resolver tokens carry none.
+    pub fn with_scope_resolver(mut self, resolver: ScopeResolver) -> Self {
+        self.scope_resolver = Some(resolver);
+        self
+    }
+
+    /// Creates the account and redeems `invite_token` in one transaction, so
+    /// a failed insert leaves the invite unused and a lost redemption race
+    /// leaves no account behind.
+    async fn create_invited_user(
+        &self,
+        invite_token: &str,
+        email: &str,
+        password_hash: &str,
+    ) -> Result<entities::user::Model, AppError> {
+        let invite_dao = self.invite_dao.clone();
+        let user_service = self.user_service.clone();
+        let (invite_token, email, password_hash) = (
+            invite_token.to_string(),
+            email.to_string(),
+            password_hash.to_string(),
+        );
+        self.invite_dao
+            .db()
+            .transaction::<_, _, AppError>(|txn| {
+                Box::pin(async move {
+                    let invite = invite_dao
+                        .redeem_in(txn, &invite_token)
+                        .await?
+                        .ok_or_else(|| AppError::forbidden("Invalid invite token"))?;
+                    let role = Role::try_from(invite.role.as_str()).unwrap_or(Role::User);
+                    user_service
+                        .create_user_in(txn, &email, &password_hash, role.as_str())
+                        .await
+                })
+            })
+            .await
+            .map_err(|err| match err {
+                TransactionError::Connection(err) => DaoLayerError::from(err).into(),
+                TransactionError::Transaction(err) => err,
+            })
+    }
+
+    fn roles_for(user: &entities::user::Model) -> Vec<Role> {
+        Role::granted_for(&user.role)
+    }
+
+    fn scopes_for(&self, user: &entities::user::Model) -> Vec<String> {
+        self.scope_resolver
+            .as_ref()
+            .map(|resolve| resolve(user))
+            .unwrap_or_default()
+    }
+
+    async fn issue_tokens(
+        &self,
+        user: &entities::user::Model,
+        fingerprint: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
+        let mut claims = make_access_claims(&user.id, Self::roles_for(user), ACCESS_TTL_SECS);
+        claims.scopes = self.scopes_for(user);
         let access_token = encode_token(&self.jwt, &claims)?;
 
         let refresh = self
             .refresh_token_dao
-            .create_refresh_token(&user.id, Some(REFRESH_TTL_DAYS))
+            .create_bound_refresh_token(
+                &user.id,
+                Some(REFRESH_TTL_DAYS),
+                fingerprint.filter(|_| self.bind_refresh_tokens),
+            )
             .await?;
 
         Ok(TokenBundle {
@@ -67,26 +179,69 @@ impl AuthProvider for LocalAuthProvider {
         AuthProviderId::Local
     }
 
-    async fn register(&self, email: &str, password: &str) -> Result<TokenBundle, AppError> {
+    async fn register(
+        &self,
+        email: &str,
+        password: &str,
+        invite_token: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
+        self.register_bound(email, password, invite_token, None)
+            .await
+    }
+
+    async fn login(&self, email: &str, password: &str) -> Result<TokenBundle, AppError> {
+        self.login_bound(email, password, None).await
+    }
+
+    async fn refresh(&self, refresh_token: &str) -> Result<TokenBundle, AppError> {
+        self.refresh_bound(refresh_token, None).await
+    }
+
+    async fn register_bound(
+        &self,
+        email: &str,
+        password: &str,
+        invite_token: Option<&str>,
+        fingerprint: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
+        if self.registration_mode == RegistrationMode::Closed {
+            return Err(AppError::forbidden("Registration is closed"));
+        }
+
         let email = email.trim();
         if email.is_empty() {
             return Err(AppError::bad_request("Email required"));
         }
 
-        if self.user_service.find_by_email(email).await?.is_some() {
+        if self.user_service.email_taken(email).await? {
             return Err(AppError::conflict("User already exists"));
         }
 
-        let password_hash = hash_password(password)?;
-        let user = self
-            .user_service
-            .create_user(email, &password_hash, Role::User.as_str())
-            .await?;
+        let password_hash = hash_password_with(password, self.password_kdf)?;
+        let user = match (self.registration_mode, invite_token) {
+            (RegistrationMode::Invite, None) => {
+                return Err(AppError::forbidden("Invite token required"));
+            }
+            (_, None) => {
+                self.user_service
+                    .create_user(email, &password_hash, Role::User.as_str())
+                    .await?
+            }
+            (_, Some(token)) => {
+                self.create_invited_user(token, email, &password_hash)
+                    .await?
+            }
+        };
 
-        self.issue_tokens(&user).await
+        self.issue_tokens(&user, fingerprint).await
     }
 
-    async fn login(&self, email: &str, password: &str) -> Result<TokenBundle, AppError> {
+    async fn login_bound(
+        &self,
+        email: &str,
+        password: &str,
+        fingerprint: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
         let user = self
             .user_service
             .find_by_email(email)
@@ -100,11 +255,18 @@ impl AuthProvider for LocalAuthProvider {
 
         let now = chrono::Utc::now().fixed_offset();
         self.user_service.set_last_login(&user.id, &now).await?;
+        if self.single_session {
+            self.refresh_token_dao.revoke_all_for_user(&user.id).await?;
+        }
 
-        self.issue_tokens(&user).await
+        self.issue_tokens(&user, fingerprint).await
     }
 
-    async fn refresh(&self, refresh_token: &str) -> Result<TokenBundle, AppError> {
+    async fn refresh_bound(
+        &self,
+        refresh_token: &str,
+        fingerprint: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
         let token = self
             .refresh_token_dao
             .find_active_by_token(refresh_token)
@@ -114,6 +276,15 @@ impl AuthProvider for LocalAuthProvider {
         if token.expires_at < chrono::Utc::now().fixed_offset() || token.revoked {
             return Err(AppError::unauthorized("Refresh token expired"));
         }
+        // Tokens issued before binding was enabled carry no fingerprint.
+        if self.bind_refresh_tokens
+            && let Some(expected) = token.fingerprint_hash.as_deref()
+            && fingerprint != Some(expected)
+        {
+            return Err(AppError::unauthorized(
+                "Refresh token was issued to another client",
+            ));
+        }
 
         let user = self
             .user_service
@@ -123,16 +294,96 @@ impl AuthProvider for LocalAuthProvider {
 
         self.refresh_token_dao.revoke_token(refresh_token).await?;
 
-        self.issue_tokens(&user).await
+        self.issue_tokens(&user, fingerprint).await
+    }
+
+    async fn logout(&self, refresh_token: &str) -> Result<Uuid, AppError> {
+        let token = self
+            .refresh_token_dao
+            .find_active_by_token(refresh_token)
+            .await?
+            .ok_or_else(|| AppError::unauthorized("Invalid refresh token"))?;
+        self.refresh_token_dao.revoke_token(refresh_token).await?;
+        Ok(token.user_id)
     }
 
     async fn verify(&self, access_token: &str) -> Result<Claims, AppError> {
         let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
         validation.validate_exp = true;
-        let data = jsonwebtoken::decode::<Claims>(access_token, &self.jwt.dec, &validation)?;
+        let data = decode_token(&self.jwt, access_token, &validation)?;
         Ok(data.claims)
     }
 
+    async fn impersonate(
+        &self,
+        actor: &Claims,
+        user_id: Uuid,
+    ) -> Result<ImpersonationToken, AppError> {
+        if actor.act.is_some() {
+            return Err(AppError::forbidden(
+                "Impersonation tokens cannot impersonate other users",
+            ));
+        }
+        if actor.sub == user_id.to_string() {
+            return Err(AppError::bad_request("Cannot impersonate yourself"));
+        }
+
+        let user = self
+            .user_service
+            .find_by_id(&user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("User not found"))?;
+
+        let mut claims =
+            make_access_claims(&user.id, Self::roles_for(&user), IMPERSONATION_TTL_SECS);
+        claims.scopes = self.scopes_for(&user);
+        claims.act = Some(Actor {
+            sub: actor.sub.clone(),
+        });
+        let access_token = encode_token(&self.jwt, &claims)?;
+
+        Ok(ImpersonationToken {
+            access_token,
+            token_type: "Bearer",
+            expires_in: IMPERSONATION_TTL_SECS,
+            impersonator: actor.sub.clone(),
+        })
+    }
+
+    async fn change_password(
+        &self,
+        user_id: Uuid,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<(), AppError> {
+        let user = self
+            .user_service
+            .find_by_id(&user_id)
+            .await?
+            .ok_or_else(|| AppError::unauthorized("User no longer exists"))?;
+        if !verify_password(current_password, &user.password_hash)? {
+            return Err(AppError::unauthorized("Invalid credentials"));
+        }
+
+        if let Some((history, keep)) = &self.password_history {
+            let previous = history.recent_hashes(&user.id, *keep).await?;
+            for hash in std::iter::once(&user.password_hash).chain(&previous) {
+                if verify_password(new_password, hash)? {
+                    return Err(AppError::bad_request("Password was used recently"));
+                }
+            }
+        }
+
+        let password_hash = hash_password_with(new_password, self.password_kdf)?;
+        self.user_service
+            .set_password_hash(&user.id, &password_hash)
+            .await?;
+        if let Some((history, keep)) = &self.password_history {
+            history.record(&user.id, &user.password_hash, *keep).await?;
+        }
+        Ok(())
+    }
+
     async fn seed_admin(&self, cfg: &AuthConfig) -> anyhow::Result<()> {
         if let Some(existing) = self
             .user_service
@@ -144,7 +395,7 @@ impl AuthProvider for LocalAuthProvider {
             return Ok(());
         }
 
-        let hash = hash_password(&cfg.admin_password)
+        let hash = hash_password_with(&cfg.admin_password, cfg.password_kdf)
             .map_err(|e| anyhow::anyhow!("admin seed hash error: {e}"))?;
         let user = self
             .user_service
@@ -158,27 +409,35 @@ impl AuthProvider for LocalAuthProvider {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use chrono::{Duration, FixedOffset, TimeZone, Utc};
-    use sea_orm::{DatabaseBackend, DbErr, IntoMockRow, MockDatabase, MockExecResult};
+    use sea_orm::{
+        DatabaseBackend, DatabaseConnection, DbErr, IntoMockRow, MockDatabase, MockExecResult,
+        Value,
+    };
     use uuid::Uuid;
 
     use crate::{
         auth::{
-            Role,
+            Actor, RegistrationMode, Role,
             jwt::{encode_token, make_access_claims},
-            password::hash_password,
+            password::{PasswordKdf, hash_password, verify_password},
             providers::AuthProvider,
         },
-        config::AuthConfig,
-        db::entities::{refresh_token, user},
+        config::{AuthConfig, LockoutConfig, TokenSourceConfig},
+        db::dao::invite_dao::hash_invite_token,
+        db::entities::{invite, password_history, refresh_token, user},
+        error::AppError,
         services::ServiceContext,
     };
 
-    use super::{ACCESS_TTL_SECS, AuthProviderId, LocalAuthProvider};
+    use super::{ACCESS_TTL_SECS, AuthProviderId, IMPERSONATION_TTL_SECS, LocalAuthProvider};
 
     struct ProviderFixtureBuilder {
         mock: MockDatabase,
         secret: Vec<u8>,
+        registration_mode: RegistrationMode,
     }
 
     impl ProviderFixtureBuilder {
@@ -186,9 +445,15 @@ mod tests {
             Self {
                 mock: MockDatabase::new(DatabaseBackend::Postgres),
                 secret: b"test-secret".to_vec(),
+                registration_mode: RegistrationMode::Open,
             }
         }
 
+        fn with_registration_mode(mut self, mode: RegistrationMode) -> Self {
+            self.registration_mode = mode;
+            self
+        }
+
         fn with_secret(mut self, secret: &[u8]) -> Self {
             self.secret = secret.to_vec();
             self
@@ -204,6 +469,13 @@ mod tests {
             self
         }
 
+        fn with_count_result(mut self, count: i64) -> Self {
+            self.mock = self
+                .mock
+                .append_query_results([[BTreeMap::from([("num_items", Value::from(count))])]]);
+            self
+        }
+
         fn with_query_error(mut self, error: DbErr) -> Self {
             self.mock = self.mock.append_query_errors([error]);
             self
@@ -218,13 +490,21 @@ mod tests {
         }
 
         fn build(self) -> LocalAuthProvider {
+            self.build_with_db().0
+        }
+
+        /// Also returns the mock connection, for reading its transaction log.
+        fn build_with_db(self) -> (LocalAuthProvider, DatabaseConnection) {
             let db = self.mock.into_connection();
             let services = ServiceContext::new(&db);
-            LocalAuthProvider::new(
+            let provider = LocalAuthProvider::new(
                 services.user(),
                 services.refresh_token_dao(),
+                services.invite_dao(),
                 crate::auth::jwt::JwtKeys::from_secret(&self.secret),
             )
+            .with_registration_mode(self.registration_mode);
+            (provider, db)
         }
     }
 
@@ -262,15 +542,26 @@ mod tests {
             user_id,
             expires_at,
             revoked,
+            fingerprint_hash: None,
         }
     }
 
     fn test_config(admin_email: &str, admin_password: &str) -> AuthConfig {
         AuthConfig {
+            enabled: true,
             provider: AuthProviderId::Local,
             jwt_secret: "unit-test-secret".to_string(),
+            jwt_kid: None,
+            jwt_verification_keys: Vec::new(),
             admin_email: admin_email.to_string(),
             admin_password: admin_password.to_string(),
+            registration_mode: RegistrationMode::Open,
+            single_session: false,
+            bind_refresh_tokens: false,
+            password_kdf: PasswordKdf::Argon2id,
+            password_history: 0,
+            lockout: LockoutConfig::default(),
+            token: TokenSourceConfig::default(),
         }
     }
 
@@ -340,7 +631,7 @@ mod tests {
         let provider = ProviderFixtureBuilder::new().build();
 
         let err = provider
-            .register("   ", "password123")
+            .register("   ", "password123", None)
             .await
             .expect_err("register should fail");
 
@@ -349,18 +640,10 @@ mod tests {
 
     #[tokio::test]
     async fn register_rejects_existing_user() {
-        let existing_id = Uuid::new_v4();
-        let provider = ProviderFixtureBuilder::new()
-            .with_query_results([vec![user_model(
-                existing_id,
-                "alice@example.com",
-                "hash",
-                "user",
-            )]])
-            .build();
+        let provider = ProviderFixtureBuilder::new().with_count_result(1).build();
 
         let err = provider
-            .register("alice@example.com", "password123")
+            .register("Alice@Example.com", "password123", None)
             .await
             .expect_err("register should fail");
 
@@ -369,12 +652,10 @@ mod tests {
 
     #[tokio::test]
     async fn register_rejects_short_password() {
-        let provider = ProviderFixtureBuilder::new()
-            .with_query_results([Vec::<user::Model>::new()])
-            .build();
+        let provider = ProviderFixtureBuilder::new().with_count_result(0).build();
 
         let err = provider
-            .register("alice@example.com", "short")
+            .register("alice@example.com", "short", None)
             .await
             .expect_err("register should fail");
 
@@ -386,7 +667,7 @@ mod tests {
         let user_id = Uuid::new_v4();
         let provider = ProviderFixtureBuilder::new()
             .with_secret(b"register-secret")
-            .with_query_results([Vec::<user::Model>::new()])
+            .with_count_result(0)
             .with_query_results([vec![user_model(
                 user_id,
                 "alice@example.com",
@@ -402,7 +683,7 @@ mod tests {
             .build();
 
         let bundle = provider
-            .register("alice@example.com", "password123")
+            .register("alice@example.com", "password123", None)
             .await
             .expect("register should succeed");
 
@@ -414,7 +695,7 @@ mod tests {
         let user_id = Uuid::new_v4();
         let provider = ProviderFixtureBuilder::new()
             .with_secret(b"register-role-secret")
-            .with_query_results([Vec::<user::Model>::new()])
+            .with_count_result(0)
             .with_query_results([vec![user_model(
                 user_id,
                 "alice@example.com",
@@ -430,7 +711,7 @@ mod tests {
             .build();
 
         let bundle = provider
-            .register("alice@example.com", "password123")
+            .register("alice@example.com", "password123", None)
             .await
             .expect("register should succeed");
         let claims = provider
@@ -729,6 +1010,97 @@ mod tests {
         assert_eq!(bundle.refresh_token, "new-refresh-token");
     }
 
+    #[tokio::test]
+    async fn logout_revokes_refresh_token_and_returns_owner() {
+        let user_id = Uuid::new_v4();
+        let provider = ProviderFixtureBuilder::new()
+            .with_query_results([vec![refresh_token_model(
+                "active-token",
+                user_id,
+                Utc::now().fixed_offset() + Duration::days(1),
+                false,
+            )]])
+            .with_exec_result(1)
+            .build();
+
+        let owner = provider
+            .logout("active-token")
+            .await
+            .expect("logout should succeed");
+
+        assert_eq!(owner, user_id);
+    }
+
+    #[tokio::test]
+    async fn logout_rejects_unknown_refresh_token() {
+        let provider = ProviderFixtureBuilder::new()
+            .with_query_results([Vec::<refresh_token::Model>::new()])
+            .build();
+
+        let err = provider
+            .logout("missing-token")
+            .await
+            .expect_err("logout should fail");
+
+        assert_eq!(err.message(), "Invalid refresh token");
+    }
+
+    fn bound_token(fingerprint: &str, user_id: Uuid) -> refresh_token::Model {
+        refresh_token::Model {
+            fingerprint_hash: Some(fingerprint.to_string()),
+            ..refresh_token_model(
+                "bound-token",
+                user_id,
+                Utc::now().fixed_offset() + Duration::days(1),
+                false,
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn bound_refresh_succeeds_with_matching_fingerprint() {
+        let user_id = Uuid::new_v4();
+        let provider = ProviderFixtureBuilder::new()
+            .with_query_results([vec![bound_token("client-a", user_id)]])
+            .with_query_results([vec![user_model(
+                user_id,
+                "alice@example.com",
+                "hashed-password",
+                "user",
+            )]])
+            .with_exec_result(1)
+            .with_query_results([vec![bound_token("client-a", user_id)]])
+            .build()
+            .with_refresh_token_binding(true);
+
+        provider
+            .refresh_bound("bound-token", Some("client-a"))
+            .await
+            .expect("refresh should succeed");
+    }
+
+    #[tokio::test]
+    async fn bound_refresh_rejects_mismatched_fingerprint() {
+        let user_id = Uuid::new_v4();
+        let provider = ProviderFixtureBuilder::new()
+            .with_query_results([vec![bound_token("client-a", user_id)]])
+            .with_query_results([vec![bound_token("client-a", user_id)]])
+            .build()
+            .with_refresh_token_binding(true);
+
+        let err = provider
+            .refresh_bound("bound-token", Some("client-b"))
+            .await
+            .expect_err("refresh from another client should fail");
+        assert_eq!(err.message(), "Refresh token was issued to another client");
+
+        let err = provider
+            .refresh("bound-token")
+            .await
+            .expect_err("refresh without a fingerprint should fail");
+        assert_eq!(err.message(), "Refresh token was issued to another client");
+    }
+
     #[tokio::test]
     async fn seed_admin_noops_when_admin_exists() {
         let admin_id = Uuid::new_v4();
@@ -804,7 +1176,7 @@ mod tests {
     async fn token_bundle_uses_expected_type_and_ttl() {
         let user_id = Uuid::new_v4();
         let provider = ProviderFixtureBuilder::new()
-            .with_query_results([Vec::<user::Model>::new()])
+            .with_count_result(0)
             .with_query_results([vec![user_model(
                 user_id,
                 "alice@example.com",
@@ -820,11 +1192,341 @@ mod tests {
             .build();
 
         let bundle = provider
-            .register("alice@example.com", "password123")
+            .register("alice@example.com", "password123", None)
             .await
             .expect("register should succeed");
 
         assert_eq!(bundle.token_type, "Bearer");
         assert_eq!(bundle.expires_in, ACCESS_TTL_SECS);
     }
+
+    fn invite_model(role: &str, used: bool) -> invite::Model {
+        invite::Model {
+            id: Uuid::new_v4(),
+            created_at: ts(),
+            updated_at: ts(),
+            token: hash_invite_token("invite-1"),
+            role: role.to_string(),
+            created_by: None,
+            expires_at: Utc::now().fixed_offset() + Duration::days(1),
+            used_at: used.then(|| Utc::now().fixed_offset()),
+        }
+    }
+
+    #[tokio::test]
+    async fn register_rejects_when_registration_closed() {
+        let provider = ProviderFixtureBuilder::new()
+            .with_registration_mode(RegistrationMode::Closed)
+            .build();
+
+        let err = provider
+            .register("alice@example.com", "password123", None)
+            .await
+            .expect_err("register should fail");
+
+        assert!(matches!(err, AppError::Forbidden(_)));
+        assert_eq!(err.message(), "Registration is closed");
+    }
+
+    #[tokio::test]
+    async fn register_requires_invite_token_in_invite_mode() {
+        let provider = ProviderFixtureBuilder::new()
+            .with_registration_mode(RegistrationMode::Invite)
+            .with_count_result(0)
+            .build();
+
+        let err = provider
+            .register("alice@example.com", "password123", None)
+            .await
+            .expect_err("register should fail");
+
+        assert!(matches!(err, AppError::Forbidden(_)));
+        assert_eq!(err.message(), "Invite token required");
+    }
+
+    #[tokio::test]
+    async fn register_consumes_invite_and_applies_its_role() {
+        let user_id = Uuid::new_v4();
+        let provider = ProviderFixtureBuilder::new()
+            .with_registration_mode(RegistrationMode::Invite)
+            .with_count_result(0)
+            .with_query_results([vec![invite_model("admin", false)]])
+            .with_exec_result(1)
+            .with_query_results([vec![user_model(
+                user_id,
+                "alice@example.com",
+                "hashed-password",
+                "admin",
+            )]])
+            .with_query_results([vec![refresh_token_model(
+                "refresh-invite",
+                user_id,
+                Utc::now().fixed_offset() + Duration::days(30),
+                false,
+            )]])
+            .build();
+
+        let bundle = provider
+            .register("alice@example.com", "password123", Some("invite-1"))
+            .await
+            .expect("register should succeed");
+        let claims = provider
+            .verify(&bundle.access_token)
+            .await
+            .expect("token should verify");
+
+        assert_eq!(claims.roles, vec![Role::Admin, Role::User]);
+    }
+
+    #[tokio::test]
+    async fn failed_user_insert_rolls_back_the_invite_redemption() {
+        let (provider, db) = ProviderFixtureBuilder::new()
+            .with_registration_mode(RegistrationMode::Invite)
+            .with_count_result(0)
+            .with_query_results([vec![invite_model("user", false)]])
+            .with_exec_result(1)
+            .with_query_error(DbErr::Custom("insert failed".to_string()))
+            .build_with_db();
+
+        provider
+            .register("alice@example.com", "password123", Some("invite-1"))
+            .await
+            .expect_err("register should fail");
+
+        let log = format!("{:?}", db.into_transaction_log());
+        assert!(log.contains(&hash_invite_token("invite-1")));
+        assert!(log.contains("ROLLBACK"));
+        assert!(!log.contains("COMMIT"));
+    }
+
+    #[tokio::test]
+    async fn register_rejects_already_used_invite() {
+        let provider = ProviderFixtureBuilder::new()
+            .with_registration_mode(RegistrationMode::Invite)
+            .with_count_result(0)
+            .with_query_results([vec![invite_model("user", true)]])
+            .build();
+
+        let err = provider
+            .register("alice@example.com", "password123", Some("invite-1"))
+            .await
+            .expect_err("register should fail");
+
+        assert!(matches!(err, AppError::Forbidden(_)));
+        assert_eq!(err.message(), "Invalid invite token");
+    }
+
+    #[tokio::test]
+    async fn impersonate_mints_token_for_target_with_act_claim() {
+        let admin = make_access_claims(&Uuid::new_v4(), vec![Role::Admin, Role::User], 300);
+        let user_id = Uuid::new_v4();
+        let provider = ProviderFixtureBuilder::new()
+            .with_query_results([vec![user_model(
+                user_id,
+                "alice@example.com",
+                "hashed-password",
+                "user",
+            )]])
+            .build();
+
+        let token = provider
+            .impersonate(&admin, user_id)
+            .await
+            .expect("impersonate should succeed");
+        let claims = provider
+            .verify(&token.access_token)
+            .await
+            .expect("impersonation token should verify");
+
+        assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(claims.roles, vec![Role::User]);
+        assert_eq!(claims.impersonator(), Some(admin.sub.as_str()));
+        assert_eq!(
+            claims.exp.saturating_sub(claims.iat),
+            IMPERSONATION_TTL_SECS
+        );
+        assert_eq!(token.expires_in, IMPERSONATION_TTL_SECS);
+        assert_eq!(token.impersonator, admin.sub);
+    }
+
+    #[tokio::test]
+    async fn impersonation_token_cannot_be_refreshed() {
+        let admin = make_access_claims(&Uuid::new_v4(), vec![Role::Admin], 300);
+        let user_id = Uuid::new_v4();
+        let provider = ProviderFixtureBuilder::new()
+            .with_query_results([vec![user_model(
+                user_id,
+                "alice@example.com",
+                "hashed-password",
+                "user",
+            )]])
+            .with_query_results([Vec::<refresh_token::Model>::new()])
+            .build();
+        let token = provider
+            .impersonate(&admin, user_id)
+            .await
+            .expect("impersonate should succeed");
+
+        let err = provider
+            .refresh(&token.access_token)
+            .await
+            .expect_err("refresh should fail");
+
+        assert_eq!(err.message(), "Invalid refresh token");
+    }
+
+    #[tokio::test]
+    async fn impersonate_rejects_chained_impersonation() {
+        let mut actor = make_access_claims(&Uuid::new_v4(), vec![Role::Admin], 300);
+        actor.act = Some(Actor {
+            sub: Uuid::new_v4().to_string(),
+        });
+        let provider = ProviderFixtureBuilder::new().build();
+
+        let err = provider
+            .impersonate(&actor, Uuid::new_v4())
+            .await
+            .expect_err("impersonate should fail");
+
+        assert_eq!(
+            err.message(),
+            "Impersonation tokens cannot impersonate other users"
+        );
+    }
+
+    #[tokio::test]
+    async fn impersonate_rejects_missing_user() {
+        let admin = make_access_claims(&Uuid::new_v4(), vec![Role::Admin], 300);
+        let provider = ProviderFixtureBuilder::new()
+            .with_query_results([Vec::<user::Model>::new()])
+            .build();
+
+        let err = provider
+            .impersonate(&admin, Uuid::new_v4())
+            .await
+            .expect_err("impersonate should fail");
+
+        assert_eq!(err.message(), "User not found");
+    }
+
+    #[tokio::test]
+    async fn single_session_login_revokes_previous_refresh_token() {
+        // A single connection keeps every query on the same in-memory database.
+        let mut options = sea_orm::ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = sea_orm::Database::connect(options)
+            .await
+            .expect("sqlite should open");
+        db.get_schema_builder()
+            .register(user::Entity)
+            .register(refresh_token::Entity)
+            .sync(&db)
+            .await
+            .expect("auth tables should be created");
+        let services = ServiceContext::new(&db);
+        let password_hash = hash_password("password123").expect("hash should succeed");
+        services
+            .user()
+            .create_user("alice@example.com", &password_hash, "user")
+            .await
+            .expect("user should be created");
+        let provider = LocalAuthProvider::new(
+            services.user(),
+            services.refresh_token_dao(),
+            services.invite_dao(),
+            crate::auth::jwt::JwtKeys::from_secret(b"single-session-secret"),
+        )
+        .with_single_session(true);
+
+        let first = provider
+            .login("alice@example.com", "password123")
+            .await
+            .expect("first login should succeed");
+        let second = provider
+            .login("alice@example.com", "password123")
+            .await
+            .expect("second login should succeed");
+
+        let err = provider
+            .refresh(&first.refresh_token)
+            .await
+            .expect_err("first session should be revoked");
+        assert_eq!(err.message(), "Invalid refresh token");
+        provider
+            .refresh(&second.refresh_token)
+            .await
+            .expect("latest session should still refresh");
+    }
+
+    #[tokio::test]
+    async fn password_history_rejects_recent_passwords_and_prunes_old_ones() {
+        let mut options = sea_orm::ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = sea_orm::Database::connect(options)
+            .await
+            .expect("sqlite should open");
+        db.get_schema_builder()
+            .register(user::Entity)
+            .register(password_history::Entity)
+            .sync(&db)
+            .await
+            .expect("auth tables should be created");
+        let services = ServiceContext::new(&db);
+        let password_hash = hash_password("password-0").expect("hash should succeed");
+        let user = services
+            .user()
+            .create_user("alice@example.com", &password_hash, "user")
+            .await
+            .expect("user should be created");
+        let provider = LocalAuthProvider::new(
+            services.user(),
+            services.refresh_token_dao(),
+            services.invite_dao(),
+            crate::auth::jwt::JwtKeys::from_secret(b"history-secret"),
+        )
+        .with_password_history(services.password_history_dao(), 2);
+        let change = |current: &'static str, new: &'static str| {
+            let provider = provider.clone();
+            async move { provider.change_password(user.id, current, new).await }
+        };
+
+        change("password-0", "password-1")
+            .await
+            .expect("a new password should be accepted");
+        let err = change("password-1", "password-0")
+            .await
+            .expect_err("the previous password should be rejected");
+        assert_eq!(err.message(), "Password was used recently");
+        let err = change("password-1", "password-1")
+            .await
+            .expect_err("the current password should be rejected");
+        assert_eq!(err.message(), "Password was used recently");
+        let err = change("wrong-password", "password-9")
+            .await
+            .expect_err("a wrong current password should be rejected");
+        assert_eq!(err.message(), "Invalid credentials");
+
+        change("password-1", "password-2")
+            .await
+            .expect("password-2 should be accepted");
+        change("password-2", "password-3")
+            .await
+            .expect("password-3 should be accepted");
+        let history = services
+            .password_history_dao()
+            .recent_hashes(&user.id, 10)
+            .await
+            .expect("history should load");
+        assert_eq!(history.len(), 2);
+        change("password-3", "password-0")
+            .await
+            .expect("a password pruned from history should be accepted");
+        let stored = services
+            .user()
+            .find_by_id(&user.id)
+            .await
+            .expect("user should load")
+            .expect("user should exist");
+        assert!(verify_password("password-0", &stored.password_hash).expect("hash should parse"));
+    }
 }
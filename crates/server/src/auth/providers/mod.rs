@@ -1,5 +1,5 @@
 pub mod local;
 mod registry;
 
-pub use local::LocalAuthProvider;
+pub use local::{LocalAuthProvider, ScopeResolver};
 pub use registry::{AuthProvider, AuthProviderId, AuthProviders};
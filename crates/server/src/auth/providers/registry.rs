@@ -3,8 +3,10 @@ use std::{collections::HashMap, sync::Arc};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use uuid::Uuid;
+
 use crate::{
-    auth::{Claims, TokenBundle},
+    auth::{Claims, ImpersonationToken, TokenBundle},
     config::AuthConfig,
     error::AppError,
 };
@@ -38,11 +40,83 @@ impl std::str::FromStr for AuthProviderId {
 pub trait AuthProvider: Send + Sync {
     fn id(&self) -> AuthProviderId;
 
-    async fn register(&self, email: &str, password: &str) -> Result<TokenBundle, AppError>;
+    /// Creates an account. `invite_token` is checked when registration is invite-only.
+    async fn register(
+        &self,
+        email: &str,
+        password: &str,
+        invite_token: Option<&str>,
+    ) -> Result<TokenBundle, AppError>;
     async fn login(&self, email: &str, password: &str) -> Result<TokenBundle, AppError>;
     async fn refresh(&self, refresh_token: &str) -> Result<TokenBundle, AppError>;
     async fn verify(&self, access_token: &str) -> Result<Claims, AppError>;
 
+    /// Like `register`, binding the issued refresh token to the client
+    /// `fingerprint` (see `AuthRequestMeta::fingerprint`). Providers without
+    /// binding ignore it.
+    async fn register_bound(
+        &self,
+        email: &str,
+        password: &str,
+        invite_token: Option<&str>,
+        _fingerprint: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
+        self.register(email, password, invite_token).await
+    }
+
+    /// Like `login`, binding the issued refresh token to `fingerprint`.
+    async fn login_bound(
+        &self,
+        email: &str,
+        password: &str,
+        _fingerprint: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
+        self.login(email, password).await
+    }
+
+    /// Like `refresh`, rejecting a bound token presented with a different
+    /// `fingerprint`.
+    async fn refresh_bound(
+        &self,
+        refresh_token: &str,
+        _fingerprint: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
+        self.refresh(refresh_token).await
+    }
+
+    /// Revokes `refresh_token`, returning the id of the user it belonged to.
+    async fn logout(&self, _refresh_token: &str) -> Result<Uuid, AppError> {
+        Err(AppError::bad_request(format!(
+            "Logout is not supported by auth provider: {}",
+            self.id().as_str()
+        )))
+    }
+
+    /// Mints an access-only token for `user_id` on behalf of `actor`.
+    async fn impersonate(
+        &self,
+        _actor: &Claims,
+        _user_id: Uuid,
+    ) -> Result<ImpersonationToken, AppError> {
+        Err(AppError::bad_request(format!(
+            "Impersonation is not supported by auth provider: {}",
+            self.id().as_str()
+        )))
+    }
+
+    /// Replaces `user_id`'s password after checking `current_password`.
+    async fn change_password(
+        &self,
+        _user_id: Uuid,
+        _current_password: &str,
+        _new_password: &str,
+    ) -> Result<(), AppError> {
+        Err(AppError::bad_request(format!(
+            "Password changes are not supported by auth provider: {}",
+            self.id().as_str()
+        )))
+    }
+
     async fn seed_admin(&self, _cfg: &AuthConfig) -> anyhow::Result<()> {
         Ok(())
     }
@@ -136,7 +210,12 @@ mod tests {
             self.id
         }
 
-        async fn register(&self, _email: &str, _password: &str) -> Result<TokenBundle, AppError> {
+        async fn register(
+            &self,
+            _email: &str,
+            _password: &str,
+            _invite_token: Option<&str>,
+        ) -> Result<TokenBundle, AppError> {
             Err(AppError::unauthorized("not used"))
         }
 
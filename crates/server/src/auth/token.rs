@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::Query,
+    http::{HeaderMap, Uri, header},
+};
+
+use crate::config::TokenSourceConfig;
+
+/// The access token of a request, read from `Authorization: Bearer <token>`
+/// (scheme matched case-insensitively), then from `sources.alternate_header`.
+/// Never reads the query string; see [`download_token`].
+pub fn request_token(headers: &HeaderMap, sources: Option<&TokenSourceConfig>) -> Option<String> {
+    if let Some(token) = header_value(headers, header::AUTHORIZATION.as_str()).and_then(bearer) {
+        return Some(token.to_string());
+    }
+    sources?
+        .alternate_header
+        .as_deref()
+        .and_then(|name| header_value(headers, name))
+        .and_then(|value| bearer(value).or(Some(value.trim())))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
+/// [`request_token`], falling back to the `sources.query_param` query
+/// parameter. Only file downloads read it, since links cannot set headers.
+pub fn download_token(
+    headers: &HeaderMap,
+    uri: &Uri,
+    sources: Option<&TokenSourceConfig>,
+) -> Option<String> {
+    if let Some(token) = request_token(headers, sources) {
+        return Some(token);
+    }
+    let param = sources?.query_param.as_deref()?;
+    let Query(params) = Query::<HashMap<String, String>>::try_from_uri(uri).ok()?;
+    params
+        .get(param)
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// The token of a `Bearer <token>` credential, any scheme case.
+fn bearer(value: &str) -> Option<&str> {
+    let (scheme, token) = value.trim().split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue, Uri, header};
+
+    use super::{download_token, request_token};
+    use crate::config::TokenSourceConfig;
+
+    #[test]
+    fn bearer_scheme_is_case_insensitive() {
+        for value in ["Bearer abc", "bearer abc", "BEARER  abc "] {
+            let mut map = HeaderMap::new();
+            map.insert(header::AUTHORIZATION, HeaderValue::from_static(value));
+            assert_eq!(request_token(&map, None).as_deref(), Some("abc"));
+        }
+        let mut basic = HeaderMap::new();
+        basic.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+        assert_eq!(request_token(&basic, None), None);
+    }
+
+    #[test]
+    fn query_param_is_only_read_for_downloads_when_configured() {
+        let uri = Uri::from_static("/files/1?access_token=abc%3D");
+        let sources = TokenSourceConfig {
+            query_param: Some("access_token".to_string()),
+            ..TokenSourceConfig::default()
+        };
+
+        assert_eq!(download_token(&HeaderMap::new(), &uri, None), None);
+        assert_eq!(
+            download_token(&HeaderMap::new(), &uri, Some(&TokenSourceConfig::default())),
+            None
+        );
+        assert_eq!(
+            download_token(&HeaderMap::new(), &uri, Some(&sources)).as_deref(),
+            Some("abc=")
+        );
+        assert_eq!(request_token(&HeaderMap::new(), Some(&sources)), None);
+    }
+}
@@ -14,6 +14,35 @@ impl Role {
             Role::Admin => "admin",
         }
     }
+
+    /// Roles granted by a stored user role; unknown values fall back to
+    /// `user` and admins also hold `user`.
+    pub fn granted_for(stored: &str) -> Vec<Role> {
+        let primary = Role::try_from(stored).unwrap_or(Role::User);
+        Role::effective(&[primary])
+    }
+
+    /// Roles that holding this role implies.
+    pub fn implied(&self) -> &'static [Role] {
+        match self {
+            Role::User => &[],
+            Role::Admin => &[Role::User],
+        }
+    }
+
+    /// `roles` followed by the roles they imply, without duplicates.
+    pub fn effective(roles: &[Role]) -> Vec<Role> {
+        let mut effective: Vec<Role> = Vec::with_capacity(roles.len() + 1);
+        for role in roles
+            .iter()
+            .chain(roles.iter().flat_map(|role| role.implied()))
+        {
+            if !effective.contains(role) {
+                effective.push(role.clone());
+            }
+        }
+        effective
+    }
 }
 
 impl TryFrom<&str> for Role {
@@ -28,6 +57,19 @@ impl TryFrom<&str> for Role {
     }
 }
 
+/// Who may call `/register`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistrationMode {
+    /// Anyone may register.
+    #[default]
+    Open,
+    /// Registration requires a single-use invite token.
+    Invite,
+    /// Registration is disabled.
+    Closed,
+}
+
 pub trait RequiredRole {
     fn required() -> Role;
 }
@@ -48,12 +90,40 @@ impl RequiredRole for AdminRole {
     }
 }
 
+/// Marker for a permission checked by `RequireScope`, e.g. `todos:write`.
+pub trait RequiredScope {
+    fn required() -> &'static str;
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String, // user id / email
     pub exp: usize,  // expiry (unix)
     pub iat: usize,  // issued at
     pub roles: Vec<Role>,
+    // Fine-grained permissions; tokens issued before scopes existed have none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    // Set on impersonation tokens (RFC 8693 `act`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>,
+}
+
+impl Claims {
+    /// Subject of the admin acting as this user, if the token was minted by impersonation.
+    pub fn impersonator(&self) -> Option<&str> {
+        self.act.as_ref().map(|actor| actor.sub.as_str())
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| granted == scope)
+    }
+}
+
+/// The party actually holding an impersonation token.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Actor {
+    pub sub: String,
 }
 
 #[derive(Debug)]
@@ -64,9 +134,20 @@ pub struct TokenBundle {
     pub expires_in: usize,
 }
 
+/// Short-lived access token minted for an admin acting as another user.
+///
+/// No refresh token is issued; the session ends when the access token expires.
+#[derive(Debug)]
+pub struct ImpersonationToken {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: usize,
+    pub impersonator: String,
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{AdminRole, RequiredRole, Role, UserRole};
+    use super::{AdminRole, Claims, RequiredRole, Role, UserRole};
 
     #[test]
     fn role_string_roundtrip() {
@@ -78,9 +159,37 @@ mod tests {
         assert!(Role::try_from("manager").is_err());
     }
 
+    #[test]
+    fn effective_roles_add_implied_roles_once() {
+        assert_eq!(
+            Role::effective(&[Role::Admin]),
+            vec![Role::Admin, Role::User]
+        );
+        assert_eq!(
+            Role::effective(&[Role::User, Role::Admin]),
+            vec![Role::User, Role::Admin]
+        );
+        assert_eq!(Role::effective(&[Role::User]), vec![Role::User]);
+        assert_eq!(Role::granted_for("admin"), vec![Role::Admin, Role::User]);
+    }
+
     #[test]
     fn required_role_markers_map_to_expected_role() {
         assert_eq!(UserRole::required(), Role::User);
         assert_eq!(AdminRole::required(), Role::Admin);
     }
+
+    #[test]
+    fn claims_without_scopes_deserialize_with_none_granted() {
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "sub": "user",
+            "exp": 100,
+            "iat": 10,
+            "roles": ["user"],
+        }))
+        .expect("legacy claims should deserialize");
+
+        assert!(claims.scopes.is_empty());
+        assert!(!claims.has_scope("todos:write"));
+    }
 }
@@ -1,10 +1,19 @@
 use anyhow::Result;
-pub use realtime::server::RealtimeConfig;
 use serde::{Deserialize, Serialize};
 
-use crate::auth::providers::AuthProviderId;
+use crate::{
+    auth::{
+        RegistrationMode, lockout::LockoutBackend, password::PasswordKdf, providers::AuthProviderId,
+    },
+    storage::StorageBackendId,
+};
 
-use super::{defaults, envconfig::EnvConfig, validate};
+use super::{
+    defaults,
+    envconfig::EnvConfig,
+    units::{deserialize_bytes, deserialize_bytes_usize, deserialize_millis, deserialize_secs},
+    validate,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(default, deny_unknown_fields)]
@@ -14,6 +23,7 @@ pub struct AppConfig {
     pub database: Option<DatabaseConfig>,
     pub auth: Option<AuthConfig>,
     pub realtime: RealtimeConfig,
+    pub storage: StorageConfig,
 }
 
 impl AppConfig {
@@ -23,8 +33,11 @@ impl AppConfig {
 }
 
 impl EnvConfig for AppConfig {
+    const ENV_FALLBACKS: &'static [(&'static str, &'static str)] =
+        &[("DATABASE_URL", "database.url"), ("PORT", "general.port")];
+
     fn validate(&self) -> Result<()> {
-        validate::validate(self)
+        Ok(validate::validate(self)?)
     }
 }
 
@@ -33,7 +46,23 @@ impl EnvConfig for AppConfig {
 pub struct GeneralConfig {
     pub host: String,
     pub port: u16,
+    /// Listen on this Unix domain socket instead of `host:port` (Unix only).
+    pub unix_socket: Option<String>,
     pub enable_docs_in_release: bool,
+    /// Start in maintenance mode; admins can clear it at runtime.
+    pub maintenance_mode: bool,
+    /// `Retry-After` sent with maintenance `503`s.
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub maintenance_retry_after_secs: u64,
+    /// Body shape of error responses.
+    pub error_format: ErrorFormat,
+    pub rate_limit: RateLimitConfig,
+    /// Reverse proxies whose `X-Forwarded-For`/`X-Real-IP` are believed. The
+    /// headers are ignored on connections from any other address.
+    ///
+    /// From env: `APP_GENERAL__TRUSTED_PROXIES=10.0.0.2,10.0.0.3`.
+    #[serde(deserialize_with = "deserialize_comma_list")]
+    pub trusted_proxies: Vec<String>,
 }
 
 impl Default for GeneralConfig {
@@ -41,7 +70,50 @@ impl Default for GeneralConfig {
         Self {
             host: defaults::DEFAULT_HOST.to_string(),
             port: defaults::DEFAULT_PORT as u16,
+            unix_socket: None,
             enable_docs_in_release: defaults::DEFAULT_ENABLE_DOCS_IN_RELEASE,
+            maintenance_mode: false,
+            maintenance_retry_after_secs: defaults::DEFAULT_MAINTENANCE_RETRY_AFTER_SECS,
+            error_format: ErrorFormat::default(),
+            rate_limit: RateLimitConfig::default(),
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+/// Per-client request limit on every route, counted per authenticated user
+/// or, for anonymous requests, per client IP.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Requests per `window_secs` per client. `0` disables the limit.
+    pub max_requests: u32,
+    /// At most 30 days.
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub window_secs: u64,
+    /// Tokens holding one of these roles are exempt.
+    ///
+    /// From env: `APP_GENERAL__RATE_LIMIT__EXEMPT_ROLES=admin`.
+    #[serde(deserialize_with = "deserialize_comma_list")]
+    pub exempt_roles: Vec<String>,
+    /// Tokens holding one of these scopes are exempt.
+    #[serde(deserialize_with = "deserialize_comma_list")]
+    pub exempt_scopes: Vec<String>,
+    /// Limit for exempt tokens instead of `max_requests`; `0` means unlimited.
+    pub exempt_max_requests: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_requests: 0,
+            window_secs: defaults::DEFAULT_RATE_LIMIT_WINDOW_SECS,
+            exempt_roles: defaults::DEFAULT_RATE_LIMIT_EXEMPT_ROLES
+                .iter()
+                .map(|role| role.to_string())
+                .collect(),
+            exempt_scopes: Vec::new(),
+            exempt_max_requests: 0,
         }
     }
 }
@@ -50,16 +122,75 @@ impl Default for GeneralConfig {
 #[serde(default, deny_unknown_fields)]
 pub struct LoggingConfig {
     pub rust_log: String,
+    pub access_log: AccessLogFormat,
+    pub body_log: BodyLogConfig,
 }
 
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
             rust_log: defaults::DEFAULT_RUST_LOG.to_string(),
+            access_log: AccessLogFormat::default(),
+            body_log: BodyLogConfig::default(),
         }
     }
 }
 
+/// Debug logging of JSON request/response bodies under the `body_log` target.
+///
+/// Events are emitted at trace level, so `rust_log` must also enable
+/// `body_log=trace`. Not meant to be left on in production.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BodyLogConfig {
+    pub enabled: bool,
+    /// Bodies larger than this (or of unknown size) are not buffered.
+    #[serde(deserialize_with = "deserialize_bytes_usize")]
+    pub max_bytes: usize,
+    /// JSON keys whose values are masked, matched case-insensitively at any depth.
+    ///
+    /// From env: `APP_LOGGING__BODY_LOG__REDACT_FIELDS=password,token`.
+    #[serde(deserialize_with = "deserialize_comma_list")]
+    pub redact_fields: Vec<String>,
+}
+
+impl Default for BodyLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_bytes: defaults::DEFAULT_BODY_LOG_MAX_BYTES,
+            redact_fields: defaults::DEFAULT_BODY_LOG_REDACT_FIELDS
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Body shape of `4xx`/`5xx` responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorFormat {
+    /// `{ status, message, data: null }`, like every other API response.
+    #[default]
+    Envelope,
+    /// RFC 7807 `application/problem+json` documents.
+    Problem,
+}
+
+/// Shape of the per-request access log emitted under the `access_log` target.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessLogFormat {
+    /// No access log.
+    #[default]
+    Off,
+    /// Apache combined-style line.
+    Combined,
+    /// One JSON object per request.
+    Json,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct DatabaseConfig {
@@ -68,16 +199,287 @@ pub struct DatabaseConfig {
     pub max_connections: u32,
     #[serde(default = "default_db_min_idle")]
     pub min_idle: u32,
+    /// Entries each `CachedDao` keeps; `0` disables caching.
+    #[serde(default = "default_db_cache_max_entries")]
+    pub cache_max_entries: usize,
+    /// How long a `CachedDao` entry is served; `0` disables caching.
+    #[serde(
+        default = "default_db_cache_ttl_secs",
+        deserialize_with = "deserialize_secs"
+    )]
+    pub cache_ttl_secs: u64,
+    /// Longest a single statement may run before it fails as a timeout; `0`
+    /// disables. Postgres cancels it server-side; SQLite and MySQL stop waiting for it.
+    #[serde(
+        default = "default_db_statement_timeout_ms",
+        deserialize_with = "deserialize_millis"
+    )]
+    pub statement_timeout_ms: u64,
+    /// Read replica for queries that opt in through `DaoContext::read`. Must
+    /// use the same driver as `url`.
+    #[serde(default)]
+    pub replica_url: Option<String>,
+}
+
+/// Realtime settings as read from `APP_REALTIME__*`. Convert with `.into()` to
+/// get the `realtime` crate's config for `SocketServerHandle::spawn`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RealtimeConfig {
+    pub enabled: bool,
+    pub max_connections: usize,
+    pub max_channels_per_connection: usize,
+    #[serde(deserialize_with = "deserialize_bytes_usize")]
+    pub max_message_bytes: usize,
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub heartbeat_interval_secs: u64,
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub idle_timeout_secs: u64,
+    pub outbound_queue_size: usize,
+    pub emit_rate_per_sec: u32,
+    pub join_rate_per_sec: u32,
+    pub replay_buffer_size: usize,
+    pub max_meta_entries: usize,
+    #[serde(deserialize_with = "deserialize_bytes_usize")]
+    pub max_meta_entry_bytes: usize,
+    /// How long shutdown waits for realtime clients to disconnect.
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub shutdown_grace_secs: u64,
+    /// Origins allowed to open the socket; empty allows all.
+    ///
+    /// From env: `APP_REALTIME__ALLOWED_ORIGINS=https://app.example.com`.
+    #[serde(deserialize_with = "deserialize_comma_list")]
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for RealtimeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: defaults::DEFAULT_REALTIME_ENABLED,
+            max_connections: defaults::DEFAULT_REALTIME_MAX_CONNECTIONS,
+            max_channels_per_connection: defaults::DEFAULT_REALTIME_MAX_CHANNELS_PER_CONNECTION,
+            max_message_bytes: defaults::DEFAULT_REALTIME_MAX_MESSAGE_BYTES,
+            heartbeat_interval_secs: defaults::DEFAULT_REALTIME_HEARTBEAT_INTERVAL_SECS,
+            idle_timeout_secs: defaults::DEFAULT_REALTIME_IDLE_TIMEOUT_SECS,
+            outbound_queue_size: defaults::DEFAULT_REALTIME_OUTBOUND_QUEUE_SIZE,
+            emit_rate_per_sec: defaults::DEFAULT_REALTIME_EMIT_RATE_PER_SEC,
+            join_rate_per_sec: defaults::DEFAULT_REALTIME_JOIN_RATE_PER_SEC,
+            replay_buffer_size: defaults::DEFAULT_REALTIME_REPLAY_BUFFER_SIZE,
+            max_meta_entries: defaults::DEFAULT_REALTIME_MAX_META_ENTRIES,
+            max_meta_entry_bytes: defaults::DEFAULT_REALTIME_MAX_META_ENTRY_BYTES,
+            shutdown_grace_secs: defaults::DEFAULT_REALTIME_SHUTDOWN_GRACE_SECS,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+impl From<RealtimeConfig> for realtime::server::RealtimeConfig {
+    fn from(cfg: RealtimeConfig) -> Self {
+        // Destructured so a new app-level field cannot be silently dropped.
+        let RealtimeConfig {
+            enabled,
+            max_connections,
+            max_channels_per_connection,
+            max_message_bytes,
+            heartbeat_interval_secs,
+            idle_timeout_secs,
+            outbound_queue_size,
+            emit_rate_per_sec,
+            join_rate_per_sec,
+            replay_buffer_size,
+            max_meta_entries,
+            max_meta_entry_bytes,
+            shutdown_grace_secs,
+            // Checked by the socket route, not the hub.
+            allowed_origins: _,
+        } = cfg;
+        Self {
+            enabled,
+            max_connections,
+            max_channels_per_connection,
+            max_message_bytes,
+            heartbeat_interval_secs,
+            idle_timeout_secs,
+            outbound_queue_size,
+            emit_rate_per_sec,
+            join_rate_per_sec,
+            replay_buffer_size,
+            max_meta_entries,
+            max_meta_entry_bytes,
+            shutdown_grace_secs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub backend: StorageBackendId,
+    pub local_dir: String,
+    #[serde(deserialize_with = "deserialize_bytes")]
+    pub max_upload_bytes: u64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackendId::Local,
+            local_dir: defaults::DEFAULT_STORAGE_LOCAL_DIR.to_string(),
+            max_upload_bytes: defaults::DEFAULT_STORAGE_MAX_UPLOAD_BYTES,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct AuthConfig {
+    /// With `false` the server starts without a JWT secret or admin account and every
+    /// auth route answers with an error.
+    #[serde(default = "default_auth_enabled")]
+    pub enabled: bool,
     #[serde(default = "default_auth_provider")]
     pub provider: AuthProviderId,
+    #[serde(default)]
     pub jwt_secret: String,
+    /// Key id stamped into minted tokens; tokens without a `kid` verify against `jwt_secret`.
+    #[serde(default)]
+    pub jwt_kid: Option<String>,
+    /// Retired keys that still verify until their tokens expire.
+    ///
+    /// From env: `APP_AUTH__JWT_VERIFICATION_KEYS=kid1:secret1,kid2:secret2`.
+    #[serde(default, deserialize_with = "deserialize_jwt_verification_keys")]
+    pub jwt_verification_keys: Vec<JwtVerificationKey>,
+    #[serde(default)]
     pub admin_email: String,
+    #[serde(default)]
     pub admin_password: String,
+    /// `open`, `invite` (single-use invite token required), or `closed`.
+    #[serde(default)]
+    pub registration_mode: RegistrationMode,
+    /// Revoke a user's other refresh tokens on every login, so only the newest session
+    /// can refresh.
+    #[serde(default)]
+    pub single_session: bool,
+    /// Bind each refresh token to a hash of the client's `User-Agent` and
+    /// `X-Client-Nonce` header, and refuse refreshes from a different client.
+    #[serde(default)]
+    pub bind_refresh_tokens: bool,
+    /// KDF for new password hashes: `argon2id` (default) or `bcrypt`. Stored hashes of
+    /// either kind keep verifying, so existing bcrypt users need no reset.
+    #[serde(default)]
+    pub password_kdf: PasswordKdf,
+    /// Previous passwords per user that a password change may not reuse. `0`
+    /// (default) keeps no history.
+    #[serde(default)]
+    pub password_history: u32,
+    #[serde(default)]
+    pub lockout: LockoutConfig,
+    #[serde(default)]
+    pub token: TokenSourceConfig,
+}
+
+/// Failed-login lockout, counted per email.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LockoutConfig {
+    /// `memory` (per process) or `database` (shared by every instance behind a
+    /// load balancer).
+    pub backend: LockoutBackend,
+    /// Failed logins within `window_secs` before the email is locked. `0`
+    /// disables the lockout.
+    pub max_attempts: u32,
+    /// Window opened by the first failure; the lockout lifts when it ends.
+    /// At most 30 days.
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub window_secs: u64,
+}
+
+impl Default for LockoutConfig {
+    fn default() -> Self {
+        Self {
+            backend: LockoutBackend::Memory,
+            max_attempts: defaults::DEFAULT_AUTH_LOCKOUT_MAX_ATTEMPTS,
+            window_secs: defaults::DEFAULT_AUTH_LOCKOUT_WINDOW_SECS,
+        }
+    }
+}
+
+/// Where requests may carry their access token besides
+/// `Authorization: Bearer <token>`, which is always read first.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenSourceConfig {
+    /// Extra header holding the token, bare or as `Bearer <token>`, e.g.
+    /// `X-Access-Token`.
+    pub alternate_header: Option<String>,
+    /// Query parameter holding the token, read only by file downloads, where
+    /// links cannot set headers; every other route ignores it. Off by
+    /// default. Access logs and trace spans drop query strings, but download
+    /// URLs still land in browser history and proxy logs.
+    pub query_param: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct JwtVerificationKey {
+    pub kid: String,
+    pub secret: String,
+}
+
+fn deserialize_jwt_verification_keys<'de, D>(
+    deserializer: D,
+) -> Result<Vec<JwtVerificationKey>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawKeys {
+        Encoded(String),
+        List(Vec<JwtVerificationKey>),
+    }
+
+    match RawKeys::deserialize(deserializer)? {
+        RawKeys::List(keys) => Ok(keys),
+        RawKeys::Encoded(raw) => raw
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (kid, secret) = entry.split_once(':').ok_or_else(|| {
+                    serde::de::Error::custom(
+                        "auth.jwt_verification_keys entries must be kid:secret",
+                    )
+                })?;
+                Ok(JwtVerificationKey {
+                    kid: kid.trim().to_string(),
+                    secret: secret.trim().to_string(),
+                })
+            })
+            .collect(),
+    }
+}
+
+fn deserialize_comma_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawList {
+        Encoded(String),
+        List(Vec<String>),
+    }
+
+    let entries = match RawList::deserialize(deserializer)? {
+        RawList::List(entries) => entries,
+        RawList::Encoded(raw) => raw.split(',').map(str::to_string).collect(),
+    };
+    Ok(entries
+        .into_iter()
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect())
 }
 
 fn default_db_max_connections() -> u32 {
@@ -88,6 +490,74 @@ fn default_db_min_idle() -> u32 {
     defaults::DEFAULT_DB_MIN_IDLE as u32
 }
 
+fn default_db_cache_max_entries() -> usize {
+    defaults::DEFAULT_DB_CACHE_MAX_ENTRIES
+}
+
+fn default_db_cache_ttl_secs() -> u64 {
+    defaults::DEFAULT_DB_CACHE_TTL_SECS
+}
+
+fn default_db_statement_timeout_ms() -> u64 {
+    defaults::DEFAULT_DB_STATEMENT_TIMEOUT_MS
+}
+
+fn default_auth_enabled() -> bool {
+    true
+}
+
 fn default_auth_provider() -> AuthProviderId {
     AuthProviderId::Local
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RealtimeConfig;
+
+    #[test]
+    fn realtime_config_converts_every_field() {
+        let app = RealtimeConfig {
+            enabled: false,
+            max_connections: 11,
+            max_channels_per_connection: 12,
+            max_message_bytes: 13,
+            heartbeat_interval_secs: 14,
+            idle_timeout_secs: 15,
+            outbound_queue_size: 16,
+            emit_rate_per_sec: 17,
+            join_rate_per_sec: 18,
+            replay_buffer_size: 19,
+            max_meta_entries: 20,
+            max_meta_entry_bytes: 21,
+            shutdown_grace_secs: 22,
+            allowed_origins: vec!["https://app.example.com".to_string()],
+        };
+
+        let hub: realtime::server::RealtimeConfig = app.into();
+
+        assert!(!hub.enabled);
+        assert_eq!(hub.max_connections, 11);
+        assert_eq!(hub.max_channels_per_connection, 12);
+        assert_eq!(hub.max_message_bytes, 13);
+        assert_eq!(hub.heartbeat_interval_secs, 14);
+        assert_eq!(hub.idle_timeout_secs, 15);
+        assert_eq!(hub.outbound_queue_size, 16);
+        assert_eq!(hub.emit_rate_per_sec, 17);
+        assert_eq!(hub.join_rate_per_sec, 18);
+        assert_eq!(hub.replay_buffer_size, 19);
+        assert_eq!(hub.max_meta_entries, 20);
+        assert_eq!(hub.max_meta_entry_bytes, 21);
+        assert_eq!(hub.shutdown_grace_secs, 22);
+    }
+
+    #[test]
+    fn realtime_defaults_match_the_realtime_crate() {
+        let hub: realtime::server::RealtimeConfig = RealtimeConfig::default().into();
+        let crate_default = realtime::server::RealtimeConfig::default();
+
+        assert_eq!(
+            serde_json::to_value(hub).expect("serialize"),
+            serde_json::to_value(crate_default).expect("serialize")
+        );
+    }
+}
@@ -1,9 +1,27 @@
 pub const DEFAULT_HOST: &str = "127.0.0.1";
 pub const DEFAULT_PORT: i64 = 3000;
 pub const DEFAULT_ENABLE_DOCS_IN_RELEASE: bool = true;
+pub const DEFAULT_MAINTENANCE_RETRY_AFTER_SECS: u64 = 120;
+pub const DEFAULT_RATE_LIMIT_WINDOW_SECS: u64 = 60;
+pub const DEFAULT_RATE_LIMIT_EXEMPT_ROLES: &[&str] = &["admin"];
 pub const DEFAULT_RUST_LOG: &str = "info,tower_http=info";
+pub const DEFAULT_BODY_LOG_MAX_BYTES: usize = 16 * 1024;
+pub const DEFAULT_BODY_LOG_REDACT_FIELDS: &[&str] = &[
+    "password",
+    "token",
+    "access_token",
+    "refresh_token",
+    "secret",
+];
 pub const DEFAULT_DB_MAX_CONNECTIONS: i64 = 10;
 pub const DEFAULT_DB_MIN_IDLE: i64 = 2;
+pub const DEFAULT_DB_CACHE_MAX_ENTRIES: usize = 1024;
+pub const DEFAULT_DB_CACHE_TTL_SECS: u64 = 30;
+pub const DEFAULT_DB_STATEMENT_TIMEOUT_MS: u64 = 0;
+pub const DEFAULT_AUTH_LOCKOUT_MAX_ATTEMPTS: u32 = 5;
+pub const DEFAULT_AUTH_LOCKOUT_WINDOW_SECS: u64 = 15 * 60;
+pub const DEFAULT_STORAGE_LOCAL_DIR: &str = "uploads";
+pub const DEFAULT_STORAGE_MAX_UPLOAD_BYTES: u64 = 10 * 1024 * 1024;
 pub const DEFAULT_REALTIME_ENABLED: bool = true;
 pub const DEFAULT_REALTIME_MAX_CONNECTIONS: usize = 10_000;
 pub const DEFAULT_REALTIME_MAX_CHANNELS_PER_CONNECTION: usize = 100;
@@ -13,3 +31,7 @@ pub const DEFAULT_REALTIME_IDLE_TIMEOUT_SECS: u64 = 60;
 pub const DEFAULT_REALTIME_OUTBOUND_QUEUE_SIZE: usize = 256;
 pub const DEFAULT_REALTIME_EMIT_RATE_PER_SEC: u32 = 100;
 pub const DEFAULT_REALTIME_JOIN_RATE_PER_SEC: u32 = 50;
+pub const DEFAULT_REALTIME_REPLAY_BUFFER_SIZE: usize = 0;
+pub const DEFAULT_REALTIME_MAX_META_ENTRIES: usize = 16;
+pub const DEFAULT_REALTIME_MAX_META_ENTRY_BYTES: usize = 256;
+pub const DEFAULT_REALTIME_SHUTDOWN_GRACE_SECS: u64 = 5;
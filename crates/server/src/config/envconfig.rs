@@ -1,17 +1,40 @@
-use std::path::Path;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use ::config as config_rs;
 use anyhow::{Context, Result};
 use serde::de::DeserializeOwned;
 
+/// Selects the `.env.<APP_ENV>` overlay; not a config key itself.
+const ENV_SELECTOR: &str = "APP_ENV";
+
 pub trait EnvConfig: Sized + DeserializeOwned {
     const PREFIX: &'static str = "APP";
     const SEPARATOR: &'static str = "__";
+    /// Bare variables (as injected by PaaS platforms) mapped to config keys.
+    /// They only fill a key when no prefixed variable sets it.
+    const ENV_FALLBACKS: &'static [(&'static str, &'static str)] = &[];
 
-    fn load_dotenv() {
-        // Load .env from crate root (falls back to current dir if missing)
+    /// Loads `.env`, then `.env.<APP_ENV>` beside it, into the process
+    /// environment and returns the resulting variables. Precedence, highest
+    /// first: process env, `.env.<APP_ENV>`, `.env`.
+    fn load_dotenv() -> HashMap<String, String> {
+        // Load .env from crate root (falls back to the nearest one above the current dir)
         let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
-        let _ = dotenvy::from_filename(manifest_dir.join(".env")).or_else(|_| dotenvy::dotenv());
+        let base = Some(manifest_dir.join(".env"))
+            .filter(|path| path.is_file())
+            .or_else(find_dotenv);
+        let process = std::env::vars().collect();
+        let Some(base) = base else {
+            return process;
+        };
+        for path in dotenv_layers(&base, &process) {
+            // dotenvy never overwrites a variable that is already set.
+            let _ = dotenvy::from_path(path);
+        }
+        layered_vars(&base, process)
     }
 
     fn validate(&self) -> Result<()> {
@@ -19,14 +42,29 @@ pub trait EnvConfig: Sized + DeserializeOwned {
     }
 
     fn from_env() -> Result<Self> {
-        Self::load_dotenv();
+        Self::from_vars(Self::load_dotenv())
+    }
+
+    /// Same as [`EnvConfig::from_env`] but reads `vars` instead of the process
+    /// environment.
+    fn from_vars(mut vars: HashMap<String, String>) -> Result<Self> {
+        vars.remove(ENV_SELECTOR);
+        let mut builder = config_rs::Config::builder();
+        for (var, key) in Self::ENV_FALLBACKS {
+            if let Some(value) = vars.get(*var).filter(|value| !value.trim().is_empty()) {
+                builder = builder
+                    .set_default(*key, value.as_str())
+                    .with_context(|| format!("failed to apply {var} fallback"))?;
+            }
+        }
 
-        let settings = config_rs::Config::builder()
+        let settings = builder
             .add_source(
                 config_rs::Environment::with_prefix(Self::PREFIX)
                     .prefix_separator("_")
                     .separator(Self::SEPARATOR)
-                    .try_parsing(true),
+                    .try_parsing(true)
+                    .source(Some(vars)),
             )
             .build()
             .context("failed to read environment variables for config")?;
@@ -39,3 +77,180 @@ pub trait EnvConfig: Sized + DeserializeOwned {
         Ok(cfg)
     }
 }
+
+fn find_dotenv() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(".env"))
+        .find(|path| path.is_file())
+}
+
+/// Dotenv files to load, highest precedence first. `APP_ENV` is read from the
+/// process env, then from `base` itself.
+fn dotenv_layers(base: &Path, process: &HashMap<String, String>) -> Vec<PathBuf> {
+    let app_env = process.get(ENV_SELECTOR).cloned().or_else(|| {
+        dotenvy::from_path_iter(base)
+            .ok()?
+            .filter_map(|item| item.ok())
+            .find(|(key, _)| key == ENV_SELECTOR)
+            .map(|(_, value)| value)
+    });
+    let mut layers = Vec::new();
+    if let Some(app_env) = app_env.filter(|value| !value.trim().is_empty()) {
+        let file_name = format!(".env.{}", app_env.trim());
+        let path = base.with_file_name(file_name);
+        if path.is_file() {
+            layers.push(path);
+        }
+    }
+    layers.push(base.to_path_buf());
+    layers
+}
+
+/// `process` with every dotenv variable it does not already define added.
+fn layered_vars(base: &Path, mut process: HashMap<String, String>) -> HashMap<String, String> {
+    for path in dotenv_layers(base, &process) {
+        let Ok(items) = dotenvy::from_path_iter(&path) else {
+            continue;
+        };
+        for (key, value) in items.filter_map(|item| item.ok()) {
+            process.entry(key).or_insert(value);
+        }
+    }
+    process
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use super::{EnvConfig, layered_vars};
+    use crate::config::AppConfig;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn bare_database_url_and_port_are_used_as_fallbacks() {
+        let cfg = AppConfig::from_vars(vars(&[
+            ("DATABASE_URL", "postgres://paas/db"),
+            ("PORT", "8080"),
+        ]))
+        .expect("config should load");
+
+        assert_eq!(
+            cfg.database.expect("database config").url,
+            "postgres://paas/db"
+        );
+        assert_eq!(cfg.general.port, 8080);
+    }
+
+    #[test]
+    fn prefixed_variables_win_over_fallbacks() {
+        let cfg = AppConfig::from_vars(vars(&[
+            ("DATABASE_URL", "postgres://paas/db"),
+            ("APP_DATABASE__URL", "postgres://app/db"),
+            ("PORT", "8080"),
+            ("APP_GENERAL__PORT", "4000"),
+        ]))
+        .expect("config should load");
+
+        assert_eq!(
+            cfg.database.expect("database config").url,
+            "postgres://app/db"
+        );
+        assert_eq!(cfg.general.port, 4000);
+    }
+
+    #[test]
+    fn realtime_allowed_origins_are_read_as_a_comma_list() {
+        let cfg = AppConfig::from_vars(vars(&[(
+            "APP_REALTIME__ALLOWED_ORIGINS",
+            "https://app.example.com, https://admin.example.com",
+        )]))
+        .expect("config should load");
+
+        assert_eq!(
+            cfg.realtime.allowed_origins,
+            vec!["https://app.example.com", "https://admin.example.com"]
+        );
+    }
+
+    #[test]
+    fn durations_and_byte_sizes_accept_human_forms() {
+        let cfg = AppConfig::from_vars(vars(&[
+            ("APP_DATABASE__URL", "sqlite::memory:"),
+            ("APP_DATABASE__CACHE_TTL_SECS", "15m"),
+            ("APP_DATABASE__STATEMENT_TIMEOUT_MS", "500ms"),
+            ("APP_STORAGE__MAX_UPLOAD_BYTES", "10MiB"),
+            ("APP_REALTIME__IDLE_TIMEOUT_SECS", "90"),
+        ]))
+        .expect("config should load");
+
+        let database = cfg.database.expect("database config");
+        assert_eq!(database.cache_ttl_secs, 900);
+        assert_eq!(database.statement_timeout_ms, 500);
+        assert_eq!(cfg.storage.max_upload_bytes, 10 * 1024 * 1024);
+        assert_eq!(cfg.realtime.idle_timeout_secs, 90);
+    }
+
+    #[test]
+    fn malformed_duration_names_the_value_and_expected_forms() {
+        let err = AppConfig::from_vars(vars(&[(
+            "APP_GENERAL__MAINTENANCE_RETRY_AFTER_SECS",
+            "15x",
+        )]))
+        .expect_err("malformed duration should fail");
+
+        let message = format!("{err:#}");
+        assert!(message.contains("invalid duration `15x`"), "{message}");
+        assert!(message.contains("ms, s, m, h or d"), "{message}");
+        assert!(
+            message.contains("general.maintenance_retry_after_secs"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn environment_file_overrides_base_and_process_env_overrides_both() {
+        let dir = std::env::temp_dir().join(format!("rust-oxide-env-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("temp dir should be created");
+        let base = dir.join(".env");
+        std::fs::write(
+            &base,
+            "APP_ENV=staging\nAPP_GENERAL__HOST=base.local\nAPP_GENERAL__PORT=3000\n",
+        )
+        .expect("base env should be written");
+        std::fs::write(
+            dir.join(".env.staging"),
+            "APP_GENERAL__HOST=staging.local\nAPP_GENERAL__PORT=4000\n",
+        )
+        .expect("staging env should be written");
+        std::fs::write(
+            dir.join(".env.production"),
+            "APP_GENERAL__HOST=prod.local\n",
+        )
+        .expect("production env should be written");
+
+        let cfg =
+            AppConfig::from_vars(layered_vars(&base, HashMap::new())).expect("config should load");
+        assert_eq!(cfg.general.host, "staging.local");
+        assert_eq!(cfg.general.port, 4000);
+
+        let cfg = AppConfig::from_vars(layered_vars(
+            &base,
+            vars(&[("APP_ENV", "production"), ("APP_GENERAL__PORT", "5000")]),
+        ))
+        .expect("config should load");
+        assert_eq!(cfg.general.host, "prod.local");
+        assert_eq!(cfg.general.port, 5000);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
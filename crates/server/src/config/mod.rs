@@ -1,9 +1,13 @@
 pub mod configs;
 pub mod defaults;
 pub mod envconfig;
+pub mod units;
 pub mod validate;
 
 pub use configs::{
-    AppConfig, AuthConfig, DatabaseConfig, GeneralConfig, LoggingConfig, RealtimeConfig,
+    AccessLogFormat, AppConfig, AuthConfig, BodyLogConfig, DatabaseConfig, ErrorFormat,
+    GeneralConfig, JwtVerificationKey, LockoutConfig, LoggingConfig, RateLimitConfig,
+    RealtimeConfig, StorageConfig, TokenSourceConfig,
 };
 pub use envconfig::EnvConfig;
+pub use units::{parse_byte_size, parse_duration};
@@ -0,0 +1,225 @@
+//! Human-friendly durations (`500ms`, `15m`, `2h`) and byte sizes (`10MB`,
+//! `1GiB`) for config values.
+//!
+//! Fields keep a unit in their name (`*_secs`, `*_ms`, `*_bytes`) and still
+//! accept a plain number in that unit; the `deserialize_*` adapters also accept
+//! a string with a unit suffix.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer, de::Error as _};
+
+const DURATION_UNITS: &[(&str, u64)] = &[
+    ("ms", 1),
+    ("s", 1_000),
+    ("m", 60_000),
+    ("h", 3_600_000),
+    ("d", 86_400_000),
+];
+
+const BYTE_UNITS: &[(&str, u64)] = &[
+    ("b", 1),
+    ("kb", 1_000),
+    ("mb", 1_000_000),
+    ("gb", 1_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("kib", 1 << 10),
+    ("mib", 1 << 20),
+    ("gib", 1 << 30),
+    ("tib", 1 << 40),
+];
+
+/// Parses `<number><unit>` with unit `ms`, `s`, `m`, `h` or `d`, e.g. `15m`.
+pub fn parse_duration(raw: &str) -> Result<Duration, String> {
+    let millis = parse_with_units(raw, DURATION_UNITS).ok_or_else(|| {
+        format!("invalid duration `{raw}`: expected a whole number followed by ms, s, m, h or d (e.g. `15m`)")
+    })?;
+    Ok(Duration::from_millis(millis))
+}
+
+/// Parses a byte count with an optional unit: `B`, decimal `KB`/`MB`/`GB`/`TB`
+/// or binary `KiB`/`MiB`/`GiB`/`TiB`, case-insensitive. A bare number is bytes.
+pub fn parse_byte_size(raw: &str) -> Result<u64, String> {
+    let trimmed = raw.trim();
+    if let Ok(bytes) = trimmed.parse::<u64>() {
+        return Ok(bytes);
+    }
+    parse_with_units(trimmed, BYTE_UNITS).ok_or_else(|| {
+        format!("invalid byte size `{raw}`: expected a whole number optionally followed by B, KB, MB, GB, KiB, MiB or GiB (e.g. `10MB`)")
+    })
+}
+
+/// `<digits><unit>` scaled by the unit's factor; `None` when malformed or
+/// the result overflows.
+fn parse_with_units(raw: &str, units: &[(&str, u64)]) -> Option<u64> {
+    let trimmed = raw.trim();
+    let split = trimmed.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = trimmed.split_at(split);
+    let number = number.parse::<u64>().ok()?;
+    let unit = unit.trim().to_ascii_lowercase();
+    let (_, factor) = units.iter().find(|(name, _)| *name == unit)?;
+    number.checked_mul(*factor)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+    Number(u64),
+    Text(String),
+}
+
+impl RawValue {
+    /// The plain number, or the text parsed as one; `Err` holds text with a unit.
+    fn into_plain(self) -> Result<u64, String> {
+        match self {
+            RawValue::Number(value) => Ok(value),
+            RawValue::Text(text) => text.trim().parse::<u64>().map_err(|_| text),
+        }
+    }
+}
+
+/// A whole number of seconds, or a duration like `15m`.
+pub fn deserialize_secs<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match RawValue::deserialize(deserializer)?.into_plain() {
+        Ok(secs) => Ok(secs),
+        Err(text) => {
+            let duration = parse_duration(&text).map_err(D::Error::custom)?;
+            if duration.subsec_millis() != 0 {
+                return Err(D::Error::custom(format!(
+                    "invalid duration `{text}`: this setting takes whole seconds"
+                )));
+            }
+            Ok(duration.as_secs())
+        }
+    }
+}
+
+/// A whole number of milliseconds, or a duration like `500ms` or `2s`.
+pub fn deserialize_millis<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match RawValue::deserialize(deserializer)?.into_plain() {
+        Ok(millis) => Ok(millis),
+        Err(text) => {
+            let duration = parse_duration(&text).map_err(D::Error::custom)?;
+            u64::try_from(duration.as_millis())
+                .map_err(|_| D::Error::custom(format!("invalid duration `{text}`: too large")))
+        }
+    }
+}
+
+/// A number of bytes, or a size like `10MB` or `1GiB`.
+pub fn deserialize_bytes<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match RawValue::deserialize(deserializer)?.into_plain() {
+        Ok(bytes) => Ok(bytes),
+        Err(text) => parse_byte_size(&text).map_err(D::Error::custom),
+    }
+}
+
+/// [`deserialize_bytes`] for `usize` fields.
+pub fn deserialize_bytes_usize<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let bytes = deserialize_bytes(deserializer)?;
+    usize::try_from(bytes)
+        .map_err(|_| D::Error::custom(format!("byte size {bytes} is too large for this platform")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde::Deserialize;
+
+    use super::{
+        deserialize_bytes, deserialize_millis, deserialize_secs, parse_byte_size, parse_duration,
+    };
+
+    #[test]
+    fn parse_duration_accepts_every_unit() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("15m"), Ok(Duration::from_secs(15 * 60)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(2 * 3600)));
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(7 * 86_400)));
+        assert_eq!(parse_duration(" 2H "), Ok(Duration::from_secs(2 * 3600)));
+    }
+
+    #[test]
+    fn parse_duration_rejects_malformed_values_with_the_expected_forms() {
+        for raw in ["", "15", "m", "15x", "1.5h", "-3s", "99999999999999999999d"] {
+            let err = parse_duration(raw).expect_err(raw);
+            assert!(err.contains(&format!("`{raw}`")), "{err}");
+            assert!(err.contains("ms, s, m, h or d"), "{err}");
+        }
+    }
+
+    #[test]
+    fn parse_byte_size_accepts_decimal_and_binary_units() {
+        assert_eq!(parse_byte_size("512"), Ok(512));
+        assert_eq!(parse_byte_size("512B"), Ok(512));
+        assert_eq!(parse_byte_size("10KB"), Ok(10_000));
+        assert_eq!(parse_byte_size("10MB"), Ok(10_000_000));
+        assert_eq!(parse_byte_size("2GB"), Ok(2_000_000_000));
+        assert_eq!(parse_byte_size("1TB"), Ok(1_000_000_000_000));
+        assert_eq!(parse_byte_size("64KiB"), Ok(64 * 1024));
+        assert_eq!(parse_byte_size("10MiB"), Ok(10 * 1024 * 1024));
+        assert_eq!(parse_byte_size("1GiB"), Ok(1 << 30));
+        assert_eq!(parse_byte_size("1TiB"), Ok(1 << 40));
+        assert_eq!(parse_byte_size("10 mb"), Ok(10_000_000));
+    }
+
+    #[test]
+    fn parse_byte_size_rejects_malformed_values_with_the_expected_forms() {
+        for raw in ["", "MB", "10XB", "1.5GB", "-1KB"] {
+            let err = parse_byte_size(raw).expect_err(raw);
+            assert!(err.contains(&format!("`{raw}`")), "{err}");
+            assert!(err.contains("KB, MB, GB"), "{err}");
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        #[serde(deserialize_with = "deserialize_secs")]
+        ttl_secs: u64,
+        #[serde(deserialize_with = "deserialize_millis")]
+        timeout_ms: u64,
+        #[serde(deserialize_with = "deserialize_bytes")]
+        max_bytes: u64,
+    }
+
+    #[test]
+    fn adapters_accept_plain_numbers_in_the_field_unit_and_human_forms() {
+        let plain: Settings =
+            serde_json::from_str(r#"{"ttl_secs": 30, "timeout_ms": "250", "max_bytes": 1024}"#)
+                .expect("plain numbers should parse");
+        assert_eq!(plain.ttl_secs, 30);
+        assert_eq!(plain.timeout_ms, 250);
+        assert_eq!(plain.max_bytes, 1024);
+
+        let human: Settings =
+            serde_json::from_str(r#"{"ttl_secs": "15m", "timeout_ms": "2s", "max_bytes": "10MB"}"#)
+                .expect("human forms should parse");
+        assert_eq!(human.ttl_secs, 900);
+        assert_eq!(human.timeout_ms, 2_000);
+        assert_eq!(human.max_bytes, 10_000_000);
+    }
+
+    #[test]
+    fn secs_adapter_rejects_sub_second_durations() {
+        let err = serde_json::from_str::<Settings>(
+            r#"{"ttl_secs": "1500ms", "timeout_ms": 1, "max_bytes": 1}"#,
+        )
+        .expect_err("sub-second value should fail");
+
+        assert!(err.to_string().contains("takes whole seconds"), "{err}");
+    }
+}
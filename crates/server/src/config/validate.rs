@@ -1,79 +1,436 @@
-use anyhow::{Result, bail};
+use std::{fmt, net::IpAddr};
 
-use super::AppConfig;
+use super::{AppConfig, defaults};
+use crate::{db::providers::default_registry, window_counter::MAX_WINDOW};
 
-pub fn validate(cfg: &AppConfig) -> Result<()> {
-    let mut errors: Vec<String> = Vec::new();
+/// One invalid setting: the dotted config key and what is wrong with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+/// Every problem found in one validation pass, so a single run reports them all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl ConfigErrors {
+    pub fn errors(&self) -> &[ConfigError] {
+        &self.0
+    }
+
+    pub fn has_field(&self, field: &str) -> bool {
+        self.0.iter().any(|err| err.field == field)
+    }
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid app config:")?;
+        for err in &self.0 {
+            write!(f, "\n- {err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+pub fn validate(cfg: &AppConfig) -> Result<(), ConfigErrors> {
+    let mut errors: Vec<ConfigError> = Vec::new();
 
     if cfg.general.host.trim().is_empty() {
-        errors.push("general.host must not be empty".to_string());
+        errors.push(ConfigError::new("general.host", "must not be empty"));
+    }
+
+    if cfg.general.port == 0 {
+        errors.push(ConfigError::new(
+            "general.port",
+            "must be between 1 and 65535",
+        ));
+    }
+
+    if let Some(socket) = cfg.general.unix_socket.as_deref() {
+        if socket.trim().is_empty() {
+            errors.push(ConfigError::new(
+                "general.unix_socket",
+                "must not be empty when set",
+            ));
+        } else if !cfg!(unix) {
+            errors.push(ConfigError::new(
+                "general.unix_socket",
+                "is only supported on Unix platforms",
+            ));
+        }
+        if cfg.general.host != defaults::DEFAULT_HOST
+            || i64::from(cfg.general.port) != defaults::DEFAULT_PORT
+        {
+            errors.push(ConfigError::new(
+                "general.unix_socket",
+                "cannot be combined with general.host/general.port; configure one listener",
+            ));
+        }
+    }
+
+    if cfg.general.rate_limit.max_requests > 0 && cfg.general.rate_limit.window_secs == 0 {
+        errors.push(ConfigError::new(
+            "general.rate_limit.window_secs",
+            "must be > 0 when general.rate_limit.max_requests is set",
+        ));
+    }
+    if cfg.general.rate_limit.window_secs > MAX_WINDOW.as_secs() {
+        errors.push(ConfigError::new(
+            "general.rate_limit.window_secs",
+            format!("must be <= {}", MAX_WINDOW.as_secs()),
+        ));
+    }
+
+    for proxy in &cfg.general.trusted_proxies {
+        if proxy.parse::<IpAddr>().is_err() {
+            errors.push(ConfigError::new(
+                "general.trusted_proxies",
+                format!("'{proxy}' is not an IP address"),
+            ));
+        }
     }
 
     if let Some(database) = cfg.database.as_ref() {
         if database.url.trim().is_empty() {
-            errors.push("database.url must not be empty".to_string());
+            errors.push(ConfigError::new("database.url", "must not be empty"));
+        } else if let Ok(providers) = default_registry()
+            && let Err(err) = providers.provider_for_url(&database.url)
+        {
+            errors.push(ConfigError::new("database.url", err.to_string()));
         }
 
         if database.min_idle > database.max_connections {
-            errors.push(format!(
-                "database.min_idle ({}) must be <= database.max_connections ({})",
-                database.min_idle, database.max_connections
+            errors.push(ConfigError::new(
+                "database.min_idle",
+                format!(
+                    "({}) must be <= database.max_connections ({})",
+                    database.min_idle, database.max_connections
+                ),
             ));
         }
+
+        if let Some(replica_url) = database.replica_url.as_deref() {
+            if replica_url.trim().is_empty() {
+                errors.push(ConfigError::new(
+                    "database.replica_url",
+                    "must not be empty when set",
+                ));
+            } else if !same_driver(&database.url, replica_url) {
+                errors.push(ConfigError::new(
+                    "database.replica_url",
+                    "must use the same driver as database.url",
+                ));
+            }
+        }
     }
 
-    if let Some(auth) = cfg.auth.as_ref() {
+    if let Some(auth) = cfg.auth.as_ref().filter(|auth| auth.enabled) {
         if auth.admin_email.trim().is_empty() {
-            errors.push("auth.admin_email must not be empty".to_string());
+            errors.push(ConfigError::new("auth.admin_email", "must not be empty"));
         }
 
         if auth.admin_password.len() < 8 {
-            errors.push("auth.admin_password must be at least 8 characters".to_string());
+            errors.push(ConfigError::new(
+                "auth.admin_password",
+                "must be at least 8 characters",
+            ));
         }
 
         if auth.jwt_secret.trim().is_empty() {
-            errors.push("auth.jwt_secret must not be empty".to_string());
+            errors.push(ConfigError::new("auth.jwt_secret", "must not be empty"));
+        }
+
+        if auth
+            .jwt_kid
+            .as_deref()
+            .is_some_and(|kid| kid.trim().is_empty())
+        {
+            errors.push(ConfigError::new(
+                "auth.jwt_kid",
+                "must not be empty when set",
+            ));
+        }
+
+        let mut kids: Vec<&str> = auth.jwt_kid.as_deref().into_iter().collect();
+        for key in &auth.jwt_verification_keys {
+            if key.kid.trim().is_empty() || key.secret.trim().is_empty() {
+                errors.push(ConfigError::new(
+                    "auth.jwt_verification_keys",
+                    "entries need a non-empty kid and secret",
+                ));
+            } else if kids.contains(&key.kid.as_str()) {
+                errors.push(ConfigError::new(
+                    "auth.jwt_verification_keys",
+                    format!("has duplicate kid '{}'", key.kid),
+                ));
+            }
+            kids.push(&key.kid);
+        }
+
+        if auth.lockout.max_attempts > 0 && auth.lockout.window_secs == 0 {
+            errors.push(ConfigError::new(
+                "auth.lockout.window_secs",
+                "must be > 0 when auth.lockout.max_attempts is set",
+            ));
+        }
+        if auth.lockout.window_secs > MAX_WINDOW.as_secs() {
+            errors.push(ConfigError::new(
+                "auth.lockout.window_secs",
+                format!("must be <= {}", MAX_WINDOW.as_secs()),
+            ));
         }
     }
 
     if cfg.realtime.max_connections == 0 {
-        errors.push("realtime.max_connections must be > 0".to_string());
+        errors.push(ConfigError::new("realtime.max_connections", "must be > 0"));
     }
 
     if cfg.realtime.max_channels_per_connection == 0 {
-        errors.push("realtime.max_channels_per_connection must be > 0".to_string());
+        errors.push(ConfigError::new(
+            "realtime.max_channels_per_connection",
+            "must be > 0",
+        ));
     }
 
     if cfg.realtime.max_message_bytes == 0 {
-        errors.push("realtime.max_message_bytes must be > 0".to_string());
+        errors.push(ConfigError::new(
+            "realtime.max_message_bytes",
+            "must be > 0",
+        ));
     }
 
     if cfg.realtime.heartbeat_interval_secs == 0 {
-        errors.push("realtime.heartbeat_interval_secs must be > 0".to_string());
+        errors.push(ConfigError::new(
+            "realtime.heartbeat_interval_secs",
+            "must be > 0",
+        ));
     }
 
     if cfg.realtime.idle_timeout_secs <= cfg.realtime.heartbeat_interval_secs {
-        errors.push(
-            "realtime.idle_timeout_secs must be greater than realtime.heartbeat_interval_secs"
-                .to_string(),
-        );
+        errors.push(ConfigError::new(
+            "realtime.idle_timeout_secs",
+            "must be greater than realtime.heartbeat_interval_secs",
+        ));
     }
 
     if cfg.realtime.outbound_queue_size == 0 {
-        errors.push("realtime.outbound_queue_size must be > 0".to_string());
+        errors.push(ConfigError::new(
+            "realtime.outbound_queue_size",
+            "must be > 0",
+        ));
     }
 
     if cfg.realtime.emit_rate_per_sec == 0 {
-        errors.push("realtime.emit_rate_per_sec must be > 0".to_string());
+        errors.push(ConfigError::new(
+            "realtime.emit_rate_per_sec",
+            "must be > 0",
+        ));
     }
 
     if cfg.realtime.join_rate_per_sec == 0 {
-        errors.push("realtime.join_rate_per_sec must be > 0".to_string());
+        errors.push(ConfigError::new(
+            "realtime.join_rate_per_sec",
+            "must be > 0",
+        ));
+    }
+    // A join replays the whole buffer into the outbound queue at once.
+    if cfg.realtime.replay_buffer_size >= cfg.realtime.outbound_queue_size {
+        errors.push(ConfigError::new(
+            "realtime.replay_buffer_size",
+            "must be less than realtime.outbound_queue_size",
+        ));
+    }
+
+    if cfg.storage.local_dir.trim().is_empty() {
+        errors.push(ConfigError::new("storage.local_dir", "must not be empty"));
+    }
+
+    if cfg.storage.max_upload_bytes == 0 {
+        errors.push(ConfigError::new("storage.max_upload_bytes", "must be > 0"));
     }
 
     if errors.is_empty() {
         return Ok(());
     }
 
-    bail!("invalid app config:\n- {}", errors.join("\n- "))
+    Err(ConfigErrors(errors))
+}
+
+// An unrecognized primary URL is reported on its own by `validate`.
+fn same_driver(primary_url: &str, replica_url: &str) -> bool {
+    let Ok(providers) = default_registry() else {
+        return true;
+    };
+    let Ok(primary) = providers.provider_for_url(primary_url) else {
+        return true;
+    };
+    providers
+        .provider_for_url(replica_url)
+        .is_ok_and(|replica| replica.id() == primary.id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigErrors, validate};
+    use crate::config::{AppConfig, AuthConfig, DatabaseConfig};
+
+    fn config_with_auth(auth: serde_json::Value) -> AppConfig {
+        AppConfig {
+            auth: Some(serde_json::from_value::<AuthConfig>(auth).expect("auth config")),
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn enabled_auth_without_secret_fails() {
+        let cfg = config_with_auth(serde_json::json!({
+            "admin_email": "admin@example.com",
+            "admin_password": "adminpassword",
+        }));
+
+        let err = validate(&cfg).expect_err("missing secret should fail");
+        assert!(
+            err.to_string()
+                .contains("auth.jwt_secret must not be empty")
+        );
+    }
+
+    #[test]
+    fn disabled_auth_skips_secret_and_admin_checks() {
+        let cfg = config_with_auth(serde_json::json!({ "enabled": false }));
+
+        validate(&cfg).expect("disabled auth should not need a secret or admin");
+    }
+
+    #[test]
+    fn replay_buffer_must_fit_outbound_queue() {
+        let mut cfg = AppConfig::default();
+        cfg.realtime.replay_buffer_size = cfg.realtime.outbound_queue_size;
+
+        let err = validate(&cfg).expect_err("oversized replay buffer should fail");
+        assert!(err.to_string().contains("realtime.replay_buffer_size"));
+    }
+
+    #[test]
+    fn trusted_proxies_must_be_ip_addresses() {
+        let mut cfg = AppConfig::default();
+        cfg.general.trusted_proxies = vec!["10.0.0.2".to_string(), "::1".to_string()];
+        validate(&cfg).expect("IP addresses should pass");
+
+        cfg.general
+            .trusted_proxies
+            .push("proxy.internal".to_string());
+        let err = validate(&cfg).expect_err("a hostname should fail");
+        assert!(err.has_field("general.trusted_proxies"));
+    }
+
+    #[test]
+    fn counter_windows_are_bounded() {
+        let mut cfg = config_with_auth(serde_json::json!({
+            "lockout": { "window_secs": u64::MAX },
+        }));
+        cfg.general.rate_limit.window_secs = u64::MAX;
+
+        let err = validate(&cfg).expect_err("oversized windows should fail");
+        assert!(err.has_field("auth.lockout.window_secs"));
+        assert!(err.has_field("general.rate_limit.window_secs"));
+    }
+
+    #[test]
+    fn unix_socket_excludes_a_custom_tcp_address() {
+        let mut cfg = AppConfig::default();
+        cfg.general.unix_socket = Some("/run/oxide.sock".to_string());
+        if cfg!(unix) {
+            validate(&cfg).expect("socket with default host/port should pass");
+        }
+
+        cfg.general.port = 8080;
+        let err = validate(&cfg).expect_err("socket plus custom port should fail");
+        assert!(err.has_field("general.unix_socket"));
+        assert!(err.to_string().contains("configure one listener"));
+    }
+
+    #[test]
+    fn zero_cache_ttl_disables_caching_without_failing() {
+        let cfg = AppConfig {
+            database: Some(
+                serde_json::from_value::<DatabaseConfig>(serde_json::json!({
+                    "url": "sqlite://app.db",
+                    "cache_ttl_secs": 0,
+                }))
+                .expect("database config"),
+            ),
+            ..AppConfig::default()
+        };
+
+        validate(&cfg).expect("a zero ttl with the default entry limit should pass");
+    }
+
+    fn config_with_replica(url: &str, replica_url: &str) -> AppConfig {
+        AppConfig {
+            database: Some(
+                serde_json::from_value::<DatabaseConfig>(serde_json::json!({
+                    "url": url,
+                    "replica_url": replica_url,
+                }))
+                .expect("database config"),
+            ),
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn replica_url_must_match_primary_driver() {
+        let cfg = config_with_replica("postgres://app@primary/app", "sqlite://replica.db?mode=ro");
+
+        let err = validate(&cfg).expect_err("mixed drivers should fail");
+        assert!(err.to_string().contains("database.replica_url"));
+
+        validate(&config_with_replica(
+            "postgres://app@primary/app",
+            "postgresql://app@replica/app",
+        ))
+        .expect("postgres aliases are the same driver");
+    }
+
+    #[test]
+    fn reports_every_problem_at_once() {
+        let mut cfg = config_with_auth(serde_json::json!({
+            "admin_email": "admin@example.com",
+            "admin_password": "adminpassword",
+        }));
+        cfg.general.port = 0;
+        cfg.database = Some(
+            serde_json::from_value::<DatabaseConfig>(serde_json::json!({
+                "url": "oracle://app@localhost/app",
+            }))
+            .expect("database config"),
+        );
+
+        let err: ConfigErrors = validate(&cfg).expect_err("three problems should fail");
+        assert_eq!(err.errors().len(), 3, "{err}");
+        for field in ["general.port", "auth.jwt_secret", "database.url"] {
+            assert!(err.has_field(field), "missing {field} in {err}");
+        }
+        assert!(err.to_string().starts_with("invalid app config:\n- "));
+    }
 }
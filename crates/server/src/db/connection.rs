@@ -4,7 +4,13 @@ use tracing::info;
 use crate::config::DatabaseConfig;
 use crate::db::providers::default_registry;
 
-pub async fn connect(cfg: &DatabaseConfig) -> anyhow::Result<DatabaseConnection> {
+/// The primary connection plus the optional read replica from `replica_url`.
+pub struct DatabaseConnections {
+    pub primary: DatabaseConnection,
+    pub replica: Option<DatabaseConnection>,
+}
+
+pub async fn connect(cfg: &DatabaseConfig) -> anyhow::Result<DatabaseConnections> {
     let providers = default_registry()?;
     let provider = providers.provider_for_url(&cfg.url)?;
 
@@ -16,5 +22,29 @@ pub async fn connect(cfg: &DatabaseConfig) -> anyhow::Result<DatabaseConnection>
     db.get_schema_registry("rust_oxide::db::entities::*")
         .sync(&db)
         .await?;
-    Ok(db)
+
+    // The replica is read-only, so it is connected after schema sync and never synced itself.
+    let replica = match cfg.replica_url.as_deref() {
+        Some(url) => {
+            let replica_cfg = DatabaseConfig {
+                url: url.to_string(),
+                replica_url: None,
+                ..cfg.clone()
+            };
+            let provider = providers.provider_for_url(url)?;
+            info!(
+                provider = provider.id().as_str(),
+                "connecting to read replica"
+            );
+            let replica = provider.connect(&replica_cfg).await?;
+            provider.post_connect(&replica, &replica_cfg).await?;
+            Some(replica)
+        }
+        None => None,
+    };
+
+    Ok(DatabaseConnections {
+        primary: db,
+        replica,
+    })
 }
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use sea_orm::DatabaseConnection;
+
+use super::DaoBase;
+use crate::db::entities::prelude::AuthEvent;
+
+#[derive(Clone)]
+pub struct AuthEventDao {
+    db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
+}
+
+impl DaoBase for AuthEventDao {
+    type Entity = AuthEvent;
+
+    fn new(db: &DatabaseConnection) -> Self {
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
+    }
+
+    fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+}
@@ -1,13 +1,23 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Duration;
+
 use chrono::Utc;
-use sea_orm::sea_query::{Expr, ExprTrait, LikeExpr};
+use sea_orm::sea_query::{Expr, ExprTrait, Func, LikeExpr, NullOrdering};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult,
-    IntoActiveModel, Order, PrimaryKeyTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection,
+    DatabaseTransaction, DbErr, EntityTrait, FromQueryResult, IntoActiveModel, Iterable,
+    LoaderTrait, ModelTrait, Order, PaginatorTrait, PrimaryKeyToColumn, PrimaryKeyTrait,
+    QueryFilter, QueryOrder, QueryResult, QuerySelect, Related, Select, SqlErr, TransactionTrait,
 };
 use uuid::Uuid;
 
-use super::base_traits::{HasCreatedAtColumn, HasIdActiveModel, TimestampedActiveModel};
+use super::base_traits::{
+    HasArchivedAtColumn, HasCreatedAtColumn, HasDeletedAtColumn, HasIdActiveModel,
+    TimestampedActiveModel,
+};
 use super::error::{DaoLayerError, DaoResult};
+use super::timeout::timed;
 
 #[derive(Debug, serde::Serialize)]
 pub struct PaginatedResponse<T> {
@@ -15,9 +25,52 @@ pub struct PaginatedResponse<T> {
     pub page: u64,
     pub page_size: u64,
     pub has_next: bool,
+    pub has_prev: bool,
     pub total: Option<u64>,
 }
 
+/// Rows sharing one value of a grouped column, from [`DaoBase::count_by`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FacetCount {
+    pub value: serde_json::Value,
+    pub count: u64,
+}
+
+/// Reads `value` with the JSON decoder, which picks the type from the column,
+/// and `count` as an integer; expression columns have no type to go by.
+struct FacetRow(FacetCount);
+
+impl FromQueryResult for FacetRow {
+    fn from_query_result(row: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        let mut json = serde_json::Value::from_query_result(row, pre)?;
+        let count: i64 = row.try_get(pre, "count")?;
+        Ok(Self(FacetCount {
+            value: json
+                .get_mut("value")
+                .map(serde_json::Value::take)
+                .unwrap_or_default(),
+            count: u64::try_from(count).unwrap_or(0),
+        }))
+    }
+}
+
+impl<T> PaginatedResponse<T> {
+    /// Converts every item, keeping the paging fields.
+    pub fn try_map<U, E>(
+        self,
+        f: impl FnMut(T) -> Result<U, E>,
+    ) -> Result<PaginatedResponse<U>, E> {
+        Ok(PaginatedResponse {
+            data: self.data.into_iter().map(f).collect::<Result<_, _>>()?,
+            page: self.page,
+            page_size: self.page_size,
+            has_next: self.has_next,
+            has_prev: self.has_prev,
+            total: self.total,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum FilterOp {
     Eq(sea_orm::sea_query::Value),
@@ -35,6 +88,42 @@ pub enum FilterOp {
     },
 }
 
+/// Escape character of the patterns built by [`FilterOp::like_literal`].
+///
+/// Always sent as an explicit `ESCAPE` clause: Postgres falls back to `\` on
+/// its own, but SQLite treats `\` as an ordinary character unless named.
+pub const LIKE_ESCAPE: char = '\\';
+
+impl FilterOp {
+    /// `LIKE` matching `literal` as plain text, with a `%` wildcard before
+    /// and/or after it.
+    pub fn like_literal(literal: &str, leading: bool, trailing: bool) -> Self {
+        let escaped = escape_like(literal);
+        let pattern = match (leading, trailing) {
+            (true, true) => format!("%{escaped}%"),
+            (true, false) => format!("%{escaped}"),
+            (false, true) => format!("{escaped}%"),
+            (false, false) => escaped,
+        };
+        FilterOp::Like {
+            pattern,
+            escape: LIKE_ESCAPE,
+        }
+    }
+}
+
+/// Escapes `%`, `_` and [`LIKE_ESCAPE`] so `raw` matches only itself.
+pub fn escape_like(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for ch in raw.chars() {
+        if matches!(ch, '%' | '_' | LIKE_ESCAPE) {
+            escaped.push(LIKE_ESCAPE);
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum CompareOp {
     Lt,
@@ -49,6 +138,133 @@ pub struct ColumnFilter<C> {
     pub op: FilterOp,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// Sort specification for DAO list queries.
+///
+/// `nulls: None` keeps the backend's default placement of NULLs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnOrder<C> {
+    pub column: C,
+    pub order: Order,
+    pub nulls: Option<NullsOrder>,
+}
+
+impl<C> ColumnOrder<C> {
+    pub fn new(column: C, order: Order) -> Self {
+        Self {
+            column,
+            order,
+            nulls: None,
+        }
+    }
+
+    pub fn nulls(mut self, nulls: NullsOrder) -> Self {
+        self.nulls = Some(nulls);
+        self
+    }
+}
+
+impl<C> From<(C, Order)> for ColumnOrder<C> {
+    fn from((column, order): (C, Order)) -> Self {
+        Self::new(column, order)
+    }
+}
+
+fn apply_column_filters<Q, C>(select: Q, filters: &[ColumnFilter<C>]) -> Q
+where
+    Q: QueryFilter,
+    C: ColumnTrait + Copy,
+{
+    filters
+        .iter()
+        .fold(select, |select, filter| match &filter.op {
+            FilterOp::Eq(value) => select.filter(filter.column.eq(value.clone())),
+            FilterOp::Compare { op, value } => {
+                let expr = Expr::col(filter.column);
+                let value = Expr::val(value.clone());
+                let expr = match op {
+                    CompareOp::Lt => expr.lt(value),
+                    CompareOp::Lte => expr.lte(value),
+                    CompareOp::Gt => expr.gt(value),
+                    CompareOp::Gte => expr.gte(value),
+                };
+                select.filter(expr)
+            }
+            FilterOp::Like { pattern, escape } => {
+                select.filter(Expr::col(filter.column).like(LikeExpr::new(pattern).escape(*escape)))
+            }
+            FilterOp::Between { min, max } => select.filter(
+                Expr::col(filter.column).between(Expr::val(min.clone()), Expr::val(max.clone())),
+            ),
+        })
+}
+
+/// Unique-key violations from any backend. Falls back to the message text so
+/// errors that did not come through sqlx (e.g. the mock driver) still match.
+pub(crate) fn is_unique_violation(err: &DbErr) -> bool {
+    if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) {
+        return true;
+    }
+    let message = err.to_string().to_ascii_lowercase();
+    message.contains("unique constraint") || message.contains("duplicate key")
+}
+
+/// Pages are 1-based and `page_size` must be in `1..=max_page_size`.
+fn check_pagination(page: u64, page_size: u64, max_page_size: u64) -> DaoResult<()> {
+    if page == 0 || page_size == 0 || page_size > max_page_size {
+        return Err(DaoLayerError::InvalidPagination {
+            page,
+            page_size,
+            max_page_size,
+        });
+    }
+    Ok(())
+}
+
+fn apply_order<E>(
+    select: Select<E>,
+    order: Option<ColumnOrder<E::Column>>,
+    backend: DatabaseBackend,
+) -> Select<E>
+where
+    E: EntityTrait + HasCreatedAtColumn,
+    E::Column: ColumnTrait,
+{
+    let Some(ColumnOrder {
+        column,
+        order,
+        nulls,
+    }) = order
+    else {
+        return select.order_by_desc(E::created_at_column());
+    };
+    match (nulls, backend) {
+        (None, _) => select.order_by(column, order),
+        (Some(nulls), DatabaseBackend::Postgres) => {
+            let nulls = match nulls {
+                NullsOrder::First => NullOrdering::First,
+                NullsOrder::Last => NullOrdering::Last,
+            };
+            select.order_by_with_nulls(column, order, nulls)
+        }
+        // No portable NULLS FIRST/LAST: sort on `col IS NULL` before the column itself.
+        (Some(nulls), _) => {
+            let null_rank = match nulls {
+                NullsOrder::First => Order::Desc,
+                NullsOrder::Last => Order::Asc,
+            };
+            select
+                .order_by(Expr::col(column).is_null(), null_rank)
+                .order_by(column, order)
+        }
+    }
+}
+
 pub struct DaoPager<D, F>
 where
     D: DaoBase,
@@ -57,7 +273,7 @@ where
     dao: D,
     page: u64,
     page_size: u64,
-    order: Option<(<D::Entity as EntityTrait>::Column, Order)>,
+    order: Option<ColumnOrder<<D::Entity as EntityTrait>::Column>>,
     apply: F,
     done: bool,
 }
@@ -112,23 +328,75 @@ where
 
     fn db(&self) -> &DatabaseConnection;
 
+    /// Client-side bound on each statement this DAO runs, from
+    /// [`client_statement_timeout`](super::timeout::client_statement_timeout).
+    /// `None` leaves timing to the database.
+    fn statement_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns this DAO with `timeout` as its statement bound. DAOs that do not
+    /// store one keep the default of no bound.
+    fn with_statement_timeout(self, _timeout: Option<Duration>) -> Self {
+        self
+    }
+
     async fn create(
         &self,
         data: impl IntoActiveModel<<Self::Entity as EntityTrait>::ActiveModel> + Send,
     ) -> DaoResult<<Self::Entity as EntityTrait>::Model> {
+        self.create_in(self.db(), data).await
+    }
+
+    /// Like [`create`](Self::create) but runs on `conn`, e.g. a transaction.
+    async fn create_in<C>(
+        &self,
+        conn: &C,
+        data: impl IntoActiveModel<<Self::Entity as EntityTrait>::ActiveModel> + Send,
+    ) -> DaoResult<<Self::Entity as EntityTrait>::Model>
+    where
+        C: ConnectionTrait + Sync,
+    {
         let now = Utc::now().fixed_offset();
         let mut active = data.into_active_model();
         active.set_id(Uuid::new_v4());
         active.set_created_at(now);
         active.set_updated_at(now);
-        active.insert(self.db()).await.map_err(DaoLayerError::Db)
+        timed(self.statement_timeout(), active.insert(conn)).await
     }
 
     async fn find_by_id(&self, id: Uuid) -> DaoResult<<Self::Entity as EntityTrait>::Model> {
-        let model = Self::Entity::find_by_id(id)
-            .one(self.db())
-            .await
-            .map_err(DaoLayerError::Db)?;
+        let model = timed(
+            self.statement_timeout(),
+            Self::Entity::find_by_id(id).one(self.db()),
+        )
+        .await?;
+
+        model.ok_or(DaoLayerError::NotFound {
+            entity: std::any::type_name::<Self::Entity>(),
+            id,
+        })
+    }
+
+    /// Loads a row inside `txn` and locks it until the transaction ends, so a
+    /// read-modify-write (e.g. decrementing stock) cannot interleave with
+    /// another writer of the same row.
+    ///
+    /// Postgres and MySQL run `SELECT ... FOR UPDATE`; a concurrent caller
+    /// blocks until this transaction commits or rolls back. SQLite has no row
+    /// locks, so it runs a plain select and relies on its single-writer lock:
+    /// the first write in the transaction takes the database lock, and a
+    /// competing writer waits or fails with `database is locked`.
+    async fn find_by_id_for_update(
+        &self,
+        txn: &DatabaseTransaction,
+        id: Uuid,
+    ) -> DaoResult<<Self::Entity as EntityTrait>::Model> {
+        let mut select = Self::Entity::find_by_id(id);
+        if txn.get_database_backend() != DatabaseBackend::Sqlite {
+            select = select.lock_exclusive();
+        }
+        let model = timed(self.statement_timeout(), select.one(txn)).await?;
 
         model.ok_or(DaoLayerError::NotFound {
             entity: std::any::type_name::<Self::Entity>(),
@@ -140,27 +408,21 @@ where
         &self,
         page: u64,
         page_size: u64,
-        order: Option<(<Self::Entity as EntityTrait>::Column, Order)>,
+        order: Option<ColumnOrder<<Self::Entity as EntityTrait>::Column>>,
         apply: impl FnOnce(Select<Self::Entity>) -> Select<Self::Entity> + Send,
     ) -> DaoResult<PaginatedResponse<<Self::Entity as EntityTrait>::Model>> {
-        if page == 0 || page_size == 0 || page_size > Self::MAX_PAGE_SIZE {
-            return Err(DaoLayerError::InvalidPagination { page, page_size });
-        }
+        check_pagination(page, page_size, Self::MAX_PAGE_SIZE)?;
 
         let base = Self::Entity::find();
         let filtered = apply(base);
-        let ordered = match order {
-            Some((column, order)) => filtered.order_by(column, order),
-            None => filtered.order_by_desc(Self::Entity::created_at_column()),
-        };
+        let ordered = apply_order(filtered, order, self.db().get_database_backend());
         let fetch_size = page_size.saturating_add(1);
         let offset = page.saturating_sub(1).saturating_mul(page_size);
-        let mut data = ordered
-            .limit(fetch_size)
-            .offset(offset)
-            .all(self.db())
-            .await
-            .map_err(DaoLayerError::Db)?;
+        let mut data = timed(
+            self.statement_timeout(),
+            ordered.limit(fetch_size).offset(offset).all(self.db()),
+        )
+        .await?;
 
         let has_next = data.len() > page_size as usize;
         if has_next {
@@ -172,6 +434,7 @@ where
             page,
             page_size,
             has_next,
+            has_prev: page > 1,
             total: None,
         })
     }
@@ -180,53 +443,24 @@ where
         &self,
         page: u64,
         page_size: u64,
-        order: Option<(<Self::Entity as EntityTrait>::Column, Order)>,
+        order: Option<ColumnOrder<<Self::Entity as EntityTrait>::Column>>,
         filters: &[ColumnFilter<<Self::Entity as EntityTrait>::Column>],
         apply: impl FnOnce(Select<Self::Entity>) -> Select<Self::Entity> + Send,
     ) -> DaoResult<PaginatedResponse<<Self::Entity as EntityTrait>::Model>>
     where
         <Self::Entity as EntityTrait>::Column: Copy,
     {
-        if page == 0 || page_size == 0 || page_size > Self::MAX_PAGE_SIZE {
-            return Err(DaoLayerError::InvalidPagination { page, page_size });
-        }
+        check_pagination(page, page_size, Self::MAX_PAGE_SIZE)?;
 
-        let base = Self::Entity::find();
-        let filtered = apply(base);
-        let filtered = filters
-            .iter()
-            .fold(filtered, |select, filter| match &filter.op {
-                FilterOp::Eq(value) => select.filter(filter.column.eq(value.clone())),
-                FilterOp::Compare { op, value } => {
-                    let expr = Expr::col(filter.column);
-                    let value = Expr::val(value.clone());
-                    let expr = match op {
-                        CompareOp::Lt => expr.lt(value),
-                        CompareOp::Lte => expr.lte(value),
-                        CompareOp::Gt => expr.gt(value),
-                        CompareOp::Gte => expr.gte(value),
-                    };
-                    select.filter(expr)
-                }
-                FilterOp::Like { pattern, escape } => select
-                    .filter(Expr::col(filter.column).like(LikeExpr::new(pattern).escape(*escape))),
-                FilterOp::Between { min, max } => select.filter(
-                    Expr::col(filter.column)
-                        .between(Expr::val(min.clone()), Expr::val(max.clone())),
-                ),
-            });
-        let ordered = match order {
-            Some((column, order)) => filtered.order_by(column, order),
-            None => filtered.order_by_desc(Self::Entity::created_at_column()),
-        };
+        let filtered = apply_column_filters(apply(Self::Entity::find()), filters);
+        let ordered = apply_order(filtered, order, self.db().get_database_backend());
         let fetch_size = page_size.saturating_add(1);
         let offset = page.saturating_sub(1).saturating_mul(page_size);
-        let mut data = ordered
-            .limit(fetch_size)
-            .offset(offset)
-            .all(self.db())
-            .await
-            .map_err(DaoLayerError::Db)?;
+        let mut data = timed(
+            self.statement_timeout(),
+            ordered.limit(fetch_size).offset(offset).all(self.db()),
+        )
+        .await?;
 
         let has_next = data.len() > page_size as usize;
         if has_next {
@@ -238,14 +472,173 @@ where
             page,
             page_size,
             has_next,
+            has_prev: page > 1,
             total: None,
         })
     }
 
+    async fn count_with_filters(
+        &self,
+        filters: &[ColumnFilter<<Self::Entity as EntityTrait>::Column>],
+        apply: impl FnOnce(Select<Self::Entity>) -> Select<Self::Entity> + Send,
+    ) -> DaoResult<u64>
+    where
+        <Self::Entity as EntityTrait>::Column: Copy,
+    {
+        timed(
+            self.statement_timeout(),
+            apply_column_filters(apply(Self::Entity::find()), filters).count(self.db()),
+        )
+        .await
+    }
+
+    /// Counts rows matching `filters` per distinct value of `column`, largest
+    /// group first. `NULL` is reported as its own group.
+    async fn count_by(
+        &self,
+        column: <Self::Entity as EntityTrait>::Column,
+        filters: &[ColumnFilter<<Self::Entity as EntityTrait>::Column>],
+        apply: impl FnOnce(Select<Self::Entity>) -> Select<Self::Entity> + Send,
+    ) -> DaoResult<Vec<FacetCount>>
+    where
+        <Self::Entity as EntityTrait>::Column: Copy,
+    {
+        let select = apply_column_filters(apply(Self::Entity::find()), filters)
+            .select_only()
+            .column_as(column, "value")
+            .column_as(Expr::cust("COUNT(*)"), "count")
+            .group_by(column)
+            .into_model::<FacetRow>();
+        let rows = timed(self.statement_timeout(), select.all(self.db())).await?;
+
+        let mut counts: Vec<FacetCount> = rows.into_iter().map(|row| row.0).collect();
+        counts.sort_by_key(|facet| std::cmp::Reverse(facet.count));
+        Ok(counts)
+    }
+
+    /// Fetches every row matching `filters`, grouped by `key_fn` (typically a
+    /// foreign key column). Rows keep the default newest-first order within
+    /// each group. There is no page limit, so filter narrowly.
+    async fn find_map_by<K, F>(
+        &self,
+        filters: &[ColumnFilter<<Self::Entity as EntityTrait>::Column>],
+        key_fn: F,
+    ) -> DaoResult<HashMap<K, Vec<<Self::Entity as EntityTrait>::Model>>>
+    where
+        <Self::Entity as EntityTrait>::Column: Copy,
+        K: Eq + Hash + Send,
+        F: Fn(&<Self::Entity as EntityTrait>::Model) -> K + Send,
+    {
+        let select = apply_order(
+            apply_column_filters(Self::Entity::find(), filters),
+            None,
+            self.db().get_database_backend(),
+        );
+        let rows = timed(self.statement_timeout(), select.all(self.db())).await?;
+
+        let mut grouped: HashMap<K, Vec<_>> = HashMap::new();
+        for row in rows {
+            grouped.entry(key_fn(&row)).or_default().push(row);
+        }
+        Ok(grouped)
+    }
+
+    /// Like `find_map_by` for keys expected to be unique. When several rows
+    /// share a key the first one wins, which is the newest by `created_at`.
+    async fn find_one_map_by<K, F>(
+        &self,
+        filters: &[ColumnFilter<<Self::Entity as EntityTrait>::Column>],
+        key_fn: F,
+    ) -> DaoResult<HashMap<K, <Self::Entity as EntityTrait>::Model>>
+    where
+        <Self::Entity as EntityTrait>::Column: Copy,
+        K: Eq + Hash + Send,
+        F: Fn(&<Self::Entity as EntityTrait>::Model) -> K + Send,
+    {
+        Ok(self
+            .find_map_by(filters, key_fn)
+            .await?
+            .into_iter()
+            .filter_map(|(key, rows)| rows.into_iter().next().map(|row| (key, row)))
+            .collect())
+    }
+
+    /// Lists the direct children of a row in a self-referential table: rows
+    /// whose `parent_column` (e.g. `parent_id`) equals `parent_id`, or the
+    /// roots when `parent_id` is `None`. Newest first, with no page limit.
+    async fn find_children(
+        &self,
+        parent_column: <Self::Entity as EntityTrait>::Column,
+        parent_id: Option<Uuid>,
+    ) -> DaoResult<Vec<<Self::Entity as EntityTrait>::Model>> {
+        let condition = match parent_id {
+            Some(id) => parent_column.eq(id),
+            None => parent_column.is_null(),
+        };
+        let select = apply_order(
+            Self::Entity::find().filter(condition),
+            None,
+            self.db().get_database_backend(),
+        );
+        timed(self.statement_timeout(), select.all(self.db())).await
+    }
+
+    /// Returns whether a row exists whose `column` equals `value` ignoring case.
+    ///
+    /// Compares `LOWER(col) = LOWER(?)`, which behaves the same on every backend.
+    /// Plain unique indexes are case-sensitive; back this check with an index on
+    /// `LOWER(col)` so concurrent inserts cannot slip past it.
+    async fn exists_ci(
+        &self,
+        column: <Self::Entity as EntityTrait>::Column,
+        value: &str,
+    ) -> DaoResult<bool> {
+        let select = Self::Entity::find().filter(
+            Expr::expr(Func::lower(Expr::col((Self::Entity::default(), column))))
+                .eq(Func::lower(Expr::val(value))),
+        );
+        let count = timed(self.statement_timeout(), select.count(self.db())).await?;
+        Ok(count > 0)
+    }
+
+    /// Returns the first row matching `lookup`, or inserts `default` when none
+    /// exists. The flag is `true` when the row was created.
+    ///
+    /// When a concurrent insert wins the race the insert fails on a unique
+    /// index, and the lookup is retried once to return the winner's row. This
+    /// needs a unique index covering the `lookup` columns to be race-free.
+    async fn find_or_create(
+        &self,
+        lookup: &[ColumnFilter<<Self::Entity as EntityTrait>::Column>],
+        default: impl IntoActiveModel<<Self::Entity as EntityTrait>::ActiveModel> + Send,
+    ) -> DaoResult<(<Self::Entity as EntityTrait>::Model, bool)>
+    where
+        <Self::Entity as EntityTrait>::Column: Copy,
+    {
+        let find_existing = || {
+            timed(
+                self.statement_timeout(),
+                apply_column_filters(Self::Entity::find(), lookup).one(self.db()),
+            )
+        };
+
+        if let Some(existing) = find_existing().await? {
+            return Ok((existing, false));
+        }
+        match self.create(default).await {
+            Ok(created) => Ok((created, true)),
+            Err(DaoLayerError::Db(err)) if is_unique_violation(&err) => find_existing()
+                .await?
+                .map(|existing| (existing, false))
+                .ok_or(DaoLayerError::Db(err)),
+            Err(err) => Err(err),
+        }
+    }
+
     fn find_iter<F>(
         &self,
         page_size: Option<u64>,
-        order: Option<(<Self::Entity as EntityTrait>::Column, Order)>,
+        order: Option<ColumnOrder<<Self::Entity as EntityTrait>::Column>>,
         apply: F,
     ) -> DaoPager<Self, F>
     where
@@ -263,31 +656,296 @@ where
         }
     }
 
+    /// Eager-loads a has-many relation for `models`, returning one `Vec` per input model in order.
+    async fn load_many_related<R>(
+        &self,
+        models: &[<Self::Entity as EntityTrait>::Model],
+    ) -> DaoResult<Vec<Vec<R::Model>>>
+    where
+        R: EntityTrait,
+        R::Model: Send + Sync,
+        Self::Entity: Related<R>,
+        <Self::Entity as EntityTrait>::Model: ModelTrait<Entity = Self::Entity>,
+    {
+        timed(
+            self.statement_timeout(),
+            models.load_many(R::default(), self.db()),
+        )
+        .await
+    }
+
     async fn update<F>(&self, id: Uuid, apply: F) -> DaoResult<<Self::Entity as EntityTrait>::Model>
     where
         F: for<'a> FnOnce(&'a mut <Self::Entity as EntityTrait>::ActiveModel) + Send,
     {
-        let model = Self::Entity::find_by_id(id)
-            .one(self.db())
-            .await
-            .map_err(DaoLayerError::Db)?
-            .ok_or(DaoLayerError::NotFound {
-                entity: std::any::type_name::<Self::Entity>(),
-                id,
-            })?;
+        self.update_with_touch(id, true, apply).await
+    }
+
+    /// Like [`update`](Self::update) but leaves `updated_at` alone, for
+    /// backfills and other maintenance edits that are not user changes.
+    async fn update_untouched<F>(
+        &self,
+        id: Uuid,
+        apply: F,
+    ) -> DaoResult<<Self::Entity as EntityTrait>::Model>
+    where
+        F: for<'a> FnOnce(&'a mut <Self::Entity as EntityTrait>::ActiveModel) + Send,
+    {
+        self.update_with_touch(id, false, apply).await
+    }
+
+    /// Applies `apply` to the stored row; `touch` controls whether
+    /// `updated_at` is refreshed.
+    async fn update_with_touch<F>(
+        &self,
+        id: Uuid,
+        touch: bool,
+        apply: F,
+    ) -> DaoResult<<Self::Entity as EntityTrait>::Model>
+    where
+        F: for<'a> FnOnce(&'a mut <Self::Entity as EntityTrait>::ActiveModel) + Send,
+    {
+        self.update_in(self.db(), id, touch, apply).await
+    }
+
+    /// Like [`update_with_touch`](Self::update_with_touch) but runs on `conn`,
+    /// e.g. a transaction.
+    async fn update_in<C, F>(
+        &self,
+        conn: &C,
+        id: Uuid,
+        touch: bool,
+        apply: F,
+    ) -> DaoResult<<Self::Entity as EntityTrait>::Model>
+    where
+        C: ConnectionTrait + Sync,
+        F: for<'a> FnOnce(&'a mut <Self::Entity as EntityTrait>::ActiveModel) + Send,
+    {
+        let model = timed(
+            self.statement_timeout(),
+            Self::Entity::find_by_id(id).one(conn),
+        )
+        .await?
+        .ok_or(DaoLayerError::NotFound {
+            entity: std::any::type_name::<Self::Entity>(),
+            id,
+        })?;
 
         let mut active = model.into_active_model();
         apply(&mut active);
-        active.set_updated_at(Utc::now().fixed_offset());
+        if touch {
+            active.set_updated_at(Utc::now().fixed_offset());
+        }
 
-        active.update(self.db()).await.map_err(DaoLayerError::Db)
+        timed(self.statement_timeout(), active.update(conn)).await
     }
 
-    async fn delete(&self, id: Uuid) -> DaoResult<Uuid> {
-        let result = Self::Entity::delete_by_id(id)
-            .exec(self.db())
+    /// Walks every row in primary-key order, `batch_size` rows at a time, and
+    /// saves what `transform` returns; `None` leaves a row as it is.
+    ///
+    /// For data backfills that go with a schema change. Each batch is read
+    /// with a keyset cursor and written in its own transaction, so a large
+    /// table is never loaded whole or locked for the whole run. `updated_at`
+    /// is left alone. Returns the number of rows written, or
+    /// [`DaoLayerError::InvalidBatchSize`] when `batch_size` is 0.
+    async fn backfill<F>(&self, batch_size: u64, mut transform: F) -> DaoResult<u64>
+    where
+        F: FnMut(
+                <Self::Entity as EntityTrait>::Model,
+            ) -> Option<<Self::Entity as EntityTrait>::ActiveModel>
+            + Send,
+    {
+        if batch_size == 0 {
+            return Err(DaoLayerError::InvalidBatchSize);
+        }
+        let Some(key) = <Self::Entity as EntityTrait>::PrimaryKey::iter().next() else {
+            return Ok(0);
+        };
+        let key = key.into_column();
+
+        let mut cursor: Option<sea_orm::Value> = None;
+        let mut batches = 0u64;
+        let mut written = 0u64;
+        loop {
+            let mut select = Self::Entity::find().order_by_asc(key).limit(batch_size);
+            if let Some(last) = cursor.clone() {
+                select = select.filter(Expr::col(key).gt(last));
+            }
+            let rows = timed(self.statement_timeout(), select.all(self.db())).await?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            cursor = Some(last.get(key));
+            let fetched = rows.len() as u64;
+
+            let txn = timed(self.statement_timeout(), self.db().begin()).await?;
+            for row in rows {
+                if let Some(active) = transform(row) {
+                    timed(self.statement_timeout(), active.update(&txn)).await?;
+                    written += 1;
+                }
+            }
+            timed(self.statement_timeout(), txn.commit()).await?;
+
+            batches += 1;
+            tracing::info!(
+                entity = std::any::type_name::<Self::Entity>(),
+                batch = batches,
+                rows_written = written,
+                "backfill batch committed"
+            );
+            if fetched < batch_size {
+                break;
+            }
+        }
+
+        Ok(written)
+    }
+
+    /// Refreshes `updated_at` (and `last_seen` when the entity has one) without
+    /// reading or rewriting any other column, e.g. for heartbeats.
+    async fn touch(&self, id: Uuid) -> DaoResult<<Self::Entity as EntityTrait>::Model> {
+        let now = Utc::now().fixed_offset();
+        let mut active =
+            <<Self::Entity as EntityTrait>::ActiveModel as ActiveModelTrait>::default();
+        active.set_id(id);
+        active.set_updated_at(now);
+        active.set_last_seen(now);
+
+        timed(self.statement_timeout(), active.update(self.db()))
             .await
-            .map_err(DaoLayerError::Db)?;
+            .map_err(|err| match err {
+                DaoLayerError::Db(DbErr::RecordNotUpdated) => DaoLayerError::NotFound {
+                    entity: std::any::type_name::<Self::Entity>(),
+                    id,
+                },
+                other => other,
+            })
+    }
+
+    /// Stamps `archived_at`, hiding the row from `find_unarchived` and from
+    /// list routes. Unlike [`delete`](Self::delete) the row stays recoverable.
+    async fn archive(&self, id: Uuid) -> DaoResult<<Self::Entity as EntityTrait>::Model>
+    where
+        Self::Entity: HasArchivedAtColumn,
+    {
+        let now = Utc::now().fixed_offset();
+        self.update(id, move |active| {
+            active.set(
+                <Self::Entity as HasArchivedAtColumn>::archived_at_column(),
+                Some(now).into(),
+            )
+        })
+        .await
+    }
+
+    /// Clears `archived_at`, restoring an archived row.
+    async fn unarchive(&self, id: Uuid) -> DaoResult<<Self::Entity as EntityTrait>::Model>
+    where
+        Self::Entity: HasArchivedAtColumn,
+    {
+        self.update(id, |active| {
+            active.set(
+                <Self::Entity as HasArchivedAtColumn>::archived_at_column(),
+                Option::<chrono::DateTime<chrono::FixedOffset>>::None.into(),
+            )
+        })
+        .await
+    }
+
+    /// [`find`](Self::find) limited to rows that are not archived.
+    async fn find_unarchived(
+        &self,
+        page: u64,
+        page_size: u64,
+        order: Option<ColumnOrder<<Self::Entity as EntityTrait>::Column>>,
+        apply: impl FnOnce(Select<Self::Entity>) -> Select<Self::Entity> + Send,
+    ) -> DaoResult<PaginatedResponse<<Self::Entity as EntityTrait>::Model>>
+    where
+        Self::Entity: HasArchivedAtColumn,
+    {
+        let column = <Self::Entity as HasArchivedAtColumn>::archived_at_column();
+        self.find(page, page_size, order, move |select| {
+            apply(select).filter(column.is_null())
+        })
+        .await
+    }
+
+    /// Stamps `deleted_at`, tombstoning the row: list routes skip it, but it
+    /// stays in the table until [`delete`](Self::delete) removes it.
+    async fn soft_delete(&self, id: Uuid) -> DaoResult<<Self::Entity as EntityTrait>::Model>
+    where
+        Self::Entity: HasDeletedAtColumn,
+    {
+        let now = Utc::now().fixed_offset();
+        self.update(id, move |active| {
+            active.set(
+                <Self::Entity as HasDeletedAtColumn>::deleted_at_column(),
+                Some(now).into(),
+            )
+        })
+        .await
+    }
+
+    /// Clears `deleted_at`, restoring a soft-deleted row.
+    async fn restore(&self, id: Uuid) -> DaoResult<<Self::Entity as EntityTrait>::Model>
+    where
+        Self::Entity: HasDeletedAtColumn,
+    {
+        self.update(id, |active| {
+            active.set(
+                <Self::Entity as HasDeletedAtColumn>::deleted_at_column(),
+                Option::<chrono::DateTime<chrono::FixedOffset>>::None.into(),
+            )
+        })
+        .await
+    }
+
+    /// Clears `deleted_at` on every soft-deleted row matching `filters`, e.g.
+    /// to undo a bulk soft delete, and returns how many rows were restored.
+    ///
+    /// At least one filter is required so a missing filter cannot restore the
+    /// whole table. `updated_at` is left alone.
+    async fn restore_many_with_filters(
+        &self,
+        filters: &[ColumnFilter<<Self::Entity as EntityTrait>::Column>],
+    ) -> DaoResult<u64>
+    where
+        Self::Entity: HasDeletedAtColumn,
+    {
+        if filters.is_empty() {
+            return Err(DaoLayerError::MissingFilter);
+        }
+        let column = <Self::Entity as HasDeletedAtColumn>::deleted_at_column();
+        let update = Self::Entity::update_many()
+            .col_expr(
+                column,
+                Expr::value(Option::<chrono::DateTime<chrono::FixedOffset>>::None),
+            )
+            .filter(column.is_not_null());
+        let result = timed(
+            self.statement_timeout(),
+            apply_column_filters(update, filters).exec(self.db()),
+        )
+        .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn delete(&self, id: Uuid) -> DaoResult<Uuid> {
+        self.delete_in(self.db(), id).await
+    }
+
+    /// Like [`delete`](Self::delete) but runs on `conn`, e.g. a transaction.
+    async fn delete_in<C>(&self, conn: &C, id: Uuid) -> DaoResult<Uuid>
+    where
+        C: ConnectionTrait + Sync,
+    {
+        let result = timed(
+            self.statement_timeout(),
+            Self::Entity::delete_by_id(id).exec(conn),
+        )
+        .await?;
 
         if result.rows_affected == 0 {
             return Err(DaoLayerError::NotFound {
@@ -307,13 +965,13 @@ mod tests {
     use sea_orm::sea_query::Value;
     use sea_orm::{
         DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, MockExecResult, Order,
-        QueryFilter, Set,
+        QueryFilter, Set, TransactionTrait,
     };
     use uuid::Uuid;
 
     use super::{
-        ColumnFilter, CompareOp, DaoBase, DaoLayerError, FilterOp, HasCreatedAtColumn,
-        HasIdActiveModel, TimestampedActiveModel,
+        ColumnFilter, ColumnOrder, CompareOp, DaoBase, DaoLayerError, FilterOp, HasCreatedAtColumn,
+        HasIdActiveModel, NullsOrder, TimestampedActiveModel,
     };
 
     mod test_entity {
@@ -336,6 +994,129 @@ mod tests {
         impl ActiveModelBehavior for ActiveModel {}
     }
 
+    mod archivable_entity {
+        use base_entity_derive::base_entity;
+        use sea_orm::entity::prelude::*;
+
+        #[base_entity(archived_at = "archived_at")]
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "archivable_records")]
+        pub struct Model {
+            pub name: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    mod soft_deletable_entity {
+        use base_entity_derive::base_entity;
+        use sea_orm::entity::prelude::*;
+
+        #[base_entity(deleted_at = "deleted_at")]
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "soft_deletable_records")]
+        pub struct Model {
+            pub name: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    mod tree_entity {
+        use base_entity_derive::base_entity;
+        use sea_orm::entity::prelude::*;
+
+        #[base_entity]
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "tree_nodes")]
+        pub struct Model {
+            pub name: String,
+            pub parent_id: Option<Uuid>,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    #[derive(Clone)]
+    struct TreeDao {
+        db: DatabaseConnection,
+    }
+
+    impl DaoBase for TreeDao {
+        type Entity = tree_entity::Entity;
+
+        fn new(db: &DatabaseConnection) -> Self {
+            Self { db: db.clone() }
+        }
+
+        fn db(&self) -> &DatabaseConnection {
+            &self.db
+        }
+    }
+
+    #[derive(Clone)]
+    struct ArchivableDao {
+        db: DatabaseConnection,
+    }
+
+    impl DaoBase for ArchivableDao {
+        type Entity = archivable_entity::Entity;
+
+        fn new(db: &DatabaseConnection) -> Self {
+            Self { db: db.clone() }
+        }
+
+        fn db(&self) -> &DatabaseConnection {
+            &self.db
+        }
+    }
+
+    #[derive(Clone)]
+    struct SoftDeletableDao {
+        db: DatabaseConnection,
+    }
+
+    impl DaoBase for SoftDeletableDao {
+        type Entity = soft_deletable_entity::Entity;
+
+        fn new(db: &DatabaseConnection) -> Self {
+            Self { db: db.clone() }
+        }
+
+        fn db(&self) -> &DatabaseConnection {
+            &self.db
+        }
+    }
+
+    fn soft_deletable(id: Uuid, deleted: bool) -> soft_deletable_entity::Model {
+        soft_deletable_entity::Model {
+            id,
+            created_at: ts(),
+            updated_at: ts(),
+            name: "report".to_string(),
+            deleted_at: deleted.then(ts),
+        }
+    }
+
+    fn archivable(id: Uuid, archived: bool) -> archivable_entity::Model {
+        archivable_entity::Model {
+            id,
+            created_at: ts(),
+            updated_at: ts(),
+            name: "report".to_string(),
+            archived_at: archived.then(ts),
+        }
+    }
+
     impl HasCreatedAtColumn for test_entity::Entity {
         fn created_at_column() -> Self::Column {
             test_entity::Column::CreatedAt
@@ -396,6 +1177,16 @@ mod tests {
             self
         }
 
+        fn with_count_result(mut self, count: i64) -> Self {
+            self.mock = self
+                .mock
+                .append_query_results([[std::collections::BTreeMap::from([(
+                    "num_items",
+                    Value::from(count),
+                )])]]);
+            self
+        }
+
         fn with_query_error(mut self, error: DbErr) -> Self {
             self.mock = self.mock.append_query_errors([error]);
             self
@@ -576,6 +1367,50 @@ mod tests {
         assert!(matches!(err, DaoLayerError::Db(_)));
     }
 
+    #[tokio::test]
+    async fn find_by_id_for_update_locks_row_on_postgres() {
+        let id = Uuid::new_v4();
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![model(id, "first", 1)]])
+            .build();
+
+        let txn = fixture.db.begin().await.expect("begin should succeed");
+        let found = fixture
+            .dao
+            .find_by_id_for_update(&txn, id)
+            .await
+            .expect("find_by_id_for_update should succeed");
+        txn.commit().await.expect("commit should succeed");
+
+        assert_eq!(found.id, id);
+        let select = sql_log(&fixture.db)
+            .into_iter()
+            .find(|sql| sql.starts_with("select"))
+            .expect("select should be logged");
+        assert!(select.ends_with("for update"), "{select}");
+    }
+
+    #[tokio::test]
+    async fn find_by_id_for_update_skips_lock_clause_on_sqlite() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Sqlite)
+            .append_query_results(vec![vec![model(id, "first", 1)]])
+            .into_connection();
+        let dao = TestDao::new(&db);
+
+        let txn = db.begin().await.expect("begin should succeed");
+        dao.find_by_id_for_update(&txn, id)
+            .await
+            .expect("find_by_id_for_update should succeed");
+        txn.commit().await.expect("commit should succeed");
+
+        let select = sql_log(&db)
+            .into_iter()
+            .find(|sql| sql.starts_with("select"))
+            .expect("select should be logged");
+        assert!(!select.contains("for update"), "{select}");
+    }
+
     #[tokio::test]
     async fn find_returns_requested_page_value() {
         let fixture = DaoFixtureBuilder::new()
@@ -606,7 +1441,8 @@ mod tests {
             err,
             DaoLayerError::InvalidPagination {
                 page: 0,
-                page_size: 1
+                page_size: 1,
+                ..
             }
         ));
     }
@@ -625,7 +1461,8 @@ mod tests {
             err,
             DaoLayerError::InvalidPagination {
                 page: 1,
-                page_size: 0
+                page_size: 0,
+                ..
             }
         ));
     }
@@ -644,7 +1481,8 @@ mod tests {
             err,
             DaoLayerError::InvalidPagination {
                 page: 1,
-                page_size: v
+                page_size: v,
+                ..
             } if v == TestDao::MAX_PAGE_SIZE + 1
         ));
     }
@@ -732,7 +1570,7 @@ mod tests {
             .find(
                 1,
                 1,
-                Some((test_entity::Column::Name, Order::Asc)),
+                Some((test_entity::Column::Name, Order::Asc).into()),
                 |query| query,
             )
             .await
@@ -743,6 +1581,79 @@ mod tests {
         assert!(sql.contains("order by") && sql.contains("name") && sql.contains("asc"));
     }
 
+    #[tokio::test]
+    async fn find_appends_nulls_last_for_postgres() {
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![Vec::<test_entity::Model>::new()])
+            .build();
+
+        fixture
+            .dao
+            .find(
+                1,
+                1,
+                Some(
+                    ColumnOrder::new(test_entity::Column::Name, Order::Desc)
+                        .nulls(NullsOrder::Last),
+                ),
+                |query| query,
+            )
+            .await
+            .expect("find should succeed");
+
+        let sql = first_sql(&fixture.db);
+
+        assert!(sql.contains(r#"order by "test_records"."name" desc nulls last"#));
+    }
+
+    #[tokio::test]
+    async fn find_with_filters_appends_nulls_first_for_postgres() {
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![Vec::<test_entity::Model>::new()])
+            .build();
+
+        fixture
+            .dao
+            .find_with_filters(
+                1,
+                1,
+                Some(
+                    ColumnOrder::new(test_entity::Column::Score, Order::Asc)
+                        .nulls(NullsOrder::First),
+                ),
+                &[],
+                |query| query,
+            )
+            .await
+            .expect("find_with_filters should succeed");
+
+        let sql = first_sql(&fixture.db);
+
+        assert!(sql.contains(r#"order by "test_records"."score" asc nulls first"#));
+    }
+
+    #[tokio::test]
+    async fn find_emulates_nulls_order_for_sqlite() {
+        let db = MockDatabase::new(DatabaseBackend::Sqlite)
+            .append_query_results(vec![Vec::<test_entity::Model>::new()])
+            .into_connection();
+        let dao = TestDao::new(&db);
+
+        dao.find(
+            1,
+            1,
+            Some(ColumnOrder::new(test_entity::Column::Name, Order::Desc).nulls(NullsOrder::Last)),
+            |query| query,
+        )
+        .await
+        .expect("find should succeed");
+
+        let sql = first_sql(&db);
+
+        assert!(sql.contains(r#"order by "name" is null asc, "test_records"."name" desc"#));
+        assert!(!sql.contains("nulls last"));
+    }
+
     #[tokio::test]
     async fn find_applies_query_transformer_closure() {
         let fixture = DaoFixtureBuilder::new()
@@ -807,7 +1718,8 @@ mod tests {
             err,
             DaoLayerError::InvalidPagination {
                 page: 0,
-                page_size: 1
+                page_size: 1,
+                ..
             }
         ));
     }
@@ -826,7 +1738,8 @@ mod tests {
             err,
             DaoLayerError::InvalidPagination {
                 page: 1,
-                page_size: v
+                page_size: v,
+                ..
             } if v == TestDao::MAX_PAGE_SIZE + 1
         ));
     }
@@ -969,7 +1882,142 @@ mod tests {
 
         let sql = first_sql(&fixture.db);
 
-        assert!(sql.contains(" like ") && sql.contains("escape '!'") && sql.contains("%a!_%"));
+        assert!(sql.contains(" like ") && sql.contains("escape '!'") && sql.contains("%a!_%"));
+    }
+
+    #[test]
+    fn like_literal_emits_an_escape_clause_on_every_backend() {
+        use sea_orm::QueryTrait;
+
+        let filters = vec![ColumnFilter {
+            column: test_entity::Column::Name,
+            op: FilterOp::like_literal("50%_off\\", true, true),
+        }];
+
+        for backend in [DatabaseBackend::Postgres, DatabaseBackend::Sqlite] {
+            let statement =
+                super::apply_column_filters(test_entity::Entity::find(), &filters).build(backend);
+
+            let expected_escape = match backend {
+                DatabaseBackend::Postgres => "ESCAPE E'\\\\'",
+                _ => "ESCAPE '\\'",
+            };
+            assert!(
+                statement.sql.contains(expected_escape),
+                "{backend:?}: {}",
+                statement.sql
+            );
+            let values = statement.values.expect("pattern should be bound").0;
+            assert_eq!(
+                values,
+                vec![Value::from("%50\\%\\_off\\\\%".to_string())],
+                "{backend:?}"
+            );
+        }
+    }
+
+    /// Rows whose title contains `%` or `_` are matched only by the literal.
+    async fn assert_like_literal_matches_only_literals(db: &DatabaseConnection) {
+        use crate::db::{dao::DaoContext, entities::todo_list};
+
+        let dao = DaoContext::new(db).todo();
+        let marker = Uuid::new_v4().simple().to_string();
+        let mut ids = Vec::new();
+        for title in ["50%", "500", "a_b", "axb"] {
+            let row = dao
+                .create(todo_list::ActiveModel {
+                    title: Set(format!("{marker}:{title}")),
+                    score: Set(0),
+                    ..Default::default()
+                })
+                .await
+                .expect("create should succeed");
+            ids.push(row.id);
+        }
+
+        let search = |literal: &str, leading: bool, trailing: bool| {
+            let filters = vec![
+                ColumnFilter {
+                    column: todo_list::Column::Title,
+                    op: FilterOp::like_literal(&format!("{marker}:"), false, true),
+                },
+                ColumnFilter {
+                    column: todo_list::Column::Title,
+                    op: FilterOp::like_literal(literal, leading, trailing),
+                },
+            ];
+            let dao = dao.clone();
+            async move {
+                let page = dao
+                    .find_with_filters(1, 10, None, &filters, |select| select)
+                    .await
+                    .expect("search should succeed");
+                let mut titles: Vec<_> = page
+                    .data
+                    .into_iter()
+                    .map(|row| row.title.split_once(':').unwrap().1.to_string())
+                    .collect();
+                titles.sort();
+                titles
+            }
+        };
+
+        assert_eq!(search("50%", true, false).await, vec!["50%"]);
+        assert_eq!(search("0%", true, true).await, vec!["50%"]);
+        assert_eq!(search("a_b", true, true).await, vec!["a_b"]);
+        assert_eq!(search("_", true, true).await, vec!["a_b"]);
+
+        for id in ids {
+            dao.delete(id).await.expect("delete should succeed");
+        }
+    }
+
+    #[tokio::test]
+    async fn like_literal_matches_only_literals_on_sqlite() {
+        use sea_orm::{ConnectOptions, Database};
+
+        use crate::db::entities::todo_list;
+
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = Database::connect(options)
+            .await
+            .expect("sqlite should open");
+        db.get_schema_builder()
+            .register(todo_list::Entity)
+            .sync(&db)
+            .await
+            .expect("tables should be created");
+
+        assert_like_literal_matches_only_literals(&db).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres database"]
+    async fn like_literal_matches_only_literals_on_postgres() {
+        use sea_orm::{ConnectOptions, Database};
+
+        use crate::{config::AppConfig, db::entities::todo_list};
+
+        let cfg = AppConfig::from_env().expect("load app config");
+        let url = cfg
+            .database
+            .as_ref()
+            .expect("database config should be present")
+            .url
+            .clone();
+        let mut options = ConnectOptions::new(url);
+        options.max_connections(1).sqlx_logging(false);
+        let db = Database::connect(options)
+            .await
+            .expect("postgres should connect");
+        db.get_schema_builder()
+            .register(todo_list::Entity)
+            .sync(&db)
+            .await
+            .expect("tables should be created");
+
+        assert_like_literal_matches_only_literals(&db).await;
     }
 
     #[tokio::test]
@@ -1054,7 +2102,7 @@ mod tests {
             .find_with_filters(
                 1,
                 1,
-                Some((test_entity::Column::Score, Order::Asc)),
+                Some((test_entity::Column::Score, Order::Asc).into()),
                 &[],
                 |query| query,
             )
@@ -1081,6 +2129,233 @@ mod tests {
         assert!(matches!(err, DaoLayerError::Db(_)));
     }
 
+    #[tokio::test]
+    async fn count_with_filters_counts_filtered_rows() {
+        let fixture = DaoFixtureBuilder::new().with_count_result(7).build();
+        let filters = vec![ColumnFilter {
+            column: test_entity::Column::Name,
+            op: FilterOp::Eq(Value::from("alice".to_string())),
+        }];
+
+        let count = fixture
+            .dao
+            .count_with_filters(&filters, |query| query)
+            .await
+            .expect("count_with_filters should succeed");
+
+        let sql = first_sql(&fixture.db);
+
+        assert_eq!(count, 7);
+        assert!(sql.contains("count(*)") && sql.contains("= 'alice'"));
+    }
+
+    #[tokio::test]
+    async fn find_map_by_groups_rows_by_key_column() {
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![
+                model(first, "alice", 3),
+                model(second, "bob", 3),
+                model(third, "carol", 5),
+            ]])
+            .build();
+        let filters = vec![ColumnFilter {
+            column: test_entity::Column::Score,
+            op: FilterOp::Compare {
+                op: CompareOp::Gte,
+                value: Value::from(3),
+            },
+        }];
+
+        let grouped = fixture
+            .dao
+            .find_map_by(&filters, |row| row.score)
+            .await
+            .expect("find_map_by should succeed");
+
+        let ids = |score: i32| -> Vec<Uuid> { grouped[&score].iter().map(|row| row.id).collect() };
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(ids(3), vec![first, second]);
+        assert_eq!(ids(5), vec![third]);
+        assert!(first_sql(&fixture.db).contains(r#""score" >= 3"#));
+    }
+
+    #[tokio::test]
+    async fn find_one_map_by_keeps_first_row_per_key() {
+        let (newest, older) = (Uuid::new_v4(), Uuid::new_v4());
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![
+                model(newest, "alice", 3),
+                model(older, "bob", 3),
+            ]])
+            .build();
+
+        let by_score = fixture
+            .dao
+            .find_one_map_by(&[], |row| row.score)
+            .await
+            .expect("find_one_map_by should succeed");
+
+        assert_eq!(by_score.len(), 1);
+        assert_eq!(by_score[&3].id, newest);
+    }
+
+    #[tokio::test]
+    async fn find_children_filters_on_the_parent_column_or_null_for_roots() {
+        let (root, child) = (Uuid::new_v4(), Uuid::new_v4());
+        let node = |id: Uuid, parent_id: Option<Uuid>| tree_entity::Model {
+            id,
+            created_at: ts(),
+            updated_at: ts(),
+            name: "node".to_string(),
+            parent_id,
+        };
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![node(child, Some(root))], vec![node(root, None)]])
+            .into_connection();
+        let dao = TreeDao::new(&db);
+
+        let children = dao
+            .find_children(tree_entity::Column::ParentId, Some(root))
+            .await
+            .expect("find_children should succeed");
+        let roots = dao
+            .find_children(tree_entity::Column::ParentId, None)
+            .await
+            .expect("find_children should succeed");
+
+        assert_eq!(children[0].id, child);
+        assert_eq!(roots[0].id, root);
+        let sql = sql_log(&db);
+        assert!(
+            sql[0].contains(&format!(r#""tree_nodes"."parent_id" = '{root}'"#)),
+            "{}",
+            sql[0]
+        );
+        assert!(sql[0].contains(r#"order by "tree_nodes"."created_at" desc"#));
+        assert!(sql[1].contains(r#""tree_nodes"."parent_id" is null"#));
+    }
+
+    #[tokio::test]
+    async fn exists_ci_compares_lower_cased_values() {
+        let fixture = DaoFixtureBuilder::new().with_count_result(1).build();
+
+        let exists = fixture
+            .dao
+            .exists_ci(test_entity::Column::Name, "Alice@Example.com")
+            .await
+            .expect("exists_ci should succeed");
+
+        let sql = first_sql(&fixture.db);
+
+        assert!(exists);
+        assert!(
+            sql.contains(r#"lower("test_records"."name") = lower('alice@example.com')"#),
+            "{sql}"
+        );
+    }
+
+    #[tokio::test]
+    async fn exists_ci_returns_false_without_matches() {
+        let fixture = DaoFixtureBuilder::new().with_count_result(0).build();
+
+        let exists = fixture
+            .dao
+            .exists_ci(test_entity::Column::Name, "bob")
+            .await
+            .expect("exists_ci should succeed");
+
+        assert!(!exists);
+    }
+
+    fn name_lookup(name: &str) -> Vec<ColumnFilter<test_entity::Column>> {
+        vec![ColumnFilter {
+            column: test_entity::Column::Name,
+            op: FilterOp::Eq(Value::from(name)),
+        }]
+    }
+
+    #[tokio::test]
+    async fn find_or_create_returns_existing_row_without_insert() {
+        let id = Uuid::new_v4();
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![model(id, "alice", 1)]])
+            .build();
+
+        let (found, created) = fixture
+            .dao
+            .find_or_create(&name_lookup("alice"), active("alice", 1))
+            .await
+            .expect("find_or_create should succeed");
+
+        assert_eq!(found.id, id);
+        assert!(!created);
+        let log = sql_log(&fixture.db);
+        assert_eq!(log.len(), 1);
+        assert!(
+            log[0].contains(r#""test_records"."name" = 'alice'"#),
+            "{}",
+            log[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn find_or_create_inserts_when_lookup_misses() {
+        let id = Uuid::new_v4();
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![], vec![model(id, "alice", 1)]])
+            .build();
+
+        let (row, created) = fixture
+            .dao
+            .find_or_create(&name_lookup("alice"), active("alice", 1))
+            .await
+            .expect("find_or_create should succeed");
+
+        assert_eq!(row.id, id);
+        assert!(created);
+        assert!(second_sql(&fixture.db).starts_with("insert into"));
+    }
+
+    #[tokio::test]
+    async fn find_or_create_rereads_row_after_unique_conflict() {
+        let winner = Uuid::new_v4();
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![]])
+            .with_query_error(DbErr::Custom(
+                "duplicate key value violates unique constraint \"test_records_name_key\""
+                    .to_string(),
+            ))
+            .with_query_results(vec![vec![model(winner, "alice", 1)]])
+            .build();
+
+        let (row, created) = fixture
+            .dao
+            .find_or_create(&name_lookup("alice"), active("alice", 1))
+            .await
+            .expect("conflict should resolve to the existing row");
+
+        assert_eq!(row.id, winner);
+        assert!(!created);
+        assert_eq!(sql_log(&fixture.db).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn find_or_create_propagates_other_insert_errors() {
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![]])
+            .with_query_error(DbErr::Custom("insert failed".to_string()))
+            .build();
+
+        let err = fixture
+            .dao
+            .find_or_create(&name_lookup("alice"), active("alice", 1))
+            .await
+            .expect_err("non-conflict errors should surface");
+
+        assert!(matches!(err, DaoLayerError::Db(_)));
+    }
+
     #[tokio::test]
     async fn find_iter_defaults_page_size_to_max() {
         let fixture = DaoFixtureBuilder::new()
@@ -1295,7 +2570,210 @@ mod tests {
 
         let sql = second_sql(&fixture.db);
 
-        assert!(sql.contains("\"updated_at\""));
+        assert!(sql.contains("\"updated_at\" ="));
+    }
+
+    #[tokio::test]
+    async fn update_untouched_leaves_updated_at_out_of_update_statement() {
+        let id = Uuid::new_v4();
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![
+                vec![model(id, "before", 1)],
+                vec![model(id, "after", 1)],
+            ])
+            .build();
+
+        fixture
+            .dao
+            .update_untouched(id, |active| {
+                active.name = Set("after".to_string());
+            })
+            .await
+            .expect("update should succeed");
+
+        let sql = second_sql(&fixture.db);
+
+        assert!(sql.contains("\"name\" ="));
+        assert!(!sql.contains("\"updated_at\" ="));
+    }
+
+    #[tokio::test]
+    async fn touch_sets_only_updated_at_in_update_statement() {
+        let id = Uuid::new_v4();
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![model(id, "unchanged", 1)]])
+            .build();
+
+        let touched = fixture.dao.touch(id).await.expect("touch should succeed");
+
+        assert_eq!(touched.id, id);
+        let sql = first_sql(&fixture.db);
+        let set_clause = sql
+            .split(" where ")
+            .next()
+            .expect("update statement should have a SET clause");
+        assert!(set_clause.starts_with("update \"test_records\" set \"updated_at\" ="));
+        assert!(!set_clause.contains("\"name\""));
+        assert!(!set_clause.contains("\"score\""));
+        assert!(!set_clause.contains("\"created_at\""));
+    }
+
+    #[tokio::test]
+    async fn touch_returns_not_found_when_record_missing() {
+        let id = Uuid::new_v4();
+        let fixture = DaoFixtureBuilder::new()
+            .with_query_results(vec![vec![]])
+            .build();
+
+        let err = fixture
+            .dao
+            .touch(id)
+            .await
+            .expect_err("touch should fail for a missing row");
+
+        assert!(matches!(err, DaoLayerError::NotFound { id: missing, .. } if missing == id));
+    }
+
+    #[tokio::test]
+    async fn archive_stamps_archived_at_and_find_unarchived_hides_the_row() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![
+                vec![archivable(id, false)],
+                vec![archivable(id, true)],
+                vec![],
+            ])
+            .into_connection();
+        let dao = ArchivableDao::new(&db);
+
+        let archived = dao.archive(id).await.expect("archive should succeed");
+        let page = dao
+            .find_unarchived(1, 10, None, |query| query)
+            .await
+            .expect("find_unarchived should succeed");
+
+        assert!(archived.archived_at.is_some());
+        assert!(page.data.is_empty());
+        let sql = sql_log(&db);
+        assert!(sql[1].starts_with("update \"archivable_records\" set"));
+        assert!(sql[1].contains("\"archived_at\" = '"));
+        assert!(sql[1].contains("\"updated_at\" ="));
+        assert!(sql[2].contains("\"archived_at\" is null"));
+    }
+
+    #[tokio::test]
+    async fn unarchive_clears_archived_at_to_restore_the_row() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![
+                vec![archivable(id, true)],
+                vec![archivable(id, false)],
+                vec![archivable(id, false)],
+            ])
+            .into_connection();
+        let dao = ArchivableDao::new(&db);
+
+        let restored = dao.unarchive(id).await.expect("unarchive should succeed");
+        let page = dao
+            .find_unarchived(1, 10, None, |query| query)
+            .await
+            .expect("find_unarchived should succeed");
+
+        assert_eq!(restored.archived_at, None);
+        assert_eq!(page.data, vec![archivable(id, false)]);
+        let sql = sql_log(&db);
+        assert!(sql[1].contains("\"archived_at\" = null"));
+    }
+
+    #[tokio::test]
+    async fn soft_delete_stamps_deleted_at_and_restore_clears_it() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![
+                vec![soft_deletable(id, false)],
+                vec![soft_deletable(id, true)],
+                vec![soft_deletable(id, true)],
+                vec![soft_deletable(id, false)],
+            ])
+            .into_connection();
+        let dao = SoftDeletableDao::new(&db);
+
+        let deleted = dao
+            .soft_delete(id)
+            .await
+            .expect("soft_delete should succeed");
+        let restored = dao.restore(id).await.expect("restore should succeed");
+
+        assert!(deleted.deleted_at.is_some());
+        assert_eq!(restored.deleted_at, None);
+        let sql = sql_log(&db);
+        assert!(sql[1].starts_with("update \"soft_deletable_records\" set"));
+        assert!(sql[1].contains("\"deleted_at\" = '"));
+        assert!(sql[3].contains("\"deleted_at\" = null"));
+    }
+
+    #[tokio::test]
+    async fn restore_many_with_filters_clears_deleted_at_on_matching_rows() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 3,
+            }])
+            .into_connection();
+        let dao = SoftDeletableDao::new(&db);
+        let filters = vec![ColumnFilter {
+            column: soft_deletable_entity::Column::DeletedAt,
+            op: FilterOp::Compare {
+                op: CompareOp::Gte,
+                value: ts().into(),
+            },
+        }];
+
+        let restored = dao
+            .restore_many_with_filters(&filters)
+            .await
+            .expect("restore_many_with_filters should succeed");
+
+        assert_eq!(restored, 3);
+        let sql = first_sql(&db);
+        assert!(sql.starts_with("update \"soft_deletable_records\" set \"deleted_at\" = null"));
+        assert!(sql.contains("\"deleted_at\" is not null"));
+        assert!(sql.contains("\"deleted_at\" >= '"));
+    }
+
+    #[tokio::test]
+    async fn restore_many_with_filters_requires_a_filter() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let dao = SoftDeletableDao::new(&db);
+
+        let err = dao
+            .restore_many_with_filters(&[])
+            .await
+            .expect_err("an unfiltered restore should be refused");
+
+        assert!(matches!(err, DaoLayerError::MissingFilter));
+        assert!(sql_log(&db).is_empty());
+    }
+
+    #[test]
+    fn base_entity_reports_archived_at_column_only_when_configured() {
+        assert!(matches!(
+            <archivable_entity::Entity as HasCreatedAtColumn>::archived_at_column(),
+            Some(archivable_entity::Column::ArchivedAt)
+        ));
+        assert!(<test_entity::Entity as HasCreatedAtColumn>::archived_at_column().is_none());
+        assert!(
+            <soft_deletable_entity::Entity as HasCreatedAtColumn>::archived_at_column().is_none()
+        );
+    }
+
+    #[test]
+    fn base_entity_reports_deleted_at_column_only_when_configured() {
+        assert!(matches!(
+            <soft_deletable_entity::Entity as HasCreatedAtColumn>::deleted_at_column(),
+            Some(soft_deletable_entity::Column::DeletedAt)
+        ));
+        assert!(<archivable_entity::Entity as HasCreatedAtColumn>::deleted_at_column().is_none());
     }
 
     #[tokio::test]
@@ -1347,4 +2825,116 @@ mod tests {
 
         assert_eq!(deleted, id);
     }
+
+    #[tokio::test]
+    async fn backfill_updates_every_row_across_batches() {
+        use sea_orm::{ConnectOptions, Database, IntoActiveModel};
+
+        use crate::db::{dao::DaoContext, entities::todo_list};
+
+        // A single connection keeps every query on the same in-memory database.
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = Database::connect(options)
+            .await
+            .expect("sqlite should open");
+        db.get_schema_builder()
+            .register(todo_list::Entity)
+            .sync(&db)
+            .await
+            .expect("tables should be created");
+        let dao = DaoContext::new(&db).todo();
+        for n in 0..7 {
+            dao.create(todo_list::ActiveModel {
+                title: Set(format!("list {n}")),
+                score: Set(n),
+                ..Default::default()
+            })
+            .await
+            .expect("create should succeed");
+        }
+
+        let written = dao
+            .backfill(3, |row| {
+                let score = row.score;
+                let mut active = row.into_active_model();
+                active.score = Set(score * 10);
+                Some(active)
+            })
+            .await
+            .expect("backfill should succeed");
+
+        assert_eq!(written, 7);
+        let rows = todo_list::Entity::find()
+            .all(&db)
+            .await
+            .expect("rows should load");
+        let mut scores: Vec<_> = rows.iter().map(|row| row.score).collect();
+        scores.sort();
+        assert_eq!(scores, vec![0, 10, 20, 30, 40, 50, 60]);
+    }
+
+    #[tokio::test]
+    async fn backfill_rejects_zero_batch_size() {
+        let fixture = DaoFixtureBuilder::new().build();
+
+        let result = fixture.dao.backfill(0, |_| None).await;
+
+        assert!(matches!(result, Err(DaoLayerError::InvalidBatchSize)));
+    }
+
+    #[tokio::test]
+    async fn count_by_groups_matching_rows_by_column_value() {
+        use sea_orm::{ConnectOptions, Database};
+
+        use super::FacetCount;
+        use crate::db::{dao::DaoContext, entities::todo_list};
+
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = Database::connect(options)
+            .await
+            .expect("sqlite should open");
+        db.get_schema_builder()
+            .register(todo_list::Entity)
+            .sync(&db)
+            .await
+            .expect("tables should be created");
+        let dao = DaoContext::new(&db).todo();
+        for (title, score) in [("a", 1), ("b", 2), ("c", 2), ("d", 2), ("e", 3)] {
+            dao.create(todo_list::ActiveModel {
+                title: Set(title.to_string()),
+                score: Set(score),
+                ..Default::default()
+            })
+            .await
+            .expect("create should succeed");
+        }
+        let filters = vec![ColumnFilter {
+            column: todo_list::Column::Score,
+            op: FilterOp::Compare {
+                op: CompareOp::Lt,
+                value: Value::Int(Some(3)),
+            },
+        }];
+
+        let counts = dao
+            .count_by(todo_list::Column::Score, &filters, |select| select)
+            .await
+            .expect("count_by should succeed");
+
+        assert_eq!(
+            counts,
+            vec![
+                FacetCount {
+                    value: serde_json::json!(2),
+                    count: 3,
+                },
+                FacetCount {
+                    value: serde_json::json!(1),
+                    count: 1,
+                },
+            ]
+        );
+    }
 }
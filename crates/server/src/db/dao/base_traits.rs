@@ -1,5 +1,29 @@
 pub trait HasCreatedAtColumn: sea_orm::EntityTrait {
     fn created_at_column() -> Self::Column;
+
+    /// Lets generic list code hide archived rows; entities without an
+    /// `archived_at` column keep `None`.
+    fn archived_at_column() -> Option<Self::Column> {
+        None
+    }
+
+    /// Lets generic list code hide soft-deleted rows; entities without a
+    /// `deleted_at` column keep `None`.
+    fn deleted_at_column() -> Option<Self::Column> {
+        None
+    }
+}
+
+/// Entities declared with `#[base_entity(archived_at = "...")]`; gates
+/// `DaoBase::archive`, `unarchive` and `find_unarchived`.
+pub trait HasArchivedAtColumn: sea_orm::EntityTrait {
+    fn archived_at_column() -> Self::Column;
+}
+
+/// Entities declared with `#[base_entity(deleted_at = "...")]`; gates
+/// `DaoBase::soft_delete`, `restore` and `restore_many_with_filters`.
+pub trait HasDeletedAtColumn: sea_orm::EntityTrait {
+    fn deleted_at_column() -> Self::Column;
 }
 
 pub trait HasIdActiveModel {
@@ -9,4 +33,7 @@ pub trait HasIdActiveModel {
 pub trait TimestampedActiveModel {
     fn set_created_at(&mut self, ts: sea_orm::entity::prelude::DateTimeWithTimeZone);
     fn set_updated_at(&mut self, ts: sea_orm::entity::prelude::DateTimeWithTimeZone);
+
+    /// Stamps a `last_seen` column on `touch`; entities without one keep this no-op.
+    fn set_last_seen(&mut self, _ts: sea_orm::entity::prelude::DateTimeWithTimeZone) {}
 }
@@ -0,0 +1,667 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseConnection, EntityTrait, FromQueryResult,
+    IntoActiveModel, PrimaryKeyTrait,
+};
+use uuid::Uuid;
+
+use super::base::{ColumnFilter, DaoBase};
+use super::base_traits::{
+    HasCreatedAtColumn, HasDeletedAtColumn, HasIdActiveModel, TimestampedActiveModel,
+};
+use super::error::DaoResult;
+use crate::config::{DatabaseConfig, defaults};
+
+type Model<D> = <<D as DaoBase>::Entity as EntityTrait>::Model;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DaoCacheConfig {
+    pub max_entries: usize,
+    pub ttl: Duration,
+}
+
+impl DaoCacheConfig {
+    pub fn from_database_config(cfg: &DatabaseConfig) -> Self {
+        Self {
+            max_entries: cfg.cache_max_entries,
+            ttl: Duration::from_secs(cfg.cache_ttl_secs),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.max_entries > 0 && !self.ttl.is_zero()
+    }
+}
+
+impl Default for DaoCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: defaults::DEFAULT_DB_CACHE_MAX_ENTRIES,
+            ttl: Duration::from_secs(defaults::DEFAULT_DB_CACHE_TTL_SECS),
+        }
+    }
+}
+
+struct CacheEntry<M> {
+    model: M,
+    inserted_at: Instant,
+}
+
+/// Where an id's invalidations stood when a read started; a read only stores
+/// its row if neither moved on while it was running.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Generation {
+    cleared: u64,
+    invalidated: u64,
+}
+
+struct CacheState<M> {
+    entries: HashMap<Uuid, CacheEntry<M>>,
+    /// Invalidations per id since the last clear.
+    generations: HashMap<Uuid, u64>,
+    clears: u64,
+}
+
+impl<M> CacheState<M> {
+    fn generation(&self, id: Uuid) -> Generation {
+        Generation {
+            cleared: self.clears,
+            invalidated: self.generations.get(&id).copied().unwrap_or(0),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.forget_generations();
+    }
+
+    /// Drops per-id generations; bumping `clears` keeps reads in flight from
+    /// storing what they fetched.
+    fn forget_generations(&mut self) {
+        self.generations.clear();
+        self.clears += 1;
+    }
+}
+
+/// Opt-in `find_by_id` cache around an existing DAO.
+///
+/// Entries are keyed by id within the wrapped entity and dropped after `update`
+/// or `delete` of that id. Bulk writes that do not know their ids, such as
+/// `restore_many_with_filters` and `backfill`, clear the whole cache. A read
+/// that overlaps one of these writes is returned but not cached, so it cannot
+/// pin the old row for the TTL. Clones share the same cache.
+pub struct CachedDao<D: DaoBase> {
+    inner: D,
+    config: DaoCacheConfig,
+    state: Arc<Mutex<CacheState<Model<D>>>>,
+}
+
+impl<D: DaoBase> Clone for CachedDao<D> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            config: self.config,
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl<D> CachedDao<D>
+where
+    D: DaoBase,
+    Model<D>: Clone,
+{
+    pub fn with_config(inner: D, config: DaoCacheConfig) -> Self {
+        Self {
+            inner,
+            config,
+            state: Arc::new(Mutex::new(CacheState {
+                entries: HashMap::new(),
+                generations: HashMap::new(),
+                clears: 0,
+            })),
+        }
+    }
+
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    pub fn invalidate(&self, id: Uuid) {
+        if !self.config.is_enabled() {
+            return;
+        }
+        let mut state = self.lock();
+        state.entries.remove(&id);
+        *state.generations.entry(id).or_default() += 1;
+        // Generations only matter to reads in flight, so cap them at the
+        // entry limit rather than keep one per id ever written.
+        if state.generations.len() > self.config.max_entries {
+            state.forget_generations();
+        }
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState<Model<D>>> {
+        self.state.lock().expect("dao cache mutex poisoned")
+    }
+
+    fn cached(&self, id: Uuid) -> Result<Model<D>, Generation> {
+        let mut state = self.lock();
+        if let Some(entry) = state.entries.get(&id) {
+            if entry.inserted_at.elapsed() < self.config.ttl {
+                return Ok(entry.model.clone());
+            }
+            state.entries.remove(&id);
+        }
+        Err(state.generation(id))
+    }
+
+    fn store(&self, id: Uuid, model: Model<D>, read_at: Generation) {
+        if !self.config.is_enabled() {
+            return;
+        }
+        let mut state = self.lock();
+        if state.generation(id) != read_at {
+            return;
+        }
+        let entries = &mut state.entries;
+        if entries.len() >= self.config.max_entries && !entries.contains_key(&id) {
+            let ttl = self.config.ttl;
+            entries.retain(|_, entry| entry.inserted_at.elapsed() < ttl);
+            if entries.len() >= self.config.max_entries
+                && let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.inserted_at)
+                    .map(|(key, _)| *key)
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            id,
+            CacheEntry {
+                model,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+}
+
+#[async_trait::async_trait]
+impl<D> DaoBase for CachedDao<D>
+where
+    D: DaoBase,
+    Model<D>: FromQueryResult
+        + IntoActiveModel<<D::Entity as EntityTrait>::ActiveModel>
+        + Clone
+        + Send
+        + Sync,
+    <D::Entity as EntityTrait>::ActiveModel:
+        ActiveModelTrait<Entity = D::Entity> + HasIdActiveModel + TimestampedActiveModel + Send,
+    <<D::Entity as EntityTrait>::PrimaryKey as PrimaryKeyTrait>::ValueType:
+        From<Uuid> + Send + Sync,
+    D::Entity: HasCreatedAtColumn,
+{
+    type Entity = D::Entity;
+    const MAX_PAGE_SIZE: u64 = D::MAX_PAGE_SIZE;
+
+    fn new(db: &DatabaseConnection) -> Self {
+        Self::with_config(D::new(db), DaoCacheConfig::default())
+    }
+
+    fn db(&self) -> &DatabaseConnection {
+        self.inner.db()
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.inner.statement_timeout()
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.inner = self.inner.with_statement_timeout(timeout);
+        self
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> DaoResult<Model<D>> {
+        let read_at = match self.cached(id) {
+            Ok(model) => return Ok(model),
+            Err(read_at) => read_at,
+        };
+        let model = self.inner.find_by_id(id).await?;
+        self.store(id, model.clone(), read_at);
+        Ok(model)
+    }
+
+    async fn update<F>(&self, id: Uuid, apply: F) -> DaoResult<Model<D>>
+    where
+        F: for<'a> FnOnce(&'a mut <Self::Entity as EntityTrait>::ActiveModel) + Send,
+    {
+        let result = self.inner.update(id, apply).await;
+        self.invalidate(id);
+        result
+    }
+
+    async fn update_with_touch<F>(&self, id: Uuid, touch: bool, apply: F) -> DaoResult<Model<D>>
+    where
+        F: for<'a> FnOnce(&'a mut <Self::Entity as EntityTrait>::ActiveModel) + Send,
+    {
+        let result = self.inner.update_with_touch(id, touch, apply).await;
+        self.invalidate(id);
+        result
+    }
+
+    async fn update_in<C, F>(
+        &self,
+        conn: &C,
+        id: Uuid,
+        touch: bool,
+        apply: F,
+    ) -> DaoResult<Model<D>>
+    where
+        C: ConnectionTrait + Sync,
+        F: for<'a> FnOnce(&'a mut <Self::Entity as EntityTrait>::ActiveModel) + Send,
+    {
+        let result = self.inner.update_in(conn, id, touch, apply).await;
+        self.invalidate(id);
+        result
+    }
+
+    async fn touch(&self, id: Uuid) -> DaoResult<Model<D>> {
+        let result = self.inner.touch(id).await;
+        self.invalidate(id);
+        result
+    }
+
+    async fn delete(&self, id: Uuid) -> DaoResult<Uuid> {
+        let result = self.inner.delete(id).await;
+        self.invalidate(id);
+        result
+    }
+
+    async fn delete_in<C>(&self, conn: &C, id: Uuid) -> DaoResult<Uuid>
+    where
+        C: ConnectionTrait + Sync,
+    {
+        let result = self.inner.delete_in(conn, id).await;
+        self.invalidate(id);
+        result
+    }
+
+    async fn backfill<F>(&self, batch_size: u64, transform: F) -> DaoResult<u64>
+    where
+        F: FnMut(Model<D>) -> Option<<Self::Entity as EntityTrait>::ActiveModel> + Send,
+    {
+        // Batches commit one by one, so even a failed run may have written rows.
+        let result = self.inner.backfill(batch_size, transform).await;
+        self.clear();
+        result
+    }
+
+    async fn restore_many_with_filters(
+        &self,
+        filters: &[ColumnFilter<<Self::Entity as EntityTrait>::Column>],
+    ) -> DaoResult<u64>
+    where
+        Self::Entity: HasDeletedAtColumn,
+    {
+        let result = self.inner.restore_many_with_filters(filters).await;
+        self.clear();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{FixedOffset, TimeZone};
+    use sea_orm::{
+        DatabaseBackend, DatabaseConnection, IntoActiveModel, MockDatabase, MockExecResult, Set,
+    };
+    use uuid::Uuid;
+
+    use super::{CachedDao, DaoCacheConfig};
+    use crate::db::dao::{ColumnFilter, DaoBase, FilterOp, TodoDao};
+    use crate::db::entities::todo_list;
+
+    mod soft_deletable_entity {
+        use base_entity_derive::base_entity;
+        use sea_orm::entity::prelude::*;
+
+        #[base_entity(deleted_at = "deleted_at")]
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "soft_deletable_records")]
+        pub struct Model {
+            pub name: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    #[derive(Clone)]
+    struct SoftDeletableDao {
+        db: DatabaseConnection,
+    }
+
+    impl DaoBase for SoftDeletableDao {
+        type Entity = soft_deletable_entity::Entity;
+
+        fn new(db: &DatabaseConnection) -> Self {
+            Self { db: db.clone() }
+        }
+
+        fn db(&self) -> &DatabaseConnection {
+            &self.db
+        }
+    }
+
+    fn soft_deletable(id: Uuid, deleted: bool) -> soft_deletable_entity::Model {
+        soft_deletable_entity::Model {
+            id,
+            created_at: ts(),
+            updated_at: ts(),
+            name: "report".to_string(),
+            deleted_at: deleted.then(ts),
+        }
+    }
+
+    fn ts() -> chrono::DateTime<chrono::FixedOffset> {
+        FixedOffset::east_opt(0)
+            .expect("offset should be valid")
+            .with_ymd_and_hms(2026, 1, 1, 0, 0, 0)
+            .single()
+            .expect("timestamp should be valid")
+    }
+
+    fn list_model(id: Uuid, title: &str) -> todo_list::Model {
+        let now = ts();
+        todo_list::Model {
+            id,
+            created_at: now,
+            updated_at: now,
+            title: title.to_string(),
+            score: 0,
+        }
+    }
+
+    fn cached_dao(db: &DatabaseConnection, config: DaoCacheConfig) -> CachedDao<TodoDao> {
+        CachedDao::with_config(TodoDao::new(db), config)
+    }
+
+    fn enabled_config() -> DaoCacheConfig {
+        DaoCacheConfig {
+            max_entries: 8,
+            ttl: Duration::from_secs(60),
+        }
+    }
+
+    fn statement_count(db: &DatabaseConnection) -> usize {
+        db.clone().into_transaction_log().len()
+    }
+
+    #[tokio::test]
+    async fn find_by_id_serves_second_read_from_cache_within_ttl() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![list_model(id, "cached")]])
+            .into_connection();
+        let dao = cached_dao(&db, enabled_config());
+
+        dao.find_by_id(id)
+            .await
+            .expect("first find_by_id should succeed");
+        let second = dao
+            .find_by_id(id)
+            .await
+            .expect("second find_by_id should succeed");
+
+        assert_eq!(second.title, "cached");
+        assert_eq!(statement_count(&db), 1);
+    }
+
+    #[tokio::test]
+    async fn find_by_id_queries_again_after_ttl_expires() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([
+                vec![list_model(id, "first")],
+                vec![list_model(id, "second")],
+            ])
+            .into_connection();
+        let dao = cached_dao(
+            &db,
+            DaoCacheConfig {
+                max_entries: 8,
+                ttl: Duration::from_millis(1),
+            },
+        );
+
+        dao.find_by_id(id)
+            .await
+            .expect("first find_by_id should succeed");
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let second = dao
+            .find_by_id(id)
+            .await
+            .expect("second find_by_id should succeed");
+
+        assert_eq!(second.title, "second");
+    }
+
+    #[tokio::test]
+    async fn update_invalidates_cached_entry() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([
+                vec![list_model(id, "before")],
+                vec![list_model(id, "before")],
+                vec![list_model(id, "after")],
+                vec![list_model(id, "after")],
+            ])
+            .into_connection();
+        let dao = cached_dao(&db, enabled_config());
+
+        dao.find_by_id(id)
+            .await
+            .expect("initial find_by_id should succeed");
+        dao.update(id, |active| {
+            active.title = Set("after".to_string());
+        })
+        .await
+        .expect("update should succeed");
+        let reread = dao
+            .find_by_id(id)
+            .await
+            .expect("find_by_id after update should succeed");
+
+        assert_eq!(reread.title, "after");
+    }
+
+    #[tokio::test]
+    async fn delete_invalidates_cached_entry() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![list_model(id, "doomed")]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .append_query_results([Vec::<todo_list::Model>::new()])
+            .into_connection();
+        let dao = cached_dao(&db, enabled_config());
+
+        dao.find_by_id(id)
+            .await
+            .expect("initial find_by_id should succeed");
+        dao.delete(id).await.expect("delete should succeed");
+        let result = dao.find_by_id(id).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn zero_max_entries_disables_caching() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([
+                vec![list_model(id, "first")],
+                vec![list_model(id, "second")],
+            ])
+            .into_connection();
+        let dao = cached_dao(
+            &db,
+            DaoCacheConfig {
+                max_entries: 0,
+                ttl: Duration::from_secs(60),
+            },
+        );
+
+        dao.find_by_id(id)
+            .await
+            .expect("first find_by_id should succeed");
+        dao.find_by_id(id)
+            .await
+            .expect("second find_by_id should succeed");
+
+        assert_eq!(statement_count(&db), 2);
+    }
+
+    #[tokio::test]
+    async fn store_evicts_oldest_entry_when_full() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([
+                vec![list_model(first, "first")],
+                vec![list_model(second, "second")],
+                vec![list_model(first, "first again")],
+            ])
+            .into_connection();
+        let dao = cached_dao(
+            &db,
+            DaoCacheConfig {
+                max_entries: 1,
+                ttl: Duration::from_secs(60),
+            },
+        );
+
+        dao.find_by_id(first).await.expect("first should load");
+        dao.find_by_id(second).await.expect("second should load");
+        let reloaded = dao.find_by_id(first).await.expect("first should reload");
+
+        assert_eq!(reloaded.title, "first again");
+    }
+
+    #[tokio::test]
+    async fn restore_many_with_filters_clears_cached_entries() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![soft_deletable(id, true)]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .append_query_results([vec![soft_deletable(id, false)]])
+            .into_connection();
+        let dao = CachedDao::with_config(SoftDeletableDao::new(&db), enabled_config());
+        let filters = vec![ColumnFilter {
+            column: soft_deletable_entity::Column::Id,
+            op: FilterOp::Eq(id.into()),
+        }];
+
+        dao.find_by_id(id)
+            .await
+            .expect("initial find_by_id should succeed");
+        dao.restore_many_with_filters(&filters)
+            .await
+            .expect("restore should succeed");
+        let reread = dao
+            .find_by_id(id)
+            .await
+            .expect("find_by_id after restore should succeed");
+
+        assert_eq!(reread.deleted_at, None);
+    }
+
+    #[tokio::test]
+    async fn backfill_clears_cached_entries() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([
+                vec![list_model(id, "before")],
+                vec![list_model(id, "before")],
+                vec![list_model(id, "after")],
+                vec![list_model(id, "after")],
+            ])
+            .into_connection();
+        let dao = cached_dao(&db, enabled_config());
+
+        dao.find_by_id(id)
+            .await
+            .expect("initial find_by_id should succeed");
+        let written = dao
+            .backfill(10, |model| {
+                let mut active = model.into_active_model();
+                active.title = Set("after".to_string());
+                Some(active)
+            })
+            .await
+            .expect("backfill should succeed");
+        let reread = dao
+            .find_by_id(id)
+            .await
+            .expect("find_by_id after backfill should succeed");
+
+        assert_eq!(written, 1);
+        assert_eq!(reread.title, "after");
+    }
+
+    #[tokio::test]
+    async fn read_that_overlaps_an_invalidation_is_not_cached() {
+        let id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![list_model(id, "before")]])
+            .append_query_results([vec![list_model(id, "after")]])
+            .into_connection();
+        let dao = cached_dao(&db, enabled_config());
+
+        // A find_by_id that reads the row, then loses the race to an update.
+        let read_at = dao.cached(id).expect_err("cache should start empty");
+        let stale = dao
+            .inner()
+            .find_by_id(id)
+            .await
+            .expect("inner find_by_id should succeed");
+        dao.invalidate(id);
+        dao.store(id, stale, read_at);
+        let reread = dao
+            .find_by_id(id)
+            .await
+            .expect("find_by_id after the race should succeed");
+
+        assert_eq!(reread.title, "after");
+        assert_eq!(statement_count(&db), 2);
+    }
+
+    #[test]
+    fn invalidated_ids_are_capped_at_the_entry_limit() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let dao = cached_dao(&db, enabled_config());
+
+        for _ in 0..=enabled_config().max_entries {
+            dao.invalidate(Uuid::new_v4());
+        }
+
+        assert!(dao.lock().generations.is_empty());
+    }
+}
@@ -1,26 +1,182 @@
-use sea_orm::DatabaseConnection;
+use std::{future::Future, pin::Pin, time::Duration};
 
-use super::{DaoBase, RefreshTokenDao, TodoDao, UserDao};
+use sea_orm::{DatabaseConnection, DatabaseTransaction, TransactionError, TransactionTrait};
+
+use super::{
+    AuthEventDao, DaoBase, DaoLayerError, DaoResult, InviteDao, JobDao, LockDao, LockGuard,
+    LoginAttemptDao, OutboxDao, PasswordHistoryDao, RefreshTokenDao, TodoDao, UserDao,
+};
 
 #[derive(Clone)]
 pub struct DaoContext {
     db: DatabaseConnection,
+    replica: Option<DatabaseConnection>,
+    statement_timeout: Option<Duration>,
 }
 
 impl DaoContext {
     pub fn new(db: &DatabaseConnection) -> Self {
-        Self { db: db.clone() }
+        Self {
+            db: db.clone(),
+            replica: None,
+            statement_timeout: None,
+        }
+    }
+
+    /// Lets `read()` hand out DAOs on `replica`. DAOs from the context itself
+    /// keep using the primary.
+    pub fn with_replica(mut self, replica: Option<DatabaseConnection>) -> Self {
+        self.replica = replica;
+        self
+    }
+
+    /// Bounds each statement run by this context's DAOs, from
+    /// [`client_statement_timeout`](super::client_statement_timeout).
+    pub fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+
+    /// DAOs backed by the read replica, or the primary when none is configured.
+    ///
+    /// Replicas lag the primary, so only use this for reads that tolerate
+    /// slightly stale rows, and never write through it.
+    pub fn read(&self) -> DaoContext {
+        DaoContext::new(self.replica.as_ref().unwrap_or(&self.db))
+            .with_statement_timeout(self.statement_timeout)
     }
 
     pub fn user(&self) -> UserDao {
-        DaoBase::new(&self.db)
+        UserDao::new(&self.db).with_statement_timeout(self.statement_timeout)
     }
 
     pub fn refresh_token(&self) -> RefreshTokenDao {
-        DaoBase::new(&self.db)
+        RefreshTokenDao::new(&self.db).with_statement_timeout(self.statement_timeout)
     }
 
     pub fn todo(&self) -> TodoDao {
-        DaoBase::new(&self.db)
+        TodoDao::new(&self.db).with_statement_timeout(self.statement_timeout)
+    }
+
+    pub fn auth_event(&self) -> AuthEventDao {
+        AuthEventDao::new(&self.db).with_statement_timeout(self.statement_timeout)
+    }
+
+    pub fn invite(&self) -> InviteDao {
+        InviteDao::new(&self.db).with_statement_timeout(self.statement_timeout)
+    }
+
+    pub fn outbox(&self) -> OutboxDao {
+        OutboxDao::new(&self.db).with_statement_timeout(self.statement_timeout)
+    }
+
+    pub fn job(&self) -> JobDao {
+        JobDao::new(&self.db).with_statement_timeout(self.statement_timeout)
+    }
+
+    pub fn lock(&self) -> LockDao {
+        LockDao::new(&self.db).with_statement_timeout(self.statement_timeout)
+    }
+
+    pub fn login_attempt(&self) -> LoginAttemptDao {
+        LoginAttemptDao::new(&self.db).with_statement_timeout(self.statement_timeout)
+    }
+
+    pub fn password_history(&self) -> PasswordHistoryDao {
+        PasswordHistoryDao::new(&self.db).with_statement_timeout(self.statement_timeout)
+    }
+
+    /// Shorthand for `self.lock().try_lock(key)`.
+    pub async fn try_lock(&self, key: &str) -> DaoResult<Option<LockGuard>> {
+        self.lock().try_lock(key).await
+    }
+
+    /// Runs `work` in a database transaction, committing on `Ok` and rolling back on `Err`.
+    ///
+    /// Pair writes with `OutboxDao::enqueue(txn, ..)` so events are only
+    /// published once the data they describe is committed.
+    pub async fn transaction<F, T>(&self, work: F) -> DaoResult<T>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = DaoResult<T>> + Send + 'c>>
+            + Send,
+        T: Send,
+    {
+        self.db.transaction(work).await.map_err(|err| match err {
+            TransactionError::Connection(err) => DaoLayerError::from(err),
+            TransactionError::Transaction(err) => err,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use uuid::Uuid;
+
+    use super::DaoContext;
+    use crate::db::{dao::DaoBase, entities::invite};
+
+    fn invite_model(id: Uuid) -> invite::Model {
+        let now = Utc::now().fixed_offset();
+        invite::Model {
+            id,
+            created_at: now,
+            updated_at: now,
+            token: "invite-1".to_string(),
+            role: "user".to_string(),
+            created_by: None,
+            expires_at: now,
+            used_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn read_daos_use_replica_while_writes_stay_on_primary() {
+        let id = Uuid::new_v4();
+        let primary = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+        let replica = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[invite_model(id)]])
+            .into_connection();
+        let daos = DaoContext::new(&primary).with_replica(Some(replica.clone()));
+
+        let found = daos
+            .read()
+            .invite()
+            .find_by_id(id)
+            .await
+            .expect("replica read should succeed");
+        daos.outbox()
+            .mark_sent(id)
+            .await
+            .expect("primary write should succeed");
+
+        assert_eq!(found.id, id);
+        assert_eq!(replica.into_transaction_log().len(), 1);
+        assert_eq!(primary.into_transaction_log().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn read_falls_back_to_primary_without_replica() {
+        let id = Uuid::new_v4();
+        let primary = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[invite_model(id)]])
+            .into_connection();
+        let daos = DaoContext::new(&primary);
+
+        daos.read()
+            .invite()
+            .find_by_id(id)
+            .await
+            .expect("primary read should succeed");
+
+        assert_eq!(primary.into_transaction_log().len(), 1);
     }
 }
@@ -1,12 +1,26 @@
-use sea_orm::DbErr;
+use sea_orm::{DbErr, RuntimeErr};
 use std::fmt;
 use uuid::Uuid;
 
 #[derive(Debug)]
 pub enum DaoLayerError {
     Db(DbErr),
-    NotFound { entity: &'static str, id: Uuid },
-    InvalidPagination { page: u64, page_size: u64 },
+    /// A statement ran past `database.statement_timeout_ms` and was cancelled.
+    Timeout(DbErr),
+    NotFound {
+        entity: &'static str,
+        id: Uuid,
+    },
+    /// Pages are numbered from 1; `page_size` must be in `1..=max_page_size`.
+    InvalidPagination {
+        page: u64,
+        page_size: u64,
+        max_page_size: u64,
+    },
+    /// A bulk write was called without filters and would touch every row.
+    MissingFilter,
+    /// `backfill` was asked for batches of zero rows.
+    InvalidBatchSize,
 }
 
 pub type DaoResult<T> = Result<T, DaoLayerError>;
@@ -15,14 +29,179 @@ impl fmt::Display for DaoLayerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DaoLayerError::Db(err) => write!(f, "Database error: {err}"),
+            DaoLayerError::Timeout(err) => write!(f, "Database statement timed out: {err}"),
             DaoLayerError::NotFound { entity, id } => {
-                write!(f, "{entity} not found (id={id})")
-            }
-            DaoLayerError::InvalidPagination { page, page_size } => {
-                write!(f, "Invalid pagination: page={page} page_size={page_size}")
+                write!(f, "{} {id} not found", entity_label(entity))
             }
+            DaoLayerError::InvalidPagination {
+                page,
+                page_size,
+                max_page_size,
+            } => write!(
+                f,
+                "Invalid pagination: {}",
+                pagination_reason(*page, *page_size, *max_page_size)
+            ),
+            DaoLayerError::MissingFilter => write!(f, "At least one filter is required"),
+            DaoLayerError::InvalidBatchSize => write!(f, "batch_size must be at least 1"),
         }
     }
 }
 
 impl std::error::Error for DaoLayerError {}
+
+/// Sorts driver errors into [`DaoLayerError::Timeout`] or [`DaoLayerError::Db`].
+impl From<DbErr> for DaoLayerError {
+    fn from(err: DbErr) -> Self {
+        if is_statement_timeout(&err) {
+            DaoLayerError::Timeout(err)
+        } else {
+            DaoLayerError::Db(err)
+        }
+    }
+}
+
+/// SQLSTATE `query_canceled`, which Postgres raises when `statement_timeout`
+/// cancels a statement.
+const QUERY_CANCELED: &str = "57014";
+
+fn is_statement_timeout(err: &DbErr) -> bool {
+    let (DbErr::Exec(RuntimeErr::SqlxError(err)) | DbErr::Query(RuntimeErr::SqlxError(err))) = err
+    else {
+        return false;
+    };
+    err.as_database_error()
+        .and_then(|err| err.code())
+        .is_some_and(|code| code == QUERY_CANCELED)
+}
+
+/// What is wrong with a rejected `page`/`page_size` pair, phrased for API clients.
+pub fn pagination_reason(page: u64, page_size: u64, max_page_size: u64) -> String {
+    if page == 0 {
+        "page must be 1 or greater (pages are numbered from 1)".to_string()
+    } else if page_size == 0 {
+        "page_size must be at least 1".to_string()
+    } else {
+        format!("page_size {page_size} exceeds the maximum of {max_page_size}")
+    }
+}
+
+/// Readable name for the `entity` carried by [`DaoLayerError::NotFound`]:
+/// `app::db::entities::todo_list::Entity` becomes `TodoList`.
+pub fn entity_label(entity: &str) -> String {
+    let mut segments = entity.rsplit("::");
+    let last = segments.next().unwrap_or(entity);
+    let name = match last {
+        "Entity" => segments.next().unwrap_or(last),
+        _ => last,
+    };
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, fmt, sync::Arc};
+
+    use sea_orm::{
+        DbErr, RuntimeErr,
+        sqlx::{
+            self,
+            error::{DatabaseError, ErrorKind},
+        },
+    };
+    use uuid::Uuid;
+
+    use super::{DaoLayerError, entity_label};
+
+    #[test]
+    fn entity_label_uses_module_name_for_entity_types() {
+        assert_eq!(
+            entity_label("app::db::entities::todo_list::Entity"),
+            "TodoList"
+        );
+        assert_eq!(entity_label("User"), "User");
+    }
+
+    #[test]
+    fn not_found_display_names_entity_and_id() {
+        let id = Uuid::nil();
+        let err = DaoLayerError::NotFound {
+            entity: "app::db::entities::user::Entity",
+            id,
+        };
+
+        assert_eq!(err.to_string(), format!("User {id} not found"));
+    }
+
+    #[derive(Debug)]
+    struct SqlStateError(&'static str);
+
+    impl fmt::Display for SqlStateError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "sqlstate {}", self.0)
+        }
+    }
+
+    impl std::error::Error for SqlStateError {}
+
+    impl DatabaseError for SqlStateError {
+        fn message(&self) -> &str {
+            "canceling statement"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.0))
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    fn query_error(sqlstate: &'static str) -> DbErr {
+        DbErr::Query(RuntimeErr::SqlxError(Arc::new(sqlx::Error::database(
+            SqlStateError(sqlstate),
+        ))))
+    }
+
+    #[test]
+    fn postgres_statement_timeouts_are_told_apart_from_other_errors() {
+        let cancelled = query_error("57014");
+        let undefined_table = query_error("42P01");
+        let lookalike = DbErr::Custom("canceling statement due to statement timeout".into());
+
+        assert!(matches!(
+            DaoLayerError::from(cancelled),
+            DaoLayerError::Timeout(_)
+        ));
+        assert!(matches!(
+            DaoLayerError::from(undefined_table),
+            DaoLayerError::Db(_)
+        ));
+        assert!(matches!(
+            DaoLayerError::from(lookalike),
+            DaoLayerError::Db(_)
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+    sea_query::Expr,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{DaoBase, DaoLayerError, DaoResult, timeout::timed};
+use crate::db::entities::invite::{self, Entity as Invite};
+
+pub const DEFAULT_INVITE_TTL_DAYS: i64 = 7;
+/// Longest lifetime an invite may be created with.
+pub const MAX_INVITE_TTL_DAYS: i64 = 365;
+
+/// A newly created invite and the token to hand out, which only its hash
+/// is stored for.
+#[derive(Debug, Clone)]
+pub struct NewInvite {
+    pub token: String,
+    pub invite: invite::Model,
+}
+
+/// Hex SHA-256 of an invite token, as stored in `invites.token`.
+pub fn hash_invite_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct InviteDao {
+    db: DatabaseConnection,
+    statement_timeout: Option<std::time::Duration>,
+}
+
+impl DaoBase for InviteDao {
+    type Entity = Invite;
+
+    fn new(db: &DatabaseConnection) -> Self {
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
+    }
+
+    fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    fn statement_timeout(&self) -> Option<std::time::Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<std::time::Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+}
+
+impl InviteDao {
+    pub async fn create_invite(
+        &self,
+        role: &str,
+        created_by: Option<Uuid>,
+        ttl_days: Option<i64>,
+    ) -> DaoResult<NewInvite> {
+        let ttl_days = ttl_days.unwrap_or(DEFAULT_INVITE_TTL_DAYS);
+        let expires_at = Duration::try_days(ttl_days)
+            .and_then(|ttl| Utc::now().fixed_offset().checked_add_signed(ttl))
+            .ok_or_else(|| {
+                DaoLayerError::Db(DbErr::Custom(format!(
+                    "invite lifetime of {ttl_days} days is out of range"
+                )))
+            })?;
+        let token = Uuid::new_v4().to_string();
+        let model = invite::ActiveModel {
+            token: Set(hash_invite_token(&token)),
+            role: Set(role.to_string()),
+            created_by: Set(created_by),
+            expires_at: Set(expires_at),
+            used_at: Set(None),
+            ..Default::default()
+        };
+        let invite = self.create(model).await?;
+        Ok(NewInvite { token, invite })
+    }
+
+    /// Marks an unused, unexpired invite as used and returns it.
+    ///
+    /// The update only matches rows with no `used_at`, so concurrent redemptions
+    /// of the same token cannot both succeed.
+    pub async fn redeem(&self, token: &str) -> DaoResult<Option<invite::Model>> {
+        self.redeem_in(self.db(), token).await
+    }
+
+    /// Like [`redeem`](Self::redeem) but runs on `conn`, e.g. the transaction
+    /// that creates the invited account.
+    pub async fn redeem_in<C>(&self, conn: &C, token: &str) -> DaoResult<Option<invite::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        let select = Invite::find().filter(invite::Column::Token.eq(hash_invite_token(token)));
+        let Some(invite) = timed(self.statement_timeout(), select.one(conn)).await? else {
+            return Ok(None);
+        };
+
+        let now = Utc::now().fixed_offset();
+        if invite.used_at.is_some() || invite.expires_at < now {
+            return Ok(None);
+        }
+
+        let update = Invite::update_many()
+            .col_expr(invite::Column::UsedAt, Expr::value(now))
+            .filter(invite::Column::Id.eq(invite.id))
+            .filter(invite::Column::UsedAt.is_null());
+        let result = timed(self.statement_timeout(), update.exec(conn)).await?;
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(invite::Model {
+            used_at: Some(now),
+            ..invite
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use uuid::Uuid;
+
+    use super::{InviteDao, hash_invite_token};
+    use crate::db::{dao::DaoBase, entities::invite};
+
+    fn invite_model(used: bool, expires_in: Duration) -> invite::Model {
+        let now = Utc::now().fixed_offset();
+        invite::Model {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            token: hash_invite_token("invite-1"),
+            role: "user".to_string(),
+            created_by: None,
+            expires_at: now + expires_in,
+            used_at: used.then_some(now),
+        }
+    }
+
+    #[tokio::test]
+    async fn redeem_marks_unused_invite_as_used() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[invite_model(false, Duration::days(1))]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+        let dao = InviteDao::new(&db);
+
+        let invite = dao
+            .redeem("invite-1")
+            .await
+            .expect("query should succeed")
+            .expect("invite should be redeemed");
+        assert!(invite.used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn redeem_rejects_used_and_expired_invites() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[invite_model(true, Duration::days(1))]])
+            .append_query_results([[invite_model(false, -Duration::days(1))]])
+            .into_connection();
+        let dao = InviteDao::new(&db);
+
+        assert!(dao.redeem("invite-1").await.expect("query").is_none());
+        assert!(dao.redeem("invite-1").await.expect("query").is_none());
+    }
+
+    #[tokio::test]
+    async fn redeem_loses_race_when_no_row_is_updated() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[invite_model(false, Duration::days(1))]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .into_connection();
+        let dao = InviteDao::new(&db);
+
+        assert!(dao.redeem("invite-1").await.expect("query").is_none());
+    }
+
+    #[tokio::test]
+    async fn create_invite_stores_only_the_token_hash() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[invite_model(false, Duration::days(7))]])
+            .into_connection();
+        let dao = InviteDao::new(&db);
+
+        let created = dao
+            .create_invite("user", None, None)
+            .await
+            .expect("insert should succeed");
+        let log = format!("{:?}", db.into_transaction_log());
+
+        assert!(!log.contains(&created.token));
+        assert!(log.contains(&hash_invite_token(&created.token)));
+    }
+
+    #[tokio::test]
+    async fn create_invite_rejects_lifetimes_past_the_calendar() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let dao = InviteDao::new(&db);
+
+        dao.create_invite("user", None, Some(i64::MAX))
+            .await
+            .expect_err("overflowing lifetime should fail");
+        assert!(db.into_transaction_log().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, sea_query::Expr,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{DaoBase, DaoResult, timeout::timed};
+use crate::db::entities::job::{
+    self, Entity as Job, STATUS_COMPLETED, STATUS_FAILED, STATUS_QUEUED, STATUS_RUNNING,
+};
+
+#[derive(Clone)]
+pub struct JobDao {
+    db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
+}
+
+impl DaoBase for JobDao {
+    type Entity = Job;
+
+    fn new(db: &DatabaseConnection) -> Self {
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
+    }
+
+    fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+}
+
+impl JobDao {
+    pub async fn enqueue(
+        &self,
+        job_type: &str,
+        payload: Value,
+        submitted_by: Option<Uuid>,
+    ) -> DaoResult<job::Model> {
+        let now = Utc::now().fixed_offset();
+        let job = job::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            job_type: Set(job_type.to_string()),
+            payload: Set(payload),
+            status: Set(STATUS_QUEUED.to_string()),
+            result: Set(None),
+            error: Set(None),
+            submitted_by: Set(submitted_by),
+            finished_at: Set(None),
+        }
+        .insert(&self.db);
+        timed(self.statement_timeout(), job).await
+    }
+
+    /// Oldest queued jobs first.
+    pub async fn queued(&self, limit: u64) -> DaoResult<Vec<job::Model>> {
+        let select = Job::find()
+            .filter(job::Column::Status.eq(STATUS_QUEUED))
+            .order_by_asc(job::Column::CreatedAt)
+            .limit(limit);
+        timed(self.statement_timeout(), select.all(&self.db)).await
+    }
+
+    /// Moves a queued job to `running`. Returns `false` when another worker got
+    /// there first, so each job runs once even with several instances polling.
+    pub async fn claim(&self, id: Uuid) -> DaoResult<bool> {
+        let update = Job::update_many()
+            .col_expr(job::Column::Status, Expr::value(STATUS_RUNNING))
+            .col_expr(
+                job::Column::UpdatedAt,
+                Expr::value(Utc::now().fixed_offset()),
+            )
+            .filter(job::Column::Id.eq(id))
+            .filter(job::Column::Status.eq(STATUS_QUEUED));
+        let result = timed(self.statement_timeout(), update.exec(&self.db)).await?;
+        Ok(result.rows_affected == 1)
+    }
+
+    /// Stores the handler outcome and marks the job `completed` or `failed`.
+    pub async fn finish(&self, id: Uuid, outcome: Result<Value, String>) -> DaoResult<()> {
+        let now = Utc::now().fixed_offset();
+        let (status, result, error) = match outcome {
+            Ok(result) => (STATUS_COMPLETED, Some(result), None),
+            Err(error) => (STATUS_FAILED, None, Some(error)),
+        };
+        let update = Job::update_many()
+            .col_expr(job::Column::Status, Expr::value(status))
+            .col_expr(job::Column::Result, Expr::value(result))
+            .col_expr(job::Column::Error, Expr::value(error))
+            .col_expr(job::Column::FinishedAt, Expr::value(now))
+            .col_expr(job::Column::UpdatedAt, Expr::value(now))
+            .filter(job::Column::Id.eq(id));
+        timed(self.statement_timeout(), update.exec(&self.db)).await?;
+        Ok(())
+    }
+}
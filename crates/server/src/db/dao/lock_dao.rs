@@ -0,0 +1,275 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection,
+    DatabaseTransaction, EntityTrait, QueryFilter, Set, Statement, TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::{DaoBase, DaoLayerError, DaoResult, base::is_unique_violation, timeout::timed};
+use crate::db::entities::lock::{self, Entity as Lock};
+
+/// How long a row lock survives a holder that never releases it.
+pub const LOCK_TTL: Duration = Duration::from_secs(60);
+
+/// Named locks shared by every instance on the same database.
+///
+/// Postgres uses transaction-scoped advisory locks, so the guard keeps one
+/// pooled connection busy until it is released and a crashed holder frees the
+/// lock as soon as its connection closes. Other backends (sqlite) insert a row
+/// into `locks` instead; a holder that dies keeps the key until the row's TTL
+/// runs out.
+#[derive(Clone)]
+pub struct LockDao {
+    db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
+}
+
+impl DaoBase for LockDao {
+    type Entity = Lock;
+
+    fn new(db: &DatabaseConnection) -> Self {
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
+    }
+
+    fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+}
+
+/// A held lock. Dropping it releases the lock; call `release` to wait for that
+/// to finish.
+pub struct LockGuard {
+    key: String,
+    held: Held,
+}
+
+enum Held {
+    Advisory(DatabaseTransaction),
+    Row {
+        db: DatabaseConnection,
+        holder: Uuid,
+        statement_timeout: Option<Duration>,
+    },
+    Released,
+}
+
+impl LockDao {
+    /// Takes `key` for up to `LOCK_TTL`, or returns `None` when someone else
+    /// holds it.
+    pub async fn try_lock(&self, key: &str) -> DaoResult<Option<LockGuard>> {
+        self.try_lock_for(key, LOCK_TTL).await
+    }
+
+    /// Like `try_lock`, with a custom TTL for the row-based backends. Advisory
+    /// locks on Postgres ignore `ttl`; they last until released.
+    pub async fn try_lock_for(&self, key: &str, ttl: Duration) -> DaoResult<Option<LockGuard>> {
+        let held = match self.db.get_database_backend() {
+            DatabaseBackend::Postgres => self.try_advisory_lock(key).await?,
+            _ => self.try_row_lock(key, ttl).await?,
+        };
+        Ok(held.map(|held| LockGuard {
+            key: key.to_string(),
+            held,
+        }))
+    }
+
+    async fn try_advisory_lock(&self, key: &str) -> DaoResult<Option<Held>> {
+        let txn = timed(self.statement_timeout(), self.db.begin()).await?;
+        let row = timed(
+            self.statement_timeout(),
+            txn.query_one_raw(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                "SELECT pg_try_advisory_xact_lock(hashtext($1)) AS locked",
+                [key.into()],
+            )),
+        )
+        .await?;
+        let locked = match row {
+            Some(row) => row
+                .try_get::<bool>("", "locked")
+                .map_err(DaoLayerError::from)?,
+            None => false,
+        };
+        // Dropping the transaction rolls it back, which is all an unused
+        // attempt needs.
+        Ok(locked.then_some(Held::Advisory(txn)))
+    }
+
+    async fn try_row_lock(&self, key: &str, ttl: Duration) -> DaoResult<Option<Held>> {
+        let now = Utc::now().fixed_offset();
+        let expired = Lock::delete_many()
+            .filter(lock::Column::Key.eq(key))
+            .filter(lock::Column::ExpiresAt.lt(now));
+        timed(self.statement_timeout(), expired.exec(&self.db)).await?;
+
+        let holder = Uuid::new_v4();
+        let ttl = chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+        let insert = lock::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            key: Set(key.to_string()),
+            holder: Set(holder),
+            expires_at: Set(now + ttl),
+        }
+        .insert(&self.db);
+        match timed(self.statement_timeout(), insert).await {
+            Ok(_) => Ok(Some(Held::Row {
+                db: self.db.clone(),
+                holder,
+                statement_timeout: self.statement_timeout(),
+            })),
+            Err(DaoLayerError::Db(err)) if is_unique_violation(&err) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl LockGuard {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Releases the lock and waits until other instances can take it.
+    pub async fn release(mut self) -> DaoResult<()> {
+        match std::mem::replace(&mut self.held, Held::Released) {
+            Held::Advisory(txn) => txn.rollback().await.map_err(DaoLayerError::from),
+            Held::Row {
+                db,
+                holder,
+                statement_timeout,
+            } => delete_row(&db, &self.key, holder, statement_timeout).await,
+            Held::Released => Ok(()),
+        }
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        match std::mem::replace(&mut self.held, Held::Released) {
+            // Dropping the transaction rolls it back and frees the advisory lock.
+            Held::Advisory(txn) => drop(txn),
+            Held::Row {
+                db,
+                holder,
+                statement_timeout,
+            } => {
+                let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+                    return;
+                };
+                let key = std::mem::take(&mut self.key);
+                runtime.spawn(async move {
+                    if let Err(err) = delete_row(&db, &key, holder, statement_timeout).await {
+                        tracing::warn!(error = %err, key = %key, "failed to release lock");
+                    }
+                });
+            }
+            Held::Released => {}
+        }
+    }
+}
+
+async fn delete_row(
+    db: &DatabaseConnection,
+    key: &str,
+    holder: Uuid,
+    statement_timeout: Option<Duration>,
+) -> DaoResult<()> {
+    let delete = Lock::delete_many()
+        .filter(lock::Column::Key.eq(key))
+        .filter(lock::Column::Holder.eq(holder));
+    timed(statement_timeout, delete.exec(db)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+
+    use super::LockDao;
+    use crate::db::{dao::DaoBase, entities::lock};
+
+    async fn sqlite_db() -> DatabaseConnection {
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+        let db = Database::connect(options)
+            .await
+            .expect("sqlite should connect");
+        db.get_schema_builder()
+            .register(lock::Entity)
+            .sync(&db)
+            .await
+            .expect("schema should sync");
+        db
+    }
+
+    #[tokio::test]
+    async fn held_key_cannot_be_taken_until_released() {
+        let db = sqlite_db().await;
+        let dao = LockDao::new(&db);
+
+        let guard = dao
+            .try_lock("nightly-report")
+            .await
+            .expect("lock should query")
+            .expect("first lock should succeed");
+        assert!(
+            dao.try_lock("nightly-report")
+                .await
+                .expect("lock should query")
+                .is_none()
+        );
+        assert!(
+            dao.try_lock("other-key")
+                .await
+                .expect("lock should query")
+                .is_some()
+        );
+
+        guard.release().await.expect("release should succeed");
+        assert!(
+            dao.try_lock("nightly-report")
+                .await
+                .expect("lock should query")
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_row_lock_can_be_taken_over() {
+        let db = sqlite_db().await;
+        let dao = LockDao::new(&db);
+
+        let stale = dao
+            .try_lock_for("sweep", Duration::ZERO)
+            .await
+            .expect("lock should query")
+            .expect("first lock should succeed");
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        assert!(
+            dao.try_lock("sweep")
+                .await
+                .expect("lock should query")
+                .is_some()
+        );
+        // The stale guard only deletes its own row, not the new holder's.
+        drop(stale);
+    }
+}
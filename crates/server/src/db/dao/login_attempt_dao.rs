@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+    sea_query::{Expr, ExprTrait},
+};
+
+use super::{DaoBase, DaoLayerError, DaoResult, base::is_unique_violation, timeout::timed};
+use crate::{
+    db::entities::login_attempt::{self, Entity as LoginAttempt},
+    window_counter::MAX_WINDOW,
+};
+
+/// Failed login counters behind the database lockout store.
+#[derive(Clone)]
+pub struct LoginAttemptDao {
+    db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
+}
+
+impl DaoBase for LoginAttemptDao {
+    type Entity = LoginAttempt;
+
+    fn new(db: &DatabaseConnection) -> Self {
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
+    }
+
+    fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+}
+
+impl LoginAttemptDao {
+    /// Failures recorded for `key` in its current, unexpired window.
+    pub async fn failures(&self, key: &str) -> DaoResult<u32> {
+        let select = LoginAttempt::find()
+            .filter(login_attempt::Column::Key.eq(key))
+            .filter(login_attempt::Column::ExpiresAt.gt(Utc::now().fixed_offset()));
+        let row = timed(self.statement_timeout(), select.one(&self.db)).await?;
+        Ok(row.map_or(0, |row| u32::try_from(row.failures).unwrap_or(0)))
+    }
+
+    /// Counts one failure and returns the new total. The first failure after
+    /// the previous window lapsed starts a new window of length `window`,
+    /// clamped to [`MAX_WINDOW`].
+    pub async fn record_failure(&self, key: &str, window: Duration) -> DaoResult<u32> {
+        let now = Utc::now().fixed_offset();
+        let delete = LoginAttempt::delete_many()
+            .filter(login_attempt::Column::Key.eq(key))
+            .filter(login_attempt::Column::ExpiresAt.lte(now));
+        timed(self.statement_timeout(), delete.exec(&self.db)).await?;
+
+        let expires_at = chrono::Duration::from_std(window.min(MAX_WINDOW))
+            .ok()
+            .and_then(|window| now.checked_add_signed(window))
+            .ok_or_else(|| {
+                DaoLayerError::Db(DbErr::Custom(format!(
+                    "lockout window of {window:?} is out of range"
+                )))
+            })?;
+        let created = self
+            .create(login_attempt::ActiveModel {
+                key: Set(key.to_string()),
+                failures: Set(1),
+                expires_at: Set(expires_at),
+                ..Default::default()
+            })
+            .await;
+        match created {
+            Ok(_) => return Ok(1),
+            Err(DaoLayerError::Db(err)) if is_unique_violation(&err) => {}
+            Err(err) => return Err(err),
+        }
+
+        // Another request (or instance) already opened the window; increment
+        // in place so concurrent failures are not lost.
+        let update = LoginAttempt::update_many()
+            .col_expr(
+                login_attempt::Column::Failures,
+                Expr::col(login_attempt::Column::Failures).add(1),
+            )
+            .col_expr(login_attempt::Column::UpdatedAt, Expr::value(now))
+            .filter(login_attempt::Column::Key.eq(key));
+        timed(self.statement_timeout(), update.exec(&self.db)).await?;
+        self.failures(key).await
+    }
+
+    pub async fn clear(&self, key: &str) -> DaoResult<()> {
+        let delete = LoginAttempt::delete_many().filter(login_attempt::Column::Key.eq(key));
+        timed(self.statement_timeout(), delete.exec(&self.db)).await?;
+        Ok(())
+    }
+}
@@ -1,15 +1,41 @@
+pub mod auth_event_dao;
 pub mod base;
 pub mod base_traits;
+pub mod cache;
 mod context;
 pub mod error;
+pub mod invite_dao;
+pub mod job_dao;
+pub mod lock_dao;
+pub mod login_attempt_dao;
+pub mod outbox_dao;
+pub mod password_history_dao;
+pub mod patch;
 pub mod refresh_token_dao;
+pub mod timeout;
 pub mod todo_dao;
 pub mod user_dao;
 
-pub use base::{ColumnFilter, CompareOp, DaoBase, DaoPager, FilterOp, PaginatedResponse};
-pub use base_traits::{HasCreatedAtColumn, HasIdActiveModel, TimestampedActiveModel};
+pub use auth_event_dao::AuthEventDao;
+pub use base::{
+    ColumnFilter, ColumnOrder, CompareOp, DaoBase, DaoPager, FacetCount, FilterOp, LIKE_ESCAPE,
+    NullsOrder, PaginatedResponse, escape_like,
+};
+pub use base_traits::{
+    HasArchivedAtColumn, HasCreatedAtColumn, HasDeletedAtColumn, HasIdActiveModel,
+    TimestampedActiveModel,
+};
+pub use cache::{CachedDao, DaoCacheConfig};
 pub use context::DaoContext;
-pub use error::{DaoLayerError, DaoResult};
+pub use error::{DaoLayerError, DaoResult, entity_label, pagination_reason};
+pub use invite_dao::{InviteDao, MAX_INVITE_TTL_DAYS, NewInvite};
+pub use job_dao::JobDao;
+pub use lock_dao::{LOCK_TTL, LockDao, LockGuard};
+pub use login_attempt_dao::LoginAttemptDao;
+pub use outbox_dao::OutboxDao;
+pub use password_history_dao::PasswordHistoryDao;
+pub use patch::{double_option, set_if_present};
 pub use refresh_token_dao::RefreshTokenDao;
+pub use timeout::client_statement_timeout;
 pub use todo_dao::TodoDao;
 pub use user_dao::UserDao;
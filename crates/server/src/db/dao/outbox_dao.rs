@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+    sea_query::{Expr, ExprTrait},
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{DaoBase, DaoLayerError, DaoResult, timeout::timed};
+use crate::db::entities::outbox::{self, Entity as Outbox};
+
+// Rows that keep failing (e.g. an invalid channel name) stop being retried.
+pub const MAX_OUTBOX_ATTEMPTS: i32 = 5;
+
+#[derive(Clone)]
+pub struct OutboxDao {
+    db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
+}
+
+impl DaoBase for OutboxDao {
+    type Entity = Outbox;
+
+    fn new(db: &DatabaseConnection) -> Self {
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
+    }
+
+    fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+}
+
+impl OutboxDao {
+    /// Queues an event on `conn`, usually the transaction carrying the write it describes.
+    pub async fn enqueue<C>(
+        conn: &C,
+        channel: &str,
+        event: &str,
+        payload: Value,
+    ) -> DaoResult<outbox::Model>
+    where
+        C: ConnectionTrait,
+    {
+        let now = Utc::now().fixed_offset();
+        outbox::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            channel: Set(channel.to_string()),
+            event: Set(event.to_string()),
+            payload: Set(payload),
+            sent_at: Set(None),
+            attempts: Set(0),
+            last_error: Set(None),
+        }
+        .insert(conn)
+        .await
+        .map_err(DaoLayerError::from)
+    }
+
+    /// Oldest unsent rows that have not exhausted their attempts.
+    pub async fn pending(&self, limit: u64) -> DaoResult<Vec<outbox::Model>> {
+        let select = Outbox::find()
+            .filter(outbox::Column::SentAt.is_null())
+            .filter(outbox::Column::Attempts.lt(MAX_OUTBOX_ATTEMPTS))
+            .order_by_asc(outbox::Column::CreatedAt)
+            .limit(limit);
+        timed(self.statement_timeout(), select.all(&self.db)).await
+    }
+
+    pub async fn mark_sent(&self, id: Uuid) -> DaoResult<()> {
+        let now = Utc::now().fixed_offset();
+        let update = Outbox::update_many()
+            .col_expr(outbox::Column::SentAt, Expr::value(now))
+            .col_expr(outbox::Column::UpdatedAt, Expr::value(now))
+            .filter(outbox::Column::Id.eq(id));
+        timed(self.statement_timeout(), update.exec(&self.db)).await?;
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, id: Uuid, error: &str) -> DaoResult<()> {
+        let update = Outbox::update_many()
+            .col_expr(
+                outbox::Column::Attempts,
+                Expr::col(outbox::Column::Attempts).add(1),
+            )
+            .col_expr(outbox::Column::LastError, Expr::value(error))
+            .col_expr(
+                outbox::Column::UpdatedAt,
+                Expr::value(Utc::now().fixed_offset()),
+            )
+            .filter(outbox::Column::Id.eq(id));
+        timed(self.statement_timeout(), update.exec(&self.db)).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use uuid::Uuid;
+
+use super::{DaoBase, DaoResult, timeout::timed};
+use crate::db::entities::password_history::{self, Entity as PasswordHistory};
+
+/// Previous password hashes per user, newest first.
+#[derive(Clone)]
+pub struct PasswordHistoryDao {
+    db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
+}
+
+impl DaoBase for PasswordHistoryDao {
+    type Entity = PasswordHistory;
+
+    fn new(db: &DatabaseConnection) -> Self {
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
+    }
+
+    fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+}
+
+impl PasswordHistoryDao {
+    /// Up to `limit` of the user's previous hashes, newest first.
+    pub async fn recent_hashes(&self, user_id: &Uuid, limit: u64) -> DaoResult<Vec<String>> {
+        let select = PasswordHistory::find()
+            .filter(password_history::Column::UserId.eq(*user_id))
+            .order_by_desc(password_history::Column::CreatedAt)
+            .limit(limit);
+        let rows = timed(self.statement_timeout(), select.all(&self.db)).await?;
+        Ok(rows.into_iter().map(|row| row.password_hash).collect())
+    }
+
+    /// Stores `password_hash` and deletes all but the user's newest `keep` rows.
+    pub async fn record(&self, user_id: &Uuid, password_hash: &str, keep: u64) -> DaoResult<()> {
+        self.create(password_history::ActiveModel {
+            user_id: Set(*user_id),
+            password_hash: Set(password_hash.to_string()),
+            ..Default::default()
+        })
+        .await?;
+
+        // SQLite rejects OFFSET without LIMIT, so the newest rows are skipped here.
+        let select = PasswordHistory::find()
+            .select_only()
+            .column(password_history::Column::Id)
+            .filter(password_history::Column::UserId.eq(*user_id))
+            .order_by_desc(password_history::Column::CreatedAt)
+            .into_tuple();
+        let ids: Vec<Uuid> = timed(self.statement_timeout(), select.all(&self.db)).await?;
+        let stale: Vec<Uuid> = ids
+            .into_iter()
+            .skip(usize::try_from(keep).unwrap_or(usize::MAX))
+            .collect();
+        if stale.is_empty() {
+            return Ok(());
+        }
+        let delete =
+            PasswordHistory::delete_many().filter(password_history::Column::Id.is_in(stale));
+        timed(self.statement_timeout(), delete.exec(&self.db)).await?;
+        Ok(())
+    }
+}
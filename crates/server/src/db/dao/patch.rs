@@ -0,0 +1,78 @@
+use sea_orm::ActiveValue;
+use serde::{Deserialize, Deserializer};
+
+/// Deserializer for nullable fields of partial-update requests, so an absent
+/// key and an explicit `null` stay distinguishable:
+///
+/// ```ignore
+/// #[serde(default, deserialize_with = "double_option")]
+/// pub note: Option<Option<String>>,
+/// ```
+///
+/// A missing key gives `None` (leave the column alone), `null` gives
+/// `Some(None)` (clear it) and a value gives `Some(Some(value))`.
+pub fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Sets `target` when the request carried the field and leaves it untouched
+/// otherwise. Pass an `Option<Option<T>>` to clear nullable columns on `null`.
+pub fn set_if_present<V>(target: &mut ActiveValue<V>, value: Option<V>)
+where
+    V: Into<sea_orm::Value>,
+{
+    if let Some(value) = value {
+        *target = ActiveValue::Set(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::ActiveValue;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::{double_option, set_if_present};
+    use crate::db::entities::job;
+
+    #[derive(Debug, Deserialize)]
+    struct UpdateJobRequest {
+        #[serde(default, deserialize_with = "double_option")]
+        error: Option<Option<String>>,
+    }
+
+    fn apply(body: serde_json::Value) -> ActiveValue<Option<String>> {
+        let request: UpdateJobRequest = serde_json::from_value(body).expect("request should parse");
+        let mut active = job::ActiveModel {
+            error: ActiveValue::Unchanged(Some("old".to_string())),
+            ..Default::default()
+        };
+        set_if_present(&mut active.error, request.error);
+        active.error
+    }
+
+    #[test]
+    fn absent_field_is_preserved() {
+        assert_eq!(
+            apply(json!({})),
+            ActiveValue::Unchanged(Some("old".to_string()))
+        );
+    }
+
+    #[test]
+    fn explicit_null_clears_field() {
+        assert_eq!(apply(json!({ "error": null })), ActiveValue::Set(None));
+    }
+
+    #[test]
+    fn value_sets_field() {
+        assert_eq!(
+            apply(json!({ "error": "new" })),
+            ActiveValue::Set(Some("new".to_string()))
+        );
+    }
+}
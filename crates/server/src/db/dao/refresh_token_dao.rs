@@ -2,7 +2,7 @@ use chrono::{Duration, Utc};
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use uuid::Uuid;
 
-use super::{DaoBase, DaoLayerError, DaoResult};
+use super::{DaoBase, DaoResult, timeout::timed};
 use crate::db::entities::refresh_token::{self, Entity as RefreshToken};
 
 const DEFAULT_REFRESH_TTL_DAYS: i64 = 30;
@@ -10,18 +10,31 @@ const DEFAULT_REFRESH_TTL_DAYS: i64 = 30;
 #[derive(Clone)]
 pub struct RefreshTokenDao {
     db: DatabaseConnection,
+    statement_timeout: Option<std::time::Duration>,
 }
 
 impl DaoBase for RefreshTokenDao {
     type Entity = RefreshToken;
 
     fn new(db: &DatabaseConnection) -> Self {
-        Self { db: db.clone() }
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
     }
 
     fn db(&self) -> &DatabaseConnection {
         &self.db
     }
+
+    fn statement_timeout(&self) -> Option<std::time::Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<std::time::Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
 }
 
 impl RefreshTokenDao {
@@ -29,6 +42,18 @@ impl RefreshTokenDao {
         &self,
         user_id: &Uuid,
         ttl_days: Option<i64>,
+    ) -> DaoResult<refresh_token::Model> {
+        self.create_bound_refresh_token(user_id, ttl_days, None)
+            .await
+    }
+
+    /// Like `create_refresh_token`, storing the client fingerprint the token
+    /// is bound to.
+    pub async fn create_bound_refresh_token(
+        &self,
+        user_id: &Uuid,
+        ttl_days: Option<i64>,
+        fingerprint_hash: Option<&str>,
     ) -> DaoResult<refresh_token::Model> {
         let expires_at = Utc::now().fixed_offset()
             + Duration::days(ttl_days.unwrap_or(DEFAULT_REFRESH_TTL_DAYS));
@@ -37,6 +62,7 @@ impl RefreshTokenDao {
             user_id: Set(*user_id),
             expires_at: Set(expires_at),
             revoked: Set(false),
+            fingerprint_hash: Set(fingerprint_hash.map(str::to_string)),
             ..Default::default()
         };
         self.create(model).await
@@ -57,17 +83,28 @@ impl RefreshTokenDao {
     }
 
     pub async fn revoke_token(&self, token: &str) -> DaoResult<()> {
-        RefreshToken::update_many()
+        let update = RefreshToken::update_many()
             .col_expr(
                 refresh_token::Column::Revoked,
                 sea_orm::sea_query::Expr::value(true),
             )
-            .filter(refresh_token::Column::Token.eq(token))
-            .exec(&self.db)
-            .await
-            .map_err(DaoLayerError::Db)?;
+            .filter(refresh_token::Column::Token.eq(token));
+        timed(self.statement_timeout(), update.exec(&self.db)).await?;
         Ok(())
     }
+
+    /// Revokes every active refresh token for `user_id`, returning how many were revoked.
+    pub async fn revoke_all_for_user(&self, user_id: &Uuid) -> DaoResult<u64> {
+        let update = RefreshToken::update_many()
+            .col_expr(
+                refresh_token::Column::Revoked,
+                sea_orm::sea_query::Expr::value(true),
+            )
+            .filter(refresh_token::Column::UserId.eq(*user_id))
+            .filter(refresh_token::Column::Revoked.eq(false));
+        let result = timed(self.statement_timeout(), update.exec(&self.db)).await?;
+        Ok(result.rows_affected)
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +136,7 @@ mod tests {
             user_id,
             expires_at: now + Duration::days(30),
             revoked,
+            fingerprint_hash: None,
         }
     }
 
@@ -0,0 +1,85 @@
+use std::{future::Future, time::Duration};
+
+use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr};
+
+use super::error::{DaoLayerError, DaoResult};
+
+/// The bound DAOs on `db` enforce themselves for `database.statement_timeout_ms`.
+///
+/// Postgres cancels slow statements on the server, so it gets `None`. Other
+/// backends have no statement timeout of their own and are bounded here.
+pub fn client_statement_timeout(
+    db: &DatabaseConnection,
+    statement_timeout_ms: u64,
+) -> Option<Duration> {
+    (db.get_database_backend() != DatabaseBackend::Postgres && statement_timeout_ms > 0)
+        .then(|| Duration::from_millis(statement_timeout_ms))
+}
+
+/// Runs `statement`, failing with [`DaoLayerError::Timeout`] when it outlives
+/// `timeout`. The driver may finish the statement in the background; only the
+/// caller stops waiting. `None` waits for as long as the statement takes.
+pub(crate) async fn timed<T>(
+    timeout: Option<Duration>,
+    statement: impl Future<Output = Result<T, DbErr>>,
+) -> DaoResult<T> {
+    let Some(timeout) = timeout else {
+        return statement.await.map_err(DaoLayerError::from);
+    };
+    match tokio::time::timeout(timeout, statement).await {
+        Ok(result) => result.map_err(DaoLayerError::from),
+        Err(_) => Err(DaoLayerError::Timeout(DbErr::Custom(format!(
+            "statement timed out after {}ms",
+            timeout.as_millis()
+        )))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sea_orm::{DatabaseBackend, DbErr, MockDatabase};
+
+    use super::{client_statement_timeout, timed};
+    use crate::db::dao::DaoLayerError;
+
+    #[tokio::test]
+    async fn statement_over_the_timeout_surfaces_as_timeout_error() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok::<_, DbErr>(())
+        };
+
+        let err = timed(Some(Duration::from_millis(10)), slow)
+            .await
+            .expect_err("slow statement should time out");
+
+        assert!(matches!(err, DaoLayerError::Timeout(_)));
+        assert!(err.to_string().contains("after 10ms"));
+    }
+
+    #[tokio::test]
+    async fn statement_within_the_timeout_returns_its_result() {
+        let fast = async { Ok::<_, DbErr>(7) };
+
+        let value = timed(Some(Duration::from_secs(5)), fast)
+            .await
+            .expect("fast statement should finish");
+
+        assert_eq!(value, 7);
+    }
+
+    #[test]
+    fn only_backends_without_a_server_timeout_get_a_client_bound() {
+        let sqlite = MockDatabase::new(DatabaseBackend::Sqlite).into_connection();
+        let postgres = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+
+        assert_eq!(
+            client_statement_timeout(&sqlite, 250),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(client_statement_timeout(&sqlite, 0), None);
+        assert_eq!(client_statement_timeout(&postgres, 250), None);
+    }
+}
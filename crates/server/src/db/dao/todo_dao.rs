@@ -1,47 +1,75 @@
+use std::time::Duration;
+
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, Set};
 use uuid::Uuid;
 
-use super::{DaoBase, DaoLayerError, DaoResult};
+use super::{DaoBase, DaoResult, set_if_present, timeout::timed};
 use crate::db::entities::prelude::{TodoItem, TodoList};
 use crate::db::entities::{todo_item, todo_list};
 
 #[derive(Clone)]
 pub struct TodoDao {
     db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
 }
 
 impl DaoBase for TodoDao {
     type Entity = TodoList;
 
     fn new(db: &DatabaseConnection) -> Self {
-        Self { db: db.clone() }
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
     }
 
     fn db(&self) -> &DatabaseConnection {
         &self.db
     }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
 }
 
 #[derive(Clone)]
 struct TodoItemDao {
     db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
 }
 
 impl DaoBase for TodoItemDao {
     type Entity = TodoItem;
 
     fn new(db: &DatabaseConnection) -> Self {
-        Self { db: db.clone() }
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
     }
 
     fn db(&self) -> &DatabaseConnection {
         &self.db
     }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
 }
 
 impl TodoDao {
     fn item_dao(&self) -> TodoItemDao {
-        TodoItemDao::new(&self.db)
+        TodoItemDao::new(&self.db).with_statement_timeout(self.statement_timeout)
     }
 
     pub async fn create_list(&self, title: &str) -> DaoResult<todo_list::Model> {
@@ -62,10 +90,7 @@ impl TodoDao {
     }
 
     pub async fn count_lists(&self) -> DaoResult<u64> {
-        TodoList::find()
-            .count(&self.db)
-            .await
-            .map_err(DaoLayerError::Db)
+        timed(self.statement_timeout(), TodoList::find().count(&self.db)).await
     }
 
     pub async fn find_list_by_id(&self, id: &Uuid) -> DaoResult<todo_list::Model> {
@@ -109,6 +134,13 @@ impl TodoDao {
         Ok(items)
     }
 
+    pub async fn load_items(&self, list: &todo_list::Model) -> DaoResult<Vec<todo_item::Model>> {
+        let mut loaded = self
+            .load_many_related::<TodoItem>(std::slice::from_ref(list))
+            .await?;
+        Ok(loaded.pop().unwrap_or_default())
+    }
+
     pub async fn find_item_by_id(
         &self,
         list_id: &Uuid,
@@ -137,12 +169,8 @@ impl TodoDao {
         let model = self
             .item_dao()
             .update(*item_id, move |active| {
-                if let Some(description) = description {
-                    active.description = Set(description);
-                }
-                if let Some(done) = done {
-                    active.done = Set(done);
-                }
+                set_if_present(&mut active.description, description);
+                set_if_present(&mut active.done, done);
             })
             .await?;
         Ok(Some(model))
@@ -157,11 +185,8 @@ impl TodoDao {
     }
 
     pub async fn count_items_by_list(&self, list_id: &Uuid) -> DaoResult<u64> {
-        TodoItem::find()
-            .filter(todo_item::Column::ListId.eq(*list_id))
-            .count(&self.db)
-            .await
-            .map_err(DaoLayerError::Db)
+        let select = TodoItem::find().filter(todo_item::Column::ListId.eq(*list_id));
+        timed(self.statement_timeout(), select.count(&self.db)).await
     }
 }
 
@@ -1,4 +1,6 @@
-use sea_orm::{ColumnTrait, DatabaseConnection, QueryFilter, Set};
+use std::time::Duration;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, QueryFilter, Set};
 use uuid::Uuid;
 
 use super::{DaoBase, DaoResult};
@@ -8,18 +10,31 @@ use crate::db::entities::{prelude::User, user};
 #[derive(Clone)]
 pub struct UserDao {
     db: DatabaseConnection,
+    statement_timeout: Option<Duration>,
 }
 
 impl DaoBase for UserDao {
     type Entity = User;
 
     fn new(db: &DatabaseConnection) -> Self {
-        Self { db: db.clone() }
+        Self {
+            db: db.clone(),
+            statement_timeout: None,
+        }
     }
 
     fn db(&self) -> &DatabaseConnection {
         &self.db
     }
+
+    fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+
+    fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
 }
 
 impl UserDao {
@@ -38,6 +53,21 @@ impl UserDao {
         password_hash: &str,
         role: &str,
     ) -> DaoResult<user::Model> {
+        self.create_user_in(self.db(), email, password_hash, role)
+            .await
+    }
+
+    /// Like [`create_user`](Self::create_user) but runs on `conn`, e.g. a transaction.
+    pub async fn create_user_in<C>(
+        &self,
+        conn: &C,
+        email: &str,
+        password_hash: &str,
+        role: &str,
+    ) -> DaoResult<user::Model>
+    where
+        C: ConnectionTrait + Sync,
+    {
         let model = user::ActiveModel {
             email: Set(email.to_string()),
             password_hash: Set(password_hash.to_string()),
@@ -45,13 +75,30 @@ impl UserDao {
             last_login_at: Set(None),
             ..Default::default()
         };
-        self.create(model).await
+        self.create_in(conn, model).await
     }
 
     pub async fn touch_updated_at(&self, id: &Uuid) -> DaoResult<()> {
         self.update(*id, |_| {}).await.map(|_| ())
     }
 
+    pub async fn set_password_hash(&self, id: &Uuid, password_hash: &str) -> DaoResult<()> {
+        let password_hash = password_hash.to_string();
+        self.update(*id, move |active| {
+            active.password_hash = Set(password_hash);
+        })
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn set_role(&self, id: &Uuid, role: &str) -> DaoResult<user::Model> {
+        let role = role.to_string();
+        self.update(*id, move |active| {
+            active.role = Set(role);
+        })
+        .await
+    }
+
     pub async fn set_last_login(
         &self,
         id: &Uuid,
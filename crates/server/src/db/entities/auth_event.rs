@@ -0,0 +1,20 @@
+use base_entity_derive::base_entity;
+use sea_orm::entity::prelude::*;
+
+// Audit rows outlive the users they mention, so `user_id` is not a foreign key.
+#[base_entity]
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "auth_events")]
+pub struct Model {
+    #[sea_orm(indexed)]
+    pub event_type: String,
+    #[sea_orm(indexed)]
+    pub user_id: Option<Uuid>,
+    pub subject: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
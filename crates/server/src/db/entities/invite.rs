@@ -0,0 +1,18 @@
+use base_entity_derive::base_entity;
+use sea_orm::entity::prelude::*;
+
+#[base_entity]
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "invites")]
+pub struct Model {
+    /// Hex SHA-256 of the token handed out; the token itself is never stored.
+    #[sea_orm(unique)]
+    pub token: String,
+    pub role: String,
+    pub created_by: Option<Uuid>,
+    pub expires_at: DateTimeWithTimeZone,
+    pub used_at: Option<DateTimeWithTimeZone>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
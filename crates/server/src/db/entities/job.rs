@@ -0,0 +1,26 @@
+use base_entity_derive::base_entity;
+use sea_orm::entity::prelude::*;
+
+pub const STATUS_QUEUED: &str = "queued";
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_COMPLETED: &str = "completed";
+pub const STATUS_FAILED: &str = "failed";
+
+// Background work submitted through `POST /jobs`; the job worker claims queued
+// rows, runs the registered handler for `job_type`, and stores the outcome.
+#[base_entity]
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "job")]
+pub struct Model {
+    pub job_type: String,
+    pub payload: Json,
+    #[sea_orm(indexed)]
+    pub status: String,
+    pub result: Option<Json>,
+    pub error: Option<String>,
+    pub submitted_by: Option<Uuid>,
+    pub finished_at: Option<DateTimeWithTimeZone>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,17 @@
+use base_entity_derive::base_entity;
+use sea_orm::entity::prelude::*;
+
+// Row-based lock used by `LockDao` on backends without advisory locks. A row
+// whose `expires_at` has passed is stale and may be taken over.
+#[base_entity]
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "locks")]
+pub struct Model {
+    #[sea_orm(unique)]
+    pub key: String,
+    pub holder: Uuid,
+    pub expires_at: DateTimeWithTimeZone,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,17 @@
+use base_entity_derive::base_entity;
+use sea_orm::entity::prelude::*;
+
+// Failed login count per normalized email, shared by every instance using the
+// database lockout store. A row whose `expires_at` has passed counts as zero.
+#[base_entity]
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "login_attempts")]
+pub struct Model {
+    #[sea_orm(unique)]
+    pub key: String,
+    pub failures: i32,
+    pub expires_at: DateTimeWithTimeZone,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
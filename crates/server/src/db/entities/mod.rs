@@ -1,3 +1,10 @@
+pub mod auth_event;
+pub mod invite;
+pub mod job;
+pub mod lock;
+pub mod login_attempt;
+pub mod outbox;
+pub mod password_history;
 pub mod prelude;
 pub mod refresh_token;
 pub mod todo_item;
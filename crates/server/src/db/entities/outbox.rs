@@ -0,0 +1,20 @@
+use base_entity_derive::base_entity;
+use sea_orm::entity::prelude::*;
+
+// Events written in the same transaction as the data they describe; the
+// outbox relay publishes pending rows and stamps `sent_at`.
+#[base_entity]
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "outbox")]
+pub struct Model {
+    pub channel: String,
+    pub event: String,
+    pub payload: Json,
+    #[sea_orm(indexed)]
+    pub sent_at: Option<DateTimeWithTimeZone>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
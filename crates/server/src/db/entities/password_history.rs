@@ -0,0 +1,18 @@
+use base_entity_derive::base_entity;
+use sea_orm::entity::prelude::*;
+
+// A password hash the user has since replaced, kept so it cannot be chosen
+// again while `auth.password_history` is on.
+#[base_entity]
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "password_history")]
+pub struct Model {
+    #[sea_orm(indexed)]
+    pub user_id: Uuid,
+    pub password_hash: String,
+    #[sea_orm(belongs_to, from = "user_id", to = "id", on_delete = "Cascade")]
+    pub user: HasOne<super::user::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,12 @@
 #![allow(unused_imports)]
 
+pub use super::auth_event::Entity as AuthEvent;
+pub use super::invite::Entity as Invite;
+pub use super::job::Entity as Job;
+pub use super::lock::Entity as Lock;
+pub use super::login_attempt::Entity as LoginAttempt;
+pub use super::outbox::Entity as Outbox;
+pub use super::password_history::Entity as PasswordHistory;
 pub use super::refresh_token::Entity as RefreshToken;
 pub use super::todo_item::Entity as TodoItem;
 pub use super::todo_list::Entity as TodoList;
@@ -12,6 +12,8 @@ pub struct Model {
     pub user_id: Uuid,
     pub expires_at: DateTimeWithTimeZone,
     pub revoked: bool,
+    /// Client fingerprint the token was issued to, when refresh-token binding is on.
+    pub fingerprint_hash: Option<String>,
     #[sea_orm(belongs_to, from = "user_id", to = "id", on_delete = "Cascade")]
     pub user: HasOne<super::user::Entity>,
 }
@@ -13,6 +13,8 @@ pub struct Model {
     pub last_login_at: Option<DateTimeWithTimeZone>,
     #[sea_orm(has_many)]
     pub refresh_tokens: HasMany<super::refresh_token::Entity>,
+    #[sea_orm(has_many)]
+    pub password_history: HasMany<super::password_history::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,3 +1,4 @@
+mod mysql;
 mod postgres;
 mod registry;
 mod sqlite;
@@ -6,10 +7,11 @@ use std::sync::Arc;
 
 pub use registry::{DbProvider, DbProviderId, DbProviders};
 
-use self::{postgres::PostgresDbProvider, sqlite::SqliteDbProvider};
+use self::{mysql::MySqlDbProvider, postgres::PostgresDbProvider, sqlite::SqliteDbProvider};
 
 pub fn default_registry() -> anyhow::Result<DbProviders> {
     DbProviders::new()
         .with_provider(Arc::new(PostgresDbProvider))?
-        .with_provider(Arc::new(SqliteDbProvider))
+        .with_provider(Arc::new(SqliteDbProvider))?
+        .with_provider(Arc::new(MySqlDbProvider))
 }
@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+
+use super::registry::{DbProvider, DbProviderId};
+use crate::config::DatabaseConfig;
+
+pub struct MySqlDbProvider;
+
+#[async_trait]
+impl DbProvider for MySqlDbProvider {
+    fn id(&self) -> DbProviderId {
+        DbProviderId::MySql
+    }
+
+    fn supports_url(&self, url: &str) -> bool {
+        url.trim().to_ascii_lowercase().starts_with("mysql://")
+    }
+
+    async fn connect(&self, cfg: &DatabaseConfig) -> Result<DatabaseConnection> {
+        let mut options = ConnectOptions::new(cfg.url.clone());
+        options
+            .max_connections(cfg.max_connections)
+            .min_connections(cfg.min_idle)
+            .connect_timeout(Duration::from_secs(5))
+            .sqlx_logging(false);
+
+        let db = Database::connect(options).await?;
+        Ok(db)
+    }
+}
@@ -21,7 +21,8 @@ impl DbProvider for PostgresDbProvider {
     }
 
     async fn connect(&self, cfg: &DatabaseConfig) -> Result<DatabaseConnection> {
-        let mut options = ConnectOptions::new(cfg.url.clone());
+        let mut options =
+            ConnectOptions::new(with_statement_timeout(&cfg.url, cfg.statement_timeout_ms));
         options
             .max_connections(cfg.max_connections)
             .min_connections(cfg.min_idle)
@@ -32,3 +33,34 @@ impl DbProvider for PostgresDbProvider {
         Ok(db)
     }
 }
+
+/// Adds `statement_timeout` to the startup options in `url`, so every pooled
+/// connection has the server cancel statements that run longer.
+fn with_statement_timeout(url: &str, statement_timeout_ms: u64) -> String {
+    if statement_timeout_ms == 0 {
+        return url.to_string();
+    }
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{url}{separator}options[statement_timeout]={statement_timeout_ms}ms")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::with_statement_timeout;
+
+    #[test]
+    fn statement_timeout_is_appended_as_a_startup_option() {
+        assert_eq!(
+            with_statement_timeout("postgres://localhost/app", 500),
+            "postgres://localhost/app?options[statement_timeout]=500ms"
+        );
+        assert_eq!(
+            with_statement_timeout("postgres://localhost/app?sslmode=require", 500),
+            "postgres://localhost/app?sslmode=require&options[statement_timeout]=500ms"
+        );
+        assert_eq!(
+            with_statement_timeout("postgres://localhost/app", 0),
+            "postgres://localhost/app"
+        );
+    }
+}
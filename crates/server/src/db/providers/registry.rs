@@ -8,6 +8,7 @@ use crate::config::DatabaseConfig;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DbProviderId {
+    MySql,
     Postgres,
     Sqlite,
 }
@@ -15,6 +16,7 @@ pub enum DbProviderId {
 impl DbProviderId {
     pub fn as_str(self) -> &'static str {
         match self {
+            DbProviderId::MySql => "mysql",
             DbProviderId::Postgres => "postgres",
             DbProviderId::Sqlite => "sqlite",
         }
@@ -63,7 +65,7 @@ impl DbProviders {
             .cloned()
             .ok_or_else(|| {
                 anyhow::anyhow!(
-                    "unsupported database url '{}'; expected scheme postgres://, postgresql://, or sqlite://",
+                    "unsupported database url '{}'; expected scheme postgres://, postgresql://, mysql://, or sqlite://",
                     redact_url(url)
                 )
             })
@@ -164,7 +166,7 @@ mod tests {
     #[test]
     fn returns_error_for_unsupported_scheme() {
         let providers = DbProviders::new();
-        let err = match providers.provider_for_url("mysql://localhost/db") {
+        let err = match providers.provider_for_url("oracle://localhost/db") {
             Ok(_) => panic!("unsupported url should fail"),
             Err(err) => err,
         };
@@ -13,6 +13,10 @@ pub enum AppError {
     Forbidden(String),
     NotFound(String),
     Conflict(String),
+    MethodNotAllowed(String),
+    UnprocessableEntity(String),
+    TooManyRequests(String),
+    GatewayTimeout(String),
     Internal(InternalError),
 }
 
@@ -37,6 +41,22 @@ impl AppError {
         Self::Conflict(message.into())
     }
 
+    pub fn method_not_allowed(message: impl Into<String>) -> Self {
+        Self::MethodNotAllowed(message.into())
+    }
+
+    pub fn unprocessable_entity(message: impl Into<String>) -> Self {
+        Self::UnprocessableEntity(message.into())
+    }
+
+    pub fn too_many_requests(message: impl Into<String>) -> Self {
+        Self::TooManyRequests(message.into())
+    }
+
+    pub fn gateway_timeout(message: impl Into<String>) -> Self {
+        Self::GatewayTimeout(message.into())
+    }
+
     pub fn internal(message: impl Into<String>) -> Self {
         Self::Internal(InternalError {
             message: message.into(),
@@ -60,11 +80,40 @@ impl AppError {
             | Self::Unauthorized(message)
             | Self::Forbidden(message)
             | Self::NotFound(message)
-            | Self::Conflict(message) => message.as_str(),
+            | Self::Conflict(message)
+            | Self::MethodNotAllowed(message)
+            | Self::UnprocessableEntity(message)
+            | Self::TooManyRequests(message)
+            | Self::GatewayTimeout(message) => message.as_str(),
             Self::Internal(internal) => internal.message.as_str(),
         }
     }
 
+    /// Prefixes the message with `context`, keeping the status.
+    pub fn context(self, context: impl std::fmt::Display) -> Self {
+        match self {
+            Self::BadRequest(message) => Self::BadRequest(format!("{context}: {message}")),
+            Self::Unauthorized(message) => Self::Unauthorized(format!("{context}: {message}")),
+            Self::Forbidden(message) => Self::Forbidden(format!("{context}: {message}")),
+            Self::NotFound(message) => Self::NotFound(format!("{context}: {message}")),
+            Self::Conflict(message) => Self::Conflict(format!("{context}: {message}")),
+            Self::MethodNotAllowed(message) => {
+                Self::MethodNotAllowed(format!("{context}: {message}"))
+            }
+            Self::UnprocessableEntity(message) => {
+                Self::UnprocessableEntity(format!("{context}: {message}"))
+            }
+            Self::TooManyRequests(message) => {
+                Self::TooManyRequests(format!("{context}: {message}"))
+            }
+            Self::GatewayTimeout(message) => Self::GatewayTimeout(format!("{context}: {message}")),
+            Self::Internal(mut internal) => {
+                internal.message = format!("{context}: {}", internal.message);
+                Self::Internal(internal)
+            }
+        }
+    }
+
     pub fn source(&self) -> Option<&(dyn Error + Send + Sync + 'static)> {
         match self {
             Self::Internal(internal) => internal.source.as_deref(),
@@ -110,6 +159,9 @@ impl From<crate::db::dao::DaoLayerError> for AppError {
                 "database operation failed. Please check the logs for more details",
                 db_err,
             ),
+            crate::db::dao::DaoLayerError::Timeout(_) => {
+                AppError::gateway_timeout("database operation timed out")
+            }
             _ => AppError::bad_request(format!("database operation failed: {}", err)),
         }
     }
@@ -128,3 +180,14 @@ impl From<realtime::server::RealtimeError> for AppError {
         }
     }
 }
+
+impl From<crate::storage::StorageError> for AppError {
+    fn from(err: crate::storage::StorageError) -> Self {
+        match err {
+            crate::storage::StorageError::Io(io_err) => {
+                AppError::internal_with_source("file storage failed", io_err)
+            }
+            _ => AppError::bad_request(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod registry;
+mod worker;
+
+pub use registry::{JobFuture, JobHandler, JobRegistry, job_registry};
+pub use worker::{JOB_POLL_INTERVAL, run_jobs_once, spawn_job_worker};
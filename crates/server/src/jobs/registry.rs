@@ -0,0 +1,42 @@
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
+
+use serde_json::Value;
+
+pub type JobFuture = Pin<Box<dyn Future<Output = Result<Value, String>> + Send>>;
+pub type JobHandler = Arc<dyn Fn(Value) -> JobFuture + Send + Sync>;
+
+/// Job types the app accepts, keyed by the `type` clients submit.
+///
+/// A handler gets the submitted params and returns the JSON result stored on
+/// the job, or an error message that marks it failed.
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    handlers: HashMap<&'static str, JobHandler>,
+}
+
+impl JobRegistry {
+    pub fn register<F, Fut>(mut self, job_type: &'static str, handler: F) -> Self
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        self.handlers.insert(
+            job_type,
+            Arc::new(move |params| Box::pin(handler(params)) as JobFuture),
+        );
+        self
+    }
+
+    pub fn contains(&self, job_type: &str) -> bool {
+        self.handlers.contains_key(job_type)
+    }
+
+    pub fn get(&self, job_type: &str) -> Option<&JobHandler> {
+        self.handlers.get(job_type)
+    }
+}
+
+/// The registry used by `POST /jobs` and the job worker; add app job types here.
+pub fn job_registry() -> JobRegistry {
+    JobRegistry::default().register("echo", |params| async move { Ok(params) })
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use super::JobRegistry;
+use crate::db::dao::{DaoResult, JobDao};
+
+pub const JOB_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const JOB_BATCH_SIZE: u64 = 20;
+
+/// Runs one batch of queued jobs, oldest first, and returns how many ran.
+///
+/// Jobs whose type is no longer registered are marked failed rather than left
+/// queued forever.
+pub async fn run_jobs_once(dao: &JobDao, registry: &JobRegistry) -> DaoResult<usize> {
+    let mut ran = 0;
+    for job in dao.queued(JOB_BATCH_SIZE).await? {
+        if !dao.claim(job.id).await? {
+            continue;
+        }
+        let outcome = match registry.get(&job.job_type) {
+            Some(handler) => handler(job.payload).await,
+            None => Err(format!("Unknown job type `{}`", job.job_type)),
+        };
+        if let Err(err) = &outcome {
+            tracing::warn!(job_id = %job.id, job_type = %job.job_type, error = %err, "job failed");
+        }
+        dao.finish(job.id, outcome).await?;
+        ran += 1;
+    }
+    Ok(ran)
+}
+
+/// Polls for queued jobs every `JOB_POLL_INTERVAL` until the runtime shuts down.
+pub fn spawn_job_worker(dao: JobDao, registry: JobRegistry) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(JOB_POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = run_jobs_once(&dao, &registry).await {
+                tracing::warn!(error = %err, "job worker pass failed");
+            }
+        }
+    })
+}
@@ -2,9 +2,13 @@ pub mod auth;
 pub mod config;
 pub mod db;
 pub mod error;
+pub mod jobs;
+pub mod listener;
 pub mod logging;
 pub mod realtime;
 pub mod routes;
 pub mod services;
 pub mod state;
+pub mod storage;
 pub mod test_helpers;
+pub mod window_counter;
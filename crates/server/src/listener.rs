@@ -0,0 +1,264 @@
+use std::{future::Future, net::SocketAddr};
+
+use anyhow::Context;
+use axum::Router;
+
+use crate::config::GeneralConfig;
+
+/// Serves `app` on the configured listener, the Unix socket at
+/// `general.unix_socket` when set and TCP `host:port` otherwise, until
+/// `shutdown` resolves.
+pub async fn serve(
+    app: Router,
+    general: &GeneralConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    if let Some(path) = general.unix_socket.as_deref() {
+        #[cfg(unix)]
+        {
+            tracing::info!("listening on unix:{}", path);
+            return unix::serve_unix(app, path, shutdown)
+                .await
+                .with_context(|| format!("failed to serve on unix socket {path}"));
+        }
+        #[cfg(not(unix))]
+        anyhow::bail!("general.unix_socket ({path}) is only supported on Unix platforms");
+    }
+
+    let addr: SocketAddr = format!("{}:{}", general.host, general.port)
+        .parse()
+        .context("invalid host/port")?;
+    tracing::info!("listening on http://{}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown)
+    .await?;
+    Ok(())
+}
+
+/// Connect info of requests that arrived on the Unix socket listener. Only
+/// local processes can reach the socket, so the peer is taken to be a
+/// reverse proxy and its forwarding headers name the client.
+#[derive(Debug, Clone, Copy)]
+pub struct UnixSocketPeer;
+
+/// Resolves on Ctrl-C or, on Unix, `SIGTERM`.
+#[cfg(unix)]
+pub async fn shutdown_signal() {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let terminate = async {
+        match signal(SignalKind::terminate()) {
+            Ok(mut stream) => {
+                stream.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
+
+/// Resolves on Ctrl-C or, on Unix, `SIGTERM`.
+#[cfg(not(unix))]
+pub async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(unix)]
+pub use unix::serve_unix;
+
+#[cfg(unix)]
+mod unix {
+    use std::{
+        future::Future,
+        io,
+        os::unix::fs::FileTypeExt,
+        path::{Path, PathBuf},
+    };
+
+    use axum::{Router, extract::connect_info::Connected, serve::IncomingStream};
+    use tokio::net::UnixListener;
+
+    use super::UnixSocketPeer;
+
+    impl Connected<IncomingStream<'_, UnixListener>> for UnixSocketPeer {
+        fn connect_info(_stream: IncomingStream<'_, UnixListener>) -> Self {
+            UnixSocketPeer
+        }
+    }
+
+    /// Serves `app` on a Unix socket at `path` until `shutdown` resolves.
+    ///
+    /// A socket file left behind by a previous run is replaced; any other file
+    /// at `path` is an error. The socket file is removed when serving stops.
+    /// Like the TCP listener, in-flight requests are drained on shutdown;
+    /// websockets are closed by the realtime hub's own shutdown.
+    pub async fn serve_unix(
+        app: Router,
+        path: impl AsRef<Path>,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> io::Result<()> {
+        let path = path.as_ref();
+        remove_stale_socket(path)?;
+        let listener = UnixListener::bind(path)?;
+        let _socket = SocketFile(path.to_path_buf());
+
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<UnixSocketPeer>(),
+        )
+        .with_graceful_shutdown(shutdown)
+        .await
+    }
+
+    fn remove_stale_socket(path: &Path) -> io::Result<()> {
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_socket() => std::fs::remove_file(path),
+            Ok(_) => Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            )),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Removes the socket file when the listener goes away.
+    struct SocketFile(PathBuf);
+
+    impl Drop for SocketFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use axum::{Router, routing::get};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::UnixStream,
+        sync::oneshot,
+    };
+    use uuid::Uuid;
+
+    use super::serve_unix;
+
+    #[tokio::test]
+    async fn unix_socket_serves_requests_and_is_removed_on_shutdown() {
+        let path = std::env::temp_dir().join(format!("oxide-{}.sock", Uuid::new_v4().simple()));
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = tokio::spawn({
+            let path = path.clone();
+            async move {
+                serve_unix(app, path, async {
+                    let _ = stopped.await;
+                })
+                .await
+            }
+        });
+
+        let mut stream = loop {
+            match UnixStream::connect(&path).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        };
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("request should be written");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("response should be read");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("ok"), "{response}");
+
+        stop.send(()).expect("server should still be running");
+        server
+            .await
+            .expect("server task should join")
+            .expect("server should stop cleanly");
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn unix_socket_refuses_to_replace_a_regular_file() {
+        let path = std::env::temp_dir().join(format!("oxide-{}.sock", Uuid::new_v4().simple()));
+        std::fs::write(&path, "keep me").expect("file should be written");
+
+        let err = serve_unix(Router::new(), &path, async {})
+            .await
+            .expect_err("a regular file should not be replaced");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn unix_socket_drains_in_flight_requests_on_shutdown() {
+        let path = std::env::temp_dir().join(format!("oxide-{}.sock", Uuid::new_v4().simple()));
+        let (started, on_started) = oneshot::channel::<()>();
+        let started = std::sync::Arc::new(std::sync::Mutex::new(Some(started)));
+        let app = Router::new().route(
+            "/slow",
+            get(move || {
+                if let Some(started) = started.lock().expect("started lock").take() {
+                    let _ = started.send(());
+                }
+                async {
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    "done"
+                }
+            }),
+        );
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = tokio::spawn({
+            let path = path.clone();
+            async move {
+                serve_unix(app, path, async {
+                    let _ = stopped.await;
+                })
+                .await
+            }
+        });
+
+        let mut stream = loop {
+            match UnixStream::connect(&path).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        };
+        stream
+            .write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("request should be written");
+        on_started.await.expect("handler should start");
+        stop.send(()).expect("server should still be running");
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("response should be read");
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("done"), "{response}");
+        server
+            .await
+            .expect("server task should join")
+            .expect("server should stop cleanly");
+    }
+}
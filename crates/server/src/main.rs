@@ -1,4 +1,4 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::sync::Arc;
 
 use anyhow::Context;
 use axum::{Router, middleware};
@@ -8,10 +8,15 @@ use rust_oxide::{
     auth::bootstrap::init_providers,
     config::AppConfig,
     db::connection,
+    jobs::{job_registry, spawn_job_worker},
+    listener,
     logging::init_tracing,
-    realtime::{AppRealtimeVerifier, SocketAppState},
+    realtime::{spawn_outbox_relay, spawn_realtime_runtime},
     routes::{
-        middleware::{catch_panic_layer, json_error_middleware},
+        middleware::{
+            access_log_middleware, body_log_middleware, catch_panic_layer, json_error_middleware,
+            problem_json_middleware,
+        },
         router,
     },
     services::ServiceContext,
@@ -27,7 +32,14 @@ async fn main() {
 }
 
 async fn run() -> anyhow::Result<()> {
-    let cfg = AppConfig::from_env().expect("failed to load config");
+    // Tracing is configured from this config, so report load errors directly.
+    let cfg = match AppConfig::from_env() {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            eprintln!("failed to load config: {err:#}");
+            std::process::exit(1);
+        }
+    };
     init_tracing(&cfg.logging.rust_log);
 
     #[cfg(debug_assertions)]
@@ -42,37 +54,71 @@ async fn run() -> anyhow::Result<()> {
         .as_ref()
         .context("database config missing; set APP_DATABASE__URL")?;
     let auth_cfg = cfg.auth.as_ref().context(
-        "auth config missing; set APP_AUTH__JWT_SECRET, APP_AUTH__ADMIN_EMAIL, APP_AUTH__ADMIN_PASSWORD",
+        "auth config missing; set APP_AUTH__JWT_SECRET, APP_AUTH__ADMIN_EMAIL, APP_AUTH__ADMIN_PASSWORD or APP_AUTH__ENABLED=false",
     )?;
 
-    let db = connection::connect(db_cfg).await?;
+    let connection::DatabaseConnections {
+        primary: db,
+        replica,
+    } = connection::connect(db_cfg).await?;
     let services = ServiceContext::new(&db);
 
     let providers = init_providers(auth_cfg, &services).await?;
-    let realtime = rust_oxide::realtime::SocketServerHandle::spawn(cfg.realtime.clone());
-    let realtime_runtime = Arc::new(SocketAppState::new(
-        realtime.clone(),
-        AppRealtimeVerifier::new(providers.clone()),
-    ));
+    let realtime_runtime = spawn_realtime_runtime(&cfg.realtime, providers.clone());
+    match &realtime_runtime {
+        Some(runtime) => {
+            spawn_outbox_relay(services.outbox_dao(), runtime.handle.clone());
+        }
+        None => tracing::info!("realtime disabled; socket routes are not mounted"),
+    }
+    spawn_job_worker(services.job_dao(), job_registry());
+
+    let realtime_handle = realtime_runtime
+        .as_ref()
+        .map(|runtime| runtime.handle.clone());
 
-    let state = AppState::new(cfg, db, providers);
+    let access_log = cfg.logging.access_log;
+    let error_format = cfg.general.error_format;
+    let body_log = Arc::new(cfg.logging.body_log.clone());
+    let state = AppState::with_replica(cfg, db, replica, providers);
 
     let app = Router::new()
         .merge(router(Arc::clone(&state), realtime_runtime))
         .layer(middleware::from_fn(json_error_middleware))
+        .layer(middleware::from_fn_with_state(
+            body_log,
+            body_log_middleware,
+        ))
         .layer(catch_panic_layer())
-        .layer(TraceLayer::new_for_http());
-
-    let addr: SocketAddr = format!(
-        "{}:{}",
-        state.config.general.host.as_str(),
-        state.config.general.port
-    )
-    .parse()
-    .expect("invalid host/port");
-    tracing::info!("listening on http://{}", addr);
+        .layer(middleware::from_fn_with_state(
+            error_format,
+            problem_json_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            access_log,
+            access_log_middleware,
+        ))
+        // Spans record the path only: the query may carry an access token.
+        .layer(
+            TraceLayer::new_for_http().make_span_with(|req: &axum::extract::Request| {
+                tracing::debug_span!(
+                    "request",
+                    method = %req.method(),
+                    path = %req.uri().path(),
+                    version = ?req.version(),
+                )
+            }),
+        );
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
-    Ok(())
+    listener::serve(app, &state.config.general, async move {
+        listener::shutdown_signal().await;
+        // Drain websockets first: upgraded connections do not hold up the
+        // HTTP listener's own graceful shutdown.
+        if let Some(handle) = realtime_handle
+            && let Err(err) = handle.shutdown().await
+        {
+            tracing::warn!(error = %err, "realtime shutdown failed");
+        }
+    })
+    .await
 }
@@ -1,9 +1,14 @@
+mod outbox_relay;
+mod runtime;
 mod verifier;
 
+pub use outbox_relay::{OUTBOX_POLL_INTERVAL, relay_outbox_once, spawn_outbox_relay};
 pub use realtime::client;
 pub use realtime::protocol;
 pub use realtime::server::{
-    ChannelName, ConnectionId, ConnectionMeta, DisconnectReason, RealtimeConfig, RealtimeError,
-    RealtimeTokenVerifier, SessionAuth, SocketAppState, SocketServerHandle, SubscriptionId,
+    ChannelEvent, ChannelName, ConnectionId, ConnectionMeta, DisconnectReason, RealtimeConfig,
+    RealtimeError, RealtimeTokenVerifier, SessionAuth, SocketAppState, SocketServerHandle,
+    SubscriptionId,
 };
+pub use runtime::spawn_realtime_runtime;
 pub use verifier::AppRealtimeVerifier;
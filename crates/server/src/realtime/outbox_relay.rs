@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use super::SocketServerHandle;
+use crate::db::dao::{DaoResult, OutboxDao};
+
+pub const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const OUTBOX_BATCH_SIZE: u64 = 100;
+
+/// Publishes one batch of pending outbox rows to the realtime hub, oldest first.
+///
+/// Returns how many rows were marked sent. Rows stay pending while realtime is
+/// disabled. Delivery is at-least-once: a crash between publishing and
+/// `mark_sent` republishes the row on the next pass.
+pub async fn relay_outbox_once(dao: &OutboxDao, realtime: &SocketServerHandle) -> DaoResult<usize> {
+    if !realtime.is_enabled() {
+        return Ok(0);
+    }
+
+    let mut sent = 0;
+    for row in dao.pending(OUTBOX_BATCH_SIZE).await? {
+        match realtime
+            .send_event(row.channel.clone(), row.event.clone(), row.payload.clone())
+            .await
+        {
+            Ok(()) => {
+                dao.mark_sent(row.id).await?;
+                sent += 1;
+            }
+            Err(err) => {
+                tracing::warn!(
+                    outbox_id = %row.id,
+                    channel = %row.channel,
+                    error = %err.message(),
+                    "outbox relay failed to publish event"
+                );
+                dao.mark_failed(row.id, err.message()).await?;
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// Polls the outbox every `OUTBOX_POLL_INTERVAL` until the runtime shuts down.
+pub fn spawn_outbox_relay(dao: OutboxDao, realtime: SocketServerHandle) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(OUTBOX_POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = relay_outbox_once(&dao, &realtime).await {
+                tracing::warn!(error = %err, "outbox relay pass failed");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::relay_outbox_once;
+    use crate::{
+        db::{
+            dao::{DaoBase, DaoContext, DaoLayerError, OutboxDao},
+            entities::outbox,
+        },
+        realtime::{RealtimeConfig, SocketServerHandle},
+    };
+
+    async fn sqlite_db() -> DatabaseConnection {
+        // A single connection keeps every query on the same in-memory database.
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = Database::connect(options)
+            .await
+            .expect("sqlite should open");
+        db.get_schema_builder()
+            .register(outbox::Entity)
+            .sync(&db)
+            .await
+            .expect("outbox table should be created");
+        db
+    }
+
+    #[tokio::test]
+    async fn rolled_back_transaction_leaves_no_outbox_row() {
+        let db = sqlite_db().await;
+        let daos = DaoContext::new(&db);
+
+        let err = daos
+            .transaction(|txn| {
+                Box::pin(async move {
+                    OutboxDao::enqueue(txn, "todo:lists", "todo.created", json!({ "id": 1 }))
+                        .await?;
+                    Err::<(), _>(DaoLayerError::NotFound {
+                        entity: "todo_list",
+                        id: Uuid::nil(),
+                    })
+                })
+            })
+            .await
+            .expect_err("transaction should roll back");
+        let pending = daos
+            .outbox()
+            .pending(10)
+            .await
+            .expect("pending should load");
+
+        assert!(matches!(err, DaoLayerError::NotFound { .. }));
+        assert!(pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn committed_outbox_row_is_relayed_and_marked_sent() {
+        let db = sqlite_db().await;
+        let daos = DaoContext::new(&db);
+        let realtime = SocketServerHandle::spawn(RealtimeConfig::default());
+        let mut events = Box::pin(
+            realtime
+                .subscribe("todo:lists")
+                .expect("channel should parse"),
+        );
+
+        let row = daos
+            .transaction(|txn| {
+                Box::pin(async move {
+                    OutboxDao::enqueue(txn, "todo:lists", "todo.created", json!({ "id": 1 })).await
+                })
+            })
+            .await
+            .expect("transaction should commit");
+        let sent = relay_outbox_once(&daos.outbox(), &realtime)
+            .await
+            .expect("relay should succeed");
+        let event = tokio::time::timeout(std::time::Duration::from_secs(1), events.next())
+            .await
+            .expect("event should arrive")
+            .expect("stream should stay open");
+        let stored = daos
+            .outbox()
+            .find_by_id(row.id)
+            .await
+            .expect("row should load");
+
+        assert_eq!(sent, 1);
+        assert_eq!(event.event, "todo.created");
+        assert_eq!(event.payload, json!({ "id": 1 }));
+        assert!(stored.sent_at.is_some());
+        assert!(
+            daos.outbox()
+                .pending(10)
+                .await
+                .expect("pending should load")
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use super::{AppRealtimeVerifier, SocketAppState, SocketServerHandle};
+use crate::{auth::providers::AuthProviders, config::RealtimeConfig};
+
+/// Spawns the realtime hub and wraps it in the state the socket routes need.
+///
+/// Returns `None` when `realtime.enabled` is false: no hub task is spawned and
+/// callers should leave the realtime routes unmounted.
+pub fn spawn_realtime_runtime(
+    cfg: &RealtimeConfig,
+    providers: AuthProviders,
+) -> Option<Arc<SocketAppState>> {
+    if !cfg.enabled {
+        return None;
+    }
+    let handle = SocketServerHandle::spawn(cfg.clone().into());
+    Some(Arc::new(SocketAppState::new(
+        handle,
+        AppRealtimeVerifier::new(providers),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::spawn_realtime_runtime;
+    use crate::{
+        auth::providers::{AuthProviderId, AuthProviders},
+        config::RealtimeConfig,
+    };
+
+    fn providers() -> AuthProviders {
+        AuthProviders::new(AuthProviderId::Local)
+    }
+
+    #[tokio::test]
+    async fn disabled_realtime_spawns_no_hub_task() {
+        let metrics = tokio::runtime::Handle::current().metrics();
+        let before = metrics.num_alive_tasks();
+
+        let disabled = RealtimeConfig {
+            enabled: false,
+            ..RealtimeConfig::default()
+        };
+        assert!(spawn_realtime_runtime(&disabled, providers()).is_none());
+        assert_eq!(metrics.num_alive_tasks(), before);
+
+        let runtime = spawn_realtime_runtime(&RealtimeConfig::default(), providers())
+            .expect("enabled realtime should spawn");
+        assert!(runtime.handle.is_enabled());
+        assert!(metrics.num_alive_tasks() > before);
+    }
+}
@@ -40,12 +40,18 @@ impl realtime::server::RealtimeTokenVerifier for AppRealtimeVerifier {
 
 fn map_app_error(err: AppError) -> realtime::server::RealtimeError {
     match err {
-        AppError::BadRequest(message) | AppError::Conflict(message) => {
+        AppError::BadRequest(message)
+        | AppError::Conflict(message)
+        | AppError::MethodNotAllowed(message)
+        | AppError::UnprocessableEntity(message)
+        | AppError::TooManyRequests(message) => {
             realtime::server::RealtimeError::bad_request(message)
         }
         AppError::Unauthorized(message) => realtime::server::RealtimeError::unauthorized(message),
         AppError::Forbidden(message) => realtime::server::RealtimeError::forbidden(message),
         AppError::NotFound(message) => realtime::server::RealtimeError::not_found(message),
-        AppError::Internal(_) => realtime::server::RealtimeError::internal("internal server error"),
+        AppError::GatewayTimeout(_) | AppError::Internal(_) => {
+            realtime::server::RealtimeError::internal("internal server error")
+        }
     }
 }
@@ -1,16 +1,125 @@
 use std::sync::Arc;
 
-use axum::{Router, routing::get};
+use axum::{
+    Extension, Router,
+    extract::State,
+    http::StatusCode,
+    routing::{get, post, put},
+};
+use uuid::Uuid;
 
 use crate::{
-    routes::{AdminRole, ApiResult, AuthRoleGuard, JsonApiResponse},
+    auth::{AuthEventType, AuthRequestMeta, ImpersonationToken, Role},
+    db::dao::{MAX_INVITE_TTL_DAYS, NewInvite},
+    error::AppError,
+    realtime::SocketAppState,
+    routes::{
+        AdminRole, ApiResult, AuthRolGuardLayer, AuthRoleGuard, CrudApiRouter, JsonApiResponse,
+        Method,
+        extract::{Json, Path},
+        middleware::MaintenanceMode,
+    },
+    services::ServiceContext,
     state::AppState,
 };
 
-pub fn router(state: Arc<AppState>) -> Router {
-    Router::new()
+const AUTH_EVENTS_PATH: &str = "/admin/auth-events";
+
+#[derive(Debug, serde::Serialize)]
+pub struct ImpersonationResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: usize,
+    pub impersonator: String,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct CreateInviteRequest {
+    /// Role granted to the account that redeems the invite; defaults to `user`.
+    pub role: Option<Role>,
+    /// `1..=365`; defaults to 7.
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateRoleRequest {
+    pub role: Role,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserRoleResponse {
+    pub id: Uuid,
+    pub role: String,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct CloseChannelRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct KickRequest {
+    pub user_id: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RealtimeRemovalResponse {
+    /// Connections removed from the channel.
+    pub removed: usize,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct MaintenanceRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MaintenanceResponse {
+    pub enabled: bool,
+    pub retry_after_secs: u64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct InviteResponse {
+    pub token: String,
+    pub role: String,
+    pub expires_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+pub fn router(state: Arc<AppState>, realtime_runtime: Option<Arc<SocketAppState>>) -> Router {
+    let auth_events = ServiceContext::from_state(state.as_ref()).auth_event();
+    let auth_events_router = CrudApiRouter::new(auth_events, AUTH_EVENTS_PATH)
+        .set_allowed_methods(&[Method::List])
+        .set_method_middleware(
+            Method::List,
+            AuthRolGuardLayer::new(state.clone(), Role::Admin),
+        );
+
+    let router = auth_events_router
+        .router()
         .route("/admin/stats", get(admin_stats))
-        .with_state(state)
+        .route("/admin/users/{id}/impersonate", post(impersonate))
+        .route("/admin/users/{id}/role", put(update_role))
+        .route("/admin/invites", post(create_invite))
+        .route(
+            "/admin/maintenance",
+            get(maintenance_status).put(set_maintenance),
+        );
+    let router = match realtime_runtime {
+        Some(runtime) => router
+            .route(
+                "/admin/realtime/channels/{channel}/close",
+                post(close_realtime_channel),
+            )
+            .route(
+                "/admin/realtime/channels/{channel}/kick",
+                post(kick_realtime_user),
+            )
+            .layer(Extension(runtime)),
+        None => router,
+    };
+    router.with_state(state)
 }
 
 async fn admin_stats(
@@ -18,3 +127,158 @@ async fn admin_stats(
 ) -> ApiResult<serde_json::Value> {
     JsonApiResponse::ok(serde_json::json!({ "ok": true, "admin": claims.sub }))
 }
+
+async fn maintenance_status(
+    State(state): State<Arc<AppState>>,
+    _admin: AuthRoleGuard<AdminRole>,
+) -> ApiResult<MaintenanceResponse> {
+    JsonApiResponse::ok((&state.maintenance).into())
+}
+
+/// Flips maintenance mode on this instance without a restart.
+async fn set_maintenance(
+    State(state): State<Arc<AppState>>,
+    AuthRoleGuard { claims, .. }: AuthRoleGuard<AdminRole>,
+    Json(body): Json<MaintenanceRequest>,
+) -> ApiResult<MaintenanceResponse> {
+    state.maintenance.set_enabled(body.enabled);
+    tracing::warn!(admin = %claims.sub, enabled = body.enabled, "maintenance mode changed");
+    JsonApiResponse::ok((&state.maintenance).into())
+}
+
+async fn impersonate(
+    State(state): State<Arc<AppState>>,
+    AuthRoleGuard { claims, .. }: AuthRoleGuard<AdminRole>,
+    meta: AuthRequestMeta,
+    Path(id): Path<Uuid>,
+) -> ApiResult<ImpersonationResponse> {
+    let services = ServiceContext::from_state(state.as_ref());
+    let service = services.audited_auth(&state.auth_providers, meta);
+    let token = service.impersonate(&claims, id).await?;
+    JsonApiResponse::ok(token.into())
+}
+
+/// Changes a user's role and records who changed it. Tokens already issued
+/// keep their old roles until they expire or are refreshed.
+async fn update_role(
+    State(state): State<Arc<AppState>>,
+    AuthRoleGuard { claims, .. }: AuthRoleGuard<AdminRole>,
+    meta: AuthRequestMeta,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateRoleRequest>,
+) -> ApiResult<UserRoleResponse> {
+    let services = ServiceContext::from_state(state.as_ref());
+    let users = services.user();
+    let user = users
+        .find_by_id(&id)
+        .await?
+        .ok_or_else(|| AppError::not_found("User not found"))?;
+    let updated = users.set_role(&user.id, body.role.as_str()).await?;
+    let detail = format!("role {} -> {}", user.role, updated.role);
+    services
+        .auth_audit(meta)
+        .record(
+            AuthEventType::RoleChange,
+            Some(updated.id),
+            Some(&claims.sub),
+            Some(&detail),
+        )
+        .await;
+    JsonApiResponse::ok(UserRoleResponse {
+        id: updated.id,
+        role: updated.role,
+    })
+}
+
+async fn create_invite(
+    State(state): State<Arc<AppState>>,
+    AuthRoleGuard { claims, .. }: AuthRoleGuard<AdminRole>,
+    Json(body): Json<CreateInviteRequest>,
+) -> ApiResult<InviteResponse> {
+    if body
+        .expires_in_days
+        .is_some_and(|days| !(1..=MAX_INVITE_TTL_DAYS).contains(&days))
+    {
+        return Err(AppError::bad_request(format!(
+            "expires_in_days must be between 1 and {MAX_INVITE_TTL_DAYS}"
+        )));
+    }
+    let role = body.role.unwrap_or(Role::User);
+    let invite = ServiceContext::from_state(state.as_ref())
+        .invite_dao()
+        .create_invite(
+            role.as_str(),
+            Uuid::parse_str(&claims.sub).ok(),
+            body.expires_in_days,
+        )
+        .await?;
+    JsonApiResponse::with_status(StatusCode::CREATED, "created", invite.into())
+}
+
+async fn close_realtime_channel(
+    Extension(runtime): Extension<Arc<SocketAppState>>,
+    AuthRoleGuard { claims, .. }: AuthRoleGuard<AdminRole>,
+    Path(channel): Path<String>,
+    Json(body): Json<CloseChannelRequest>,
+) -> ApiResult<RealtimeRemovalResponse> {
+    let reason = body.reason.unwrap_or_else(|| "channel closed".to_string());
+    let removed = runtime
+        .handle
+        .close_channel(channel.as_str(), reason)
+        .await?;
+    tracing::info!(admin = %claims.sub, channel = %channel, removed, "realtime channel closed");
+    JsonApiResponse::ok(RealtimeRemovalResponse { removed })
+}
+
+async fn kick_realtime_user(
+    Extension(runtime): Extension<Arc<SocketAppState>>,
+    AuthRoleGuard { claims, .. }: AuthRoleGuard<AdminRole>,
+    Path(channel): Path<String>,
+    Json(body): Json<KickRequest>,
+) -> ApiResult<RealtimeRemovalResponse> {
+    let reason = body
+        .reason
+        .unwrap_or_else(|| "removed by moderator".to_string());
+    let removed = runtime
+        .handle
+        .kick(channel.as_str(), body.user_id.as_str(), reason)
+        .await?;
+    tracing::info!(
+        admin = %claims.sub,
+        channel = %channel,
+        user_id = %body.user_id,
+        removed,
+        "realtime user kicked"
+    );
+    JsonApiResponse::ok(RealtimeRemovalResponse { removed })
+}
+
+impl From<NewInvite> for InviteResponse {
+    fn from(NewInvite { token, invite }: NewInvite) -> Self {
+        Self {
+            token,
+            role: invite.role,
+            expires_at: invite.expires_at,
+        }
+    }
+}
+
+impl From<&MaintenanceMode> for MaintenanceResponse {
+    fn from(mode: &MaintenanceMode) -> Self {
+        Self {
+            enabled: mode.is_enabled(),
+            retry_after_secs: mode.retry_after_secs(),
+        }
+    }
+}
+
+impl From<ImpersonationToken> for ImpersonationResponse {
+    fn from(token: ImpersonationToken) -> Self {
+        Self {
+            access_token: token.access_token,
+            token_type: token.token_type,
+            expires_in: token.expires_in,
+            impersonator: token.impersonator,
+        }
+    }
+}
@@ -1,11 +1,20 @@
 use std::sync::Arc;
 
-use axum::{Json, Router, extract::State, routing::post};
+use axum::{
+    Router,
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
 use serde::Deserialize;
+use uuid::Uuid;
 
 use crate::{
-    auth::TokenBundle,
-    routes::{ApiResult, JsonApiResponse},
+    auth::{AuthRequestMeta, Claims, Role, TokenBundle},
+    db::entities::user,
+    error::AppError,
+    routes::{ApiResult, AuthGuard, JsonApiResponse, extract::Json},
     services::ServiceContext,
     state::AppState,
 };
@@ -20,6 +29,15 @@ pub struct LoginRequest {
 pub struct RegisterRequest {
     pub email: String,
     pub password: String,
+    /// Required when `APP_AUTH__REGISTRATION_MODE=invite`.
+    #[serde(default)]
+    pub invite_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,6 +45,30 @@ pub struct RefreshRequest {
     pub refresh_token: String,
 }
 
+/// Scope that lets a non-admin caller (e.g. a gateway's service account) use
+/// `POST /auth/introspect`.
+pub const INTROSPECT_SCOPE: &str = "auth:introspect";
+
+#[derive(Debug, Deserialize)]
+pub struct IntrospectRequest {
+    pub token: String,
+}
+
+/// RFC 7662-style introspection result. Inactive tokens carry no other
+/// fields, so callers cannot tell a malformed token from an expired one.
+#[derive(Debug, Default, serde::Serialize)]
+pub struct IntrospectResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<Role>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct TokenResponse {
     pub access_token: String,
@@ -35,44 +77,203 @@ pub struct TokenResponse {
     pub expires_in: usize,
 }
 
+/// Stored profile of the authenticated user. The password hash is never included.
+#[derive(Debug, serde::Serialize)]
+pub struct ProfileResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub roles: Vec<Role>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub last_login_at: Option<DateTimeWithTimeZone>,
+}
+
+/// What the caller may do, for frontends that show or hide UI by permission.
+#[derive(Debug, serde::Serialize)]
+pub struct PermissionsResponse {
+    pub sub: String,
+    /// Roles carried by the token.
+    pub roles: Vec<Role>,
+    /// `roles` plus the roles they imply, e.g. `admin` adds `user`.
+    pub effective_roles: Vec<Role>,
+    pub scopes: Vec<String>,
+}
+
 pub fn router(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/login", post(login))
         .route("/register", post(register))
         .route("/refresh", post(refresh))
+        .route("/logout", post(logout))
+        .route("/auth/me", get(me))
+        .route("/auth/permissions", get(permissions))
+        .route("/auth/password", post(change_password))
+        .route("/auth/introspect", post(introspect))
         .with_state(state)
 }
 
 async fn register(
     State(state): State<Arc<AppState>>,
+    meta: AuthRequestMeta,
     Json(body): Json<RegisterRequest>,
 ) -> ApiResult<TokenResponse> {
     let services = ServiceContext::from_state(state.as_ref());
-    let service = services.auth(&state.auth_providers);
-    let tokens = service.register(&body.email, &body.password).await?;
+    let fingerprint = meta.fingerprint();
+    let service = services
+        .audited_auth(&state.auth_providers, meta)
+        .with_fingerprint(fingerprint);
+    let tokens = service
+        .register(&body.email, &body.password, body.invite_token.as_deref())
+        .await?;
     JsonApiResponse::ok(tokens.into())
 }
 
 async fn login(
     State(state): State<Arc<AppState>>,
+    meta: AuthRequestMeta,
     Json(body): Json<LoginRequest>,
 ) -> ApiResult<TokenResponse> {
     let services = ServiceContext::from_state(state.as_ref());
-    let service = services.auth(&state.auth_providers);
+    let fingerprint = meta.fingerprint();
+    let mut service = services
+        .audited_auth(&state.auth_providers, meta)
+        .with_fingerprint(fingerprint);
+    if let Some(lockout) = &state.login_lockout {
+        service = service.with_lockout(lockout.clone());
+    }
     let tokens = service.login(&body.email, &body.password).await?;
     JsonApiResponse::ok(tokens.into())
 }
 
 async fn refresh(
     State(state): State<Arc<AppState>>,
+    meta: AuthRequestMeta,
     Json(body): Json<RefreshRequest>,
 ) -> ApiResult<TokenResponse> {
     let services = ServiceContext::from_state(state.as_ref());
-    let service = services.auth(&state.auth_providers);
+    let fingerprint = meta.fingerprint();
+    let service = services
+        .audited_auth(&state.auth_providers, meta)
+        .with_fingerprint(fingerprint);
     let tokens = service.refresh(&body.refresh_token).await?;
     JsonApiResponse::ok(tokens.into())
 }
 
+/// Revokes the presented refresh token. Access tokens already issued stay
+/// valid until they expire.
+async fn logout(
+    State(state): State<Arc<AppState>>,
+    meta: AuthRequestMeta,
+    Json(body): Json<RefreshRequest>,
+) -> ApiResult<serde_json::Value> {
+    ServiceContext::from_state(state.as_ref())
+        .audited_auth(&state.auth_providers, meta)
+        .logout(&body.refresh_token)
+        .await?;
+    JsonApiResponse::with_status(StatusCode::OK, "logged out", serde_json::Value::Null)
+}
+
+/// Loads the caller's user row. A valid token whose user has since been
+/// deleted gets `401` so the client signs in again.
+async fn me(State(state): State<Arc<AppState>>, claims: AuthGuard) -> ApiResult<ProfileResponse> {
+    let user_id: Uuid = claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::unauthorized("Invalid token subject"))?;
+    let user = ServiceContext::from_state(state.as_ref())
+        .user()
+        .find_by_id(&user_id)
+        .await?
+        .ok_or_else(|| AppError::unauthorized("User no longer exists"))?;
+    JsonApiResponse::ok(user.into())
+}
+
+/// Roles and scopes of the caller, read from the verified token without a
+/// database lookup, so frontends need not decode the JWT themselves.
+async fn permissions(claims: AuthGuard) -> ApiResult<PermissionsResponse> {
+    JsonApiResponse::ok(claims.into())
+}
+
+/// Sets a new password for the caller. With `APP_AUTH__PASSWORD_HISTORY` set,
+/// recently used passwords are rejected with `400`.
+async fn change_password(
+    State(state): State<Arc<AppState>>,
+    claims: AuthGuard,
+    meta: AuthRequestMeta,
+    Json(body): Json<ChangePasswordRequest>,
+) -> ApiResult<serde_json::Value> {
+    if claims.impersonator().is_some() {
+        return Err(AppError::forbidden(
+            "Impersonation tokens cannot change passwords",
+        ));
+    }
+    let user_id: Uuid = claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::unauthorized("Invalid token subject"))?;
+    ServiceContext::from_state(state.as_ref())
+        .audited_auth(&state.auth_providers, meta)
+        .change_password(user_id, &body.current_password, &body.new_password)
+        .await?;
+    JsonApiResponse::with_status(StatusCode::OK, "password changed", serde_json::Value::Null)
+}
+
+/// Reports whether `token` is currently valid, using the active provider's
+/// `verify`. Open to admins and to tokens carrying `INTROSPECT_SCOPE`.
+async fn introspect(
+    State(state): State<Arc<AppState>>,
+    caller: AuthGuard,
+    Json(body): Json<IntrospectRequest>,
+) -> ApiResult<IntrospectResponse> {
+    if !caller.roles.contains(&Role::Admin) && !caller.has_scope(INTROSPECT_SCOPE) {
+        return Err(AppError::forbidden("Missing required role"));
+    }
+    let response = match state.auth_providers.active()?.verify(&body.token).await {
+        Ok(claims) => claims.into(),
+        Err(err) => {
+            tracing::debug!(error = %err, "introspected token is inactive");
+            IntrospectResponse::default()
+        }
+    };
+    JsonApiResponse::ok(response)
+}
+
+impl From<Claims> for IntrospectResponse {
+    fn from(claims: Claims) -> Self {
+        Self {
+            active: true,
+            sub: Some(claims.sub),
+            exp: Some(claims.exp),
+            roles: Some(claims.roles),
+            scopes: Some(claims.scopes),
+        }
+    }
+}
+
+impl From<Claims> for PermissionsResponse {
+    fn from(claims: Claims) -> Self {
+        Self {
+            effective_roles: Role::effective(&claims.roles),
+            sub: claims.sub,
+            roles: claims.roles,
+            scopes: claims.scopes,
+        }
+    }
+}
+
+impl From<user::Model> for ProfileResponse {
+    fn from(user: user::Model) -> Self {
+        Self {
+            roles: Role::granted_for(&user.role),
+            id: user.id,
+            email: user.email,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            last_login_at: user.last_login_at,
+        }
+    }
+}
+
 impl From<TokenBundle> for TokenResponse {
     fn from(bundle: TokenBundle) -> Self {
         Self {
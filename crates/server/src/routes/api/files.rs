@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router,
+    extract::{DefaultBodyLimit, Multipart, State},
+    http::{HeaderMap, StatusCode},
+    response::Response,
+    routing::{get, post},
+};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    routes::{
+        ApiResult, AuthGuard, JsonApiResponse,
+        download::file_response,
+        extract::Path,
+        middleware::DownloadGuard,
+        upload::{MULTIPART_OVERHEAD_BYTES, store_multipart},
+    },
+    state::AppState,
+    storage::{FileStorage, StoredFile, build_storage},
+};
+
+pub fn router(state: Arc<AppState>) -> Router {
+    let storage = build_storage(&state.config.storage);
+    let body_limit = usize::try_from(state.config.storage.max_upload_bytes)
+        .unwrap_or(usize::MAX)
+        .saturating_add(MULTIPART_OVERHEAD_BYTES);
+
+    Router::new()
+        .route("/files", post(upload))
+        .route("/files/{id}", get(download))
+        .layer(DefaultBodyLimit::max(body_limit))
+        .layer(Extension(storage))
+        .with_state(state)
+}
+
+async fn upload(
+    State(state): State<Arc<AppState>>,
+    Extension(storage): Extension<Arc<dyn FileStorage>>,
+    _claims: AuthGuard,
+    multipart: Multipart,
+) -> ApiResult<Vec<StoredFile>> {
+    let max_bytes = state.config.storage.max_upload_bytes;
+    let stored = store_multipart(storage.as_ref(), multipart, max_bytes).await?;
+    JsonApiResponse::with_status(StatusCode::CREATED, "created", stored)
+}
+
+/// Any authenticated caller that knows the id can download the file. The
+/// token may come from `auth.token.query_param` so plain links work.
+async fn download(
+    Extension(storage): Extension<Arc<dyn FileStorage>>,
+    _claims: DownloadGuard,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let file = storage
+        .metadata(id)
+        .await?
+        .ok_or_else(|| AppError::not_found("File not found"))?;
+    file_response(storage.as_ref(), &file, &headers).await
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router,
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    db::{dao::DaoBase, entities::job},
+    error::AppError,
+    jobs::{JobRegistry, job_registry},
+    routes::{
+        ApiResult, AuthGuard, JsonApiResponse,
+        extract::{Json, Path},
+    },
+    services::ServiceContext,
+    state::AppState,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct SubmitJobRequest {
+    #[serde(rename = "type")]
+    pub job_type: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct JobResponse {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub job_type: String,
+    pub status: String,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub finished_at: Option<DateTimeWithTimeZone>,
+}
+
+pub fn router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/jobs", post(submit_job))
+        .route("/jobs/{id}", get(job_status))
+        .layer(Extension(Arc::new(job_registry())))
+        .with_state(state)
+}
+
+async fn submit_job(
+    State(state): State<Arc<AppState>>,
+    Extension(registry): Extension<Arc<JobRegistry>>,
+    claims: AuthGuard,
+    Json(body): Json<SubmitJobRequest>,
+) -> ApiResult<JobResponse> {
+    if !registry.contains(&body.job_type) {
+        return Err(AppError::bad_request(format!(
+            "Unknown job type `{}`",
+            body.job_type
+        )));
+    }
+    let job = ServiceContext::from_state(state.as_ref())
+        .job_dao()
+        .enqueue(&body.job_type, body.params, claims.sub.parse().ok())
+        .await?;
+    JsonApiResponse::with_status(StatusCode::ACCEPTED, "accepted", job.into())
+}
+
+/// Only the submitter can poll a job; anyone else gets the same 404 as for a
+/// missing id.
+async fn job_status(
+    State(state): State<Arc<AppState>>,
+    claims: AuthGuard,
+    Path(id): Path<Uuid>,
+) -> ApiResult<JobResponse> {
+    let job = ServiceContext::from_state(state.as_ref())
+        .job_dao()
+        .find_by_id(id)
+        .await?;
+    if job.submitted_by != claims.sub.parse().ok() {
+        return Err(AppError::not_found("Job not found"));
+    }
+    JsonApiResponse::ok(job.into())
+}
+
+impl From<job::Model> for JobResponse {
+    fn from(job: job::Model) -> Self {
+        Self {
+            id: job.id,
+            job_type: job.job_type,
+            status: job.status,
+            result: job.result,
+            error: job.error,
+            created_at: job.created_at,
+            finished_at: job.finished_at,
+        }
+    }
+}
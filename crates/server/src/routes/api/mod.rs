@@ -1,5 +1,7 @@
 pub mod admin;
 pub mod auth;
+pub mod files;
+pub mod jobs;
 pub mod protected;
 pub mod public;
 pub mod realtime;
@@ -2,8 +2,14 @@ use std::sync::Arc;
 
 use axum::Router;
 
-use crate::realtime::SocketAppState;
+use realtime::server::RealtimeRouteOptions;
 
-pub fn router(runtime: Arc<SocketAppState>) -> Router {
-    realtime::server::axum::router(runtime)
+use crate::{config::RealtimeConfig, realtime::SocketAppState};
+
+pub fn router(runtime: Arc<SocketAppState>, config: &RealtimeConfig) -> Router {
+    let options = RealtimeRouteOptions {
+        allowed_origins: config.allowed_origins.clone(),
+        ..RealtimeRouteOptions::default()
+    };
+    realtime::server::axum::router_with_options(runtime, options)
 }
@@ -4,14 +4,20 @@ use axum::Router;
 
 use crate::{realtime::SocketAppState, state::AppState};
 
-use super::{admin, auth, protected, public, realtime, todo_crud};
+use super::{admin, auth, files, jobs, protected, public, realtime, todo_crud};
 
-pub fn router(state: Arc<AppState>, realtime_runtime: Arc<SocketAppState>) -> Router {
-    Router::new()
+pub fn router(state: Arc<AppState>, realtime_runtime: Option<Arc<SocketAppState>>) -> Router {
+    let router = Router::new()
         .merge(public::router())
-        .merge(auth::router(state.clone()))
-        .merge(realtime::router(realtime_runtime))
+        .merge(auth::router(state.clone()));
+    let router = match &realtime_runtime {
+        Some(runtime) => router.merge(realtime::router(runtime.clone(), &state.config.realtime)),
+        None => router,
+    };
+    router
         .merge(todo_crud::router(state.clone()))
+        .merge(files::router(state.clone()))
+        .merge(jobs::router(state.clone()))
         .merge(protected::router(state.clone()))
-        .merge(admin::router(state))
+        .merge(admin::router(state, realtime_runtime))
 }
@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use axum::{
-    Json, Router,
-    extract::{Path, State},
+    Router,
+    extract::State,
     http::StatusCode,
     routing::{get, patch, post},
 };
@@ -14,7 +14,10 @@ use crate::{
     auth::Role,
     db::entities::{todo_item, todo_list},
     error::AppError,
-    routes::{ApiResult, AuthGuard, AuthRolGuardLayer, CrudApiRouter, JsonApiResponse, Method},
+    routes::{
+        ApiResult, AuthGuard, AuthRolGuardLayer, CrudApiRouter, JsonApiResponse, Method,
+        extract::{Json, Path},
+    },
     services::{ServiceContext, todo_service},
     state::AppState,
 };
@@ -1,21 +1,28 @@
 use axum::{
-    Json, Router,
+    Router,
     extract::rejection::QueryRejection,
-    extract::{Path, Query},
-    http::StatusCode,
+    extract::{OriginalUri, Query},
+    http::{HeaderMap, HeaderValue, StatusCode, Uri, header},
+    response::{IntoResponse, Response},
     routing::{MethodRouter, delete, get, patch, post},
 };
 use sea_orm::{
-    ActiveModelTrait, ActiveValue, EntityTrait, IdenStatic, Iterable, Order, PrimaryKeyToColumn,
-    Select, TryIntoModel,
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, IdenStatic, Iterable, Order,
+    PrimaryKeyToColumn, QueryFilter, Select, TryIntoModel,
 };
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 use super::base_router::BaseRouter;
 use crate::{
-    db::dao::DaoBase, error::AppError, routes::JsonApiResponse, services::crud_service::CrudService,
+    db::dao::{ColumnOrder, DaoBase, FacetCount, HasCreatedAtColumn, PaginatedResponse},
+    error::AppError,
+    routes::{
+        JsonApiResponse,
+        extract::{Json, Path},
+    },
+    services::crud_service::{BulkItemResult, BulkWrite, CreateStatus, CrudService, ListEnvelope},
 };
 
 pub(crate) type DaoOf<S> = <S as CrudService>::Dao;
@@ -28,10 +35,59 @@ pub(crate) type ColumnOf<S> = <EntityOf<S> as EntityTrait>::Column;
 pub struct ListQuery {
     pub page: Option<u64>,
     pub page_size: Option<u64>,
+    pub sort: Option<String>,
+    /// Archived rows are hidden unless this is `true`.
+    pub include_archived: Option<bool>,
+    /// Comma-separated columns to return per-value row counts for.
+    pub facets: Option<String>,
+    /// `true` skips the rows and returns only the total (and any facets).
+    pub total_only: Option<bool>,
     #[serde(flatten, default)]
     pub filters: HashMap<String, String>,
 }
 
+/// A wrapped list page with the `?facets=` counts next to it.
+#[derive(serde::Serialize)]
+struct FacetedPage<T> {
+    #[serde(flatten)]
+    page: PaginatedResponse<T>,
+    facets: BTreeMap<String, Vec<FacetCount>>,
+}
+
+/// Body of `?total_only=true` list calls.
+#[derive(serde::Serialize)]
+struct ListSummary {
+    total: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    facets: Option<BTreeMap<String, Vec<FacetCount>>>,
+}
+
+/// Body of `POST <base>/bulk`. Update items carry the row `id` next to the
+/// fields to change.
+#[derive(Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BulkRequest {
+    pub create: Vec<Value>,
+    pub update: Vec<Value>,
+    pub delete: Vec<Uuid>,
+}
+
+#[derive(Clone, Default, serde::Deserialize)]
+pub struct BulkQuery {
+    /// `true` commits the items that succeed and reports the rest.
+    pub partial: Option<bool>,
+}
+
+#[derive(serde::Serialize)]
+struct BulkResponse {
+    results: Vec<BulkItemResult>,
+}
+
+#[derive(Clone, Default, serde::Deserialize)]
+pub struct DetailQuery {
+    pub include: Option<String>,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum Method {
     Create,
@@ -39,6 +95,8 @@ pub enum Method {
     Get,
     Patch,
     Delete,
+    /// `POST <base>/bulk`; not in the default set.
+    Bulk,
 }
 
 pub(crate) const DEFAULT_ALLOWED_METHODS: [Method; 5] = [
@@ -51,6 +109,8 @@ pub(crate) const DEFAULT_ALLOWED_METHODS: [Method; 5] = [
 
 const INVALID_PAYLOAD_MESSAGE: &str = "Invalid payload";
 const INVALID_QUERY_MESSAGE: &str = "Invalid query";
+const INVALID_PATCH_MESSAGE: &str = "Invalid patch";
+const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
 
 #[allow(async_fn_in_trait)]
 pub trait BaseApiRouter: BaseRouter
@@ -73,6 +133,11 @@ where
         25
     }
 
+    /// Most items (creates, updates and deletes together) one bulk call may carry.
+    fn bulk_max_items() -> usize {
+        100
+    }
+
     fn list_order() -> Option<(ColumnOf<Self::Service>, Order)> {
         None
     }
@@ -117,6 +182,71 @@ where
         }
     }
 
+    /// Builds the active models for a bulk call; any invalid item rejects the
+    /// whole request before anything is written.
+    fn build_bulk(
+        request: BulkRequest,
+    ) -> Result<BulkWrite<ActiveModelOf<Self::Service>>, AppError> {
+        let items = request.create.len() + request.update.len() + request.delete.len();
+        if items == 0 {
+            return Err(AppError::bad_request(format!(
+                "{INVALID_PAYLOAD_MESSAGE}: bulk request has no items"
+            )));
+        }
+        if items > Self::bulk_max_items() {
+            return Err(AppError::bad_request(format!(
+                "{INVALID_PAYLOAD_MESSAGE}: bulk request has {items} items, the maximum is {}",
+                Self::bulk_max_items()
+            )));
+        }
+
+        let create = request
+            .create
+            .into_iter()
+            .enumerate()
+            .map(|(index, payload)| {
+                Self::build_create(payload).map_err(|err| err.context(format!("create[{index}]")))
+            })
+            .collect::<Result<_, _>>()?;
+        let update = request
+            .update
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut payload)| {
+                let id = payload
+                    .as_object_mut()
+                    .and_then(|fields| fields.remove("id"))
+                    .and_then(|id| serde_json::from_value::<Uuid>(id).ok())
+                    .ok_or_else(|| {
+                        AppError::bad_request(format!(
+                            "{INVALID_PAYLOAD_MESSAGE}: update[{index}] needs a uuid `id`"
+                        ))
+                    })?;
+                let changes = Self::build_update(payload)
+                    .map_err(|err| err.context(format!("update[{index}]")))?;
+                Ok((id, changes))
+            })
+            .collect::<Result<_, AppError>>()?;
+        Ok(BulkWrite {
+            create,
+            update,
+            delete: request.delete,
+        })
+    }
+
+    /// `<collection path>/<primary key>` for a newly created model.
+    fn location_for(uri: &Uri, model: &ModelOf<Self::Service>) -> Option<HeaderValue> {
+        let pk = <EntityOf<Self::Service> as EntityTrait>::PrimaryKey::iter()
+            .next()?
+            .into_column();
+        let id = match serde_json::to_value(model).ok()?.get(pk.as_str())? {
+            Value::String(id) => id.clone(),
+            Value::Number(id) => id.to_string(),
+            _ => return None,
+        };
+        HeaderValue::from_str(&format!("{}/{}", uri.path().trim_end_matches('/'), id)).ok()
+    }
+
     fn apply_router_middleware<S>(&self, router: Router<S>) -> Router<S>
     where
         S: Clone + Send + Sync + 'static,
@@ -150,10 +280,21 @@ where
         if allowed.contains(&Method::Create) {
             let route = post({
                 let service = self.service();
-                move |Json(payload)| async move {
+                move |OriginalUri(uri): OriginalUri, Json(payload)| async move {
                     let active = Self::build_create(payload)?;
                     let model: ModelOf<Self::Service> = service.create(active).await?;
-                    JsonApiResponse::with_status(StatusCode::CREATED, "created", model)
+                    let location = Self::location_for(&uri, &model);
+                    let status = match service.create_status() {
+                        CreateStatus::Created => StatusCode::CREATED,
+                        CreateStatus::Ok => StatusCode::OK,
+                    };
+                    let body = service.to_response_json(&model)?;
+                    let mut response =
+                        JsonApiResponse::with_status(status, "created", body)?.into_response();
+                    if let Some(location) = location {
+                        response.headers_mut().insert(header::LOCATION, location);
+                    }
+                    Ok::<_, AppError>(response)
                 }
             });
             router = router.route(base, self.apply_method_middleware(Method::Create, route));
@@ -162,32 +303,127 @@ where
         if allowed.contains(&Method::List) {
             let route = get({
                 let service = self.service();
-                move |query: Result<Query<ListQuery>, QueryRejection>| async move {
+                move |OriginalUri(uri): OriginalUri,
+                      query: Result<Query<ListQuery>, QueryRejection>| async move {
                     let Query(query) = query.map_err(|err| {
                         AppError::bad_request(format!("{INVALID_QUERY_MESSAGE}: {err}"))
                     })?;
+                    let facets = match query.facets.as_deref() {
+                        Some(raw) => service.parse_facets(raw)?,
+                        None => Vec::new(),
+                    };
+                    let apply = |select| {
+                        hide_deleted(hide_archived(Self::list_apply(&query, select), &query))
+                    };
+
+                    if query.total_only == Some(true) {
+                        let total = service
+                            .count_with_filters(query.filters.clone(), apply)
+                            .await?;
+                        let facets = if facets.is_empty() {
+                            None
+                        } else {
+                            Some(
+                                service
+                                    .facet_counts(&facets, query.filters.clone(), apply)
+                                    .await?,
+                            )
+                        };
+                        let summary = ListSummary { total, facets };
+                        return Ok(JsonApiResponse::ok(summary)?.into_response());
+                    }
+                    if !facets.is_empty() && service.list_envelope() == ListEnvelope::Bare {
+                        return Err(AppError::bad_request(format!(
+                            "{INVALID_QUERY_MESSAGE}: facets need the wrapped list envelope"
+                        )));
+                    }
+
                     let page = query.page.unwrap_or(1);
                     let page_size = query.page_size.unwrap_or_else(Self::list_default_page_size);
+                    let order = match query.sort.as_deref() {
+                        Some(raw) => Some(service.parse_sort(raw)?),
+                        None => Self::list_order().map(ColumnOrder::from),
+                    };
                     let response = service
-                        .find_with_filters(
-                            page,
-                            page_size,
-                            Self::list_order(),
-                            query.filters.clone(),
-                            |select| Self::list_apply(&query, select),
-                        )
-                        .await?;
-                    JsonApiResponse::ok(response)
+                        .find_with_filters(page, page_size, order, query.filters.clone(), apply)
+                        .await?
+                        .try_map(|model| service.to_response_json(&model))?;
+                    match service.list_envelope() {
+                        ListEnvelope::Wrapped => {
+                            let link = pagination_link_header(&uri, &response);
+                            let mut http = if facets.is_empty() {
+                                JsonApiResponse::ok(response)?.into_response()
+                            } else {
+                                let facets = service
+                                    .facet_counts(&facets, query.filters.clone(), apply)
+                                    .await?;
+                                JsonApiResponse::ok(FacetedPage {
+                                    page: response,
+                                    facets,
+                                })?
+                                .into_response()
+                            };
+                            if let Some(link) = link {
+                                let headers = http.headers_mut();
+                                headers.insert(header::LINK, link);
+                                headers.insert(
+                                    header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                                    HeaderValue::from_static("Link"),
+                                );
+                            }
+                            Ok::<_, AppError>(http)
+                        }
+                        ListEnvelope::Bare => {
+                            let total = service
+                                .count_with_filters(query.filters.clone(), apply)
+                                .await?;
+                            Ok(bare_list_response(&uri, response, total))
+                        }
+                    }
                 }
             });
             router = router.route(base, self.apply_method_middleware(Method::List, route));
+
+            let schema_route = get({
+                let service = self.service();
+                move || async move { JsonApiResponse::ok(service.list_schema()) }
+            });
+            router = router.route(
+                &format!("{base}/schema"),
+                self.apply_method_middleware(Method::List, schema_route),
+            );
+        }
+
+        if allowed.contains(&Method::Bulk) {
+            let route = post({
+                let service = self.service();
+                move |query: Result<Query<BulkQuery>, QueryRejection>,
+                      Json(request): Json<BulkRequest>| async move {
+                    let Query(query) = query.map_err(|err| {
+                        AppError::bad_request(format!("{INVALID_QUERY_MESSAGE}: {err}"))
+                    })?;
+                    let write = Self::build_bulk(request)?;
+                    let results = service
+                        .bulk(write, Self::apply_patch, query.partial == Some(true))
+                        .await?;
+                    JsonApiResponse::ok(BulkResponse { results })
+                }
+            });
+            router = router.route(
+                &format!("{base}/bulk"),
+                self.apply_method_middleware(Method::Bulk, route),
+            );
         }
 
         if allowed.contains(&Method::Get) {
             let route = get({
                 let service = self.service();
-                move |Path(id): Path<Uuid>| async move {
-                    let model: ModelOf<Self::Service> = service.find_by_id(id).await?;
+                move |Path(id): Path<Uuid>, query: Result<Query<DetailQuery>, QueryRejection>| async move {
+                    let Query(query) = query.map_err(|err| {
+                        AppError::bad_request(format!("{INVALID_QUERY_MESSAGE}: {err}"))
+                    })?;
+                    let includes = service.parse_includes(query.include.as_deref())?;
+                    let model = service.find_by_id_with_relations(id, &includes).await?;
                     JsonApiResponse::ok(model)
                 }
             });
@@ -197,12 +433,18 @@ where
         if allowed.contains(&Method::Patch) {
             let route = patch({
                 let service = self.service();
-                move |Path(id): Path<Uuid>, Json(payload)| async move {
+                move |Path(id): Path<Uuid>, headers: HeaderMap, Json(payload)| async move {
+                    let payload = if is_json_patch(&headers) {
+                        let current: ModelOf<Self::Service> = service.find_by_id(id).await?;
+                        apply_json_patch(&current, payload)?
+                    } else {
+                        payload
+                    };
                     let patch = Self::build_update(payload)?;
                     let model: ModelOf<Self::Service> = service
                         .update(id, move |active| Self::apply_patch(active, patch))
                         .await?;
-                    JsonApiResponse::ok(model)
+                    JsonApiResponse::ok(service.to_response_json(&model)?)
                 }
             });
             router = router.route(&id_path, self.apply_method_middleware(Method::Patch, route));
@@ -227,6 +469,115 @@ where
     }
 }
 
+/// Drops archived rows from list results unless `?include_archived=true`.
+fn hide_archived<E: HasCreatedAtColumn>(select: Select<E>, query: &ListQuery) -> Select<E> {
+    match E::archived_at_column() {
+        Some(column) if query.include_archived != Some(true) => select.filter(column.is_null()),
+        _ => select,
+    }
+}
+
+/// Drops soft-deleted rows from list results; `restore` brings them back.
+fn hide_deleted<E: HasCreatedAtColumn>(select: Select<E>) -> Select<E> {
+    match E::deleted_at_column() {
+        Some(column) => select.filter(column.is_null()),
+        None => select,
+    }
+}
+
+fn is_json_patch(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case(JSON_PATCH_CONTENT_TYPE))
+}
+
+/// Applies an RFC 6902 patch document to the JSON form of `current`.
+///
+/// The result must only use the model's existing fields and deserialize back
+/// into the model; otherwise the patch is rejected with 422. A failed `test`
+/// op is a 409. Returns the patched model as JSON so removed optional fields
+/// come back as `null`.
+fn apply_json_patch<M>(current: &M, document: Value) -> Result<Value, AppError>
+where
+    M: serde::Serialize + for<'de> serde::Deserialize<'de>,
+{
+    let operations: json_patch::Patch = serde_json::from_value(document)
+        .map_err(|err| AppError::bad_request(format!("{INVALID_PAYLOAD_MESSAGE}: {err}")))?;
+    let original = serde_json::to_value(current)
+        .map_err(|err| AppError::internal_with_source("failed to serialize model", err))?;
+
+    let mut patched = original.clone();
+    json_patch::patch(&mut patched, &operations).map_err(|err| {
+        if matches!(err.kind, json_patch::PatchErrorKind::TestFailed) {
+            AppError::conflict(format!("{INVALID_PATCH_MESSAGE}: {err}"))
+        } else {
+            AppError::unprocessable_entity(format!("{INVALID_PATCH_MESSAGE}: {err}"))
+        }
+    })?;
+
+    if let (Some(before), Some(after)) = (original.as_object(), patched.as_object())
+        && let Some(unknown) = after.keys().find(|key| !before.contains_key(*key))
+    {
+        return Err(AppError::unprocessable_entity(format!(
+            "{INVALID_PATCH_MESSAGE}: unknown field '{unknown}'"
+        )));
+    }
+
+    let model: M = serde_json::from_value(patched)
+        .map_err(|err| AppError::unprocessable_entity(format!("{INVALID_PATCH_MESSAGE}: {err}")))?;
+    serde_json::to_value(model)
+        .map_err(|err| AppError::internal_with_source("failed to serialize model", err))
+}
+
+/// Renders a list page as a bare JSON array with `X-Total-Count` and `Link` headers.
+fn bare_list_response<M: serde::Serialize>(
+    uri: &Uri,
+    response: PaginatedResponse<M>,
+    total: u64,
+) -> Response {
+    let link = pagination_link_header(uri, &response);
+    let mut http = Json(response.data).into_response();
+    let headers = http.headers_mut();
+    headers.insert("x-total-count", HeaderValue::from(total));
+    if let Some(link) = link {
+        headers.insert(header::LINK, link);
+    }
+    headers.insert(
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static("X-Total-Count, Link"),
+    );
+    http
+}
+
+/// RFC 5988 `Link` value with `next`/`prev` page URLs, or `None` on a lone page.
+fn pagination_link_header<M>(uri: &Uri, response: &PaginatedResponse<M>) -> Option<HeaderValue> {
+    let mut links = Vec::new();
+    if response.has_next {
+        links.push(page_link(uri, response.page + 1, "next"));
+    }
+    if response.has_prev {
+        links.push(page_link(uri, response.page - 1, "prev"));
+    }
+    if links.is_empty() {
+        return None;
+    }
+    HeaderValue::from_str(&links.join(", ")).ok()
+}
+
+fn page_link(uri: &Uri, page: u64, rel: &str) -> String {
+    let mut params: Vec<&str> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !pair.starts_with("page="))
+        .collect();
+    let page = format!("page={page}");
+    params.push(&page);
+    format!("<{}?{}>; rel=\"{rel}\"", uri.path(), params.join("&"))
+}
+
 // Blanket implementation so all CRUD routers automatically satisfy BaseRouter.
 impl<T> BaseRouter for T
 where
@@ -249,15 +600,15 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use axum::{
         body::{Body, to_bytes},
         http::{Request, StatusCode},
     };
     use chrono::{FixedOffset, TimeZone};
     use sea_orm::entity::prelude::*;
-    use sea_orm::{
-        ActiveValue, DatabaseBackend, DatabaseConnection, MockDatabase, Order, Select, Set,
-    };
+    use sea_orm::{ActiveValue, DatabaseBackend, DatabaseConnection, MockDatabase, Select, Set};
     use serde_json::json;
     use tower::ServiceExt;
     use uuid::Uuid;
@@ -265,11 +616,11 @@ mod tests {
     use super::{BaseApiRouter, Method};
     use crate::{
         db::dao::{
-            DaoBase, HasCreatedAtColumn, HasIdActiveModel, PaginatedResponse,
-            TimestampedActiveModel,
+            ColumnOrder, DaoBase, FacetCount, HasCreatedAtColumn, HasIdActiveModel,
+            PaginatedResponse, TimestampedActiveModel,
         },
         error::AppError,
-        services::crud_service::CrudService,
+        services::crud_service::{CreateStatus, CrudService, ListEnvelope},
     };
 
     mod test_entity {
@@ -335,6 +686,9 @@ mod tests {
     #[derive(Clone)]
     struct TestCrudService {
         dao: TestDao,
+        envelope: ListEnvelope,
+        create_status: CreateStatus,
+        hidden_columns: &'static [&'static str],
     }
 
     impl TestCrudService {
@@ -342,6 +696,9 @@ mod tests {
             let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
             Self {
                 dao: TestDao::new(&db),
+                envelope: ListEnvelope::Wrapped,
+                create_status: CreateStatus::Created,
+                hidden_columns: &[],
             }
         }
     }
@@ -354,6 +711,18 @@ mod tests {
             &self.dao
         }
 
+        fn list_envelope(&self) -> ListEnvelope {
+            self.envelope
+        }
+
+        fn create_status(&self) -> CreateStatus {
+            self.create_status
+        }
+
+        fn serialize_hidden_columns(&self) -> &'static [&'static str] {
+            self.hidden_columns
+        }
+
         async fn create<T>(&self, data: T) -> Result<test_entity::Model, AppError>
         where
             T: sea_orm::IntoActiveModel<test_entity::ActiveModel> + Send,
@@ -374,7 +743,7 @@ mod tests {
             &self,
             page: u64,
             page_size: u64,
-            _order: Option<(test_entity::Column, Order)>,
+            _order: Option<ColumnOrder<test_entity::Column>>,
             _filters: std::collections::HashMap<String, String>,
             _apply: F,
         ) -> Result<PaginatedResponse<test_entity::Model>, AppError>
@@ -383,14 +752,55 @@ mod tests {
             test_entity::Column: sea_orm::ColumnTrait + Clone,
         {
             Ok(PaginatedResponse {
-                data: vec![],
+                data: vec![model(Uuid::nil(), "listed")],
                 page,
                 page_size,
-                has_next: false,
+                has_next: page < 3,
+                has_prev: page > 1,
                 total: None,
             })
         }
 
+        async fn count_with_filters<F>(
+            &self,
+            _filters: std::collections::HashMap<String, String>,
+            _apply: F,
+        ) -> Result<u64, AppError>
+        where
+            F: FnOnce(Select<test_entity::Entity>) -> Select<test_entity::Entity> + Send,
+            test_entity::Column: sea_orm::ColumnTrait + Copy,
+        {
+            Ok(3)
+        }
+
+        async fn facet_counts<F>(
+            &self,
+            facets: &[test_entity::Column],
+            _filters: std::collections::HashMap<String, String>,
+            _apply: F,
+        ) -> Result<BTreeMap<String, Vec<FacetCount>>, AppError>
+        where
+            F: Fn(Select<test_entity::Entity>) -> Select<test_entity::Entity> + Clone + Send,
+            test_entity::Column: sea_orm::ColumnTrait + Copy,
+        {
+            Ok(facets
+                .iter()
+                .map(|column| {
+                    let counts = vec![
+                        FacetCount {
+                            value: json!("listed"),
+                            count: 2,
+                        },
+                        FacetCount {
+                            value: json!("other"),
+                            count: 1,
+                        },
+                    ];
+                    (column.as_str().to_string(), counts)
+                })
+                .collect())
+        }
+
         async fn update<F>(&self, id: Uuid, apply: F) -> Result<test_entity::Model, AppError>
         where
             F: for<'a> FnOnce(&'a mut test_entity::ActiveModel) + Send,
@@ -412,6 +822,18 @@ mod tests {
         async fn delete(&self, _id: Uuid) -> Result<(), AppError> {
             Ok(())
         }
+
+        fn relations(&self) -> &'static [&'static str] {
+            &["notes"]
+        }
+
+        async fn load_relation(
+            &self,
+            _model: &test_entity::Model,
+            _relation: &str,
+        ) -> Result<serde_json::Value, AppError> {
+            Ok(json!([{ "body": "first note" }]))
+        }
     }
 
     #[derive(Clone)]
@@ -427,6 +849,21 @@ mod tests {
                 allowed_methods: allowed_methods.to_vec(),
             }
         }
+
+        fn with_envelope(mut self, envelope: ListEnvelope) -> Self {
+            self.service.envelope = envelope;
+            self
+        }
+
+        fn with_create_status(mut self, status: CreateStatus) -> Self {
+            self.service.create_status = status;
+            self
+        }
+
+        fn with_hidden_columns(mut self, columns: &'static [&'static str]) -> Self {
+            self.service.hidden_columns = columns;
+            self
+        }
     }
 
     impl BaseApiRouter for TestBaseRouter {
@@ -628,4 +1065,447 @@ mod tests {
             .expect("message should be a string");
         assert!(message.starts_with("Invalid query:"));
     }
+
+    #[tokio::test]
+    async fn list_route_wraps_page_in_envelope_by_default() {
+        let router = TestBaseRouter::new(&[Method::List]).router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/items?page=2")
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        assert!(response.headers().get("x-total-count").is_none());
+        let json = response_json(response).await;
+        assert_eq!(json["status"], 200);
+        assert_eq!(json["data"]["data"][0]["title"], "listed");
+        assert_eq!(json["data"]["has_next"], true);
+        assert_eq!(json["data"]["has_prev"], true);
+    }
+
+    async fn list_json(uri: &str, envelope: ListEnvelope) -> (StatusCode, serde_json::Value) {
+        let router = TestBaseRouter::new(&[Method::List])
+            .with_envelope(envelope)
+            .router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(uri)
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+        let status = response.status();
+        (status, response_json(response).await)
+    }
+
+    #[tokio::test]
+    async fn list_route_returns_facet_counts_next_to_page() {
+        let (status, json) = list_json("/items?facets=title", ListEnvelope::Wrapped).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["data"][0]["title"], "listed");
+        assert_eq!(
+            json["data"]["facets"],
+            json!({ "title": [
+                { "value": "listed", "count": 2 },
+                { "value": "other", "count": 1 },
+            ] })
+        );
+    }
+
+    #[tokio::test]
+    async fn list_route_rejects_unknown_facet_column() {
+        let (status, json) = list_json("/items?facets=title,nope", ListEnvelope::Wrapped).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "Invalid facet: nope");
+    }
+
+    #[tokio::test]
+    async fn list_route_rejects_facets_with_bare_envelope() {
+        let (status, _) = list_json("/items?facets=title", ListEnvelope::Bare).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn list_route_total_only_skips_rows() {
+        let (status, json) =
+            list_json("/items?total_only=true&facets=title", ListEnvelope::Wrapped).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["total"], 3);
+        assert!(json["data"].get("data").is_none());
+        assert_eq!(json["data"]["facets"]["title"][1]["count"], 1);
+    }
+
+    #[tokio::test]
+    async fn list_route_links_next_page_from_middle_wrapped_page() {
+        let router = TestBaseRouter::new(&[Method::List]).router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/items?page=2&page_size=1&sort=-title")
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        let link = response.headers()["link"]
+            .to_str()
+            .expect("link should be ascii");
+        assert!(link.contains("</items?page_size=1&sort=-title&page=3>; rel=\"next\""));
+        assert!(link.contains("</items?page_size=1&sort=-title&page=1>; rel=\"prev\""));
+    }
+
+    #[tokio::test]
+    async fn list_route_reports_no_next_page_on_last_wrapped_page() {
+        let router = TestBaseRouter::new(&[Method::List]).router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/items?page=3")
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        assert_eq!(response.headers()["link"], "</items?page=2>; rel=\"prev\"");
+        let json = response_json(response).await;
+        assert_eq!(json["data"]["has_next"], false);
+        assert_eq!(json["data"]["has_prev"], true);
+    }
+
+    #[tokio::test]
+    async fn list_route_returns_bare_array_with_pagination_headers() {
+        let router = TestBaseRouter::new(&[Method::List])
+            .with_envelope(ListEnvelope::Bare)
+            .router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/items?title=listed&page=2&page_size=1")
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers["x-total-count"], "3");
+        assert_eq!(
+            headers["link"],
+            "</items?title=listed&page_size=1&page=3>; rel=\"next\", \
+             </items?title=listed&page_size=1&page=1>; rel=\"prev\""
+        );
+        assert_eq!(
+            headers["access-control-expose-headers"],
+            "X-Total-Count, Link"
+        );
+        let json = response_json(response).await;
+        assert_eq!(json[0]["title"], "listed");
+        assert_eq!(json.as_array().map(Vec::len), Some(1));
+    }
+
+    #[tokio::test]
+    async fn list_route_links_only_previous_page_on_last_bare_page() {
+        let router = TestBaseRouter::new(&[Method::List])
+            .with_envelope(ListEnvelope::Bare)
+            .router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/items?page=3")
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        assert_eq!(response.headers()["x-total-count"], "3");
+        assert_eq!(response.headers()["link"], "</items?page=2>; rel=\"prev\"");
+    }
+
+    async fn create_item(router: TestBaseRouter) -> axum::response::Response {
+        router
+            .router_for()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/items")
+                    .header("content-type", "application/json")
+                    .body(Body::from(json!({ "title": "new" }).to_string()))
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed")
+    }
+
+    #[tokio::test]
+    async fn create_route_returns_created_with_location() {
+        let response = create_item(TestBaseRouter::new(&[Method::Create])).await;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let location = response.headers()["location"]
+            .to_str()
+            .expect("location should be ascii")
+            .to_string();
+        let json = response_json(response).await;
+        assert_eq!(
+            location,
+            format!("/items/{}", json["data"]["id"].as_str().expect("id"))
+        );
+    }
+
+    #[tokio::test]
+    async fn create_route_honors_ok_create_status() {
+        let response = create_item(
+            TestBaseRouter::new(&[Method::Create]).with_create_status(CreateStatus::Ok),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key("location"));
+    }
+
+    async fn json_patch(body: serde_json::Value) -> axum::response::Response {
+        TestBaseRouter::new(&[Method::Patch])
+            .router_for()
+            .oneshot(
+                Request::builder()
+                    .method("PATCH")
+                    .uri(format!("/items/{}", Uuid::new_v4()))
+                    .header("content-type", "application/json-patch+json")
+                    .body(Body::from(body.to_string()))
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed")
+    }
+
+    #[tokio::test]
+    async fn patch_route_applies_json_patch_replace() {
+        let response = json_patch(json!([
+            { "op": "test", "path": "/title", "value": "found" },
+            { "op": "replace", "path": "/title", "value": "patched" }
+        ]))
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = response_json(response).await;
+        assert_eq!(json["data"]["title"], "patched");
+    }
+
+    #[tokio::test]
+    async fn patch_route_rejects_failing_json_patch_test_op() {
+        let response = json_patch(json!([
+            { "op": "test", "path": "/title", "value": "stale" },
+            { "op": "replace", "path": "/title", "value": "patched" }
+        ]))
+        .await;
+
+        let status = response.status();
+        let json = response_json(response).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(
+            json["message"]
+                .as_str()
+                .is_some_and(|message| message.starts_with("Invalid patch"))
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_route_rejects_json_patch_on_unknown_path() {
+        let response = json_patch(json!([
+            { "op": "add", "path": "/nickname", "value": "x" }
+        ]))
+        .await;
+
+        let status = response.status();
+        let json = response_json(response).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["message"], "Invalid patch: unknown field 'nickname'");
+    }
+
+    async fn get_json(router: TestBaseRouter, uri: &str) -> serde_json::Value {
+        let response = router
+            .router_for()
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(uri)
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+        response_json(response).await
+    }
+
+    #[tokio::test]
+    async fn hidden_columns_never_appear_in_responses() {
+        let router = TestBaseRouter::new(&[Method::Create, Method::Get, Method::List])
+            .with_hidden_columns(&["title"]);
+
+        let item = get_json(router.clone(), &format!("/items/{}", Uuid::new_v4())).await;
+        assert!(item["data"]["id"].is_string());
+        assert!(item["data"].get("title").is_none());
+
+        let page = get_json(router.clone(), "/items").await;
+        assert!(page["data"]["data"][0]["id"].is_string());
+        assert!(page["data"]["data"][0].get("title").is_none());
+
+        let bare = get_json(router.clone().with_envelope(ListEnvelope::Bare), "/items").await;
+        assert!(bare[0].get("title").is_none());
+
+        let created = response_json(create_item(router).await).await;
+        assert!(created["data"]["id"].is_string());
+        assert!(created["data"].get("title").is_none());
+    }
+
+    #[tokio::test]
+    async fn get_route_omits_relations_without_include() {
+        let router = TestBaseRouter::new(&[Method::Get]).router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/items/{}", Uuid::new_v4()))
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        let json = response_json(response).await;
+        assert_eq!(json["data"]["title"], "found");
+        assert!(json["data"].get("notes").is_none());
+    }
+
+    #[tokio::test]
+    async fn get_route_embeds_declared_include() {
+        let router = TestBaseRouter::new(&[Method::Get]).router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/items/{}?include=notes", Uuid::new_v4()))
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        let json = response_json(response).await;
+        assert_eq!(json["data"]["notes"][0]["body"], "first note");
+    }
+
+    #[tokio::test]
+    async fn get_route_rejects_unknown_include() {
+        let router = TestBaseRouter::new(&[Method::Get]).router_for();
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/items/{}?include=owner", Uuid::new_v4()))
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        let status = response.status();
+        let json = response_json(response).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "Invalid include: owner");
+    }
+
+    mod archivable_entity {
+        use base_entity_derive::base_entity;
+        use sea_orm::entity::prelude::*;
+
+        #[base_entity(archived_at = "archived_at")]
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "archivable_items")]
+        pub struct Model {
+            pub title: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    mod soft_deletable_entity {
+        use base_entity_derive::base_entity;
+        use sea_orm::entity::prelude::*;
+
+        #[base_entity(deleted_at = "deleted_at")]
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+        #[sea_orm(table_name = "soft_deletable_items")]
+        pub struct Model {
+            pub title: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    fn list_query(uri: &str) -> super::ListQuery {
+        let uri: axum::http::Uri = uri.parse().expect("uri should parse");
+        axum::extract::Query::<super::ListQuery>::try_from_uri(&uri)
+            .expect("query should parse")
+            .0
+    }
+
+    fn list_sql<E: EntityTrait + HasCreatedAtColumn>(query: &super::ListQuery) -> String {
+        use sea_orm::QueryTrait;
+
+        super::hide_deleted(super::hide_archived(E::find(), query))
+            .build(DatabaseBackend::Postgres)
+            .to_string()
+    }
+
+    #[test]
+    fn list_hides_archived_rows_unless_include_archived_is_set() {
+        let default = list_query("/items?title=report");
+        assert_eq!(default.include_archived, None);
+        assert!(!default.filters.contains_key("include_archived"));
+        assert!(
+            list_sql::<archivable_entity::Entity>(&default).contains("\"archived_at\" IS NULL")
+        );
+
+        let opted_in = list_query("/items?include_archived=true");
+        assert_eq!(opted_in.include_archived, Some(true));
+        assert!(opted_in.filters.is_empty());
+        assert!(!list_sql::<archivable_entity::Entity>(&opted_in).contains("WHERE"));
+
+        assert!(!list_sql::<test_entity::Entity>(&default).contains("WHERE"));
+    }
+
+    #[test]
+    fn list_always_hides_soft_deleted_rows() {
+        for uri in ["/items", "/items?include_archived=true"] {
+            let sql = list_sql::<soft_deletable_entity::Entity>(&list_query(uri));
+            assert!(sql.contains("\"deleted_at\" IS NULL"), "{uri}: {sql}");
+        }
+    }
 }
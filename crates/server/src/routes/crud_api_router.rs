@@ -722,4 +722,232 @@ mod tests {
 
         assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
     }
+
+    #[tokio::test]
+    async fn schema_route_lists_columns_with_capabilities() {
+        let response = app_with_denied_title_filter()
+            .oneshot(empty_request("GET", "/items/schema"))
+            .await
+            .expect("request should succeed");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should read");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("body should be JSON");
+
+        let columns = json["data"].as_array().expect("data should be an array");
+        let names: Vec<&str> = columns
+            .iter()
+            .map(|column| column["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            ["id", "created_at", "updated_at", "title", "score", "done"]
+        );
+        let column = |name: &str| {
+            columns
+                .iter()
+                .find(|column| column["name"] == name)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            column("title"),
+            serde_json::json!({
+                "name": "title", "type": "string", "nullable": false,
+                "filterable": false, "sortable": false, "searchable": false
+            })
+        );
+        assert_eq!(
+            column("score"),
+            serde_json::json!({
+                "name": "score", "type": "integer", "nullable": false,
+                "filterable": true, "sortable": true, "searchable": false
+            })
+        );
+        assert_eq!(column("done")["type"], "boolean");
+        assert_eq!(column("id")["type"], "uuid");
+        assert_eq!(column("created_at")["type"], "datetime");
+    }
+
+    #[tokio::test]
+    async fn schema_route_marks_string_columns_searchable() {
+        let response = app()
+            .oneshot(empty_request("GET", "/items/schema"))
+            .await
+            .expect("request should succeed");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should read");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("body should be JSON");
+
+        let title = json["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|column| column["name"] == "title")
+            .unwrap();
+        assert_eq!(title["filterable"], true);
+        assert_eq!(title["sortable"], true);
+        assert_eq!(title["searchable"], true);
+    }
+
+    async fn bulk_app() -> (Router, crate::db::dao::TodoDao) {
+        // A single connection keeps every query on the same in-memory database.
+        let mut options = sea_orm::ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = sea_orm::Database::connect(options)
+            .await
+            .expect("sqlite should open");
+        db.get_schema_builder()
+            .register(crate::db::entities::todo_list::Entity)
+            .sync(&db)
+            .await
+            .expect("tables should be created");
+        let dao = crate::db::dao::TodoDao::new(&db);
+        let service = crate::services::todo_service::TodoService::new(dao.clone());
+        let router = CrudApiRouter::new(service, "/lists")
+            .set_allowed_methods(&[Method::Bulk])
+            .router();
+        (router, dao)
+    }
+
+    async fn bulk(
+        router: Router,
+        uri: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = router
+            .oneshot(json_request("POST", uri, &body.to_string()))
+            .await
+            .expect("request should succeed");
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should read");
+        (
+            status,
+            serde_json::from_slice(&body).expect("body should be JSON"),
+        )
+    }
+
+    async fn titles(dao: &crate::db::dao::TodoDao) -> Vec<String> {
+        let mut titles: Vec<String> = dao
+            .find(1, 100, None, |query| query)
+            .await
+            .expect("list should succeed")
+            .data
+            .into_iter()
+            .map(|list| list.title)
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    #[tokio::test]
+    async fn bulk_applies_creates_updates_and_deletes_together() {
+        let (router, dao) = bulk_app().await;
+        let keep = dao
+            .create(crate::db::entities::todo_list::ActiveModel {
+                title: Set("keep".to_string()),
+                ..Default::default()
+            })
+            .await
+            .expect("create should succeed");
+        let drop = dao
+            .create(crate::db::entities::todo_list::ActiveModel {
+                title: Set("drop".to_string()),
+                ..Default::default()
+            })
+            .await
+            .expect("create should succeed");
+
+        let (status, json) = bulk(
+            router,
+            "/lists/bulk",
+            serde_json::json!({
+                "create": [{ "title": "new" }],
+                "update": [{ "id": keep.id, "title": "kept" }],
+                "delete": [drop.id]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let results = json["data"]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result["ok"] == true));
+        assert_eq!(results[0]["op"], "create");
+        assert_eq!(results[0]["data"]["title"], "new");
+        assert_eq!(results[1]["data"]["title"], "kept");
+        assert_eq!(results[2]["data"]["id"], drop.id.to_string());
+        assert_eq!(titles(&dao).await, ["kept", "new"]);
+    }
+
+    #[tokio::test]
+    async fn bulk_rolls_back_every_item_when_one_fails() {
+        let (router, dao) = bulk_app().await;
+        let missing = Uuid::new_v4();
+
+        let (status, json) = bulk(
+            router,
+            "/lists/bulk",
+            serde_json::json!({
+                "create": [{ "title": "first" }, { "title": "second" }],
+                "delete": [missing]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            json["message"],
+            format!("delete[0]: TodoList {missing} not found")
+        );
+        assert!(titles(&dao).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn bulk_partial_mode_commits_successes_and_reports_failures() {
+        let (router, dao) = bulk_app().await;
+        let missing = Uuid::new_v4();
+
+        let (status, json) = bulk(
+            router,
+            "/lists/bulk?partial=true",
+            serde_json::json!({
+                "create": [{ "title": "first" }],
+                "update": [{ "id": missing, "title": "nope" }],
+                "delete": [missing]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let outcomes: Vec<(&str, u64, bool)> = json["data"]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| {
+                (
+                    result["op"].as_str().unwrap(),
+                    result["index"].as_u64().unwrap(),
+                    result["ok"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("create", 0, true),
+                ("update", 0, false),
+                ("delete", 0, false)
+            ]
+        );
+        assert_eq!(
+            json["data"]["results"][1]["error"],
+            format!("TodoList {missing} not found")
+        );
+        assert_eq!(titles(&dao).await, ["first"]);
+    }
 }
@@ -0,0 +1,276 @@
+use std::ops::Range;
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    error::AppError,
+    routes::JsonApiResponse,
+    storage::{FileStorage, StoredFile},
+};
+
+/// Media types shown inline in the browser. Anything else, notably HTML and
+/// SVG that could run script on the API origin, is sent as an attachment.
+const INLINE_CONTENT_TYPES: &[&str] = &[
+    "application/pdf",
+    "audio/mpeg",
+    "image/gif",
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+    "text/plain",
+    "video/mp4",
+];
+
+enum RangeRequest {
+    Full,
+    Partial(Range<u64>),
+    Unsatisfiable,
+}
+
+/// Streams a stored file, honoring a single-range `Range` header.
+///
+/// Answers `206` with `Content-Range` for a satisfiable range and `416` for a
+/// malformed, multi-range or out-of-bounds one. `If-Range` must repeat the
+/// file's `ETag` for the range to apply; any other value, including a date,
+/// gets the full file.
+///
+/// The uploader chooses `content_type`, so responses carry
+/// `X-Content-Type-Options: nosniff` and a `Content-Disposition` that only
+/// allows inline display for `INLINE_CONTENT_TYPES`.
+pub async fn file_response(
+    storage: &dyn FileStorage,
+    file: &StoredFile,
+    headers: &HeaderMap,
+) -> Result<Response, AppError> {
+    let etag = format!("\"{}\"", file.id);
+    let (status, range) = match requested_range(headers, file.size, &etag) {
+        RangeRequest::Full => (StatusCode::OK, 0..file.size),
+        RangeRequest::Partial(range) => (StatusCode::PARTIAL_CONTENT, range),
+        RangeRequest::Unsatisfiable => {
+            // Built here rather than as an `AppError`, which has no 416 variant.
+            let envelope = JsonApiResponse {
+                status: StatusCode::RANGE_NOT_SATISFIABLE.as_u16(),
+                message: "Requested range not satisfiable".to_string(),
+                data: serde_json::Value::Null,
+            };
+            return Ok((
+                [(header::CONTENT_RANGE, format!("bytes */{}", file.size))],
+                envelope,
+            )
+                .into_response());
+        }
+    };
+
+    let body = storage.read(file.id, range.clone()).await?;
+    let mut response = Response::new(Body::from_stream(body));
+    *response.status_mut() = status;
+    let response_headers = response.headers_mut();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(&file.content_type)
+            .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+    );
+    // Download URLs may carry the access token as a query parameter.
+    response_headers.insert(
+        header::REFERRER_POLICY,
+        HeaderValue::from_static("no-referrer"),
+    );
+    response_headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    if let Ok(disposition) = HeaderValue::from_str(&content_disposition(file)) {
+        response_headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    response_headers.insert(
+        header::CONTENT_LENGTH,
+        HeaderValue::from(range.end - range.start),
+    );
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Ok(etag) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, etag);
+    }
+    if status == StatusCode::PARTIAL_CONTENT
+        && let Ok(content_range) = HeaderValue::from_str(&format!(
+            "bytes {}-{}/{}",
+            range.start,
+            range.end - 1,
+            file.size
+        ))
+    {
+        response_headers.insert(header::CONTENT_RANGE, content_range);
+    }
+    Ok(response)
+}
+
+fn content_disposition(file: &StoredFile) -> String {
+    let essence = file
+        .content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let disposition = if INLINE_CONTENT_TYPES.contains(&essence.as_str()) {
+        "inline"
+    } else {
+        "attachment"
+    };
+    let name = file
+        .file_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| file.id.to_string());
+    // `filename` is an ASCII fallback; `filename*` (RFC 6266) keeps the
+    // original name for clients that understand it.
+    let fallback: String = name
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect();
+    let encoded: String = name
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect();
+    format!("{disposition}; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
+
+fn requested_range(headers: &HeaderMap, size: u64, etag: &str) -> RangeRequest {
+    let Some(range) = headers.get(header::RANGE) else {
+        return RangeRequest::Full;
+    };
+    if let Some(if_range) = headers.get(header::IF_RANGE)
+        && if_range.as_bytes() != etag.as_bytes()
+    {
+        return RangeRequest::Full;
+    }
+    match range
+        .to_str()
+        .ok()
+        .and_then(|value| parse_range(value, size))
+    {
+        Some(range) => RangeRequest::Partial(range),
+        None => RangeRequest::Unsatisfiable,
+    }
+}
+
+/// Parses `bytes=start-end`, `bytes=start-` or `bytes=-suffix` into a
+/// half-open range within `size`.
+fn parse_range(value: &str, size: u64) -> Option<Range<u64>> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') || size == 0 {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+    if start.is_empty() {
+        let suffix: u64 = end.parse().ok()?;
+        if suffix == 0 {
+            return None;
+        }
+        return Some(size - suffix.min(size)..size);
+    }
+    let start: u64 = start.parse().ok()?;
+    if start >= size {
+        return None;
+    }
+    let last = if end.is_empty() {
+        size - 1
+    } else {
+        end.parse::<u64>().ok()?.min(size - 1)
+    };
+    (last >= start).then_some(start..last + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, header};
+    use uuid::Uuid;
+
+    use super::{file_response, parse_range};
+    use crate::storage::{FileStorage, FileUpload, LocalFileStorage, StorageError};
+
+    async fn stored_response(content_type: &str, file_name: &str) -> HeaderMap {
+        let root = std::env::temp_dir().join(format!("rust-oxide-download-{}", Uuid::new_v4()));
+        let storage = LocalFileStorage::new(&root);
+        let body = futures_util::stream::iter([Ok::<_, StorageError>(
+            axum::body::Bytes::from_static(b"<script>alert(1)</script>"),
+        )]);
+        let file = storage
+            .store(FileUpload {
+                file_name: Some(file_name.to_string()),
+                content_type: content_type.to_string(),
+                max_bytes: 1024,
+                body: Box::pin(body),
+            })
+            .await
+            .expect("store should succeed");
+        let response = file_response(&storage, &file, &HeaderMap::new())
+            .await
+            .expect("download should succeed");
+        let _ = std::fs::remove_dir_all(&root);
+        response.headers().clone()
+    }
+
+    #[tokio::test]
+    async fn active_content_is_sent_as_an_attachment() {
+        let headers = stored_response("text/html; charset=utf-8", "pwn \"é\".html").await;
+
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(
+            headers[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"pwn ___.html\"; filename*=UTF-8''pwn%20%22%C3%A9%22.html"
+        );
+        let svg = stored_response("image/svg+xml", "logo.svg").await;
+        assert!(
+            svg[header::CONTENT_DISPOSITION]
+                .to_str()
+                .unwrap()
+                .starts_with("attachment;")
+        );
+    }
+
+    #[tokio::test]
+    async fn allowlisted_types_are_shown_inline() {
+        let headers = stored_response("Image/PNG", "photo.png").await;
+
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(headers[header::REFERRER_POLICY], "no-referrer");
+        assert_eq!(
+            headers[header::CONTENT_DISPOSITION],
+            "inline; filename=\"photo.png\"; filename*=UTF-8''photo.png"
+        );
+    }
+
+    #[test]
+    fn parse_range_accepts_single_byte_ranges() {
+        assert_eq!(parse_range("bytes=0-4", 10), Some(0..5));
+        assert_eq!(parse_range("bytes=6-", 10), Some(6..10));
+        assert_eq!(parse_range("bytes=-3", 10), Some(7..10));
+        assert_eq!(parse_range("bytes=-30", 10), Some(0..10));
+        assert_eq!(parse_range("bytes=8-99", 10), Some(8..10));
+    }
+
+    #[test]
+    fn parse_range_rejects_invalid_or_unsatisfiable_ranges() {
+        assert_eq!(parse_range("bytes=10-12", 10), None);
+        assert_eq!(parse_range("bytes=5-2", 10), None);
+        assert_eq!(parse_range("bytes=0-1,4-5", 10), None);
+        assert_eq!(parse_range("bytes=-0", 10), None);
+        assert_eq!(parse_range("items=0-1", 10), None);
+        assert_eq!(parse_range("bytes=0-1", 0), None);
+    }
+}
@@ -1,15 +1,37 @@
 use std::sync::Arc;
 
-use axum::Router;
+use axum::{Router, middleware, routing::get};
 
 use crate::{realtime::SocketAppState, state::AppState};
 
-use super::{api, views};
+use super::{
+    ApiResult, JsonApiResponse, api,
+    middleware::{maintenance_middleware, options_discovery_middleware, rate_limit_middleware},
+    views,
+};
 
 pub const API_PREFIX: &str = "/api/v1";
 
-pub fn router(state: Arc<AppState>, realtime_runtime: Arc<SocketAppState>) -> Router {
-    Router::new()
+/// `realtime_runtime` is `None` when realtime is disabled; its routes are then left out.
+pub fn router(state: Arc<AppState>, realtime_runtime: Option<Arc<SocketAppState>>) -> Router {
+    let maintenance = state.maintenance.clone();
+    let rate_limited = state.rate_limiter.is_some().then(|| state.clone());
+    let router = Router::new()
+        .route("/healthz", get(healthz))
         .nest(API_PREFIX, api::router(state.clone(), realtime_runtime))
         .merge(views::router(state))
+        .layer(middleware::from_fn(options_discovery_middleware));
+    let router = match rate_limited {
+        Some(state) => router.layer(middleware::from_fn_with_state(state, rate_limit_middleware)),
+        None => router,
+    };
+    router.layer(middleware::from_fn_with_state(
+        maintenance,
+        maintenance_middleware,
+    ))
+}
+
+/// Liveness probe; answers even in maintenance mode.
+async fn healthz() -> ApiResult<serde_json::Value> {
+    JsonApiResponse::ok(serde_json::json!({ "status": "ok" }))
 }
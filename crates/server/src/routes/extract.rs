@@ -0,0 +1,271 @@
+//! Drop-in `Path`, `Query` and `Json` extractors whose rejections are JSON
+//! error envelopes rather than axum's plain-text bodies.
+//!
+//! Each rejection carries a specific error kind (`invalid_path_param`,
+//! `invalid_query_param`, `invalid_json_body`), which problem+json responses
+//! report as their `type`. The names match axum's so route docs still see
+//! the usual extractors.
+
+use axum::{
+    extract::{
+        FromRequest, FromRequestParts, Request,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+    },
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::{
+    middleware::app_error_from_status,
+    response::{ErrorKind, error_response, log_app_error},
+};
+use crate::error::AppError;
+
+pub const INVALID_PATH_PARAM: &str = "invalid_path_param";
+pub const INVALID_QUERY_PARAM: &str = "invalid_query_param";
+pub const INVALID_JSON_BODY: &str = "invalid_json_body";
+
+/// `axum::extract::Path` with a JSON [`ExtractRejection`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Path<T>(pub T);
+
+/// `axum::extract::Query` with a JSON [`ExtractRejection`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+/// `axum::Json` with a JSON [`ExtractRejection`]; responds like `axum::Json`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+/// A failed extraction, rendered as the standard error envelope.
+#[derive(Debug)]
+pub struct ExtractRejection {
+    kind: &'static str,
+    error: AppError,
+}
+
+impl ExtractRejection {
+    /// The rejection's status kept, its text prefixed with `label`.
+    fn new(kind: &'static str, label: &str, status: axum::http::StatusCode, text: String) -> Self {
+        Self {
+            kind,
+            error: app_error_from_status(status, format!("{label}: {text}")),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    pub fn error(&self) -> &AppError {
+        &self.error
+    }
+}
+
+impl From<PathRejection> for ExtractRejection {
+    fn from(rejection: PathRejection) -> Self {
+        Self::new(
+            INVALID_PATH_PARAM,
+            "Invalid path parameter",
+            rejection.status(),
+            rejection.body_text(),
+        )
+    }
+}
+
+impl From<QueryRejection> for ExtractRejection {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::new(
+            INVALID_QUERY_PARAM,
+            "Invalid query",
+            rejection.status(),
+            rejection.body_text(),
+        )
+    }
+}
+
+impl From<JsonRejection> for ExtractRejection {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::new(
+            INVALID_JSON_BODY,
+            "Invalid JSON body",
+            rejection.status(),
+            rejection.body_text(),
+        )
+    }
+}
+
+impl IntoResponse for ExtractRejection {
+    fn into_response(self) -> Response {
+        let mut response = error_response(&self.error);
+        log_app_error(&self.error, response.status());
+        response.extensions_mut().insert(ErrorKind(self.kind));
+        response
+    }
+}
+
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Path(value) =
+            axum::extract::Path::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(value) =
+            axum::extract::Query::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ExtractRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::Json(value) = axum::Json::<T>::from_request(req, state).await?;
+        Ok(Self(value))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode, header},
+        routing::{get, post},
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use super::{INVALID_JSON_BODY, INVALID_PATH_PARAM, Json, Path, Query};
+    use crate::routes::response::ErrorKind;
+
+    #[derive(serde::Deserialize)]
+    struct Page {
+        page: u64,
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/items/{id}",
+                get(
+                    |Path(id): Path<Uuid>, Query(query): Query<Page>| async move {
+                        format!("{id}:{}", query.page)
+                    },
+                ),
+            )
+            .route(
+                "/items",
+                post(|Json(body): Json<Value>| async move { Json(body) }),
+            )
+    }
+
+    async fn send(request: Request<Body>) -> (StatusCode, Option<ErrorKind>, Value) {
+        let response = app()
+            .oneshot(request)
+            .await
+            .expect("request should succeed");
+        let status = response.status();
+        let kind = response.extensions().get::<ErrorKind>().copied();
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should read");
+        (
+            status,
+            kind,
+            serde_json::from_slice(&body).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn path_rejection_is_a_json_envelope() {
+        let (status, kind, json) = send(
+            Request::get("/items/not-a-uuid?page=1")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(kind, Some(ErrorKind(INVALID_PATH_PARAM)));
+        assert_eq!(json["status"], 400);
+        assert!(
+            json["message"]
+                .as_str()
+                .is_some_and(|message| message.starts_with("Invalid path parameter: ")),
+            "{json}"
+        );
+    }
+
+    #[tokio::test]
+    async fn query_and_json_rejections_are_json_envelopes() {
+        let id = Uuid::new_v4();
+        let (status, _, json) = send(
+            Request::get(format!("/items/{id}?page=first"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            json["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid query: ")
+        );
+
+        let (status, kind, json) = send(
+            Request::post("/items")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from("{not json"))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(kind, Some(ErrorKind(INVALID_JSON_BODY)));
+        assert!(
+            json["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid JSON body: ")
+        );
+
+        let (status, _, json) = send(
+            Request::post("/items")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"ok":true}"#))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json, json!({"ok": true}));
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, OnceLock},
+    time::Instant,
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{Extensions, HeaderMap, HeaderName, header},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+
+use crate::{auth::Claims, config::AccessLogFormat};
+
+pub const ACCESS_LOG_TARGET: &str = "access_log";
+const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Filled by the auth middleware and guards once a token is verified, so the
+/// outer access log can report who made the request.
+#[derive(Clone, Default)]
+struct AccessLogUser(Arc<OnceLock<String>>);
+
+pub(crate) fn record_access_log_user(extensions: &Extensions, claims: &Claims) {
+    if let Some(user) = extensions.get::<AccessLogUser>() {
+        let _ = user.0.set(claims.sub.clone());
+    }
+}
+
+/// Emits one `access_log` event per request with method, path, status,
+/// latency, request id and user.
+///
+/// Query strings are left out of the path and the referer so tokens passed as
+/// parameters (see `auth.token.query_param`) are not logged.
+pub async fn access_log_middleware(
+    State(format): State<AccessLogFormat>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    if format == AccessLogFormat::Off {
+        return next.run(req).await;
+    }
+
+    let started = Instant::now();
+    let user = AccessLogUser::default();
+    req.extensions_mut().insert(user.clone());
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let version = format!("{:?}", req.version());
+    let remote = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip().to_string());
+    let request_id = header_value(req.headers(), &REQUEST_ID_HEADER);
+    let referer =
+        header_value(req.headers(), &header::REFERER).map(|referer| without_query(&referer));
+    let user_agent = header_value(req.headers(), &header::USER_AGENT);
+
+    let response = next.run(req).await;
+
+    let status = response.status().as_u16();
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+    let request_id = request_id.or_else(|| header_value(response.headers(), &REQUEST_ID_HEADER));
+    let bytes = header_value(response.headers(), &header::CONTENT_LENGTH);
+    let user = user.0.get().cloned();
+
+    let line = match format {
+        AccessLogFormat::Json => serde_json::json!({
+            "ts": Utc::now().to_rfc3339(),
+            "remote": remote,
+            "method": method,
+            "path": path,
+            "status": status,
+            "latency_ms": latency_ms,
+            "bytes": bytes,
+            "request_id": request_id,
+            "user": user,
+            "user_agent": user_agent,
+        })
+        .to_string(),
+        _ => format!(
+            "{} - {} [{}] \"{} {} {}\" {} {} \"{}\" \"{}\" {:.3}ms",
+            remote.as_deref().unwrap_or("-"),
+            user.as_deref().unwrap_or("-"),
+            Utc::now().format("%d/%b/%Y:%H:%M:%S %z"),
+            method,
+            path,
+            version,
+            status,
+            bytes.as_deref().unwrap_or("-"),
+            referer.as_deref().unwrap_or("-"),
+            user_agent.as_deref().unwrap_or("-"),
+            latency_ms,
+        ),
+    };
+
+    tracing::info!(
+        target: ACCESS_LOG_TARGET,
+        method = %method,
+        path = %path,
+        status,
+        latency_ms,
+        request_id = request_id.as_deref().unwrap_or("-"),
+        user = user.as_deref().unwrap_or("-"),
+        "{line}"
+    );
+    response
+}
+
+fn without_query(url: &str) -> String {
+    url.split(['?', '#']).next().unwrap_or_default().to_string()
+}
+
+fn header_value(headers: &HeaderMap, name: &HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{Router, body::Body, http::Request, middleware, routing::get};
+    use tower::ServiceExt;
+    use tracing::{
+        Event, Subscriber,
+        field::{Field, Visit},
+    };
+    use tracing_subscriber::{Layer, layer::Context, prelude::*};
+
+    use super::{ACCESS_LOG_TARGET, access_log_middleware};
+    use crate::config::AccessLogFormat;
+
+    type Captured = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+    struct CaptureLayer(Captured);
+
+    struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for CaptureLayer {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            if event.metadata().target() != ACCESS_LOG_TARGET {
+                return;
+            }
+            let mut fields = HashMap::new();
+            event.record(&mut FieldVisitor(&mut fields));
+            self.0.lock().expect("capture lock").push(fields);
+        }
+    }
+
+    #[tokio::test]
+    async fn access_log_records_status_and_latency() {
+        let captured = Captured::default();
+        let _guard = tracing_subscriber::registry()
+            .with(CaptureLayer(Arc::clone(&captured)))
+            .set_default();
+        let app = Router::new()
+            .route(
+                "/teapot",
+                get(|| async { axum::http::StatusCode::IM_A_TEAPOT }),
+            )
+            .layer(middleware::from_fn_with_state(
+                AccessLogFormat::Json,
+                access_log_middleware,
+            ));
+
+        app.oneshot(
+            Request::builder()
+                .uri("/teapot?token=secret")
+                .header("x-request-id", "req-1")
+                .body(Body::empty())
+                .expect("request should build"),
+        )
+        .await
+        .expect("request should succeed");
+
+        let events = captured.lock().expect("capture lock");
+        let [event] = events.as_slice() else {
+            panic!("expected one access log event, got {events:?}");
+        };
+        assert_eq!(event["status"], "418");
+        assert!(event["latency_ms"].parse::<f64>().is_ok());
+        assert_eq!(event["path"], "/teapot");
+        assert_eq!(event["request_id"], "req-1");
+        assert_eq!(event["user"], "-");
+        assert!(!event["message"].contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn combined_log_drops_the_referer_query() {
+        let captured = Captured::default();
+        let _guard = tracing_subscriber::registry()
+            .with(CaptureLayer(Arc::clone(&captured)))
+            .set_default();
+        let app = Router::new().route("/files", get(|| async { "ok" })).layer(
+            middleware::from_fn_with_state(AccessLogFormat::Combined, access_log_middleware),
+        );
+
+        app.oneshot(
+            Request::builder()
+                .uri("/files?access_token=secret")
+                .header(
+                    "referer",
+                    "https://app.example.com/files/1?access_token=secret",
+                )
+                .body(Body::empty())
+                .expect("request should build"),
+        )
+        .await
+        .expect("request should succeed");
+
+        let events = captured.lock().expect("capture lock");
+        let [event] = events.as_slice() else {
+            panic!("expected one access log event, got {events:?}");
+        };
+        assert!(event["message"].contains("\"https://app.example.com/files/1\""));
+        assert!(!event["message"].contains("secret"));
+    }
+}
@@ -4,17 +4,19 @@ use std::{
 };
 
 use axum::{
+    Router,
     body::Body,
     extract::{Request, State},
-    http::{Request as HttpRequest, header},
-    middleware::Next,
+    http::Request as HttpRequest,
+    middleware::{self, Next},
     response::{IntoResponse, Response},
 };
 use futures_util::future::BoxFuture;
 use tower::{Layer, Service};
 
+use super::{guards::attach_claims, record_access_log_user};
 use crate::{
-    auth::{Claims, Role},
+    auth::{Claims, Role, request_token},
     error::AppError,
     state::AppState,
 };
@@ -24,13 +26,12 @@ pub async fn jwt_auth(
     mut req: Request,
     next: Next,
 ) -> Result<Response, Response> {
-    let auth = req
-        .headers()
-        .get(header::AUTHORIZATION)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
+    if req.extensions().get::<Claims>().is_some() {
+        return Ok(next.run(req).await);
+    }
 
-    let token = auth.strip_prefix("Bearer ").ok_or_else(|| {
+    let sources = state.config.auth.as_ref().map(|auth| &auth.token);
+    let token = request_token(req.headers(), sources).ok_or_else(|| {
         AppError::unauthorized("Missing/invalid Authorization header").into_response()
     })?;
 
@@ -38,15 +39,35 @@ pub async fn jwt_auth(
         .auth_providers
         .active()
         .map_err(IntoResponse::into_response)?
-        .verify(token)
+        .verify(&token)
         .await
         .map_err(IntoResponse::into_response)?;
 
-    req.extensions_mut().insert(claims);
+    let path = req.uri().path().to_string();
+    attach_claims(req.extensions_mut(), &path, &claims);
 
     Ok(next.run(req).await)
 }
 
+/// Puts every route of a group behind `jwt_auth` and a role check.
+///
+/// Call it after the group's routes are added: the layers are route layers,
+/// so they only wrap existing routes and unknown paths still fall through as
+/// `404` instead of `401`.
+pub trait ProtectedRouter {
+    fn protected(self, state: &Arc<AppState>, role: Role) -> Self;
+}
+
+impl<S> ProtectedRouter for Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn protected(self, state: &Arc<AppState>, role: Role) -> Self {
+        self.route_layer(AuthRolGuardLayer::new(Arc::clone(state), role))
+            .route_layer(middleware::from_fn_with_state(Arc::clone(state), jwt_auth))
+    }
+}
+
 #[derive(Clone)]
 pub struct AuthRolGuardLayer {
     required: Role,
@@ -102,13 +123,8 @@ where
             let claims = if let Some(claims) = req.extensions().get::<Claims>() {
                 claims.clone()
             } else {
-                let auth = req
-                    .headers()
-                    .get(header::AUTHORIZATION)
-                    .and_then(|v| v.to_str().ok())
-                    .unwrap_or("");
-
-                let token = match auth.strip_prefix("Bearer ") {
+                let sources = state.config.auth.as_ref().map(|auth| &auth.token);
+                let token = match request_token(req.headers(), sources) {
                     Some(token) => token,
                     None => {
                         return Ok(
@@ -119,7 +135,7 @@ where
                 };
 
                 match state.auth_providers.active() {
-                    Ok(provider) => match provider.verify(token).await {
+                    Ok(provider) => match provider.verify(&token).await {
                         Ok(claims) => claims,
                         Err(err) => return Ok(err.into_response()),
                     },
@@ -127,6 +143,7 @@ where
                 }
             };
 
+            record_access_log_user(req.extensions(), &claims);
             req.extensions_mut().insert(claims.clone());
 
             if !claims.roles.iter().any(|r| r == &required) {
@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, HttpBody, to_bytes},
+    extract::{Request, State},
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+
+use crate::config::BodyLogConfig;
+
+pub const BODY_LOG_TARGET: &str = "body_log";
+const REDACTED: &str = "[REDACTED]";
+
+/// Logs JSON request and response bodies at trace level with the configured
+/// fields masked.
+///
+/// Only bodies whose size is known and within `max_bytes` are buffered; the
+/// buffered bytes are put back so handlers and clients see the same body.
+/// Anything else passes through untouched.
+pub async fn body_log_middleware(
+    State(config): State<Arc<BodyLogConfig>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !config.enabled {
+        return next.run(req).await;
+    }
+
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+
+    let (parts, body) = req.into_parts();
+    let (body, logged) = capture(&config, &parts.headers, body).await;
+    if let Some(logged) = logged {
+        tracing::trace!(
+            target: BODY_LOG_TARGET,
+            direction = "request",
+            method = %method,
+            path = %path,
+            "{logged}"
+        );
+    }
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let (parts, body) = response.into_parts();
+    let (body, logged) = capture(&config, &parts.headers, body).await;
+    if let Some(logged) = logged {
+        tracing::trace!(
+            target: BODY_LOG_TARGET,
+            direction = "response",
+            method = %method,
+            path = %path,
+            status = parts.status.as_u16(),
+            "{logged}"
+        );
+    }
+    Response::from_parts(parts, body)
+}
+
+/// Buffers `body` when it is JSON and small enough, returning a replacement
+/// body plus the redacted text to log.
+async fn capture(
+    config: &BodyLogConfig,
+    headers: &HeaderMap,
+    body: Body,
+) -> (Body, Option<String>) {
+    let fits = body
+        .size_hint()
+        .upper()
+        .is_some_and(|size| size <= config.max_bytes as u64);
+    if !is_json(headers) || !fits {
+        return (body, None);
+    }
+
+    match to_bytes(body, config.max_bytes).await {
+        Ok(bytes) => {
+            let logged = redacted_json(&bytes, &config.redact_fields);
+            (Body::from(bytes), Some(logged))
+        }
+        // The size hint was checked above, so this only fails on a broken stream,
+        // which the handler would have failed on anyway.
+        Err(_) => (Body::empty(), None),
+    }
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            let value = value.to_ascii_lowercase();
+            value.contains("application/json") || value.contains("+json")
+        })
+        .unwrap_or(false)
+}
+
+fn redacted_json(bytes: &[u8], fields: &[String]) -> String {
+    if bytes.is_empty() {
+        return String::new();
+    }
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(mut value) => {
+            redact(&mut value, fields);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes, invalid JSON>", bytes.len()),
+    }
+}
+
+fn redact(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                if fields.iter().any(|field| field.eq_ignore_ascii_case(key)) {
+                    *entry = Value::String(REDACTED.to_string());
+                } else {
+                    redact(entry, fields);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| redact(item, fields)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{
+        Json, Router,
+        body::{Body, to_bytes},
+        http::{Request, header},
+        middleware,
+        routing::post,
+    };
+    use tower::ServiceExt;
+    use tracing::{
+        Event, Subscriber,
+        field::{Field, Visit},
+    };
+    use tracing_subscriber::{Layer, layer::Context, prelude::*};
+
+    use super::{BODY_LOG_TARGET, body_log_middleware};
+    use crate::config::BodyLogConfig;
+
+    type Captured = Arc<Mutex<Vec<String>>>;
+
+    struct CaptureLayer(Captured);
+
+    struct MessageVisitor<'a>(&'a mut String);
+
+    impl Visit for MessageVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{value:?}");
+            }
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for CaptureLayer {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            if event.metadata().target() != BODY_LOG_TARGET {
+                return;
+            }
+            let mut message = String::new();
+            event.record(&mut MessageVisitor(&mut message));
+            self.0.lock().expect("capture lock").push(message);
+        }
+    }
+
+    #[tokio::test]
+    async fn body_log_masks_redacted_fields_and_keeps_body_intact() {
+        let captured = Captured::default();
+        let _guard = tracing_subscriber::registry()
+            .with(CaptureLayer(Arc::clone(&captured)))
+            .set_default();
+        let config = BodyLogConfig {
+            enabled: true,
+            ..BodyLogConfig::default()
+        };
+        let app = Router::new()
+            .route(
+                "/login",
+                post(|Json(body): Json<serde_json::Value>| async move { Json(body) }),
+            )
+            .layer(middleware::from_fn_with_state(
+                Arc::new(config),
+                body_log_middleware,
+            ));
+
+        let payload = r#"{"email":"user@example.com","password":"hunter22"}"#;
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/login")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(payload))
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        let echoed = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should read");
+        let echoed: serde_json::Value = serde_json::from_slice(&echoed).expect("echo is JSON");
+        assert_eq!(echoed["password"], "hunter22");
+
+        let events = captured.lock().expect("capture lock");
+        assert_eq!(events.len(), 2, "expected request and response events");
+        for event in events.iter() {
+            assert!(event.contains("user@example.com"));
+            assert!(event.contains("[REDACTED]"));
+            assert!(!event.contains("hunter22"));
+        }
+    }
+}
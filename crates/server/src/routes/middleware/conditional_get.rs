@@ -0,0 +1,63 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, TimeZone, Utc};
+
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Build time of the binary; rendered views and generated docs only change
+/// when the server is rebuilt.
+pub fn build_last_modified() -> DateTime<Utc> {
+    let secs = env!("RUST_OXIDE_BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .unwrap_or_default();
+    Utc.timestamp_opt(secs, 0).single().unwrap_or_default()
+}
+
+/// Conditional GET for view routes: stamps `Last-Modified` with the build
+/// time and answers `304 Not Modified` when the client's `If-Modified-Since`
+/// is at or after it.
+///
+/// Only successful `GET`/`HEAD` responses are affected, so redirects and
+/// errors pass through untouched. Do not layer this on dynamic API routes.
+pub async fn not_modified_since_build(req: Request, next: Next) -> Response {
+    let cacheable = matches!(*req.method(), Method::GET | Method::HEAD);
+    let if_modified_since = if_modified_since(req.headers());
+
+    let mut response = next.run(req).await;
+    if !cacheable || response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let last_modified = build_last_modified();
+    let last_modified_header = HeaderValue::from_str(&http_date(last_modified))
+        .expect("formatted http date is a valid header value");
+    if if_modified_since.is_some_and(|since| since >= last_modified) {
+        let mut not_modified = Response::new(Body::empty());
+        *not_modified.status_mut() = StatusCode::NOT_MODIFIED;
+        not_modified
+            .headers_mut()
+            .insert(header::LAST_MODIFIED, last_modified_header);
+        return not_modified;
+    }
+
+    response
+        .headers_mut()
+        .insert(header::LAST_MODIFIED, last_modified_header);
+    response
+}
+
+fn if_modified_since(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    let raw = headers.get(header::IF_MODIFIED_SINCE)?.to_str().ok()?;
+    DateTime::parse_from_rfc2822(raw)
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+fn http_date(value: DateTime<Utc>) -> String {
+    value.format(HTTP_DATE_FORMAT).to_string()
+}
@@ -0,0 +1,174 @@
+use axum::{
+    extract::Request,
+    http::{HeaderValue, Method, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::routes::{
+    JsonApiResponse,
+    route_list::{RouteInfo, routes},
+};
+
+/// Order methods are listed in the `Allow` header.
+const METHOD_ORDER: &[&str] = &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+/// What `OPTIONS` reports for one API path, built from the route catalog.
+#[derive(Debug, Serialize)]
+pub struct ResourceOptions {
+    /// Catalog path the request matched, e.g. `/api/v1/todo-crud/{id}`.
+    pub path: &'static str,
+    pub allow: Vec<&'static str>,
+    pub operations: Vec<OperationInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OperationInfo {
+    pub method: &'static str,
+    pub request: &'static str,
+    pub response: &'static str,
+    pub required_headers: &'static str,
+}
+
+/// Answers `OPTIONS` on a catalogued API path with an `Allow` header and the
+/// request/response shapes of each method, taken from the build-time route
+/// docs. CORS preflights and paths outside the catalog pass through.
+pub async fn options_discovery_middleware(req: Request, next: Next) -> Response {
+    if req.method() != Method::OPTIONS
+        || req
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+    {
+        return next.run(req).await;
+    }
+    let Some(options) = describe(req.uri().path(), routes()) else {
+        return next.run(req).await;
+    };
+
+    let allow = options.allow.join(",");
+    let mut response = JsonApiResponse::ok(options).into_response();
+    if let Ok(value) = HeaderValue::from_str(&allow) {
+        response.headers_mut().insert(header::ALLOW, value);
+    }
+    response
+}
+
+/// Describes the API route template that best matches `path`; literal
+/// segments win over parameters, so `/todos/schema` beats `/todos/{id}`.
+pub fn describe(path: &str, catalog: &[RouteInfo]) -> Option<ResourceOptions> {
+    let api_routes = catalog
+        .iter()
+        .filter(|route| route.source.starts_with("src/routes/api/"));
+    let template = api_routes
+        .clone()
+        .filter_map(|route| literal_matches(route.path, path).map(|score| (score, route.path)))
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, template)| template)?;
+
+    let mut operations: Vec<OperationInfo> = api_routes
+        .filter(|route| route.path == template)
+        .map(|route| OperationInfo {
+            method: route.method,
+            request: route.request,
+            response: route.response,
+            required_headers: route.required_headers,
+        })
+        .collect();
+    operations.sort_by_key(|operation| method_rank(operation.method));
+
+    let mut allow: Vec<&'static str> = operations
+        .iter()
+        .map(|operation| operation.method)
+        .collect();
+    if allow.contains(&"GET") {
+        allow.push("HEAD");
+    }
+    allow.push("OPTIONS");
+    allow.sort_by_key(|method| method_rank(method));
+    allow.dedup();
+
+    Some(ResourceOptions {
+        path: template,
+        allow,
+        operations,
+    })
+}
+
+/// Number of literal segments when `template` matches `path`, `None` otherwise.
+fn literal_matches(template: &str, path: &str) -> Option<usize> {
+    let mut template_segments = template.trim_matches('/').split('/');
+    let mut path_segments = path.trim_matches('/').split('/');
+    let mut literals = 0;
+    loop {
+        match (template_segments.next(), path_segments.next()) {
+            (None, None) => return Some(literals),
+            (Some(segment), Some(_)) if segment.starts_with("{*") => return Some(literals),
+            (Some(segment), Some(actual)) if segment.starts_with('{') => {
+                if actual.is_empty() {
+                    return None;
+                }
+            }
+            (Some(segment), Some(actual)) if segment == actual => literals += 1,
+            _ => return None,
+        }
+    }
+}
+
+fn method_rank(method: &str) -> usize {
+    METHOD_ORDER
+        .iter()
+        .position(|known| *known == method)
+        .unwrap_or(METHOD_ORDER.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe, literal_matches};
+    use crate::routes::route_list::RouteInfo;
+
+    fn route(method: &'static str, path: &'static str) -> RouteInfo {
+        RouteInfo {
+            method,
+            path,
+            source: "src/routes/api/todo_crud.rs",
+            request: "JSON",
+            response: "{ \"status\": u16 }",
+            required_headers: "None",
+            curl: "",
+        }
+    }
+
+    #[test]
+    fn literal_segments_beat_parameters() {
+        assert_eq!(
+            literal_matches("/api/todos/schema", "/api/todos/schema"),
+            Some(3)
+        );
+        assert_eq!(
+            literal_matches("/api/todos/{id}", "/api/todos/schema"),
+            Some(2)
+        );
+        assert_eq!(literal_matches("/api/todos/{id}", "/api/todos"), None);
+        assert_eq!(literal_matches("/api/todos", "/api/todos/"), Some(2));
+    }
+
+    #[test]
+    fn describe_picks_the_most_specific_template() {
+        let catalog = [
+            route("GET", "/api/todos/{id}"),
+            route("DELETE", "/api/todos/{id}"),
+            route("GET", "/api/todos/schema"),
+        ];
+
+        let schema = describe("/api/todos/schema", &catalog).expect("schema should match");
+        assert_eq!(schema.path, "/api/todos/schema");
+        assert_eq!(schema.allow, ["GET", "HEAD", "OPTIONS"]);
+
+        let item = describe("/api/todos/42", &catalog).expect("item should match");
+        assert_eq!(item.allow, ["GET", "HEAD", "DELETE", "OPTIONS"]);
+        assert_eq!(item.operations.len(), 2);
+
+        assert!(describe("/api/other", &catalog).is_none());
+    }
+}
@@ -1,9 +1,22 @@
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    convert::Infallible,
+    marker::PhantomData,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 
-use axum::{extract::FromRequestParts, http::header};
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{Extensions, HeaderMap, header},
+};
 
+use super::record_access_log_user;
+use crate::listener::UnixSocketPeer;
 use crate::{
-    auth::{Claims, RequiredRole},
+    auth::{
+        AuthRequestMeta, CLIENT_NONCE_HEADER, Claims, RequiredRole, RequiredScope, download_token,
+        request_token,
+    },
     error::AppError,
     state::AppState,
 };
@@ -20,23 +33,63 @@ impl FromRequestParts<Arc<AppState>> for Claims {
             return Ok(claims);
         }
 
-        let auth = parts
-            .headers
-            .get(header::AUTHORIZATION)
-            .and_then(|value| value.to_str().ok())
-            .unwrap_or("");
+        let sources = state.config.auth.as_ref().map(|auth| &auth.token);
+        let token = request_token(&parts.headers, sources);
+        authenticate(parts, state, token).await
+    }
+}
+
+pub type AuthGuard = Claims;
+
+/// Like `AuthGuard`, but also takes the token from `auth.token.query_param`,
+/// for download links that cannot set headers. Only file downloads use it, so
+/// a token leaked through a URL opens nothing else.
+pub struct DownloadGuard(pub Claims);
 
-        let token = auth
-            .strip_prefix("Bearer ")
-            .ok_or_else(|| AppError::unauthorized("Missing/invalid Authorization header"))?;
+impl FromRequestParts<Arc<AppState>> for DownloadGuard {
+    type Rejection = AppError;
 
-        let claims = state.auth_providers.active()?.verify(token).await?;
-        parts.extensions.insert(claims.clone());
-        Ok(claims)
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(claims) = parts.extensions.get::<Claims>().cloned() {
+            return Ok(Self(claims));
+        }
+
+        let sources = state.config.auth.as_ref().map(|auth| &auth.token);
+        let token = download_token(&parts.headers, &parts.uri, sources);
+        authenticate(parts, state, token).await.map(Self)
     }
 }
 
-pub type AuthGuard = Claims;
+async fn authenticate(
+    parts: &mut axum::http::request::Parts,
+    state: &Arc<AppState>,
+    token: Option<String>,
+) -> Result<Claims, AppError> {
+    let token =
+        token.ok_or_else(|| AppError::unauthorized("Missing/invalid Authorization header"))?;
+
+    let claims = state.auth_providers.active()?.verify(&token).await?;
+    attach_claims(&mut parts.extensions, parts.uri.path(), &claims);
+    Ok(claims)
+}
+
+/// Stores verified `claims` on the request so later guards and `jwt_auth`
+/// reuse them instead of verifying the token again.
+pub(crate) fn attach_claims(extensions: &mut Extensions, path: &str, claims: &Claims) {
+    if let Some(impersonator) = claims.impersonator() {
+        tracing::info!(
+            sub = %claims.sub,
+            impersonator,
+            path = %path,
+            "impersonated request"
+        );
+    }
+    record_access_log_user(extensions, claims);
+    extensions.insert(claims.clone());
+}
 
 pub struct AuthRoleGuard<R: RequiredRole> {
     pub claims: Claims,
@@ -65,3 +118,163 @@ where
         })
     }
 }
+
+/// Like `AuthRoleGuard`, but requires the token to carry `S::required()` in its
+/// `scopes` claim.
+pub struct RequireScope<S: RequiredScope> {
+    pub claims: Claims,
+    _marker: PhantomData<S>,
+}
+
+impl<S> FromRequestParts<Arc<AppState>> for RequireScope<S>
+where
+    S: RequiredScope,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let claims = Claims::from_request_parts(parts, state).await?;
+
+        if !claims.has_scope(S::required()) {
+            return Err(AppError::forbidden("Missing required scope"));
+        }
+
+        Ok(Self {
+            claims,
+            _marker: PhantomData,
+        })
+    }
+}
+
+// Request metadata for auth audit rows.
+impl FromRequestParts<Arc<AppState>> for AuthRequestMeta {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let ip = client_ip(&parts.headers, &parts.extensions, &state.trusted_proxies);
+        let user_agent = header_str(&parts.headers, header::USER_AGENT.as_str());
+        let client_nonce = header_str(&parts.headers, CLIENT_NONCE_HEADER);
+        Ok(AuthRequestMeta::new(ip, user_agent).with_client_nonce(client_nonce))
+    }
+}
+
+/// The client address of a request: the socket peer, or the forwarded client
+/// when the peer is one of `trusted_proxies` or the Unix socket listener.
+/// Anyone can send forwarding headers, so they are ignored on connections that
+/// did not come through a trusted proxy. `None` when the address is unknown.
+pub(crate) fn client_ip(
+    headers: &HeaderMap,
+    extensions: &Extensions,
+    trusted_proxies: &[IpAddr],
+) -> Option<String> {
+    let Some(ConnectInfo(addr)) = extensions.get::<ConnectInfo<SocketAddr>>() else {
+        extensions.get::<ConnectInfo<UnixSocketPeer>>()?;
+        return nearest_untrusted_hop(headers, trusted_proxies)
+            .or_else(|| header_str(headers, "x-real-ip"));
+    };
+    let peer = addr.ip();
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer.to_string());
+    }
+    nearest_untrusted_hop(headers, trusted_proxies)
+        .or_else(|| header_str(headers, "x-real-ip"))
+        .or_else(|| Some(peer.to_string()))
+}
+
+/// The nearest `X-Forwarded-For` hop that is not itself a trusted proxy, so a
+/// client cannot pick its address by prepending entries.
+fn nearest_untrusted_hop(headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> Option<String> {
+    header_str(headers, "x-forwarded-for").and_then(|value| {
+        value
+            .rsplit(',')
+            .map(str::trim)
+            .filter(|hop| !hop.is_empty())
+            .find(|hop| {
+                hop.parse::<IpAddr>()
+                    .map_or(true, |ip| !trusted_proxies.contains(&ip))
+            })
+            .map(str::to_string)
+    })
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, SocketAddr};
+
+    use axum::{
+        extract::ConnectInfo,
+        http::{Extensions, HeaderMap, HeaderValue},
+    };
+
+    use super::client_ip;
+    use crate::listener::UnixSocketPeer;
+
+    fn from_peer(peer: &str) -> Extensions {
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::new(
+            peer.parse().expect("peer should be an IP"),
+            4000,
+        )));
+        extensions
+    }
+
+    fn forwarded(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn forwarded_headers_are_ignored_from_untrusted_peers() {
+        let headers = forwarded("203.0.113.9");
+
+        assert_eq!(
+            client_ip(&headers, &from_peer("198.51.100.7"), &[]).as_deref(),
+            Some("198.51.100.7")
+        );
+    }
+
+    #[test]
+    fn trusted_proxy_yields_the_nearest_untrusted_hop() {
+        let proxy: IpAddr = "10.0.0.2".parse().expect("proxy should be an IP");
+        let headers = forwarded("1.2.3.4, 203.0.113.9, 10.0.0.2");
+
+        assert_eq!(
+            client_ip(&headers, &from_peer("10.0.0.2"), &[proxy]).as_deref(),
+            Some("203.0.113.9")
+        );
+        assert_eq!(
+            client_ip(&HeaderMap::new(), &from_peer("10.0.0.2"), &[proxy]).as_deref(),
+            Some("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn unix_socket_peer_is_a_proxy_and_unknown_peers_have_no_address() {
+        let mut unix = Extensions::new();
+        unix.insert(ConnectInfo(UnixSocketPeer));
+        let headers = forwarded("1.2.3.4, 203.0.113.9");
+
+        assert_eq!(
+            client_ip(&headers, &unix, &[]).as_deref(),
+            Some("203.0.113.9")
+        );
+        assert_eq!(client_ip(&HeaderMap::new(), &unix, &[]), None);
+        assert_eq!(client_ip(&headers, &Extensions::new(), &[]), None);
+    }
+}
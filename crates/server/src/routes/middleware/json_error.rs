@@ -3,15 +3,15 @@ use axum::{
     extract::Request,
     http::{HeaderMap, StatusCode, header},
     middleware::Next,
-    response::{IntoResponse, Response},
+    response::Response,
 };
 
 use crate::{
     error::AppError,
-    routes::{JsonApiResponse, response::log_app_error},
+    routes::response::{error_response, log_app_error},
 };
 
-const MAX_ERROR_BODY_BYTES: usize = 16 * 1024;
+pub(super) const MAX_ERROR_BODY_BYTES: usize = 16 * 1024;
 
 pub async fn json_error_middleware(req: Request, next: Next) -> Response {
     let wants_html = accepts_html(&req);
@@ -36,7 +36,7 @@ pub async fn json_error_middleware(req: Request, next: Next) -> Response {
         log_app_error(&app_error, status);
     }
 
-    let mut new_response = JsonApiResponse::from_error(&app_error).into_response();
+    let mut new_response = error_response(&app_error);
     copy_headers(&parts.headers, &mut new_response);
     new_response
 }
@@ -49,7 +49,7 @@ fn accepts_html(req: &Request) -> bool {
         .unwrap_or(false)
 }
 
-fn is_json_response(response: &Response) -> bool {
+pub(super) fn is_json_response(response: &Response) -> bool {
     response
         .headers()
         .get(header::CONTENT_TYPE)
@@ -85,20 +85,22 @@ fn default_message(status: StatusCode) -> String {
         .to_string()
 }
 
-fn app_error_from_status(status: StatusCode, message: String) -> AppError {
+pub(crate) fn app_error_from_status(status: StatusCode, message: String) -> AppError {
     match status {
         StatusCode::BAD_REQUEST => AppError::bad_request(message),
         StatusCode::UNAUTHORIZED => AppError::unauthorized(message),
         StatusCode::FORBIDDEN => AppError::forbidden(message),
         StatusCode::NOT_FOUND => AppError::not_found(message),
         StatusCode::CONFLICT => AppError::conflict(message),
+        StatusCode::METHOD_NOT_ALLOWED => AppError::method_not_allowed(message),
         StatusCode::UNPROCESSABLE_ENTITY => AppError::bad_request(message),
+        StatusCode::TOO_MANY_REQUESTS => AppError::too_many_requests(message),
         _ if status.is_client_error() => AppError::bad_request(message),
         _ => AppError::internal(message),
     }
 }
 
-fn copy_headers(src: &HeaderMap, dest: &mut Response) {
+pub(super) fn copy_headers(src: &HeaderMap, dest: &mut Response) {
     for (name, value) in src {
         if name == header::CONTENT_TYPE || name == header::CONTENT_LENGTH {
             continue;
@@ -0,0 +1,73 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::routes::{API_PREFIX, JsonApiResponse};
+
+/// Paths still served in maintenance mode: the health check and, under
+/// `API_PREFIX`, the toggle itself so the flag can be cleared.
+const HEALTH_PATH: &str = "/healthz";
+const MAINTENANCE_TOGGLE_PATH: &str = "/admin/maintenance";
+
+/// Runtime maintenance flag shared by every clone; flipping it needs no restart.
+#[derive(Debug, Clone)]
+pub struct MaintenanceMode {
+    enabled: Arc<AtomicBool>,
+    retry_after_secs: u64,
+}
+
+impl MaintenanceMode {
+    pub fn new(enabled: bool, retry_after_secs: u64) -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(enabled)),
+            retry_after_secs,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn retry_after_secs(&self) -> u64 {
+        self.retry_after_secs
+    }
+}
+
+/// Answers `503` with `Retry-After` while maintenance mode is on, except for
+/// the health check and the toggle endpoint.
+pub async fn maintenance_middleware(
+    State(mode): State<MaintenanceMode>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !mode.is_enabled() || is_exempt(req.uri().path()) {
+        return next.run(req).await;
+    }
+
+    let envelope = JsonApiResponse {
+        status: StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+        message: "Service is down for maintenance".to_string(),
+        data: serde_json::Value::Null,
+    };
+    (
+        [(header::RETRY_AFTER, mode.retry_after_secs().to_string())],
+        envelope,
+    )
+        .into_response()
+}
+
+fn is_exempt(path: &str) -> bool {
+    path == HEALTH_PATH || path.strip_prefix(API_PREFIX) == Some(MAINTENANCE_TOGGLE_PATH)
+}
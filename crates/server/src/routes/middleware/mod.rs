@@ -1,10 +1,26 @@
+mod access_log;
 mod auth;
+mod body_log;
+mod conditional_get;
+mod discovery;
 mod guards;
 mod json_error;
+mod maintenance;
 mod panic;
+mod problem_json;
+mod rate_limit;
 
-pub use crate::auth::{AdminRole, RequiredRole, UserRole};
-pub use auth::{AuthRolGuardLayer, jwt_auth};
-pub use guards::{AuthGuard, AuthRoleGuard};
+pub use crate::auth::{AdminRole, RequiredRole, RequiredScope, UserRole};
+pub(crate) use access_log::record_access_log_user;
+pub use access_log::{ACCESS_LOG_TARGET, access_log_middleware};
+pub use auth::{AuthRolGuardLayer, ProtectedRouter, jwt_auth};
+pub use body_log::{BODY_LOG_TARGET, body_log_middleware};
+pub use conditional_get::{build_last_modified, not_modified_since_build};
+pub use discovery::{OperationInfo, ResourceOptions, options_discovery_middleware};
+pub use guards::{AuthGuard, AuthRoleGuard, DownloadGuard, RequireScope};
+pub(crate) use json_error::app_error_from_status;
 pub use json_error::json_error_middleware;
+pub use maintenance::{MaintenanceMode, maintenance_middleware};
 pub use panic::catch_panic_layer;
+pub use problem_json::{PROBLEM_JSON_CONTENT_TYPE, PROBLEM_TYPE_PREFIX, problem_json_middleware};
+pub use rate_limit::{RateLimiter, rate_limit_middleware};
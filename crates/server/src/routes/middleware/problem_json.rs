@@ -0,0 +1,173 @@
+use axum::{
+    Json,
+    body::to_bytes,
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+
+use super::json_error::{
+    MAX_ERROR_BODY_BYTES, app_error_from_status, copy_headers, is_json_response,
+};
+use crate::{
+    config::ErrorFormat,
+    routes::response::{ErrorKind, error_kind},
+};
+
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+/// Prefix of the `type` URI; the error kind (`not_found`, ...) is appended.
+pub const PROBLEM_TYPE_PREFIX: &str = "/problems/";
+
+/// RFC 7807 problem details document.
+#[derive(Debug, Serialize)]
+struct Problem {
+    #[serde(rename = "type")]
+    kind: String,
+    title: &'static str,
+    status: u16,
+    detail: String,
+    instance: String,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    message: String,
+}
+
+/// Rewrites JSON error envelopes into `application/problem+json` documents
+/// when `general.error_format` is `problem`.
+///
+/// Layer it outside `json_error_middleware` so non-JSON errors are normalized
+/// to the envelope first.
+pub async fn problem_json_middleware(
+    State(format): State<ErrorFormat>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if format == ErrorFormat::Envelope {
+        return next.run(req).await;
+    }
+
+    let instance = req.uri().path().to_string();
+    let response = next.run(req).await;
+    let status = response.status();
+    if (!status.is_client_error() && !status.is_server_error()) || !is_json_response(&response) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return app_error_from_status(status, String::new()).into_response(),
+    };
+    let Ok(envelope) = serde_json::from_slice::<Envelope>(&bytes) else {
+        return Response::from_parts(parts, bytes.into());
+    };
+
+    let kind = match parts.extensions.get::<ErrorKind>() {
+        Some(ErrorKind(kind)) => kind,
+        None => error_kind(&app_error_from_status(status, String::new())),
+    };
+    let problem = Problem {
+        kind: format!("{PROBLEM_TYPE_PREFIX}{kind}"),
+        title: status.canonical_reason().unwrap_or("Request failed"),
+        status: status.as_u16(),
+        detail: envelope.message,
+        instance,
+    };
+
+    let mut new_response = (status, Json(problem)).into_response();
+    copy_headers(&parts.headers, &mut new_response);
+    new_response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+    );
+    new_response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode, header},
+        middleware,
+        routing::get,
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::{PROBLEM_JSON_CONTENT_TYPE, problem_json_middleware};
+    use crate::{config::ErrorFormat, error::AppError};
+
+    fn app(format: ErrorFormat) -> Router {
+        Router::new()
+            .route(
+                "/widgets/{id}",
+                get(|| async { Err::<(), _>(AppError::not_found("widget not found")) }),
+            )
+            .route("/ok", get(|| async { "fine" }))
+            .layer(middleware::from_fn_with_state(
+                format,
+                problem_json_middleware,
+            ))
+    }
+
+    async fn send(app: Router, uri: &str) -> (StatusCode, Option<String>, Value) {
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .expect("request should succeed");
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().to_string());
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should read");
+        let json = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        (status, content_type, json)
+    }
+
+    #[tokio::test]
+    async fn problem_format_rewrites_errors_as_rfc7807() {
+        let (status, content_type, json) = send(app(ErrorFormat::Problem), "/widgets/42").await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(content_type.as_deref(), Some(PROBLEM_JSON_CONTENT_TYPE));
+        assert_eq!(json["type"], "/problems/not_found");
+        assert_eq!(json["title"], "Not Found");
+        assert_eq!(json["status"], 404);
+        assert_eq!(json["detail"], "widget not found");
+        assert_eq!(json["instance"], "/widgets/42");
+        assert!(json.get("data").is_none());
+    }
+
+    #[tokio::test]
+    async fn envelope_format_leaves_errors_untouched() {
+        let (status, content_type, json) = send(app(ErrorFormat::Envelope), "/widgets/42").await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert_eq!(json["status"], 404);
+        assert_eq!(json["message"], "widget not found");
+        assert!(json["data"].is_null());
+    }
+
+    #[tokio::test]
+    async fn problem_format_ignores_successful_responses() {
+        let response = app(ErrorFormat::Problem)
+            .oneshot(Request::get("/ok").body(Body::empty()).unwrap())
+            .await
+            .expect("request should succeed");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON_CONTENT_TYPE
+        );
+    }
+}
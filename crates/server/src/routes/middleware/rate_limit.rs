@@ -0,0 +1,173 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::guards::{attach_claims, client_ip};
+use crate::{
+    auth::{Claims, request_token},
+    config::RateLimitConfig,
+    error::AppError,
+    state::AppState,
+    window_counter::FixedWindowCounter,
+};
+
+/// Load balancer probes are never limited.
+const HEALTH_PATH: &str = "/healthz";
+
+/// Fixed-window request counters per client, shared by every clone.
+///
+/// Counts are per process, so each instance behind a load balancer enforces
+/// the limit on its own.
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    counter: Arc<FixedWindowCounter>,
+}
+
+impl RateLimiter {
+    /// `None` when `config.max_requests` is `0`.
+    pub fn from_config(config: &RateLimitConfig) -> Option<Self> {
+        (config.max_requests > 0).then(|| Self {
+            config: Arc::new(config.clone()),
+            counter: Arc::new(FixedWindowCounter::new()),
+        })
+    }
+
+    /// Whether `claims` carry an exempt role or scope.
+    pub fn is_exempt(&self, claims: &Claims) -> bool {
+        claims.roles.iter().any(|role| {
+            self.config
+                .exempt_roles
+                .iter()
+                .any(|exempt| exempt.eq_ignore_ascii_case(role.as_str()))
+        }) || claims
+            .scopes
+            .iter()
+            .any(|scope| self.config.exempt_scopes.contains(scope))
+    }
+
+    /// Counts one request for `key` against `limit`; `Err` holds the time left
+    /// in the window once the limit is spent.
+    fn check(&self, key: &str, limit: u32) -> Result<(), Duration> {
+        let window = Duration::from_secs(self.config.window_secs);
+        self.counter.hit(key, window, limit).map(|_| ())
+    }
+}
+
+/// Limits every request to `general.rate_limit.max_requests` per window,
+/// counted per authenticated user or, without a valid token, per client IP.
+/// The health check and anonymous clients without a known address are not
+/// limited.
+///
+/// Runs before route auth, so it decodes the token itself and leaves the
+/// claims on the request for route auth to reuse. Tokens with an exempt role
+/// or scope get `exempt_max_requests` instead, or no limit when that is `0`.
+/// Over the limit, the response is `429` with `Retry-After`.
+pub async fn rate_limit_middleware(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(limiter) = state
+        .rate_limiter
+        .as_ref()
+        .filter(|_| req.uri().path() != HEALTH_PATH)
+    else {
+        return next.run(req).await;
+    };
+
+    let token = state
+        .config
+        .auth
+        .as_ref()
+        .filter(|auth| auth.enabled)
+        .and_then(|auth| request_token(req.headers(), Some(&auth.token)));
+    let claims = match token {
+        Some(token) => verified_claims(&state, &token).await,
+        None => None,
+    };
+    if let Some(claims) = &claims {
+        let path = req.uri().path().to_string();
+        attach_claims(req.extensions_mut(), &path, claims);
+    }
+    let limit = match &claims {
+        Some(claims) if limiter.is_exempt(claims) => match limiter.config.exempt_max_requests {
+            0 => return next.run(req).await,
+            limit => limit,
+        },
+        _ => limiter.config.max_requests,
+    };
+    let key = match &claims {
+        Some(claims) => format!("user:{}", claims.sub),
+        None => match client_ip(req.headers(), req.extensions(), &state.trusted_proxies) {
+            Some(ip) => format!("ip:{ip}"),
+            // Without an address, e.g. on the Unix socket without forwarding
+            // headers, every such client would share one bucket.
+            None => return next.run(req).await,
+        },
+    };
+
+    if let Err(retry_after) = limiter.check(&key, limit) {
+        let mut response = AppError::too_many_requests("Rate limit exceeded").into_response();
+        let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        if let Ok(value) = HeaderValue::from_str(&secs.max(1).to_string()) {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+        return response;
+    }
+    next.run(req).await
+}
+
+/// Claims of `token` when valid; invalid tokens count as anonymous and are
+/// left for route auth to reject.
+async fn verified_claims(state: &AppState, token: &str) -> Option<Claims> {
+    let provider = state.auth_providers.active().ok()?;
+    provider.verify(token).await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use crate::config::RateLimitConfig;
+
+    #[test]
+    fn limiter_is_off_without_max_requests() {
+        assert!(RateLimiter::from_config(&RateLimitConfig::default()).is_none());
+    }
+
+    #[test]
+    fn check_allows_up_to_the_limit_per_key() {
+        let limiter = RateLimiter::from_config(&RateLimitConfig {
+            max_requests: 2,
+            ..RateLimitConfig::default()
+        })
+        .expect("limiter should be on");
+
+        assert!(limiter.check("ip:1", 2).is_ok());
+        assert!(limiter.check("ip:1", 2).is_ok());
+        let retry_after = limiter.check("ip:1", 2).expect_err("third request is over");
+        assert!(retry_after.as_secs() <= 60);
+        assert!(limiter.check("ip:2", 2).is_ok());
+    }
+
+    #[test]
+    fn lapsed_windows_restart_and_are_pruned_on_the_next_sweep() {
+        let limiter = RateLimiter::from_config(&RateLimitConfig {
+            max_requests: 1,
+            window_secs: 0,
+            ..RateLimitConfig::default()
+        })
+        .expect("limiter should be on");
+
+        assert!(limiter.check("ip:1", 1).is_ok());
+        assert!(limiter.check("ip:1", 1).is_ok());
+        assert!(limiter.check("ip:2", 1).is_ok());
+
+        assert_eq!(limiter.counter.tracked_keys(), 1);
+    }
+}
@@ -49,14 +49,27 @@ impl JsonApiResponse<serde_json::Value> {
     }
 }
 
+/// Kind of the [`AppError`] behind an error response, left in the response
+/// extensions so outer middleware can describe the failure without parsing
+/// the body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ErrorKind(pub(crate) &'static str);
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let status = status_for(&self);
         log_app_error(&self, status);
-        JsonApiResponse::from_error(&self).into_response()
+        error_response(&self)
     }
 }
 
+/// Envelope response for `err`, tagged with its [`ErrorKind`].
+pub(crate) fn error_response(err: &AppError) -> Response {
+    let mut response = JsonApiResponse::from_error(err).into_response();
+    response.extensions_mut().insert(ErrorKind(error_kind(err)));
+    response
+}
+
 impl<T: Serialize> IntoResponse for JsonApiResponse<T> {
     fn into_response(self) -> Response {
         let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
@@ -71,6 +84,10 @@ fn status_for(err: &AppError) -> StatusCode {
         AppError::Forbidden(_) => StatusCode::FORBIDDEN,
         AppError::NotFound(_) => StatusCode::NOT_FOUND,
         AppError::Conflict(_) => StatusCode::CONFLICT,
+        AppError::MethodNotAllowed(_) => StatusCode::METHOD_NOT_ALLOWED,
+        AppError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+        AppError::GatewayTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
         AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
@@ -101,13 +118,17 @@ pub(crate) fn log_app_error(err: &AppError, status: StatusCode) {
     }
 }
 
-fn error_kind(err: &AppError) -> &'static str {
+pub(crate) fn error_kind(err: &AppError) -> &'static str {
     match err {
         AppError::BadRequest(_) => "bad_request",
         AppError::Unauthorized(_) => "unauthorized",
         AppError::Forbidden(_) => "forbidden",
         AppError::NotFound(_) => "not_found",
         AppError::Conflict(_) => "conflict",
+        AppError::MethodNotAllowed(_) => "method_not_allowed",
+        AppError::UnprocessableEntity(_) => "unprocessable_entity",
+        AppError::TooManyRequests(_) => "too_many_requests",
+        AppError::GatewayTimeout(_) => "gateway_timeout",
         AppError::Internal(_) => "internal",
     }
 }
@@ -13,6 +13,15 @@ pub struct RouteInfo {
 
 include!(concat!(env!("OUT_DIR"), "/routes_generated.rs"));
 
+/// Postman v2.1 collection for every catalogued route; empty when the build ran
+/// with `RUST_OXIDE_BUILD_POSTMAN=0`.
+pub static POSTMAN_COLLECTION: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/postman_collection.json"));
+
+/// OpenAPI 3.0 document for the catalogued `src/routes/api` routes, served at
+/// `/openapi.json`.
+pub static OPENAPI_SPEC: &str = include_str!(concat!(env!("OUT_DIR"), "/openapi.json"));
+
 pub fn routes() -> &'static [RouteInfo] {
     ROUTES
 }
@@ -0,0 +1,60 @@
+use axum::extract::Multipart;
+use futures_util::TryStreamExt;
+
+use crate::{
+    error::AppError,
+    storage::{FileStorage, FileUpload, StorageError, StoredFile},
+};
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+// Extra room for multipart boundaries and part headers on top of the file limit.
+pub const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
+/// Streams every file part of a multipart request into `storage`.
+///
+/// Parts without a file name are treated as plain form fields and skipped.
+pub async fn store_multipart(
+    storage: &dyn FileStorage,
+    mut multipart: Multipart,
+    max_bytes: u64,
+) -> Result<Vec<StoredFile>, AppError> {
+    let mut stored = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|err| AppError::bad_request(format!("invalid multipart body: {err}")))?
+    {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let content_type = field
+            .content_type()
+            .unwrap_or(DEFAULT_CONTENT_TYPE)
+            .to_string();
+        let body = field.map_err(|err| StorageError::Upload(err.body_text()));
+
+        let file = storage
+            .store(FileUpload {
+                file_name: Some(file_name),
+                content_type,
+                max_bytes,
+                body: Box::pin(body),
+            })
+            .await;
+        match file {
+            Ok(file) => stored.push(file),
+            Err(err) => {
+                for file in &stored {
+                    let _ = storage.delete(file.id).await;
+                }
+                return Err(err.into());
+            }
+        }
+    }
+
+    if stored.is_empty() {
+        return Err(AppError::bad_request("multipart body contains no files"));
+    }
+    Ok(stored)
+}
@@ -5,12 +5,21 @@ use std::{path::PathBuf, sync::Arc};
 use askama::Template;
 #[cfg(not(debug_assertions))]
 use axum::response::Redirect;
-use axum::{Router, extract::State, http::StatusCode, response::Html, routing::get};
+use axum::{
+    Router,
+    extract::State,
+    http::{StatusCode, header},
+    middleware,
+    response::{Html, IntoResponse},
+    routing::get,
+};
 use chrono::Local;
 use tower_http::services::ServeDir;
 
 #[cfg(debug_assertions)]
 use crate::db::entity_catalog::{self, EntityInfo};
+use crate::routes::middleware::not_modified_since_build;
+use crate::routes::route_list::OPENAPI_SPEC;
 #[cfg(debug_assertions)]
 use crate::routes::route_list::routes;
 use crate::state::AppState;
@@ -84,6 +93,16 @@ struct DocsTemplate {
     show_debug_links: bool,
 }
 
+#[derive(Template)]
+#[template(path = "docs/swagger.html")]
+struct SwaggerTemplate {
+    now: String,
+    project_name: String,
+    spec_url: &'static str,
+    show_docs_link: bool,
+    show_debug_links: bool,
+}
+
 #[derive(Template)]
 #[template(path = "not_available.html")]
 struct NotAvailableTemplate {
@@ -105,13 +124,22 @@ pub fn router(state: Arc<AppState>) -> Router {
         .route("/not-available", get(not_available_view));
 
     #[cfg(debug_assertions)]
-    let router = router.route("/docs", get(docs_view));
+    let router = router
+        .route("/docs", get(docs_view))
+        .route("/docs/swagger", get(swagger_view))
+        .route("/openapi.json", get(openapi_spec));
 
     #[cfg(not(debug_assertions))]
     let router = if docs_enabled {
-        router.route("/docs", get(docs_view))
+        router
+            .route("/docs", get(docs_view))
+            .route("/docs/swagger", get(swagger_view))
+            .route("/openapi.json", get(openapi_spec))
     } else {
-        router.route("/docs", get(not_available_redirect))
+        router
+            .route("/docs", get(not_available_redirect))
+            .route("/docs/swagger", get(not_available_redirect))
+            .route("/openapi.json", get(not_available_redirect))
     };
 
     #[cfg(debug_assertions)]
@@ -124,7 +152,9 @@ pub fn router(state: Arc<AppState>) -> Router {
         .route("/entities", get(not_available_redirect))
         .route("/routes", get(not_available_redirect));
 
+    // Static files get their own Last-Modified from ServeDir.
     router
+        .route_layer(middleware::from_fn(not_modified_since_build))
         .route_service("/{*file}", ServeDir::new(public_dir))
         .with_state(state)
 }
@@ -239,6 +269,31 @@ async fn docs_view(State(state): State<Arc<AppState>>) -> Result<Html<String>, H
     Ok(Html(rendered))
 }
 
+async fn swagger_view(State(state): State<Arc<AppState>>) -> Result<Html<String>, HtmlError> {
+    let now = formatted_build_time();
+    let project_name = project_name();
+    let nav = nav_visibility(state.as_ref());
+    let rendered = SwaggerTemplate {
+        now,
+        project_name,
+        spec_url: "/openapi.json",
+        show_docs_link: nav.show_docs_link,
+        show_debug_links: nav.show_debug_links,
+    }
+    .render()
+    .map_err(|_| {
+        html_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to render swagger ui",
+        )
+    })?;
+    Ok(Html(rendered))
+}
+
+async fn openapi_spec() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI_SPEC)
+}
+
 async fn not_available_view(State(state): State<Arc<AppState>>) -> Result<Html<String>, HtmlError> {
     let now = formatted_build_time();
     let project_name = project_name();
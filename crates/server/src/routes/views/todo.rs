@@ -2,10 +2,12 @@
 use askama::Template;
 #[cfg(not(debug_assertions))]
 use axum::response::Redirect;
-use axum::{Router, routing::get};
+use axum::{Router, middleware, routing::get};
 #[cfg(debug_assertions)]
 use axum::{http::StatusCode, response::Html};
 
+use crate::routes::middleware::not_modified_since_build;
+
 #[cfg(debug_assertions)]
 #[derive(Template)]
 #[template(path = "todo.html")]
@@ -25,7 +27,7 @@ pub fn router() -> Router {
     let router = router.route("/todo/ui", get(todo_ui));
     #[cfg(not(debug_assertions))]
     let router = router.route("/todo/ui", get(todo_ui_unavailable));
-    router
+    router.route_layer(middleware::from_fn(not_modified_since_build))
 }
 
 #[cfg(debug_assertions)]
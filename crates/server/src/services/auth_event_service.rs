@@ -0,0 +1,30 @@
+use crate::{
+    db::{dao::AuthEventDao, entities::auth_event},
+    services::crud_service::{CrudService, FilterMode, FilterParseStrategy},
+};
+
+#[derive(Clone)]
+pub struct AuthEventService {
+    auth_event_dao: AuthEventDao,
+}
+
+impl CrudService for AuthEventService {
+    type Dao = AuthEventDao;
+
+    fn dao(&self) -> &Self::Dao {
+        &self.auth_event_dao
+    }
+
+    fn list_filter_mode(&self) -> FilterMode<auth_event::Column> {
+        FilterMode::AllColumns {
+            deny: &["detail", "updated_at"],
+            parse: FilterParseStrategy::ByColumnType,
+        }
+    }
+}
+
+impl AuthEventService {
+    pub fn new(auth_event_dao: AuthEventDao) -> Self {
+        Self { auth_event_dao }
+    }
+}
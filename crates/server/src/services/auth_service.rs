@@ -1,29 +1,181 @@
+use uuid::Uuid;
+
 use crate::{
-    auth::{Claims, TokenBundle, providers::AuthProviders},
+    auth::{
+        AuthAuditLog, AuthEventType, Claims, ImpersonationToken, TokenBundle,
+        lockout::LoginLockout, providers::AuthProviders,
+    },
     config::AuthConfig,
     error::AppError,
 };
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct AuthService<'a> {
     providers: &'a AuthProviders,
+    audit: Option<AuthAuditLog>,
+    lockout: Option<LoginLockout>,
+    fingerprint: Option<String>,
 }
 
 impl<'a> AuthService<'a> {
     pub fn new(providers: &'a AuthProviders) -> Self {
-        Self { providers }
+        Self {
+            providers,
+            audit: None,
+            lockout: None,
+            fingerprint: None,
+        }
+    }
+
+    /// Records register/login/refresh/logout/impersonation outcomes to the `auth_events` table.
+    pub fn with_audit(mut self, audit: AuthAuditLog) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Refuses logins for emails with too many recent failures, and counts new ones.
+    pub fn with_lockout(mut self, lockout: LoginLockout) -> Self {
+        self.lockout = Some(lockout);
+        self
+    }
+
+    /// Client fingerprint that issued refresh tokens are bound to and that
+    /// refreshes are checked against, when the provider binds tokens.
+    pub fn with_fingerprint(mut self, fingerprint: Option<String>) -> Self {
+        self.fingerprint = fingerprint;
+        self
     }
 
-    pub async fn register(&self, email: &str, password: &str) -> Result<TokenBundle, AppError> {
-        self.providers.active()?.register(email, password).await
+    pub async fn register(
+        &self,
+        email: &str,
+        password: &str,
+        invite_token: Option<&str>,
+    ) -> Result<TokenBundle, AppError> {
+        let result = match self.providers.active() {
+            Ok(provider) => {
+                provider
+                    .register_bound(email, password, invite_token, self.fingerprint.as_deref())
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        self.audit_result(
+            AuthEventType::Register,
+            AuthEventType::RegisterFailed,
+            Some(email),
+            result,
+        )
+        .await
     }
 
     pub async fn login(&self, email: &str, password: &str) -> Result<TokenBundle, AppError> {
-        self.providers.active()?.login(email, password).await
+        let result = self.attempt_login(email, password).await;
+        self.audit_result(
+            AuthEventType::Login,
+            AuthEventType::LoginFailed,
+            Some(email),
+            result,
+        )
+        .await
+    }
+
+    async fn attempt_login(&self, email: &str, password: &str) -> Result<TokenBundle, AppError> {
+        if let Some(lockout) = &self.lockout {
+            lockout.check(email).await?;
+        }
+        let result = match self.providers.active() {
+            Ok(provider) => {
+                provider
+                    .login_bound(email, password, self.fingerprint.as_deref())
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        if let Some(lockout) = &self.lockout {
+            lockout.record(email, &result).await;
+        }
+        result
     }
 
     pub async fn refresh(&self, refresh_token: &str) -> Result<TokenBundle, AppError> {
-        self.providers.active()?.refresh(refresh_token).await
+        let result = match self.providers.active() {
+            Ok(provider) => {
+                provider
+                    .refresh_bound(refresh_token, self.fingerprint.as_deref())
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        // The refresh token is a credential, so it is never used as the subject.
+        self.audit_result(
+            AuthEventType::Refresh,
+            AuthEventType::RefreshFailed,
+            None,
+            result,
+        )
+        .await
+    }
+
+    /// Revokes `refresh_token` and records the logout against its owner.
+    pub async fn logout(&self, refresh_token: &str) -> Result<(), AppError> {
+        let user_id = self.providers.active()?.logout(refresh_token).await?;
+        if let Some(audit) = &self.audit {
+            let subject = user_id.to_string();
+            audit
+                .record(AuthEventType::Logout, Some(user_id), Some(&subject), None)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Mints an impersonation token and records who acted as whom.
+    pub async fn impersonate(
+        &self,
+        actor: &Claims,
+        user_id: Uuid,
+    ) -> Result<ImpersonationToken, AppError> {
+        let token = self.providers.active()?.impersonate(actor, user_id).await?;
+        if let Some(audit) = &self.audit {
+            let detail = format!("impersonated by {}", actor.sub);
+            audit
+                .record(
+                    AuthEventType::Impersonate,
+                    Some(user_id),
+                    Some(&actor.sub),
+                    Some(&detail),
+                )
+                .await;
+        }
+        Ok(token)
+    }
+
+    /// Changes the password of `user_id`, recording the outcome without either password.
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<(), AppError> {
+        let result = match self.providers.active() {
+            Ok(provider) => {
+                provider
+                    .change_password(user_id, current_password, new_password)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        if let Some(audit) = &self.audit {
+            let subject = user_id.to_string();
+            let (event, detail) = match &result {
+                Ok(()) => (AuthEventType::PasswordChange, None),
+                Err(err) => (AuthEventType::PasswordChangeFailed, Some(err.message())),
+            };
+            audit
+                .record(event, Some(user_id), Some(&subject), detail)
+                .await;
+        }
+        result
     }
 
     pub async fn verify(&self, access_token: &str) -> Result<Claims, AppError> {
@@ -37,6 +189,34 @@ impl<'a> AuthService<'a> {
             .seed_admin(cfg)
             .await
     }
+
+    async fn audit_result(
+        &self,
+        success: AuthEventType,
+        failure: AuthEventType,
+        subject: Option<&str>,
+        result: Result<TokenBundle, AppError>,
+    ) -> Result<TokenBundle, AppError> {
+        let Some(audit) = &self.audit else {
+            return result;
+        };
+        match &result {
+            Ok(tokens) => {
+                let claims = self.verify(&tokens.access_token).await.ok();
+                let user_id = claims
+                    .as_ref()
+                    .and_then(|claims| Uuid::parse_str(&claims.sub).ok());
+                let subject = subject.or(claims.as_ref().map(|claims| claims.sub.as_str()));
+                audit.record(success, user_id, subject, None).await;
+            }
+            Err(err) => {
+                audit
+                    .record(failure, None, subject, Some(err.message()))
+                    .await;
+            }
+        }
+        result
+    }
 }
 
 #[cfg(test)]
@@ -44,13 +224,20 @@ mod tests {
     use std::sync::Arc;
 
     use async_trait::async_trait;
+    use chrono::Utc;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase};
 
     use crate::{
         auth::{
-            Role,
+            AuthRequestMeta, RegistrationMode, Role,
+            password::PasswordKdf,
             providers::{AuthProvider, AuthProviderId, AuthProviders},
         },
-        config::AuthConfig,
+        config::{AuthConfig, LockoutConfig, TokenSourceConfig},
+        db::{
+            dao::{AuthEventDao, DaoBase},
+            entities::auth_event,
+        },
     };
 
     use super::*;
@@ -76,7 +263,12 @@ mod tests {
             AuthProviderId::Local
         }
 
-        async fn register(&self, _email: &str, _password: &str) -> Result<TokenBundle, AppError> {
+        async fn register(
+            &self,
+            _email: &str,
+            _password: &str,
+            _invite_token: Option<&str>,
+        ) -> Result<TokenBundle, AppError> {
             match self.mode {
                 ProviderMode::RegisterError => Err(AppError::conflict("duplicate user")),
                 _ => Ok(token_bundle("register:ok")),
@@ -106,6 +298,26 @@ mod tests {
             }
         }
 
+        async fn logout(&self, _refresh_token: &str) -> Result<Uuid, AppError> {
+            match self.mode {
+                ProviderMode::RefreshError => Err(AppError::unauthorized("invalid refresh token")),
+                _ => Ok(Uuid::nil()),
+            }
+        }
+
+        async fn impersonate(
+            &self,
+            actor: &Claims,
+            _user_id: Uuid,
+        ) -> Result<ImpersonationToken, AppError> {
+            Ok(ImpersonationToken {
+                access_token: "impersonate:ok".to_string(),
+                token_type: "Bearer",
+                expires_in: 600,
+                impersonator: actor.sub.clone(),
+            })
+        }
+
         async fn seed_admin(&self, _cfg: &AuthConfig) -> anyhow::Result<()> {
             match self.mode {
                 ProviderMode::SeedAdminError => Err(anyhow::anyhow!("seed admin failed")),
@@ -129,15 +341,27 @@ mod tests {
             exp: 100,
             iat: 10,
             roles: vec![Role::User],
+            scopes: Vec::new(),
+            act: None,
         }
     }
 
     fn test_config() -> AuthConfig {
         AuthConfig {
+            enabled: true,
             provider: AuthProviderId::Local,
             jwt_secret: "test-secret".to_string(),
+            jwt_kid: None,
+            jwt_verification_keys: Vec::new(),
             admin_email: "admin@example.com".to_string(),
             admin_password: "adminpassword".to_string(),
+            registration_mode: RegistrationMode::Open,
+            single_session: false,
+            bind_refresh_tokens: false,
+            password_kdf: PasswordKdf::Argon2id,
+            password_history: 0,
+            lockout: LockoutConfig::default(),
+            token: TokenSourceConfig::default(),
         }
     }
 
@@ -157,7 +381,7 @@ mod tests {
         let service = AuthService::new(&providers);
 
         let result = service
-            .register("alice@example.com", "password123")
+            .register("alice@example.com", "password123", None)
             .await
             .expect("register should succeed");
 
@@ -170,7 +394,7 @@ mod tests {
         let service = AuthService::new(&providers);
 
         let err = service
-            .register("alice@example.com", "password123")
+            .register("alice@example.com", "password123", None)
             .await
             .expect_err("register should fail");
 
@@ -183,7 +407,7 @@ mod tests {
         let service = AuthService::new(&providers);
 
         let err = service
-            .register("alice@example.com", "password123")
+            .register("alice@example.com", "password123", None)
             .await
             .expect_err("register should fail");
 
@@ -342,4 +566,178 @@ mod tests {
 
         assert_eq!(err.to_string(), "Auth provider not configured: local");
     }
+
+    fn audit_row(event_type: &str, subject: &str) -> auth_event::Model {
+        let now = Utc::now().fixed_offset();
+        auth_event::Model {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            event_type: event_type.to_string(),
+            user_id: None,
+            subject: Some(subject.to_string()),
+            ip: Some("203.0.113.7".to_string()),
+            user_agent: Some("test-agent".to_string()),
+            detail: None,
+        }
+    }
+
+    fn audit_log(db: &DatabaseConnection) -> AuthAuditLog {
+        AuthAuditLog::new(
+            AuthEventDao::new(db),
+            AuthRequestMeta::new(
+                Some("203.0.113.7".to_string()),
+                Some("test-agent".to_string()),
+            ),
+        )
+    }
+
+    fn logged_statements(db: DatabaseConnection) -> Vec<String> {
+        db.into_transaction_log()
+            .iter()
+            .map(|transaction| format!("{transaction:?}"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn login_records_login_audit_event_on_success() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[audit_row("login", "alice@example.com")]])
+            .into_connection();
+        let providers = providers_with(ProviderMode::Success);
+        let service = AuthService::new(&providers).with_audit(audit_log(&db));
+
+        service
+            .login("alice@example.com", "password123")
+            .await
+            .expect("login should succeed");
+
+        let statements = logged_statements(db);
+        assert_eq!(statements.len(), 1);
+        let insert = &statements[0];
+        assert!(insert.contains(r#"INSERT INTO \"auth_events\""#));
+        assert!(insert.contains(r#"String(Some("login"))"#));
+        assert!(insert.contains("alice@example.com"));
+        assert!(insert.contains("203.0.113.7"));
+        assert!(insert.contains("test-agent"));
+        assert!(!insert.contains("password123"));
+    }
+
+    #[tokio::test]
+    async fn failed_login_records_failure_without_password() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[audit_row("login_failed", "alice@example.com")]])
+            .into_connection();
+        let providers = providers_with(ProviderMode::LoginError);
+        let service = AuthService::new(&providers).with_audit(audit_log(&db));
+
+        let err = service
+            .login("alice@example.com", "wrong-password")
+            .await
+            .expect_err("login should fail");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+        let statements = logged_statements(db);
+        assert_eq!(statements.len(), 1);
+        let insert = &statements[0];
+        assert!(insert.contains(r#"String(Some("login_failed"))"#));
+        assert!(insert.contains("alice@example.com"));
+        assert!(insert.contains("invalid credentials"));
+        assert!(!insert.contains("wrong-password"));
+    }
+
+    #[tokio::test]
+    async fn refresh_audit_event_never_records_refresh_token() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[audit_row("refresh_failed", "")]])
+            .into_connection();
+        let providers = providers_with(ProviderMode::RefreshError);
+        let service = AuthService::new(&providers).with_audit(audit_log(&db));
+
+        service
+            .refresh("refresh-token-1")
+            .await
+            .expect_err("refresh should fail");
+
+        let statements = logged_statements(db);
+        assert_eq!(statements.len(), 1);
+        assert!(statements[0].contains(r#"String(Some("refresh_failed"))"#));
+        assert!(!statements[0].contains("refresh-token-1"));
+    }
+
+    #[tokio::test]
+    async fn logout_records_audit_event_for_token_owner() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[audit_row("logout", "")]])
+            .into_connection();
+        let providers = providers_with(ProviderMode::Success);
+        let service = AuthService::new(&providers).with_audit(audit_log(&db));
+
+        service
+            .logout("refresh-token-1")
+            .await
+            .expect("logout should succeed");
+
+        let statements = logged_statements(db);
+        assert_eq!(statements.len(), 1);
+        let insert = &statements[0];
+        assert!(insert.contains(r#"String(Some("logout"))"#));
+        assert!(insert.contains(&Uuid::nil().to_string()));
+        assert!(!insert.contains("refresh-token-1"));
+    }
+
+    #[tokio::test]
+    async fn failed_logout_records_nothing() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let providers = providers_with(ProviderMode::RefreshError);
+        let service = AuthService::new(&providers).with_audit(audit_log(&db));
+
+        let err = service
+            .logout("refresh-token-1")
+            .await
+            .expect_err("logout should fail");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+        assert!(logged_statements(db).is_empty());
+    }
+
+    #[tokio::test]
+    async fn impersonate_records_audit_event_with_actor() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[audit_row("impersonate", "admin-sub")]])
+            .into_connection();
+        let providers = providers_with(ProviderMode::Success);
+        let service = AuthService::new(&providers).with_audit(audit_log(&db));
+        let target = Uuid::new_v4();
+
+        let token = service
+            .impersonate(&claims("admin-sub"), target)
+            .await
+            .expect("impersonate should succeed");
+
+        assert_eq!(token.impersonator, "admin-sub");
+        let statements = logged_statements(db);
+        assert_eq!(statements.len(), 1);
+        let insert = &statements[0];
+        assert!(insert.contains(r#"String(Some("impersonate"))"#));
+        assert!(insert.contains(&target.to_string()));
+        assert!(insert.contains("impersonated by admin-sub"));
+        assert!(!insert.contains("impersonate:ok"));
+    }
+
+    #[tokio::test]
+    async fn audit_insert_failure_does_not_fail_login() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_errors([DbErr::Custom("insert failed".to_string())])
+            .into_connection();
+        let providers = providers_with(ProviderMode::Success);
+        let service = AuthService::new(&providers).with_audit(audit_log(&db));
+
+        let result = service
+            .login("alice@example.com", "password123")
+            .await
+            .expect("login should succeed");
+
+        assert_eq!(result.access_token, "login:ok");
+    }
 }
@@ -1,9 +1,15 @@
 use sea_orm::DatabaseConnection;
 
 use crate::{
-    auth::providers::AuthProviders,
-    db::dao::{DaoContext, RefreshTokenDao},
-    services::{auth_service::AuthService, todo_service::TodoService, user_service::UserService},
+    auth::{AuthAuditLog, AuthRequestMeta, providers::AuthProviders},
+    db::dao::{
+        DaoContext, InviteDao, JobDao, OutboxDao, PasswordHistoryDao, RefreshTokenDao,
+        client_statement_timeout,
+    },
+    services::{
+        auth_event_service::AuthEventService, auth_service::AuthService, todo_service::TodoService,
+        user_service::UserService,
+    },
     state::AppState,
 };
 
@@ -20,7 +26,19 @@ impl ServiceContext {
     }
 
     pub fn from_state(state: &AppState) -> Self {
-        Self::new(&state.db)
+        let statement_timeout = state.config.database.as_ref().and_then(|database| {
+            client_statement_timeout(&state.db, database.statement_timeout_ms)
+        });
+        Self {
+            daos: DaoContext::new(&state.db)
+                .with_replica(state.replica.clone())
+                .with_statement_timeout(statement_timeout),
+        }
+    }
+
+    /// Replica-backed DAOs for reads that tolerate replication lag.
+    pub fn read_daos(&self) -> DaoContext {
+        self.daos.read()
     }
 
     pub fn user(&self) -> UserService {
@@ -35,7 +53,41 @@ impl ServiceContext {
         AuthService::new(providers)
     }
 
+    /// Auth service that writes an audit row for each register/login/refresh.
+    pub fn audited_auth<'a>(
+        &self,
+        providers: &'a AuthProviders,
+        meta: AuthRequestMeta,
+    ) -> AuthService<'a> {
+        AuthService::new(providers).with_audit(self.auth_audit(meta))
+    }
+
+    /// Audit log for auth events recorded outside `AuthService`, such as role changes.
+    pub fn auth_audit(&self, meta: AuthRequestMeta) -> AuthAuditLog {
+        AuthAuditLog::new(self.daos.auth_event(), meta)
+    }
+
+    pub fn auth_event(&self) -> AuthEventService {
+        AuthEventService::new(self.daos.auth_event())
+    }
+
     pub fn refresh_token_dao(&self) -> RefreshTokenDao {
         self.daos.refresh_token()
     }
+
+    pub fn invite_dao(&self) -> InviteDao {
+        self.daos.invite()
+    }
+
+    pub fn password_history_dao(&self) -> PasswordHistoryDao {
+        self.daos.password_history()
+    }
+
+    pub fn outbox_dao(&self) -> OutboxDao {
+        self.daos.outbox()
+    }
+
+    pub fn job_dao(&self) -> JobDao {
+        self.daos.job()
+    }
 }
@@ -1,12 +1,16 @@
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use sea_orm::sea_query::{ColumnType, Value as QueryValue};
-use sea_orm::{ColumnTrait, EntityTrait, IdenStatic, IntoActiveModel, Iterable, Order, Select};
+use sea_orm::{
+    ActiveEnum, ColumnTrait, EntityTrait, IdenStatic, IntoActiveModel, Iterable, Order, Select,
+    TransactionTrait,
+};
 use serde_json::Value as JsonValue;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use uuid::Uuid;
 
 use crate::db::dao::{
-    ColumnFilter, CompareOp, DaoBase, DaoLayerError, FilterOp, PaginatedResponse,
+    ColumnFilter, ColumnOrder, CompareOp, DaoBase, DaoLayerError, FacetCount, FilterOp, NullsOrder,
+    PaginatedResponse, entity_label, pagination_reason,
 };
 use crate::error::AppError;
 
@@ -19,7 +23,8 @@ type CrudColumn<D> = <CrudEntity<D> as EntityTrait>::Column;
 pub struct CrudErrors {
     pub create_failed: &'static str,
     pub find_failed: &'static str,
-    pub not_found: &'static str,
+    /// Fixed 404 message; `None` names the entity and id, e.g. `User <id> not found`.
+    pub not_found: Option<&'static str>,
     pub update_failed: &'static str,
     pub delete_failed: &'static str,
     pub invalid_pagination: &'static str,
@@ -30,7 +35,7 @@ impl Default for CrudErrors {
         Self {
             create_failed: "Create failed",
             find_failed: "Find failed",
-            not_found: "Resource not found",
+            not_found: None,
             update_failed: "Update failed",
             delete_failed: "Delete failed",
             invalid_pagination: "Invalid pagination",
@@ -47,8 +52,52 @@ pub enum CrudOp {
     Delete,
 }
 
+/// Shape of list route responses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListEnvelope {
+    /// `{ status, message, data: PaginatedResponse }`.
+    #[default]
+    Wrapped,
+    /// Bare JSON array; pagination goes in `X-Total-Count` and `Link` headers.
+    Bare,
+}
+
+/// Status returned by the generated create route. Both variants set `Location`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CreateStatus {
+    /// `201 Created`.
+    #[default]
+    Created,
+    /// `200 OK`, for clients that do not handle 201.
+    Ok,
+}
+
+/// Upper bounds on list filters, checked before any value is parsed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FilterLimits {
+    /// Most filter keys accepted in one request.
+    pub max_filters: usize,
+    /// Longest raw filter value, in bytes, operator prefix included.
+    pub max_value_len: usize,
+    /// Most columns accepted in `?facets=`; each costs one grouped query.
+    pub max_facets: usize,
+}
+
+impl Default for FilterLimits {
+    fn default() -> Self {
+        Self {
+            max_filters: 32,
+            max_value_len: 2048,
+            max_facets: 5,
+        }
+    }
+}
+
+const INVALID_FACET_MESSAGE: &str = "Invalid facet";
 const INVALID_FILTER_MESSAGE: &str = "Invalid filter";
 const INVALID_FILTER_VALUE_MESSAGE: &str = "Invalid filter value";
+const INVALID_INCLUDE_MESSAGE: &str = "Invalid include";
+const INVALID_SORT_MESSAGE: &str = "Invalid sort";
 
 pub struct FilterSpec<C> {
     pub key: &'static str,
@@ -71,6 +120,69 @@ pub enum FilterMode<C: 'static> {
     },
 }
 
+/// One column of a list route, as served by `GET <base>/schema`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    /// `string`, `integer`, `number`, `boolean`, `uuid`, `datetime`, `date`,
+    /// `time`, `json` or `other`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub nullable: bool,
+    /// Accepted as a `?<name>=` filter.
+    pub filterable: bool,
+    /// Accepted by `?sort=` and `?facets=`.
+    pub sortable: bool,
+    /// Filterable with `*` wildcards.
+    pub searchable: bool,
+}
+
+/// The writes of one `POST <base>/bulk` call, already built into active models.
+pub struct BulkWrite<A> {
+    pub create: Vec<A>,
+    /// Row id and the fields to change on it.
+    pub update: Vec<(Uuid, A)>,
+    pub delete: Vec<Uuid>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkOp {
+    Create,
+    Update,
+    Delete,
+}
+
+impl BulkOp {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Outcome of one item of a bulk call.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct BulkItemResult {
+    pub op: BulkOp,
+    /// Position of the item in its `create`/`update`/`delete` list.
+    pub index: usize,
+    pub ok: bool,
+    /// The stored row for creates and updates, `{ "id": .. }` for deletes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+enum BulkStep<A> {
+    Create(A),
+    Update(Uuid, A),
+    Delete(Uuid),
+}
+
 #[async_trait::async_trait]
 pub trait CrudService {
     type Dao: DaoBase;
@@ -87,21 +199,240 @@ pub trait CrudService {
         CrudErrors::default()
     }
 
+    fn list_envelope(&self) -> ListEnvelope {
+        ListEnvelope::Wrapped
+    }
+
+    fn create_status(&self) -> CreateStatus {
+        CreateStatus::Created
+    }
+
+    fn filter_limits(&self) -> FilterLimits {
+        FilterLimits::default()
+    }
+
+    /// Relation names accepted by `?include=` on detail routes. Empty by default.
+    /// Model fields removed from every JSON body the CRUD routes return, e.g.
+    /// `&["password_hash"]`.
+    fn serialize_hidden_columns(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Serializes `model` for a response without `serialize_hidden_columns`.
+    fn to_response_json(&self, model: &CrudModel<Self::Dao>) -> Result<JsonValue, AppError>
+    where
+        CrudModel<Self::Dao>: serde::Serialize,
+    {
+        let mut json = serde_json::to_value(model)
+            .map_err(|err| AppError::internal_with_source("Failed to serialize resource", err))?;
+        if let JsonValue::Object(fields) = &mut json {
+            for column in self.serialize_hidden_columns() {
+                fields.remove(*column);
+            }
+        }
+        Ok(json)
+    }
+
+    fn relations(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    async fn load_relation(
+        &self,
+        _model: &CrudModel<Self::Dao>,
+        relation: &str,
+    ) -> Result<JsonValue, AppError> {
+        Err(invalid_include(relation))
+    }
+
+    fn parse_includes(&self, raw: Option<&str>) -> Result<Vec<String>, AppError> {
+        let Some(raw) = raw else {
+            return Ok(Vec::new());
+        };
+        let declared = self.relations();
+        let mut includes: Vec<String> = Vec::new();
+        for name in raw
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            if !declared.contains(&name) {
+                return Err(invalid_include(name));
+            }
+            if !includes.iter().any(|existing| existing == name) {
+                includes.push(name.to_string());
+            }
+        }
+        Ok(includes)
+    }
+
+    /// Parses `?sort=` values like `title`, `-due_date` or `-due_date:nullslast`.
+    ///
+    /// Sortable columns follow `list_filter_mode`, see [`list_column`](Self::list_column).
+    fn parse_sort(&self, raw: &str) -> Result<ColumnOrder<CrudColumn<Self::Dao>>, AppError>
+    where
+        CrudColumn<Self::Dao>: ColumnTrait + Copy,
+    {
+        let raw = raw.trim();
+        let (field, nulls) = match raw.split_once(':') {
+            Some((field, modifier)) => {
+                let nulls = match modifier.trim().to_ascii_lowercase().as_str() {
+                    "nullsfirst" => NullsOrder::First,
+                    "nullslast" => NullsOrder::Last,
+                    _ => return Err(invalid_sort(raw)),
+                };
+                (field, Some(nulls))
+            }
+            None => (raw, None),
+        };
+        let (name, order) = match field.strip_prefix('-') {
+            Some(name) => (name, Order::Desc),
+            None => (field, Order::Asc),
+        };
+
+        let column = self.list_column(name).ok_or_else(|| invalid_sort(raw))?;
+
+        Ok(ColumnOrder {
+            column,
+            order,
+            nulls,
+        })
+    }
+
+    /// Parses `?facets=status,priority` into distinct columns, checked like
+    /// sort keys and capped at `filter_limits().max_facets`.
+    fn parse_facets(&self, raw: &str) -> Result<Vec<CrudColumn<Self::Dao>>, AppError>
+    where
+        CrudColumn<Self::Dao>: ColumnTrait + Copy,
+    {
+        let mut facets: Vec<CrudColumn<Self::Dao>> = Vec::new();
+        for name in raw
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let column = self.list_column(name).ok_or_else(|| invalid_facet(name))?;
+            if !facets.iter().any(|existing| existing.as_str() == name) {
+                facets.push(column);
+            }
+        }
+        let max_facets = self.filter_limits().max_facets;
+        if facets.len() > max_facets {
+            return Err(invalid_facet(format!(
+                "at most {max_facets} facets allowed"
+            )));
+        }
+        Ok(facets)
+    }
+
+    /// Column called `name` if list routes may sort or facet by it:
+    /// allowlisted keys, or every column not in the deny list.
+    fn list_column(&self, name: &str) -> Option<CrudColumn<Self::Dao>>
+    where
+        CrudColumn<Self::Dao>: ColumnTrait + Copy,
+    {
+        match self.list_filter_mode() {
+            FilterMode::Allowlist(specs) => specs
+                .iter()
+                .find(|spec| spec.key == name)
+                .map(|spec| spec.column),
+            FilterMode::AllColumns { deny, .. } => CrudColumn::<Self::Dao>::iter()
+                .find(|column| column.as_str() == name && !deny.contains(&name)),
+        }
+    }
+
+    /// Columns the list route returns, with what `list_filter_mode` lets
+    /// clients do with each. `serialize_hidden_columns` are left out.
+    fn list_schema(&self) -> Vec<ColumnSchema>
+    where
+        CrudColumn<Self::Dao>: ColumnTrait + Copy,
+    {
+        let hidden = self.serialize_hidden_columns();
+        let mode = self.list_filter_mode();
+        CrudColumn::<Self::Dao>::iter()
+            .filter(|column| !hidden.contains(&column.as_str()))
+            .map(|column| {
+                let name = column.as_str();
+                let def = column.def();
+                let column_type = def.get_column_type();
+                let filterable = match &mode {
+                    FilterMode::Allowlist(specs) => specs.iter().any(|spec| spec.key == name),
+                    FilterMode::AllColumns { deny, parse } => {
+                        !deny.contains(&name)
+                            && (!matches!(parse, FilterParseStrategy::StringsOnly)
+                                || is_string_column_type(column_type))
+                    }
+                };
+                ColumnSchema {
+                    name: name.to_string(),
+                    kind: column_kind(column_type).to_string(),
+                    nullable: def.is_null(),
+                    filterable,
+                    sortable: self.list_column(name).is_some(),
+                    searchable: filterable && is_string_column_type(column_type),
+                }
+            })
+            .collect()
+    }
+
+    async fn find_by_id_with_relations(
+        &self,
+        id: Uuid,
+        includes: &[String],
+    ) -> Result<JsonValue, AppError>
+    where
+        CrudModel<Self::Dao>: serde::Serialize,
+    {
+        let model = self.find_by_id(id).await?;
+        let mut json = self.to_response_json(&model)?;
+        if includes.is_empty() {
+            return Ok(json);
+        }
+        let JsonValue::Object(fields) = &mut json else {
+            return Err(AppError::internal(
+                "Resource did not serialize to an object",
+            ));
+        };
+        for relation in includes {
+            let related = self.load_relation(&model, relation).await?;
+            fields.insert(relation.clone(), related);
+        }
+        Ok(json)
+    }
+
     fn map_error(&self, op: CrudOp, err: DaoLayerError) -> AppError {
         let errors = self.errors();
+        let context = match op {
+            CrudOp::Create => errors.create_failed,
+            CrudOp::Find | CrudOp::List => errors.find_failed,
+            CrudOp::Update => errors.update_failed,
+            CrudOp::Delete => errors.delete_failed,
+        };
         match err {
             DaoLayerError::Db(db_err) => {
-                let context = match op {
-                    CrudOp::Create => errors.create_failed,
-                    CrudOp::Find | CrudOp::List => errors.find_failed,
-                    CrudOp::Update => errors.update_failed,
-                    CrudOp::Delete => errors.delete_failed,
-                };
                 let message = format!("{context}. Please check the logs for more details");
                 AppError::internal_with_source(message, db_err)
             }
-            DaoLayerError::NotFound { .. } => AppError::not_found(errors.not_found),
-            DaoLayerError::InvalidPagination { .. } => AppError::bad_request(err.to_string()),
+            DaoLayerError::Timeout(db_err) => {
+                tracing::warn!(error = %db_err, "database statement timed out");
+                AppError::gateway_timeout(format!("{context}: the database timed out"))
+            }
+            DaoLayerError::NotFound { entity, id } => match errors.not_found {
+                Some(message) => AppError::not_found(message),
+                None => AppError::not_found(format!("{} {id} not found", entity_label(entity))),
+            },
+            DaoLayerError::InvalidPagination {
+                page,
+                page_size,
+                max_page_size,
+            } => AppError::bad_request(format!(
+                "{}: {}",
+                errors.invalid_pagination,
+                pagination_reason(page, page_size, max_page_size)
+            )),
+            err @ (DaoLayerError::MissingFilter | DaoLayerError::InvalidBatchSize) => {
+                AppError::bad_request(format!("{context}: {err}"))
+            }
         }
     }
 
@@ -126,7 +457,7 @@ pub trait CrudService {
         &self,
         page: u64,
         page_size: u64,
-        order: Option<(CrudColumn<Self::Dao>, Order)>,
+        order: Option<ColumnOrder<CrudColumn<Self::Dao>>>,
         apply: F,
     ) -> Result<PaginatedResponse<CrudModel<Self::Dao>>, AppError>
     where
@@ -142,7 +473,7 @@ pub trait CrudService {
         &self,
         page: u64,
         page_size: u64,
-        order: Option<(CrudColumn<Self::Dao>, Order)>,
+        order: Option<ColumnOrder<CrudColumn<Self::Dao>>>,
         filters: HashMap<String, String>,
         apply: F,
     ) -> Result<PaginatedResponse<CrudModel<Self::Dao>>, AppError>
@@ -157,6 +488,76 @@ pub trait CrudService {
             .map_err(|err| self.map_error(CrudOp::List, err))
     }
 
+    async fn count_with_filters<F>(
+        &self,
+        filters: HashMap<String, String>,
+        apply: F,
+    ) -> Result<u64, AppError>
+    where
+        F: FnOnce(Select<CrudEntity<Self::Dao>>) -> Select<CrudEntity<Self::Dao>> + Send,
+        CrudColumn<Self::Dao>: ColumnTrait + Copy,
+    {
+        let column_filters = self.build_column_filters(filters)?;
+        self.dao()
+            .count_with_filters(&column_filters, apply)
+            .await
+            .map_err(|err| self.map_error(CrudOp::List, err))
+    }
+
+    /// Row counts per value of each `facets` column, over the rows a list
+    /// call with the same `filters` and `apply` would page through. Runs one
+    /// grouped query per column.
+    async fn facet_counts<F>(
+        &self,
+        facets: &[CrudColumn<Self::Dao>],
+        filters: HashMap<String, String>,
+        apply: F,
+    ) -> Result<BTreeMap<String, Vec<FacetCount>>, AppError>
+    where
+        F: Fn(Select<CrudEntity<Self::Dao>>) -> Select<CrudEntity<Self::Dao>> + Clone + Send,
+        CrudColumn<Self::Dao>: ColumnTrait + Copy,
+    {
+        let column_filters = self.build_column_filters(filters)?;
+        let mut counts = BTreeMap::new();
+        for column in facets {
+            let values = self
+                .dao()
+                .count_by(*column, &column_filters, apply.clone())
+                .await
+                .map_err(|err| self.map_error(CrudOp::List, err))?;
+            counts.insert(column.as_str().to_string(), values);
+        }
+        Ok(counts)
+    }
+
+    /// Case-insensitive existence check for create/registration validation.
+    async fn exists_ci(
+        &self,
+        column: CrudColumn<Self::Dao>,
+        value: &str,
+    ) -> Result<bool, AppError> {
+        self.dao()
+            .exists_ci(column, value)
+            .await
+            .map_err(|err| self.map_error(CrudOp::Find, err))
+    }
+
+    /// See [`DaoBase::find_or_create`]; the flag is `true` when the row was created.
+    async fn find_or_create<T>(
+        &self,
+        lookup: &[ColumnFilter<CrudColumn<Self::Dao>>],
+        default: T,
+    ) -> Result<(CrudModel<Self::Dao>, bool), AppError>
+    where
+        T: IntoActiveModel<CrudActiveModel<Self::Dao>> + Send,
+        CrudColumn<Self::Dao>: Copy,
+    {
+        self.dao()
+            .find_or_create(lookup, default)
+            .await
+            .map_err(|err| self.map_error(CrudOp::Create, err))
+    }
+
     async fn update<F>(&self, id: Uuid, apply: F) -> Result<CrudModel<Self::Dao>, AppError>
     where
         F: for<'a> FnOnce(&'a mut CrudActiveModel<Self::Dao>) + Send,
@@ -167,6 +568,21 @@ pub trait CrudService {
             .map_err(|err| self.map_error(CrudOp::Update, err))
     }
 
+    /// Updates without refreshing `updated_at`; see [`DaoBase::update_untouched`].
+    async fn update_untouched<F>(
+        &self,
+        id: Uuid,
+        apply: F,
+    ) -> Result<CrudModel<Self::Dao>, AppError>
+    where
+        F: for<'a> FnOnce(&'a mut CrudActiveModel<Self::Dao>) + Send,
+    {
+        self.dao()
+            .update_untouched(id, apply)
+            .await
+            .map_err(|err| self.map_error(CrudOp::Update, err))
+    }
+
     async fn delete(&self, id: Uuid) -> Result<(), AppError> {
         self.dao()
             .delete(id)
@@ -175,6 +591,125 @@ pub trait CrudService {
             .map_err(|err| self.map_error(CrudOp::Delete, err))
     }
 
+    /// Runs `write` in one transaction: creates, then updates (merged into
+    /// the stored row by `patch`), then deletes.
+    ///
+    /// The first failing item rolls the whole batch back and is returned,
+    /// prefixed with its position (e.g. `update[1]: ...`). With `partial`,
+    /// each item runs in its own savepoint instead: failed items are reported
+    /// in the results and the rest is committed.
+    async fn bulk<P>(
+        &self,
+        write: BulkWrite<CrudActiveModel<Self::Dao>>,
+        patch: P,
+        partial: bool,
+    ) -> Result<Vec<BulkItemResult>, AppError>
+    where
+        P: Fn(&mut CrudActiveModel<Self::Dao>, CrudActiveModel<Self::Dao>) + Send + Sync,
+        CrudModel<Self::Dao>: serde::Serialize,
+    {
+        let db_error = |op, err| self.map_error(op, DaoLayerError::from(err));
+        let steps = write
+            .create
+            .into_iter()
+            .enumerate()
+            .map(|(index, active)| (BulkOp::Create, index, BulkStep::Create(active)))
+            .chain(
+                write
+                    .update
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (id, changes))| {
+                        (BulkOp::Update, index, BulkStep::Update(id, changes))
+                    }),
+            )
+            .chain(
+                write
+                    .delete
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, id)| (BulkOp::Delete, index, BulkStep::Delete(id))),
+            );
+
+        let txn = self
+            .dao()
+            .db()
+            .begin()
+            .await
+            .map_err(|err| db_error(CrudOp::Create, err))?;
+        let mut results = Vec::new();
+        for (op, index, step) in steps {
+            let savepoint = if partial {
+                Some(
+                    txn.begin()
+                        .await
+                        .map_err(|err| db_error(CrudOp::Create, err))?,
+                )
+            } else {
+                None
+            };
+            let conn = savepoint.as_ref().unwrap_or(&txn);
+            let outcome = match step {
+                BulkStep::Create(active) => self
+                    .dao()
+                    .create_in(conn, active)
+                    .await
+                    .map_err(|err| self.map_error(CrudOp::Create, err))
+                    .and_then(|model| self.to_response_json(&model)),
+                BulkStep::Update(id, changes) => self
+                    .dao()
+                    .update_in(conn, id, true, |active| patch(active, changes))
+                    .await
+                    .map_err(|err| self.map_error(CrudOp::Update, err))
+                    .and_then(|model| self.to_response_json(&model)),
+                BulkStep::Delete(id) => self
+                    .dao()
+                    .delete_in(conn, id)
+                    .await
+                    .map(|id| serde_json::json!({ "id": id }))
+                    .map_err(|err| self.map_error(CrudOp::Delete, err)),
+            };
+
+            let result = match (outcome, savepoint) {
+                (Ok(data), savepoint) => {
+                    if let Some(savepoint) = savepoint {
+                        savepoint
+                            .commit()
+                            .await
+                            .map_err(|err| db_error(CrudOp::Create, err))?;
+                    }
+                    BulkItemResult {
+                        op,
+                        index,
+                        ok: true,
+                        data: Some(data),
+                        error: None,
+                    }
+                }
+                (Err(err), Some(savepoint)) => {
+                    savepoint
+                        .rollback()
+                        .await
+                        .map_err(|err| db_error(CrudOp::Create, err))?;
+                    BulkItemResult {
+                        op,
+                        index,
+                        ok: false,
+                        data: None,
+                        error: Some(err.message().to_string()),
+                    }
+                }
+                // Dropping `txn` rolls the batch back.
+                (Err(err), None) => return Err(err.context(format!("{}[{index}]", op.as_str()))),
+            };
+            results.push(result);
+        }
+        txn.commit()
+            .await
+            .map_err(|err| db_error(CrudOp::Create, err))?;
+        Ok(results)
+    }
+
     fn build_column_filters(
         &self,
         filters: HashMap<String, String>,
@@ -186,6 +721,24 @@ pub trait CrudService {
             return Ok(Vec::new());
         }
 
+        let limits = self.filter_limits();
+        if filters.len() > limits.max_filters {
+            return Err(invalid_filter_with(format!(
+                "at most {} filters allowed",
+                limits.max_filters
+            )));
+        }
+        if let Some(key) = filters
+            .iter()
+            .find(|(_, value)| value.len() > limits.max_value_len)
+            .map(|(key, _)| key)
+        {
+            return Err(invalid_filter_value_with(format!(
+                "{key} is longer than {} bytes",
+                limits.max_value_len
+            )));
+        }
+
         match self.list_filter_mode() {
             FilterMode::Allowlist(specs) => {
                 let spec_map: HashMap<&'static str, &FilterSpec<CrudColumn<Self::Dao>>> =
@@ -249,10 +802,26 @@ pub trait CrudService {
     }
 }
 
+fn invalid_include(relation: &str) -> AppError {
+    AppError::bad_request(format!("{INVALID_INCLUDE_MESSAGE}: {relation}"))
+}
+
+fn invalid_facet(detail: impl std::fmt::Display) -> AppError {
+    AppError::bad_request(format!("{INVALID_FACET_MESSAGE}: {detail}"))
+}
+
+fn invalid_sort(raw: &str) -> AppError {
+    AppError::bad_request(format!("{INVALID_SORT_MESSAGE}: {raw}"))
+}
+
 fn invalid_filter() -> AppError {
     AppError::bad_request(INVALID_FILTER_MESSAGE)
 }
 
+fn invalid_filter_with(detail: impl std::fmt::Display) -> AppError {
+    AppError::bad_request(format!("{INVALID_FILTER_MESSAGE}: {detail}"))
+}
+
 fn invalid_filter_value() -> AppError {
     AppError::bad_request(INVALID_FILTER_VALUE_MESSAGE)
 }
@@ -332,17 +901,22 @@ fn ensure_no_wildcard(raw: &str) -> Result<(), AppError> {
     Ok(())
 }
 
-fn escape_like(raw: &str) -> String {
-    let mut escaped = String::with_capacity(raw.len());
-    for ch in raw.chars() {
-        match ch {
-            '\\' => escaped.push_str("\\\\"),
-            '%' => escaped.push_str("\\%"),
-            '_' => escaped.push_str("\\_"),
-            _ => escaped.push(ch),
-        }
-    }
-    escaped
+/// Allowlist parser for an enum column: `raw` must name one of `E`'s stored
+/// values, ignoring case, and is matched as that exact value.
+///
+/// ```ignore
+/// FilterSpec { key: "status", column: Column::Status, parse: parse_enum::<Status> }
+/// ```
+pub fn parse_enum<E>(raw: &str) -> Result<FilterOp, AppError>
+where
+    E: ActiveEnum,
+    E::Value: std::fmt::Display,
+{
+    let raw = raw.trim();
+    E::iter()
+        .find(|variant| variant.to_value().to_string().eq_ignore_ascii_case(raw))
+        .map(|variant| FilterOp::Eq(variant.into_value().into()))
+        .ok_or_else(|| invalid_filter_value_with(format!("{raw} is not a known value")))
 }
 
 fn parse_string_filter(raw: &str) -> Result<FilterOp, AppError> {
@@ -365,17 +939,7 @@ fn parse_string_filter(raw: &str) -> Result<FilterOp, AppError> {
         return Ok(FilterOp::Eq(QueryValue::String(Some(inner.to_string()))));
     }
 
-    let escaped = escape_like(inner);
-    let pattern = match (leading, trailing) {
-        (true, true) => format!("%{escaped}%"),
-        (true, false) => format!("%{escaped}"),
-        (false, true) => format!("{escaped}%"),
-        (false, false) => escaped,
-    };
-    Ok(FilterOp::Like {
-        pattern,
-        escape: '\\',
-    })
+    Ok(FilterOp::like_literal(inner, leading, trailing))
 }
 
 fn parse_comparison(raw: &str) -> Option<(CompareOp, &str)> {
@@ -464,6 +1028,35 @@ fn parse_non_string_filter(raw: &str, column_type: &ColumnType) -> Result<Filter
     Ok(FilterOp::Eq(parse_value_by_column_type(raw, column_type)?))
 }
 
+fn column_kind(column_type: &ColumnType) -> &'static str {
+    match column_type {
+        ColumnType::Char(_)
+        | ColumnType::String(_)
+        | ColumnType::Text
+        | ColumnType::Enum { .. } => "string",
+        ColumnType::TinyInteger
+        | ColumnType::SmallInteger
+        | ColumnType::Integer
+        | ColumnType::BigInteger
+        | ColumnType::TinyUnsigned
+        | ColumnType::SmallUnsigned
+        | ColumnType::Unsigned
+        | ColumnType::BigUnsigned => "integer",
+        ColumnType::Float | ColumnType::Double | ColumnType::Decimal(_) | ColumnType::Money(_) => {
+            "number"
+        }
+        ColumnType::Boolean => "boolean",
+        ColumnType::Uuid => "uuid",
+        ColumnType::DateTime | ColumnType::Timestamp | ColumnType::TimestampWithTimeZone => {
+            "datetime"
+        }
+        ColumnType::Date => "date",
+        ColumnType::Time => "time",
+        ColumnType::Json | ColumnType::JsonBinary => "json",
+        _ => "other",
+    }
+}
+
 fn is_string_column_type(column_type: &ColumnType) -> bool {
     matches!(
         column_type,
@@ -557,8 +1150,8 @@ mod tests {
     use crate::error::AppError;
 
     use super::{
-        CompareOp, CrudErrors, CrudOp, CrudService, FilterMode, FilterOp, FilterParseStrategy,
-        FilterSpec, QueryValue,
+        CompareOp, CrudErrors, CrudOp, CrudService, FilterLimits, FilterMode, FilterOp,
+        FilterParseStrategy, FilterSpec, NullsOrder, Order, QueryValue, parse_enum,
     };
 
     mod test_entity {
@@ -636,6 +1229,7 @@ mod tests {
         deny: &'static [&'static str],
         errors: CrudErrors,
         filter_mode: FilterModeKind,
+        limits: FilterLimits,
     }
 
     fn parse_allowlist_title(raw: &str) -> Result<FilterOp, AppError> {
@@ -676,6 +1270,10 @@ mod tests {
         fn errors(&self) -> CrudErrors {
             self.errors
         }
+
+        fn filter_limits(&self) -> FilterLimits {
+            self.limits
+        }
     }
 
     struct CrudFixtureBuilder {
@@ -684,6 +1282,7 @@ mod tests {
         deny: &'static [&'static str],
         errors: CrudErrors,
         filter_mode: FilterModeKind,
+        limits: FilterLimits,
     }
 
     impl CrudFixtureBuilder {
@@ -694,6 +1293,7 @@ mod tests {
                 deny: &[],
                 errors: CrudErrors::default(),
                 filter_mode: FilterModeKind::AllColumns,
+                limits: FilterLimits::default(),
             }
         }
 
@@ -717,6 +1317,11 @@ mod tests {
             self
         }
 
+        fn with_limits(mut self, limits: FilterLimits) -> Self {
+            self.limits = limits;
+            self
+        }
+
         fn with_query_results<T, I, II>(mut self, sets: II) -> Self
         where
             T: IntoMockRow,
@@ -754,6 +1359,7 @@ mod tests {
                 deny: self.deny,
                 errors: self.errors,
                 filter_mode: self.filter_mode,
+                limits: self.limits,
             }
         }
     }
@@ -846,7 +1452,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn find_by_id_maps_not_found_to_service_not_found_message() {
+    async fn find_by_id_maps_not_found_to_entity_and_id_message() {
         let id = Uuid::new_v4();
         let service = CrudFixtureBuilder::new()
             .with_query_results([Vec::<test_entity::Model>::new()])
@@ -857,7 +1463,7 @@ mod tests {
             .await
             .expect_err("find_by_id should fail");
 
-        assert_eq!(err.message(), "Resource not found");
+        assert_eq!(err.message(), format!("TestEntity {id} not found"));
     }
 
     #[tokio::test]
@@ -900,7 +1506,39 @@ mod tests {
             .await
             .expect_err("find should fail");
 
-        assert_eq!(err.message(), "Invalid pagination: page=0 page_size=1");
+        assert_eq!(
+            err.message(),
+            "Invalid pagination: page must be 1 or greater (pages are numbered from 1)"
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_pagination_messages_name_the_bad_parameter() {
+        let service = CrudFixtureBuilder::new().build();
+        let max = <TestDao as DaoBase>::MAX_PAGE_SIZE;
+
+        let mut messages = Vec::new();
+        for (page, page_size) in [(0, 10), (1, 0), (1, max + 1)] {
+            let err = service
+                .find(page, page_size, None, |query| query)
+                .await
+                .expect_err("find should fail");
+            assert!(matches!(err, AppError::BadRequest(_)));
+            messages.push(err.message().to_string());
+        }
+
+        assert_eq!(
+            messages,
+            vec![
+                "Invalid pagination: page must be 1 or greater (pages are numbered from 1)"
+                    .to_string(),
+                "Invalid pagination: page_size must be at least 1".to_string(),
+                format!(
+                    "Invalid pagination: page_size {} exceeds the maximum of {max}",
+                    max + 1
+                ),
+            ]
+        );
     }
 
     #[tokio::test]
@@ -967,7 +1605,10 @@ mod tests {
             .await
             .expect_err("find_with_filters should fail");
 
-        assert_eq!(err.message(), "Invalid pagination: page=0 page_size=1");
+        assert_eq!(
+            err.message(),
+            "Invalid pagination: page must be 1 or greater (pages are numbered from 1)"
+        );
     }
 
     #[tokio::test]
@@ -987,6 +1628,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_sort_reads_direction_and_nulls_modifier() {
+        let service = CrudFixtureBuilder::new().build();
+
+        let order = service
+            .parse_sort("-scheduled_at:nullslast")
+            .expect("sort should parse");
+
+        assert!(matches!(order.column, test_entity::Column::ScheduledAt));
+        assert_eq!(order.order, Order::Desc);
+        assert_eq!(order.nulls, Some(NullsOrder::Last));
+
+        let order = service.parse_sort("title").expect("sort should parse");
+        assert_eq!(order.order, Order::Asc);
+        assert_eq!(order.nulls, None);
+    }
+
+    #[test]
+    fn parse_facets_dedupes_and_enforces_column_rules_and_cap() {
+        let service = CrudFixtureBuilder::new()
+            .with_deny(&["score"])
+            .with_limits(FilterLimits {
+                max_facets: 2,
+                ..FilterLimits::default()
+            })
+            .build();
+
+        let facets = service
+            .parse_facets("title, done,title,")
+            .expect("facets should parse");
+        assert_eq!(facets.len(), 2);
+        assert!(matches!(facets[0], test_entity::Column::Title));
+        assert!(matches!(facets[1], test_entity::Column::Done));
+
+        let err = service.parse_facets("score").expect_err("denied column");
+        assert_eq!(err.message(), "Invalid facet: score");
+        let err = service.parse_facets("nope").expect_err("unknown column");
+        assert_eq!(err.message(), "Invalid facet: nope");
+        let err = service
+            .parse_facets("title,done,scheduled_at")
+            .expect_err("too many facets");
+        assert_eq!(err.message(), "Invalid facet: at most 2 facets allowed");
+    }
+
+    #[test]
+    fn parse_sort_rejects_unknown_denied_and_malformed_values() {
+        let service = CrudFixtureBuilder::new().with_deny(&["score"]).build();
+
+        for raw in ["unknown", "-score", "title:nullsmiddle"] {
+            let err = service.parse_sort(raw).expect_err("sort should fail");
+            assert_eq!(err.message(), format!("Invalid sort: {raw}"));
+        }
+    }
+
     #[tokio::test]
     async fn update_returns_model_on_success() {
         let id = Uuid::new_v4();
@@ -1008,17 +1703,18 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn update_maps_not_found_to_service_not_found_message() {
+    async fn update_maps_not_found_to_entity_and_id_message() {
+        let id = Uuid::new_v4();
         let service = CrudFixtureBuilder::new()
             .with_query_results([Vec::<test_entity::Model>::new()])
             .build();
 
         let err = service
-            .update(Uuid::new_v4(), |_active| {})
+            .update(id, |_active| {})
             .await
             .expect_err("update should fail");
 
-        assert_eq!(err.message(), "Resource not found");
+        assert_eq!(err.message(), format!("TestEntity {id} not found"));
     }
 
     #[tokio::test]
@@ -1048,15 +1744,13 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn delete_maps_not_found_to_service_not_found_message() {
+    async fn delete_maps_not_found_to_entity_and_id_message() {
+        let id = Uuid::new_v4();
         let service = CrudFixtureBuilder::new().with_exec_result(0).build();
 
-        let err = service
-            .delete(Uuid::new_v4())
-            .await
-            .expect_err("delete should fail");
+        let err = service.delete(id).await.expect_err("delete should fail");
 
-        assert_eq!(err.message(), "Resource not found");
+        assert_eq!(err.message(), format!("TestEntity {id} not found"));
     }
 
     #[tokio::test]
@@ -1087,6 +1781,53 @@ mod tests {
         assert!(parsed.is_empty());
     }
 
+    #[test]
+    fn build_column_filters_rejects_too_many_filters() {
+        let service = CrudFixtureBuilder::new()
+            .with_limits(FilterLimits {
+                max_filters: 1,
+                ..FilterLimits::default()
+            })
+            .build();
+
+        let err = service
+            .build_column_filters(filters(&[("title", "hello"), ("score", "1")]))
+            .expect_err("too many filters should fail");
+
+        assert_eq!(err.message(), "Invalid filter: at most 1 filters allowed");
+    }
+
+    #[test]
+    fn build_column_filters_rejects_over_long_value() {
+        let service = CrudFixtureBuilder::new()
+            .with_limits(FilterLimits {
+                max_value_len: 8,
+                ..FilterLimits::default()
+            })
+            .build();
+
+        let err = service
+            .build_column_filters(filters(&[("title", "much too long")]))
+            .expect_err("over-long value should fail");
+
+        assert_eq!(
+            err.message(),
+            "Invalid filter value: title is longer than 8 bytes"
+        );
+    }
+
+    #[test]
+    fn build_column_filters_accepts_requests_within_default_limits() {
+        let service = CrudFixtureBuilder::new().build();
+        let title = "a".repeat(FilterLimits::default().max_value_len);
+
+        let parsed = service
+            .build_column_filters(filters(&[("title", title.as_str()), ("score", "1")]))
+            .expect("filters within limits should parse");
+
+        assert_eq!(parsed.len(), 2);
+    }
+
     #[test]
     fn allowlist_accepts_configured_key() {
         let service = CrudFixtureBuilder::new().with_allowlist_mode().build();
@@ -1120,6 +1861,37 @@ mod tests {
         assert_eq!(err.message(), "Invalid filter value");
     }
 
+    #[derive(Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+    #[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+    enum Priority {
+        #[sea_orm(string_value = "low")]
+        Low,
+        #[sea_orm(string_value = "high")]
+        High,
+    }
+
+    #[test]
+    fn parse_enum_matches_variants_ignoring_case() {
+        let op = parse_enum::<Priority>(" HIGH ").expect("known variant should parse");
+
+        assert!(matches!(
+            op,
+            FilterOp::Eq(QueryValue::String(Some(v))) if v == "high"
+        ));
+    }
+
+    #[test]
+    fn parse_enum_rejects_unknown_variants() {
+        for raw in ["urgent", "", "hi*"] {
+            let err = parse_enum::<Priority>(raw).expect_err(raw);
+            assert!(
+                err.message().starts_with("Invalid filter value"),
+                "{}",
+                err.message()
+            );
+        }
+    }
+
     #[test]
     fn all_columns_rejects_denied_column() {
         let service = CrudFixtureBuilder::new().with_deny(&["title"]).build();
@@ -1448,11 +2220,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn map_error_maps_statement_timeouts_to_gateway_timeout() {
+        let service = CrudFixtureBuilder::new().build();
+
+        let err = service.map_error(
+            CrudOp::List,
+            DaoLayerError::Timeout(DbErr::Custom("statement timed out after 10ms".into())),
+        );
+
+        assert!(matches!(err, AppError::GatewayTimeout(_)));
+        assert_eq!(err.message(), "Find failed: the database timed out");
+    }
+
     #[test]
     fn map_error_uses_custom_not_found_message() {
         let service = CrudFixtureBuilder::new()
             .with_errors(CrudErrors {
-                not_found: "Gone",
+                not_found: Some("Gone"),
                 ..CrudErrors::default()
             })
             .build();
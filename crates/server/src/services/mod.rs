@@ -1,3 +1,4 @@
+pub mod auth_event_service;
 pub mod auth_service;
 pub mod context;
 pub mod crud_service;
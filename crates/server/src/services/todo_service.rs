@@ -1,4 +1,5 @@
 use sea_orm::Set;
+use serde_json::Value as JsonValue;
 use uuid::Uuid;
 
 use crate::{
@@ -13,12 +14,33 @@ pub struct TodoService {
     todo_dao: TodoDao,
 }
 
+#[async_trait::async_trait]
 impl CrudService for TodoService {
     type Dao = TodoDao;
 
     fn dao(&self) -> &Self::Dao {
         &self.todo_dao
     }
+
+    fn relations(&self) -> &'static [&'static str] {
+        &["items"]
+    }
+
+    async fn load_relation(
+        &self,
+        model: &todo_list::Model,
+        relation: &str,
+    ) -> Result<JsonValue, AppError> {
+        match relation {
+            "items" => {
+                let items = self.todo_dao.load_items(model).await?;
+                serde_json::to_value(items).map_err(|err| {
+                    AppError::internal_with_source("Failed to serialize todo items", err)
+                })
+            }
+            other => Err(AppError::bad_request(format!("Invalid include: {other}"))),
+        }
+    }
 }
 
 impl TodoService {
@@ -103,11 +125,96 @@ impl TodoService {
 
 #[cfg(test)]
 mod tests {
+    use chrono::{FixedOffset, TimeZone};
     use sea_orm::{DatabaseBackend, MockDatabase};
     use uuid::Uuid;
 
     use super::TodoService;
     use crate::db::dao::{DaoBase, TodoDao};
+    use crate::db::entities::{todo_item, todo_list};
+    use crate::services::crud_service::CrudService;
+
+    fn ts() -> chrono::DateTime<chrono::FixedOffset> {
+        FixedOffset::east_opt(0)
+            .expect("offset should be valid")
+            .with_ymd_and_hms(2026, 1, 1, 0, 0, 0)
+            .single()
+            .expect("timestamp should be valid")
+    }
+
+    fn list_model(id: Uuid) -> todo_list::Model {
+        todo_list::Model {
+            id,
+            created_at: ts(),
+            updated_at: ts(),
+            title: "groceries".to_string(),
+            score: 0,
+        }
+    }
+
+    fn item_model(list_id: Uuid, description: &str) -> todo_item::Model {
+        todo_item::Model {
+            id: Uuid::new_v4(),
+            created_at: ts(),
+            updated_at: ts(),
+            list_id,
+            description: description.to_string(),
+            done: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn find_by_id_with_relations_embeds_included_items() {
+        let list_id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![list_model(list_id)]])
+            .append_query_results([vec![
+                item_model(list_id, "milk"),
+                item_model(list_id, "eggs"),
+            ]])
+            .into_connection();
+        let service = TodoService::new(TodoDao::new(&db));
+        let includes = service
+            .parse_includes(Some("items"))
+            .expect("items include should be accepted");
+
+        let json = service
+            .find_by_id_with_relations(list_id, &includes)
+            .await
+            .expect("find with relations should succeed");
+
+        assert_eq!(json["title"], "groceries");
+        assert_eq!(json["items"][0]["description"], "milk");
+        assert_eq!(json["items"][1]["description"], "eggs");
+    }
+
+    #[tokio::test]
+    async fn find_by_id_with_relations_omits_relations_by_default() {
+        let list_id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![list_model(list_id)]])
+            .into_connection();
+        let service = TodoService::new(TodoDao::new(&db));
+
+        let json = service
+            .find_by_id_with_relations(list_id, &[])
+            .await
+            .expect("find with relations should succeed");
+
+        assert!(json.get("items").is_none());
+    }
+
+    #[test]
+    fn parse_includes_rejects_undeclared_relation() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let service = TodoService::new(TodoDao::new(&db));
+
+        let err = service
+            .parse_includes(Some("items,owner"))
+            .expect_err("unknown include should fail");
+
+        assert_eq!(err.message(), "Invalid include: owner");
+    }
 
     #[tokio::test]
     async fn update_item_returns_not_found_when_missing() {
@@ -1,3 +1,4 @@
+use sea_orm::ConnectionTrait;
 use uuid::Uuid;
 
 use crate::{
@@ -24,6 +25,7 @@ impl UserService {
                 "database operation failed. Please check the logs for more details",
                 db_err,
             )),
+            Err(err @ DaoLayerError::Timeout(_)) => Err(err.into()),
             Err(err) => Err(AppError::bad_request(err.to_string())),
         }
     }
@@ -36,10 +38,16 @@ impl UserService {
                 "database operation failed. Please check the logs for more details",
                 db_err,
             )),
+            Err(err @ DaoLayerError::Timeout(_)) => Err(err.into()),
             Err(err) => Err(AppError::bad_request(err.to_string())),
         }
     }
 
+    /// Whether an account already uses `email`, ignoring case.
+    pub async fn email_taken(&self, email: &str) -> Result<bool, AppError> {
+        Ok(self.user_dao.exists_ci(user::Column::Email, email).await?)
+    }
+
     pub async fn create_user(
         &self,
         email: &str,
@@ -52,6 +60,38 @@ impl UserService {
             .await?)
     }
 
+    /// Like [`create_user`](Self::create_user) but runs on `conn`, e.g. a transaction.
+    pub async fn create_user_in<C>(
+        &self,
+        conn: &C,
+        email: &str,
+        password_hash: &str,
+        role: &str,
+    ) -> Result<user::Model, AppError>
+    where
+        C: ConnectionTrait + Sync,
+    {
+        Ok(self
+            .user_dao
+            .create_user_in(conn, email, password_hash, role)
+            .await?)
+    }
+
+    pub async fn set_password_hash(
+        &self,
+        user_id: &Uuid,
+        password_hash: &str,
+    ) -> Result<(), AppError> {
+        Ok(self
+            .user_dao
+            .set_password_hash(user_id, password_hash)
+            .await?)
+    }
+
+    pub async fn set_role(&self, user_id: &Uuid, role: &str) -> Result<user::Model, AppError> {
+        Ok(self.user_dao.set_role(user_id, role).await?)
+    }
+
     pub async fn set_last_login(
         &self,
         user_id: &Uuid,
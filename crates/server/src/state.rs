@@ -1,14 +1,30 @@
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc};
 
 use sea_orm::DatabaseConnection;
 
-use crate::{auth::providers::AuthProviders, config::AppConfig};
+use crate::{
+    auth::{lockout::LoginLockout, providers::AuthProviders},
+    config::AppConfig,
+    db::dao::{DaoContext, client_statement_timeout},
+    routes::middleware::{MaintenanceMode, RateLimiter},
+};
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: AppConfig,
     pub db: DatabaseConnection,
+    /// Read replica, if `database.replica_url` is set. Reach it through
+    /// `ServiceContext::read_daos` rather than directly.
+    pub replica: Option<DatabaseConnection>,
     pub auth_providers: AuthProviders,
+    /// Failed-login lockout from `auth.lockout`; `None` when disabled.
+    pub login_lockout: Option<LoginLockout>,
+    /// Starts from `general.maintenance_mode`; toggled at `/admin/maintenance`.
+    pub maintenance: MaintenanceMode,
+    /// Request limit from `general.rate_limit`; `None` when disabled.
+    pub rate_limiter: Option<RateLimiter>,
+    /// Parsed `general.trusted_proxies`.
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl AppState {
@@ -17,10 +33,45 @@ impl AppState {
         db: DatabaseConnection,
         auth_providers: AuthProviders,
     ) -> Arc<Self> {
+        Self::with_replica(config, db, None, auth_providers)
+    }
+
+    pub fn with_replica(
+        config: AppConfig,
+        db: DatabaseConnection,
+        replica: Option<DatabaseConnection>,
+        auth_providers: AuthProviders,
+    ) -> Arc<Self> {
+        // Same statement timeout as `ServiceContext::from_state` gives request DAOs.
+        let statement_timeout = config
+            .database
+            .as_ref()
+            .and_then(|database| client_statement_timeout(&db, database.statement_timeout_ms));
+        let daos = DaoContext::new(&db).with_statement_timeout(statement_timeout);
+        let login_lockout = config
+            .auth
+            .as_ref()
+            .and_then(|auth| LoginLockout::from_config(auth, &daos));
+        let maintenance = MaintenanceMode::new(
+            config.general.maintenance_mode,
+            config.general.maintenance_retry_after_secs,
+        );
+        let rate_limiter = RateLimiter::from_config(&config.general.rate_limit);
+        let trusted_proxies = config
+            .general
+            .trusted_proxies
+            .iter()
+            .filter_map(|proxy| proxy.parse().ok())
+            .collect();
         Arc::new(Self {
             db,
+            replica,
             config,
             auth_providers,
+            login_lockout,
+            maintenance,
+            rate_limiter,
+            trusted_proxies,
         })
     }
 }
@@ -0,0 +1,91 @@
+use std::{fmt, ops::Range, pin::Pin, sync::Arc};
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::StorageConfig;
+
+use super::local::LocalFileStorage;
+
+pub type ByteStream<'a> = Pin<Box<dyn Stream<Item = Result<Bytes, StorageError>> + Send + 'a>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackendId {
+    Local,
+}
+
+impl StorageBackendId {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StorageBackendId::Local => "local",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StoredFile {
+    pub id: Uuid,
+    pub size: u64,
+    pub content_type: String,
+    pub file_name: Option<String>,
+}
+
+pub struct FileUpload<'a> {
+    pub file_name: Option<String>,
+    pub content_type: String,
+    pub max_bytes: u64,
+    pub body: ByteStream<'a>,
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    TooLarge { limit: u64 },
+    Upload(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::TooLarge { limit } => {
+                write!(f, "file exceeds max upload size of {limit} bytes")
+            }
+            StorageError::Upload(message) => write!(f, "invalid upload: {message}"),
+            StorageError::Io(err) => write!(f, "storage io error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+/// Storage backend for uploaded files.
+///
+/// Implementations consume `FileUpload::body` incrementally and must reject
+/// uploads larger than `FileUpload::max_bytes` without keeping partial data.
+#[async_trait]
+pub trait FileStorage: Send + Sync {
+    fn id(&self) -> StorageBackendId;
+
+    async fn store(&self, upload: FileUpload<'_>) -> Result<StoredFile, StorageError>;
+    async fn metadata(&self, id: Uuid) -> Result<Option<StoredFile>, StorageError>;
+    /// Streams bytes `range` of a stored file. Callers check `metadata` first,
+    /// so `range` lies within the file.
+    async fn read(&self, id: Uuid, range: Range<u64>) -> Result<ByteStream<'static>, StorageError>;
+    async fn delete(&self, id: Uuid) -> Result<bool, StorageError>;
+}
+
+pub fn build_storage(cfg: &StorageConfig) -> Arc<dyn FileStorage> {
+    match cfg.backend {
+        StorageBackendId::Local => Arc::new(LocalFileStorage::new(&cfg.local_dir)),
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use futures_util::{StreamExt, stream};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use uuid::Uuid;
+
+use super::backend::{
+    ByteStream, FileStorage, FileUpload, StorageBackendId, StorageError, StoredFile,
+};
+
+const READ_CHUNK_BYTES: u64 = 64 * 1024;
+
+pub struct LocalFileStorage {
+    root: PathBuf,
+}
+
+impl LocalFileStorage {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn blob_path(&self, id: Uuid) -> PathBuf {
+        self.root.join(id.to_string())
+    }
+
+    fn metadata_path(&self, id: Uuid) -> PathBuf {
+        self.root.join(format!("{id}.json"))
+    }
+
+    async fn write_blob(
+        &self,
+        path: &Path,
+        upload: &mut FileUpload<'_>,
+    ) -> Result<u64, StorageError> {
+        let mut file = tokio::fs::File::create(path).await?;
+        let mut size: u64 = 0;
+        while let Some(chunk) = upload.body.next().await {
+            let chunk = chunk?;
+            size = size.saturating_add(chunk.len() as u64);
+            if size > upload.max_bytes {
+                return Err(StorageError::TooLarge {
+                    limit: upload.max_bytes,
+                });
+            }
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        Ok(size)
+    }
+}
+
+#[async_trait]
+impl FileStorage for LocalFileStorage {
+    fn id(&self) -> StorageBackendId {
+        StorageBackendId::Local
+    }
+
+    async fn store(&self, mut upload: FileUpload<'_>) -> Result<StoredFile, StorageError> {
+        tokio::fs::create_dir_all(&self.root).await?;
+
+        let id = Uuid::new_v4();
+        let blob_path = self.blob_path(id);
+        let size = match self.write_blob(&blob_path, &mut upload).await {
+            Ok(size) => size,
+            Err(err) => {
+                let _ = tokio::fs::remove_file(&blob_path).await;
+                return Err(err);
+            }
+        };
+
+        let stored = StoredFile {
+            id,
+            size,
+            content_type: upload.content_type,
+            file_name: upload.file_name,
+        };
+        let metadata = serde_json::to_vec(&stored)
+            .map_err(|err| StorageError::Io(std::io::Error::other(err)))?;
+        if let Err(err) = tokio::fs::write(self.metadata_path(id), metadata).await {
+            let _ = tokio::fs::remove_file(&blob_path).await;
+            return Err(err.into());
+        }
+
+        Ok(stored)
+    }
+
+    async fn metadata(&self, id: Uuid) -> Result<Option<StoredFile>, StorageError> {
+        let raw = match tokio::fs::read(self.metadata_path(id)).await {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        serde_json::from_slice(&raw)
+            .map(Some)
+            .map_err(|err| StorageError::Io(std::io::Error::other(err)))
+    }
+
+    async fn read(&self, id: Uuid, range: Range<u64>) -> Result<ByteStream<'static>, StorageError> {
+        let mut file = tokio::fs::File::open(self.blob_path(id)).await?;
+        file.seek(std::io::SeekFrom::Start(range.start)).await?;
+        let remaining = range.end.saturating_sub(range.start);
+        let chunks = stream::try_unfold((file, remaining), |(mut file, remaining)| async move {
+            if remaining == 0 {
+                return Ok(None);
+            }
+            let mut chunk = vec![0; remaining.min(READ_CHUNK_BYTES) as usize];
+            let read = file.read(&mut chunk).await?;
+            if read == 0 {
+                return Err(StorageError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+            }
+            chunk.truncate(read);
+            Ok(Some((Bytes::from(chunk), (file, remaining - read as u64))))
+        });
+        Ok(Box::pin(chunks))
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, StorageError> {
+        let removed = match tokio::fs::remove_file(self.blob_path(id)).await {
+            Ok(()) => true,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+            Err(err) => return Err(err.into()),
+        };
+        match tokio::fs::remove_file(self.metadata_path(id)).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use axum::body::Bytes;
+    use futures_util::{StreamExt, stream};
+    use uuid::Uuid;
+
+    use super::LocalFileStorage;
+    use crate::storage::{FileStorage, FileUpload, StorageError};
+
+    fn temp_root() -> PathBuf {
+        std::env::temp_dir().join(format!("rust-oxide-storage-{}", Uuid::new_v4()))
+    }
+
+    fn upload(chunks: Vec<&'static [u8]>, max_bytes: u64) -> FileUpload<'static> {
+        let chunks = chunks
+            .into_iter()
+            .map(|chunk| Ok::<_, StorageError>(Bytes::from_static(chunk)));
+        FileUpload {
+            file_name: Some("notes.txt".to_string()),
+            content_type: "text/plain".to_string(),
+            max_bytes,
+            body: Box::pin(stream::iter(chunks)),
+        }
+    }
+
+    fn file_count(root: &Path) -> usize {
+        std::fs::read_dir(root)
+            .map(|entries| entries.count())
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn store_persists_streamed_chunks_with_metadata() {
+        let root = temp_root();
+        let storage = LocalFileStorage::new(&root);
+
+        let stored = storage
+            .store(upload(vec![b"hello ", b"world"], 1024))
+            .await
+            .expect("store should succeed");
+
+        let contents = std::fs::read(root.join(stored.id.to_string()))
+            .expect("stored blob should be readable");
+        let metadata = storage
+            .metadata(stored.id)
+            .await
+            .expect("metadata lookup should succeed")
+            .expect("metadata should exist");
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(contents, b"hello world");
+        assert_eq!(stored.size, 11);
+        assert_eq!(metadata, stored);
+        assert_eq!(metadata.content_type, "text/plain");
+    }
+
+    #[tokio::test]
+    async fn store_rejects_upload_over_limit_and_removes_partial_file() {
+        let root = temp_root();
+        let storage = LocalFileStorage::new(&root);
+
+        let err = storage
+            .store(upload(vec![b"1234", b"5678"], 6))
+            .await
+            .expect_err("store should fail");
+        let remaining = file_count(&root);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(matches!(err, StorageError::TooLarge { limit: 6 }));
+        assert_eq!(remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn read_streams_the_requested_byte_range() {
+        let root = temp_root();
+        let storage = LocalFileStorage::new(&root);
+        let stored = storage
+            .store(upload(vec![b"hello ", b"world"], 1024))
+            .await
+            .expect("store should succeed");
+
+        let chunks: Vec<Bytes> = storage
+            .read(stored.id, 3..8)
+            .await
+            .expect("read should open the blob")
+            .map(|chunk| chunk.expect("chunk should read"))
+            .collect()
+            .await;
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(chunks.concat(), b"lo wo");
+    }
+
+    #[tokio::test]
+    async fn delete_removes_blob_and_metadata() {
+        let root = temp_root();
+        let storage = LocalFileStorage::new(&root);
+        let stored = storage
+            .store(upload(vec![b"bye"], 1024))
+            .await
+            .expect("store should succeed");
+
+        let removed = storage
+            .delete(stored.id)
+            .await
+            .expect("delete should succeed");
+        let metadata = storage
+            .metadata(stored.id)
+            .await
+            .expect("metadata lookup should succeed");
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(removed);
+        assert!(metadata.is_none());
+    }
+}
@@ -0,0 +1,7 @@
+mod backend;
+mod local;
+
+pub use backend::{
+    ByteStream, FileStorage, FileUpload, StorageBackendId, StorageError, StoredFile, build_storage,
+};
+pub use local::LocalFileStorage;
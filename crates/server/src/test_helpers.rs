@@ -1,12 +1,23 @@
 use std::sync::Arc;
 
 use axum::Router;
-use sea_orm::{DatabaseBackend, MockDatabase};
+use chrono::Utc;
+use rand::Rng;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseBackend, EntityTrait, IdenStatic, Iterable,
+    MockDatabase, PrimaryKeyToColumn,
+    sea_query::{ColumnType, Value},
+};
+use uuid::Uuid;
 
 use crate::{
-    auth::{bootstrap::build_providers, providers::AuthProviderId},
-    config::{AppConfig, AuthConfig},
-    realtime::{AppRealtimeVerifier, SocketAppState, SocketServerHandle},
+    auth::{
+        RegistrationMode, bootstrap::build_providers, password::PasswordKdf,
+        providers::AuthProviderId,
+    },
+    config::{AppConfig, AuthConfig, LockoutConfig, TokenSourceConfig},
+    db::dao::{DaoBase, DaoResult},
+    realtime::spawn_realtime_runtime,
     routes::router,
     services::ServiceContext,
     state::AppState,
@@ -16,10 +27,20 @@ pub fn test_router(secret: &[u8]) -> Router {
     let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
     let mut cfg = AppConfig::from_env().expect("load app config");
     cfg.auth = Some(AuthConfig {
+        enabled: true,
         provider: AuthProviderId::Local,
         jwt_secret: String::from_utf8_lossy(secret).into_owned(),
+        jwt_kid: None,
+        jwt_verification_keys: Vec::new(),
         admin_email: "admin@example.com".to_string(),
         admin_password: "adminpassword".to_string(),
+        registration_mode: RegistrationMode::Open,
+        single_session: false,
+        bind_refresh_tokens: false,
+        password_kdf: PasswordKdf::Argon2id,
+        password_history: 0,
+        lockout: LockoutConfig::default(),
+        token: TokenSourceConfig::default(),
     });
     let services = ServiceContext::new(&db);
     let providers = build_providers(
@@ -27,11 +48,146 @@ pub fn test_router(secret: &[u8]) -> Router {
         &services,
     )
     .expect("create auth providers");
-    let realtime = SocketServerHandle::spawn(cfg.realtime.clone());
-    let realtime_runtime = Arc::new(SocketAppState::new(
-        realtime,
-        AppRealtimeVerifier::new(providers.clone()),
-    ));
+    let realtime_runtime = spawn_realtime_runtime(&cfg.realtime, providers.clone());
     let state = AppState::new(cfg, db, providers);
     router(Arc::clone(&state), realtime_runtime)
 }
+
+type SeedModel<D> = <<D as DaoBase>::Entity as EntityTrait>::Model;
+type SeedActiveModel<D> = <<D as DaoBase>::Entity as EntityTrait>::ActiveModel;
+
+/// Inserts `count` rows with generated values for every non-key column.
+///
+/// Text values embed a per-call random tag and the row index, and integers
+/// count up from a random base, so unique columns get distinct values.
+pub async fn seed<D: DaoBase>(dao: &D, count: usize) -> DaoResult<Vec<SeedModel<D>>> {
+    seed_with(dao, count, |_, _| {}).await
+}
+
+/// Like [`seed`], but lets `customize` adjust each row before insert, for
+/// example to point a foreign key at an existing parent.
+pub async fn seed_with<D, F>(dao: &D, count: usize, customize: F) -> DaoResult<Vec<SeedModel<D>>>
+where
+    D: DaoBase,
+    F: Fn(usize, &mut SeedActiveModel<D>) + Send + Sync,
+{
+    let mut rng = rand::thread_rng();
+    let tag: u32 = rng.gen_range(0..1_000_000);
+    let int_base: i64 = rng.gen_range(0..1_000_000) * 1_000;
+    let primary_keys: Vec<&'static str> =
+        <<D::Entity as EntityTrait>::PrimaryKey as Iterable>::iter()
+            .map(|pk| pk.into_column().as_str())
+            .collect();
+
+    let mut rows = Vec::with_capacity(count);
+    for index in 0..count {
+        let mut active = SeedActiveModel::<D>::default();
+        for column in <<D::Entity as EntityTrait>::Column as Iterable>::iter() {
+            if primary_keys.contains(&column.as_str()) {
+                continue;
+            }
+            let def = column.def();
+            if let Some(value) = seed_value(
+                def.get_column_type(),
+                column.as_str(),
+                tag,
+                int_base + index as i64,
+                index,
+            ) {
+                active.set(column, value);
+            }
+        }
+        customize(index, &mut active);
+        rows.push(dao.create(active).await?);
+    }
+    Ok(rows)
+}
+
+fn seed_value(
+    column_type: &ColumnType,
+    name: &str,
+    tag: u32,
+    number: i64,
+    index: usize,
+) -> Option<Value> {
+    let text = if name.contains("email") {
+        format!("{name}-{tag}-{index}@example.com")
+    } else {
+        format!("{name}-{tag}-{index}")
+    };
+    let value = match column_type {
+        ColumnType::Char(_) => Value::Char(Some(char::from(b'a' + (index % 26) as u8))),
+        ColumnType::String(_) | ColumnType::Text => Value::String(Some(text)),
+        ColumnType::TinyInteger => Value::TinyInt(Some((index % i8::MAX as usize) as i8)),
+        ColumnType::SmallInteger => Value::SmallInt(Some((index % i16::MAX as usize) as i16)),
+        ColumnType::Integer => Value::Int(Some(number as i32)),
+        ColumnType::BigInteger => Value::BigInt(Some(number)),
+        ColumnType::TinyUnsigned => Value::TinyUnsigned(Some((index % u8::MAX as usize) as u8)),
+        ColumnType::SmallUnsigned => Value::SmallUnsigned(Some((index % u16::MAX as usize) as u16)),
+        ColumnType::Unsigned => Value::Unsigned(Some(number as u32)),
+        ColumnType::BigUnsigned => Value::BigUnsigned(Some(number as u64)),
+        ColumnType::Float => Value::Float(Some(number as f32)),
+        ColumnType::Double => Value::Double(Some(number as f64)),
+        ColumnType::Boolean => Value::Bool(Some(index.is_multiple_of(2))),
+        ColumnType::Uuid => Value::Uuid(Some(Uuid::new_v4())),
+        ColumnType::DateTime | ColumnType::Timestamp => {
+            Value::ChronoDateTime(Some(Utc::now().naive_utc()))
+        }
+        ColumnType::TimestampWithTimeZone => {
+            Value::ChronoDateTimeWithTimeZone(Some(Utc::now().fixed_offset()))
+        }
+        ColumnType::Date => Value::ChronoDate(Some(Utc::now().date_naive())),
+        ColumnType::Time => Value::ChronoTime(Some(Utc::now().time())),
+        ColumnType::Json | ColumnType::JsonBinary => {
+            Value::Json(Some(Box::new(serde_json::json!({ "seed": index }))))
+        }
+        ColumnType::Enum { variants, .. } => Value::String(Some(
+            variants.get(index % variants.len().max(1))?.to_string(),
+        )),
+        _ => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use sea_orm::{ConnectOptions, Database};
+
+    use super::seed;
+    use crate::db::{
+        dao::{DaoBase, DaoContext},
+        entities::{todo_list, user},
+    };
+
+    #[tokio::test]
+    async fn seed_inserts_requested_number_of_distinct_rows() {
+        // A single connection keeps every query on the same in-memory database.
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1).sqlx_logging(false);
+        let db = Database::connect(options)
+            .await
+            .expect("sqlite should open");
+        db.get_schema_builder()
+            .register(todo_list::Entity)
+            .register(user::Entity)
+            .sync(&db)
+            .await
+            .expect("tables should be created");
+        let daos = DaoContext::new(&db);
+
+        seed(&daos.todo(), 10).await.expect("seed should succeed");
+        let users = seed(&daos.user(), 3).await.expect("seed should succeed");
+
+        let page = daos
+            .todo()
+            .find(1, 100, None, |query| query)
+            .await
+            .expect("list should succeed");
+        assert_eq!(page.data.len(), 10);
+        let emails: HashSet<_> = users.iter().map(|user| user.email.as_str()).collect();
+        assert_eq!(emails.len(), 3);
+        assert!(emails.iter().all(|email| email.ends_with("@example.com")));
+    }
+}
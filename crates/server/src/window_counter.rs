@@ -0,0 +1,146 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Longest window a counter accepts; longer windows are clamped to it.
+/// `auth.lockout.window_secs` and `general.rate_limit.window_secs` are
+/// validated against it.
+pub const MAX_WINDOW: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+struct Window {
+    hits: u32,
+    expires_at: Instant,
+}
+
+struct Windows {
+    by_key: HashMap<String, Window>,
+    next_prune: Instant,
+}
+
+/// In-memory fixed-window hit counters per key.
+///
+/// The first hit after a key's previous window lapsed opens a new window of
+/// the given length. Counts are per process.
+pub struct FixedWindowCounter {
+    windows: Mutex<Windows>,
+}
+
+impl FixedWindowCounter {
+    pub fn new() -> Self {
+        Self {
+            windows: Mutex::new(Windows {
+                by_key: HashMap::new(),
+                next_prune: Instant::now(),
+            }),
+        }
+    }
+
+    /// Hits recorded for `key` in its current, unexpired window.
+    pub fn hits(&self, key: &str) -> u32 {
+        let windows = self.windows.lock().expect("window counter mutex poisoned");
+        windows
+            .by_key
+            .get(key)
+            .filter(|window| window.expires_at > Instant::now())
+            .map_or(0, |window| window.hits)
+    }
+
+    /// Counts one hit for `key` unless its window already holds `limit`, and
+    /// returns the new total. `Err` holds the time left in the full window.
+    pub fn hit(&self, key: &str, window: Duration, limit: u32) -> Result<u32, Duration> {
+        let now = Instant::now();
+        let expires_at = now.checked_add(window.min(MAX_WINDOW)).unwrap_or(now);
+        let mut windows = self.windows.lock().expect("window counter mutex poisoned");
+        // Drop lapsed windows once per window length so one-off keys cannot
+        // grow the map forever, without a full scan on every hit.
+        if now >= windows.next_prune {
+            windows.by_key.retain(|_, window| window.expires_at > now);
+            windows.next_prune = expires_at;
+        }
+        let entry = windows.by_key.entry(key.to_string()).or_insert(Window {
+            hits: 0,
+            expires_at,
+        });
+        if entry.expires_at <= now {
+            *entry = Window {
+                hits: 0,
+                expires_at,
+            };
+        }
+        if entry.hits >= limit {
+            return Err(entry.expires_at.saturating_duration_since(now));
+        }
+        entry.hits += 1;
+        Ok(entry.hits)
+    }
+
+    pub fn reset(&self, key: &str) {
+        self.windows
+            .lock()
+            .expect("window counter mutex poisoned")
+            .by_key
+            .remove(key);
+    }
+
+    /// Keys with a window, lapsed or not, until the next prune.
+    pub fn tracked_keys(&self) -> usize {
+        self.windows
+            .lock()
+            .expect("window counter mutex poisoned")
+            .by_key
+            .len()
+    }
+}
+
+impl Default for FixedWindowCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{FixedWindowCounter, MAX_WINDOW};
+
+    #[test]
+    fn hit_counts_up_to_the_limit_per_key() {
+        let counter = FixedWindowCounter::new();
+        let window = Duration::from_secs(60);
+
+        assert_eq!(counter.hit("a", window, 2), Ok(1));
+        assert_eq!(counter.hit("a", window, 2), Ok(2));
+        let retry_after = counter.hit("a", window, 2).expect_err("limit reached");
+        assert!(retry_after <= window);
+        assert_eq!(counter.hits("a"), 2);
+        assert_eq!(counter.hit("b", window, 2), Ok(1));
+
+        counter.reset("a");
+        assert_eq!(counter.hits("a"), 0);
+    }
+
+    #[test]
+    fn lapsed_windows_restart_and_are_pruned_on_the_next_sweep() {
+        let counter = FixedWindowCounter::new();
+
+        assert_eq!(counter.hit("a", Duration::ZERO, 1), Ok(1));
+        assert_eq!(counter.hit("a", Duration::ZERO, 1), Ok(1));
+        assert_eq!(counter.hit("b", Duration::ZERO, 1), Ok(1));
+
+        assert_eq!(counter.tracked_keys(), 1);
+    }
+
+    #[test]
+    fn oversized_windows_are_clamped_instead_of_overflowing() {
+        let counter = FixedWindowCounter::new();
+
+        assert_eq!(counter.hit("a", Duration::MAX, 1), Ok(1));
+        let retry_after = counter
+            .hit("a", Duration::MAX, 1)
+            .expect_err("limit reached");
+        assert!(retry_after <= MAX_WINDOW);
+    }
+}
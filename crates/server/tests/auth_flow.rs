@@ -11,12 +11,12 @@ use uuid::Uuid;
 
 use rust_oxide::{
     auth::{
-        Claims, Role, bootstrap::build_providers, jwt::now_unix, password,
+        Claims, RegistrationMode, Role, bootstrap::build_providers, jwt::now_unix, password,
         providers::AuthProviderId,
     },
-    config::{AppConfig, AuthConfig},
+    config::{AppConfig, AuthConfig, LockoutConfig, TokenSourceConfig},
     db::dao::DaoContext,
-    realtime::{AppRealtimeVerifier, SocketAppState, SocketServerHandle},
+    realtime::{SocketAppState, spawn_realtime_runtime},
     routes::{API_PREFIX, router},
     services::ServiceContext,
     state::AppState,
@@ -40,7 +40,10 @@ fn api_path(path: &str) -> String {
     format!("{API_PREFIX}{path}")
 }
 
-async fn app_with_db() -> (std::sync::Arc<AppState>, std::sync::Arc<SocketAppState>) {
+async fn app_with_db() -> (
+    std::sync::Arc<AppState>,
+    Option<std::sync::Arc<SocketAppState>>,
+) {
     let cfg = AppConfig::from_env().expect("load app config");
     let db_cfg = cfg
         .database
@@ -63,21 +66,38 @@ async fn app_with_db() -> (std::sync::Arc<AppState>, std::sync::Arc<SocketAppSta
     build_state(cfg, db)
 }
 
+async fn app_with_sqlite() -> (
+    std::sync::Arc<AppState>,
+    Option<std::sync::Arc<SocketAppState>>,
+) {
+    // A single connection keeps every query on the same in-memory database.
+    let mut opt = ConnectOptions::new("sqlite::memory:");
+    opt.max_connections(1).sqlx_logging(false);
+    let db = Database::connect(opt).await.expect("sqlite should open");
+    db.get_schema_registry("rust_oxide::db::entities::*")
+        .sync(&db)
+        .await
+        .expect("sync schema");
+
+    let mut cfg = AppConfig::from_env().expect("load app config");
+    cfg.auth = Some(test_auth_config("test-secret".to_string()));
+    build_state(cfg, db)
+}
+
 fn build_state(
     cfg: AppConfig,
     db: DatabaseConnection,
-) -> (std::sync::Arc<AppState>, std::sync::Arc<SocketAppState>) {
+) -> (
+    std::sync::Arc<AppState>,
+    Option<std::sync::Arc<SocketAppState>>,
+) {
     let services = ServiceContext::new(&db);
     let providers = build_providers(
         cfg.auth.as_ref().expect("auth config should be present"),
         &services,
     )
     .expect("create auth providers");
-    let realtime = SocketServerHandle::spawn(cfg.realtime.clone());
-    let realtime_runtime = std::sync::Arc::new(SocketAppState::new(
-        realtime,
-        AppRealtimeVerifier::new(providers.clone()),
-    ));
+    let realtime_runtime = spawn_realtime_runtime(&cfg.realtime, providers.clone());
     let state = AppState::new(cfg, db, providers);
     (state, realtime_runtime)
 }
@@ -211,12 +231,65 @@ async fn admin_requires_role() {
     assert_eq!(res.status(), StatusCode::FORBIDDEN);
 }
 
+async fn post_login(
+    app: axum::Router,
+    email: &str,
+    password: &str,
+) -> (StatusCode, serde_json::Value) {
+    let payload = json!({"email": email, "password": password});
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(api_path("/login"))
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = res.status();
+    let body = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    (status, json)
+}
+
+#[tokio::test]
+async fn seeded_user_logs_in_and_wrong_password_is_rejected() {
+    let (state, realtime_runtime) = app_with_sqlite().await;
+    let hash = password::hash_password("password123").unwrap();
+    DaoContext::new(&state.db)
+        .user()
+        .create_user("seeded@example.com", &hash, Role::User.as_str())
+        .await
+        .expect("seed user");
+    let app = router(state, realtime_runtime);
+
+    let (status, json) = post_login(app.clone(), "seeded@example.com", "password123").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(json["data"]["access_token"].as_str().is_some());
+
+    let (status, json) = post_login(app, "seeded@example.com", "wrong-password").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["message"], "Invalid credentials");
+}
+
 fn test_auth_config(jwt_secret: String) -> AuthConfig {
     AuthConfig {
+        enabled: true,
         provider: AuthProviderId::Local,
         jwt_secret,
+        jwt_kid: None,
+        jwt_verification_keys: Vec::new(),
         admin_email: "admin@example.com".to_string(),
         admin_password: "adminpassword".to_string(),
+        registration_mode: RegistrationMode::Open,
+        single_session: false,
+        bind_refresh_tokens: false,
+        password_kdf: password::PasswordKdf::Argon2id,
+        password_history: 0,
+        lockout: LockoutConfig::default(),
+        token: TokenSourceConfig::default(),
     }
 }
 
@@ -225,8 +298,10 @@ fn login_token(secret: &[u8], roles: Vec<Role>) -> String {
     let claims = Claims {
         sub: "user".into(),
         roles,
+        scopes: Vec::new(),
         iat,
         exp: iat + 3600,
+        act: None,
     };
 
     let mut header = Header::new(Algorithm::HS256);
@@ -0,0 +1,13 @@
+// Compiles the build-script modules so their `#[cfg(test)]` suites run.
+#![allow(dead_code)]
+
+#[path = "../build/entities.rs"]
+mod entities;
+#[path = "../build/openapi.rs"]
+mod openapi;
+#[path = "../build/postman.rs"]
+mod postman;
+#[path = "../build/routes.rs"]
+mod routes;
+#[path = "../build/utils.rs"]
+mod utils;
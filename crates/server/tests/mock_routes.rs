@@ -1,26 +1,39 @@
 use axum::{
-    Router,
+    Json, Router,
     body::{self, Body},
-    http::{Request, StatusCode},
+    http::{Request, Response, StatusCode},
     middleware,
+    routing::get,
+};
+use sea_orm::{
+    ConnectOptions, Database, DatabaseBackend, DatabaseConnection, EntityTrait, MockDatabase,
 };
-use sea_orm::{DatabaseBackend, MockDatabase};
 use serde_json::json;
 use tower::ServiceExt;
 use uuid::Uuid;
 
 use rust_oxide::{
     auth::{
-        Role,
+        RegistrationMode, Role,
         bootstrap::build_providers,
         jwt::{JwtKeys, encode_token, make_access_claims},
+        password::PasswordKdf,
         providers::AuthProviderId,
     },
-    config::{AppConfig, AuthConfig},
-    realtime::{AppRealtimeVerifier, SocketAppState, SocketServerHandle},
+    config::{AppConfig, AuthConfig, ErrorFormat, LockoutConfig, TokenSourceConfig},
+    db::{
+        dao::{DaoBase, JobDao},
+        entities::{auth_event, job, user},
+    },
+    jobs::{job_registry, run_jobs_once},
+    realtime::{SocketAppState, spawn_realtime_runtime},
     routes::{
         API_PREFIX,
-        middleware::{catch_panic_layer, json_error_middleware},
+        middleware::{
+            ProtectedRouter, RequireScope, RequiredScope, catch_panic_layer, json_error_middleware,
+            problem_json_middleware,
+        },
+        route_list::routes,
         router,
     },
     services::ServiceContext,
@@ -31,39 +44,69 @@ fn api_path(path: &str) -> String {
     format!("{API_PREFIX}{path}")
 }
 
-fn build_state(secret: &[u8]) -> (std::sync::Arc<AppState>, std::sync::Arc<SocketAppState>) {
-    let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+fn build_state(
+    secret: &[u8],
+    db: DatabaseConnection,
+    configure: impl FnOnce(&mut AppConfig),
+) -> (
+    std::sync::Arc<AppState>,
+    Option<std::sync::Arc<SocketAppState>>,
+) {
     let mut cfg = AppConfig::from_env().expect("load app config");
     cfg.auth = Some(AuthConfig {
+        enabled: true,
         provider: AuthProviderId::Local,
         jwt_secret: String::from_utf8_lossy(secret).into_owned(),
+        jwt_kid: None,
+        jwt_verification_keys: Vec::new(),
         admin_email: "admin@example.com".to_string(),
         admin_password: "adminpassword".to_string(),
+        registration_mode: RegistrationMode::Open,
+        single_session: false,
+        bind_refresh_tokens: false,
+        password_kdf: PasswordKdf::Argon2id,
+        password_history: 0,
+        lockout: LockoutConfig::default(),
+        token: TokenSourceConfig::default(),
     });
+    configure(&mut cfg);
     let services = ServiceContext::new(&db);
     let providers = build_providers(
         cfg.auth.as_ref().expect("auth config should be present"),
         &services,
     )
     .expect("create auth providers");
-    let realtime = SocketServerHandle::spawn(cfg.realtime.clone());
-    let realtime_runtime = std::sync::Arc::new(SocketAppState::new(
-        realtime,
-        AppRealtimeVerifier::new(providers.clone()),
-    ));
+    let realtime_runtime = spawn_realtime_runtime(&cfg.realtime, providers.clone());
     let state = AppState::new(cfg, db, providers);
     (state, realtime_runtime)
 }
 
 fn app(secret: &[u8]) -> Router {
-    let (state, realtime_runtime) = build_state(secret);
+    app_with_config(secret, |_| {})
+}
+
+fn app_with_config(secret: &[u8], configure: impl FnOnce(&mut AppConfig)) -> Router {
+    let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+    app_with_db(secret, db, configure)
+}
+
+fn app_with_db(
+    secret: &[u8],
+    db: DatabaseConnection,
+    configure: impl FnOnce(&mut AppConfig),
+) -> Router {
+    let (state, realtime_runtime) = build_state(secret, db, configure);
     router(state, realtime_runtime)
         .layer(middleware::from_fn(json_error_middleware))
         .layer(catch_panic_layer())
 }
 
 fn auth_header(secret: &[u8], roles: Vec<Role>) -> String {
-    let claims = make_access_claims(&Uuid::new_v4(), roles, 3600);
+    auth_header_for(secret, Uuid::new_v4(), roles)
+}
+
+fn auth_header_for(secret: &[u8], user_id: Uuid, roles: Vec<Role>) -> String {
+    let claims = make_access_claims(&user_id, roles, 3600);
     let jwt = JwtKeys::from_secret(secret);
     let token = encode_token(&jwt, &claims).expect("encode token");
     format!("Bearer {token}")
@@ -132,7 +175,64 @@ async fn todo_crud_list_rejects_invalid_pagination_without_touching_db() {
     .await;
 
     assert_eq!(status, StatusCode::BAD_REQUEST);
-    assert_eq!(json["message"], "Invalid pagination: page=0 page_size=25");
+    assert_eq!(
+        json["message"],
+        "Invalid pagination: page must be 1 or greater (pages are numbered from 1)"
+    );
+}
+
+#[tokio::test]
+async fn todo_crud_get_rejects_non_uuid_id_with_json_error() {
+    let secret = b"mock-routes-secret";
+    let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+    let (state, realtime_runtime) = build_state(secret, db, |_| {});
+    // No json_error_middleware: the extractor itself must answer in JSON.
+    let response = router(state, realtime_runtime)
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(api_path("/todo-crud/not-a-uuid"))
+                .header("accept", "text/html")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("request should succeed");
+    let status = response.status();
+    let content_type = response.headers()["content-type"].clone();
+    let body = body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body should read");
+    let json: serde_json::Value = serde_json::from_slice(&body).expect("body should be json");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(content_type, "application/json");
+    assert_eq!(json["status"], 400);
+    assert!(json["data"].is_null());
+    assert!(
+        json["message"]
+            .as_str()
+            .unwrap_or("")
+            .starts_with("Invalid path parameter: "),
+        "unexpected message: {}",
+        json["message"]
+    );
+
+    let problem = app(secret).layer(middleware::from_fn_with_state(
+        ErrorFormat::Problem,
+        problem_json_middleware,
+    ));
+    let (status, json) = json_response(
+        problem,
+        Request::builder()
+            .method("GET")
+            .uri(api_path("/todo-crud/not-a-uuid"))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["type"], "/problems/invalid_path_param");
 }
 
 #[tokio::test]
@@ -152,6 +252,418 @@ async fn todo_crud_list_rejects_invalid_filter_value_shape() {
     assert_eq!(json["message"], "Invalid filter value");
 }
 
+fn multipart_request(auth: Option<String>, file_name: &str, contents: &str) -> Request<Body> {
+    let boundary = "mock-routes-boundary";
+    let body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: text/plain\r\n\r\n{contents}\r\n--{boundary}--\r\n"
+    );
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri(api_path("/files"))
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={boundary}"),
+        );
+    if let Some(auth) = auth {
+        builder = builder.header("authorization", auth);
+    }
+    builder.body(Body::from(body)).unwrap()
+}
+
+#[tokio::test]
+async fn file_upload_persists_file_to_local_storage() {
+    let secret = b"mock-routes-secret";
+    let auth = auth_header(secret, vec![Role::User]);
+    let dir = std::env::temp_dir().join(format!("rust-oxide-uploads-{}", Uuid::new_v4()));
+    let local_dir = dir.to_string_lossy().into_owned();
+
+    let (status, json) = json_response(
+        app_with_config(secret, |cfg| cfg.storage.local_dir = local_dir),
+        multipart_request(Some(auth), "hello.txt", "hello upload"),
+    )
+    .await;
+
+    let id = json["data"][0]["id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let persisted = std::fs::read(dir.join(&id));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(json["data"][0]["size"], 12);
+    assert_eq!(json["data"][0]["content_type"], "text/plain");
+    assert_eq!(json["data"][0]["file_name"], "hello.txt");
+    assert_eq!(
+        persisted.expect("uploaded file should be persisted"),
+        b"hello upload"
+    );
+}
+
+async fn download(app: Router, id: &str, auth: &str, headers: &[(&str, &str)]) -> Response<Body> {
+    let mut builder = Request::builder()
+        .uri(api_path(&format!("/files/{id}")))
+        .header("authorization", auth);
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    app.oneshot(builder.body(Body::empty()).unwrap())
+        .await
+        .expect("request should succeed")
+}
+
+#[tokio::test]
+async fn file_download_serves_full_and_partial_content() {
+    let secret = b"mock-routes-secret";
+    let auth = auth_header(secret, vec![Role::User]);
+    let dir = std::env::temp_dir().join(format!("rust-oxide-uploads-{}", Uuid::new_v4()));
+    let local_dir = dir.to_string_lossy().into_owned();
+    let app = app_with_config(secret, |cfg| cfg.storage.local_dir = local_dir);
+
+    let (_, json) = json_response(
+        app.clone(),
+        multipart_request(Some(auth.clone()), "hello.txt", "hello upload"),
+    )
+    .await;
+    let id = json["data"][0]["id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let full = download(app.clone(), &id, &auth, &[]).await;
+    let partial = download(app.clone(), &id, &auth, &[("range", "bytes=6-11")]).await;
+    let invalid = download(app.clone(), &id, &auth, &[("range", "bytes=50-60")]).await;
+    let stale = download(
+        app,
+        &id,
+        &auth,
+        &[("range", "bytes=6-11"), ("if-range", "\"stale-etag\"")],
+    )
+    .await;
+    let full_status = full.status();
+    let full_body = body::to_bytes(full.into_body(), usize::MAX).await.unwrap();
+    let partial_status = partial.status();
+    let content_range = partial.headers().get("content-range").cloned();
+    let partial_body = body::to_bytes(partial.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(full_status, StatusCode::OK);
+    assert_eq!(&full_body[..], b"hello upload");
+    assert_eq!(partial_status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        content_range.expect("content-range header"),
+        "bytes 6-11/12"
+    );
+    assert_eq!(&partial_body[..], b"upload");
+    assert_eq!(invalid.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(invalid.headers()["content-range"], "bytes */12");
+    assert_eq!(stale.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn file_upload_rejects_file_over_max_size() {
+    let secret = b"mock-routes-secret";
+    let auth = auth_header(secret, vec![Role::User]);
+    let dir = std::env::temp_dir().join(format!("rust-oxide-uploads-{}", Uuid::new_v4()));
+    let local_dir = dir.to_string_lossy().into_owned();
+
+    let (status, json) = json_response(
+        app_with_config(secret, |cfg| {
+            cfg.storage.local_dir = local_dir;
+            cfg.storage.max_upload_bytes = 4;
+        }),
+        multipart_request(Some(auth), "big.txt", "too large"),
+    )
+    .await;
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["message"], "file exceeds max upload size of 4 bytes");
+}
+
+#[tokio::test]
+async fn file_upload_requires_auth_header() {
+    let secret = b"mock-routes-secret";
+    let (status, json) = json_response(
+        app(secret),
+        multipart_request(None, "hello.txt", "hello upload"),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["message"], "Missing/invalid Authorization header");
+}
+
+struct TodosWrite;
+
+impl RequiredScope for TodosWrite {
+    fn required() -> &'static str {
+        "todos:write"
+    }
+}
+
+fn scoped_app(secret: &[u8]) -> Router {
+    let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+    let (state, _) = build_state(secret, db, |_| {});
+    Router::new()
+        .route(
+            "/scoped",
+            get(
+                |RequireScope { claims, .. }: RequireScope<TodosWrite>| async move {
+                    Json(json!({ "sub": claims.sub }))
+                },
+            ),
+        )
+        .with_state(state)
+}
+
+fn scoped_request(secret: &[u8], scopes: &[&str]) -> Request<Body> {
+    let mut claims = make_access_claims(&Uuid::new_v4(), vec![Role::User], 3600);
+    claims.scopes = scopes.iter().map(|scope| scope.to_string()).collect();
+    let token = encode_token(&JwtKeys::from_secret(secret), &claims).expect("encode token");
+    Request::builder()
+        .uri("/scoped")
+        .header("authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn scope_guard_allows_token_with_required_scope() {
+    let secret = b"mock-routes-secret";
+
+    let (status, json) = json_response(
+        scoped_app(secret),
+        scoped_request(secret, &["todos:read", "todos:write"]),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(json["sub"].is_string());
+}
+
+#[tokio::test]
+async fn scope_guard_rejects_token_without_required_scope() {
+    let secret = b"mock-routes-secret";
+
+    let (status, json) =
+        json_response(scoped_app(secret), scoped_request(secret, &["todos:read"])).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["message"], "Missing required scope");
+}
+
+#[tokio::test]
+async fn protected_group_enforces_jwt_and_role() {
+    let secret = b"mock-routes-secret";
+    let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+    let (state, _) = build_state(secret, db, |_| {});
+    let app = Router::new()
+        .route("/reports", get(|| async { Json(json!({ "ok": true })) }))
+        .protected(&state, Role::Admin)
+        .layer(middleware::from_fn(json_error_middleware));
+    let request = |auth: Option<String>| {
+        let builder = Request::builder().uri("/reports");
+        let builder = match auth {
+            Some(auth) => builder.header("authorization", auth),
+            None => builder,
+        };
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let (status, json) = json_response(app.clone(), request(None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["message"], "Missing/invalid Authorization header");
+
+    let user = auth_header(secret, vec![Role::User]);
+    let (status, json) = json_response(app.clone(), request(Some(user))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["message"], "Missing required role");
+
+    let admin = auth_header(secret, vec![Role::Admin]);
+    let (status, json) = json_response(app.clone(), request(Some(admin))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["ok"], true);
+
+    let missing = Request::builder()
+        .uri("/elsewhere")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(missing).await.expect("request should succeed");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+fn token_sources_app(secret: &[u8], configure: impl FnOnce(&mut AppConfig)) -> Router {
+    let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+    let (state, _) = build_state(secret, db, configure);
+    Router::new()
+        .route("/reports", get(|| async { Json(json!({ "ok": true })) }))
+        .protected(&state, Role::User)
+        .layer(middleware::from_fn(json_error_middleware))
+}
+
+fn bare_token(secret: &[u8]) -> String {
+    auth_header(secret, vec![Role::User])
+        .trim_start_matches("Bearer ")
+        .to_string()
+}
+
+#[tokio::test]
+async fn bearer_scheme_is_accepted_in_any_case() {
+    let secret = b"mock-routes-secret";
+    let request = Request::builder()
+        .uri("/reports")
+        .header("authorization", format!("bearer {}", bare_token(secret)))
+        .body(Body::empty())
+        .unwrap();
+
+    let (status, json) = json_response(token_sources_app(secret, |_| {}), request).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["ok"], true);
+}
+
+#[tokio::test]
+async fn alternate_header_carries_the_token_when_configured() {
+    let secret = b"mock-routes-secret";
+    let request = || {
+        Request::builder()
+            .uri("/reports")
+            .header("x-access-token", bare_token(secret))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let (status, _) = json_response(token_sources_app(secret, |_| {}), request()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let app = token_sources_app(secret, |cfg| {
+        if let Some(auth) = cfg.auth.as_mut() {
+            auth.token.alternate_header = Some("X-Access-Token".to_string());
+        }
+    });
+    let (status, json) = json_response(app, request()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["ok"], true);
+}
+
+#[tokio::test]
+async fn query_param_carries_the_token_only_for_downloads() {
+    let secret = b"mock-routes-secret";
+    let token = bare_token(secret);
+    let enable = |cfg: &mut AppConfig| {
+        if let Some(auth) = cfg.auth.as_mut() {
+            auth.token.query_param = Some("access_token".to_string());
+        }
+    };
+    let reports = || {
+        Request::builder()
+            .uri(format!("/reports?access_token={token}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let (status, json) = json_response(token_sources_app(secret, enable), reports()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["message"], "Missing/invalid Authorization header");
+
+    let dir = std::env::temp_dir().join(format!("rust-oxide-uploads-{}", Uuid::new_v4()));
+    let local_dir = dir.to_string_lossy().into_owned();
+    let app = app_with_config(secret, |cfg| {
+        enable(cfg);
+        cfg.storage.local_dir = local_dir;
+    });
+    let (_, json) = json_response(
+        app.clone(),
+        multipart_request(Some(format!("Bearer {token}")), "hello.txt", "hello"),
+    )
+    .await;
+    let id = json["data"][0]["id"].as_str().unwrap_or_default();
+    let link = |query: &str| {
+        Request::builder()
+            .uri(api_path(&format!("/files/{id}?{query}")))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let linked = app
+        .clone()
+        .oneshot(link(&format!("access_token={token}")))
+        .await
+        .expect("request should succeed");
+    let unlinked = app
+        .oneshot(link("other=1"))
+        .await
+        .expect("request should succeed");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(linked.status(), StatusCode::OK);
+    assert_eq!(unlinked.status(), StatusCode::UNAUTHORIZED);
+}
+
+fn introspect_request(caller: String, token: &str) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(api_path("/auth/introspect"))
+        .header("authorization", caller)
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "token": token }).to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn introspect_reports_active_token_claims() {
+    let secret = b"mock-routes-secret";
+    let user_id = Uuid::new_v4();
+    let mut claims = make_access_claims(&user_id, vec![Role::User], 3600);
+    claims.scopes = vec!["todos:read".to_string()];
+    let token = encode_token(&JwtKeys::from_secret(secret), &claims).expect("encode token");
+
+    let (status, _) = json_response(
+        app(secret),
+        introspect_request(auth_header(secret, vec![Role::User]), &token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let mut gateway = make_access_claims(&Uuid::new_v4(), vec![Role::User], 3600);
+    gateway.scopes = vec!["auth:introspect".to_string()];
+    let gateway = encode_token(&JwtKeys::from_secret(secret), &gateway).expect("encode token");
+    let (status, json) = json_response(
+        app(secret),
+        introspect_request(format!("Bearer {gateway}"), &token),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["active"], true);
+    assert_eq!(json["data"]["sub"], user_id.to_string());
+    assert_eq!(json["data"]["exp"], claims.exp);
+    assert_eq!(json["data"]["roles"], json!(["user"]));
+    assert_eq!(json["data"]["scopes"], json!(["todos:read"]));
+}
+
+#[tokio::test]
+async fn introspect_reports_expired_and_malformed_tokens_as_inactive() {
+    let secret = b"mock-routes-secret";
+    let mut claims = make_access_claims(&Uuid::new_v4(), vec![Role::User], 3600);
+    claims.iat -= 7200;
+    claims.exp -= 7200;
+    let expired = encode_token(&JwtKeys::from_secret(secret), &claims).expect("encode token");
+
+    for token in [expired.as_str(), "not-a-jwt"] {
+        let (status, json) = json_response(
+            app(secret),
+            introspect_request(auth_header(secret, vec![Role::Admin]), token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"], json!({ "active": false }));
+    }
+}
+
 #[tokio::test]
 async fn admin_route_allows_admin_token() {
     let secret = b"mock-routes-secret";
@@ -193,44 +705,832 @@ async fn admin_route_rejects_user_token() {
 }
 
 #[tokio::test]
-async fn unknown_route_is_normalized_to_json_error() {
+async fn admin_impersonate_rejects_user_token() {
     let secret = b"mock-routes-secret";
+    let auth = auth_header(secret, vec![Role::User]);
+
     let (status, json) = json_response(
         app(secret),
         Request::builder()
-            .method("GET")
-            .uri(api_path("/unknown-route"))
+            .method("POST")
+            .uri(api_path(&format!(
+                "/admin/users/{}/impersonate",
+                Uuid::new_v4()
+            )))
+            .header("authorization", auth)
             .body(Body::empty())
             .unwrap(),
     )
     .await;
 
-    assert_eq!(status, StatusCode::NOT_FOUND);
-    assert_eq!(json["status"], StatusCode::NOT_FOUND.as_u16());
-    assert!(!json["message"].as_str().unwrap_or("").is_empty());
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["message"], "Missing required role");
+}
+
+fn user_row(id: Uuid, role: &str) -> user::Model {
+    let now = chrono::Utc::now().fixed_offset();
+    user::Model {
+        id,
+        created_at: now,
+        updated_at: now,
+        email: "ada@example.com".to_string(),
+        password_hash: "secret-hash".to_string(),
+        role: role.to_string(),
+        last_login_at: None,
+    }
 }
 
 #[tokio::test]
-async fn panic_route_is_caught_and_returned_as_json() {
+async fn admin_role_update_changes_role_and_records_audit_event() {
+    let secret = b"mock-routes-secret";
+    let user_id = Uuid::new_v4();
+    let now = chrono::Utc::now().fixed_offset();
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([vec![user_row(user_id, "user")]])
+        .append_query_results([vec![user_row(user_id, "user")]])
+        .append_query_results([vec![user_row(user_id, "admin")]])
+        .append_query_results([vec![auth_event::Model {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            event_type: "role_change".to_string(),
+            user_id: Some(user_id),
+            subject: None,
+            ip: None,
+            user_agent: None,
+            detail: Some("role user -> admin".to_string()),
+        }]])
+        .into_connection();
+
+    let (status, json) = json_response(
+        app_with_db(secret, db, |_| {}),
+        Request::builder()
+            .method("PUT")
+            .uri(api_path(&format!("/admin/users/{user_id}/role")))
+            .header("authorization", auth_header(secret, vec![Role::Admin]))
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"role":"admin"}"#))
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["id"], user_id.to_string());
+    assert_eq!(json["data"]["role"], "admin");
+}
+
+#[tokio::test]
+async fn admin_role_update_rejects_user_token() {
     let secret = b"mock-routes-secret";
+
     let (status, json) = json_response(
         app(secret),
         Request::builder()
-            .method("GET")
-            .uri(api_path("/todo/panic"))
-            .body(Body::empty())
+            .method("PUT")
+            .uri(api_path(&format!("/admin/users/{}/role", Uuid::new_v4())))
+            .header("authorization", auth_header(secret, vec![Role::User]))
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"role":"admin"}"#))
             .unwrap(),
     )
     .await;
 
-    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
-    assert_eq!(json["status"], StatusCode::INTERNAL_SERVER_ERROR.as_u16());
-    assert!(
-        json["message"]
-            .as_str()
-            .unwrap_or("")
-            .starts_with("internal server error"),
-        "unexpected message: {}",
-        json["message"]
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["message"], "Missing required role");
+}
+
+#[tokio::test]
+async fn register_is_rejected_when_registration_closed() {
+    let secret = b"mock-routes-secret";
+    let app = app_with_config(secret, |cfg| {
+        if let Some(auth) = cfg.auth.as_mut() {
+            auth.registration_mode = RegistrationMode::Closed;
+        }
+    });
+
+    let (status, json) = json_response(
+        app,
+        Request::builder()
+            .method("POST")
+            .uri(api_path("/register"))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"email":"alice@example.com","password":"password123"}"#,
+            ))
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["message"], "Registration is closed");
+}
+
+#[tokio::test]
+async fn admin_invites_rejects_user_token() {
+    let secret = b"mock-routes-secret";
+    let auth = auth_header(secret, vec![Role::User]);
+
+    let (status, json) = json_response(
+        app(secret),
+        Request::builder()
+            .method("POST")
+            .uri(api_path("/admin/invites"))
+            .header("authorization", auth)
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"role":"admin"}"#))
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["message"], "Missing required role");
+}
+
+#[tokio::test]
+async fn admin_invites_rejects_out_of_range_lifetimes() {
+    let secret = b"mock-routes-secret";
+
+    // The mock database has no results queued, so an insert would fail.
+    for days in ["0", "366", "9223372036854775807"] {
+        let (status, json) = json_response(
+            app(secret),
+            Request::builder()
+                .method("POST")
+                .uri(api_path("/admin/invites"))
+                .header("authorization", auth_header(secret, vec![Role::Admin]))
+                .header("content-type", "application/json")
+                .body(Body::from(format!(r#"{{"expires_in_days":{days}}}"#)))
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "expires_in_days must be between 1 and 365");
+    }
+}
+
+#[tokio::test]
+async fn admin_realtime_kick_requires_admin_and_reports_removed() {
+    let secret = b"mock-routes-secret";
+    let kick = |auth: String| {
+        Request::builder()
+            .method("POST")
+            .uri(api_path("/admin/realtime/channels/room:lobby/kick"))
+            .header("authorization", auth)
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"user_id":"u1","reason":"spam"}"#))
+            .unwrap()
+    };
+
+    let (status, _) = json_response(app(secret), kick(auth_header(secret, vec![Role::User]))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, json) =
+        json_response(app(secret), kick(auth_header(secret, vec![Role::Admin]))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["removed"], 0);
+}
+
+#[tokio::test]
+async fn disabled_realtime_leaves_socket_and_admin_routes_unmounted() {
+    let secret = b"mock-routes-secret";
+    let socket = || {
+        Request::builder()
+            .uri(api_path("/realtime/socket"))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let enabled = app(secret).oneshot(socket()).await.unwrap();
+    assert_ne!(enabled.status(), StatusCode::NOT_FOUND);
+
+    let disabled = || app_with_config(secret, |cfg| cfg.realtime.enabled = false);
+    let response = disabled().oneshot(socket()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = disabled()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(api_path("/admin/realtime/channels/room:lobby/close"))
+                .header("authorization", auth_header(secret, vec![Role::Admin]))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    // Unmatched POSTs fall through to the static file service, which only serves GET.
+    assert!(response.status().is_client_error());
+}
+
+#[tokio::test]
+async fn maintenance_mode_blocks_api_but_keeps_health_and_toggle() {
+    let secret = b"mock-routes-secret";
+    let app = app_with_config(secret, |cfg| cfg.general.maintenance_mode = true);
+    let admin = auth_header(secret, vec![Role::Admin]);
+    let public = || {
+        Request::builder()
+            .uri(api_path("/public"))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let toggle = |enabled: bool| {
+        Request::builder()
+            .method("PUT")
+            .uri(api_path("/admin/maintenance"))
+            .header("authorization", admin.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(format!(r#"{{"enabled":{enabled}}}"#)))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(public()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()["retry-after"], "120");
+
+    let (status, json) = json_response(
+        app.clone(),
+        Request::builder()
+            .uri("/healthz")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["status"], "ok");
+
+    let (status, json) = json_response(app.clone(), toggle(false)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["enabled"], false);
+    let (status, _) = json_response(app.clone(), public()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    json_response(app.clone(), toggle(true)).await;
+    let (status, json) = json_response(app, public()).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(json["message"], "Service is down for maintenance");
+}
+
+#[tokio::test]
+async fn admin_auth_events_rejects_user_token() {
+    let secret = b"mock-routes-secret";
+    let auth = auth_header(secret, vec![Role::User]);
+
+    let (status, json) = json_response(
+        app(secret),
+        Request::builder()
+            .method("GET")
+            .uri(api_path("/admin/auth-events?event_type=login_failed"))
+            .header("authorization", auth)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["message"], "Missing required role");
+}
+
+#[tokio::test]
+async fn admin_auth_events_rejects_filter_on_detail_column() {
+    let secret = b"mock-routes-secret";
+    let auth = auth_header(secret, vec![Role::Admin]);
+
+    let (status, json) = json_response(
+        app(secret),
+        Request::builder()
+            .method("GET")
+            .uri(api_path("/admin/auth-events?detail=invalid*"))
+            .header("authorization", auth)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["message"], "Invalid filter");
+}
+
+#[tokio::test]
+async fn unknown_route_is_normalized_to_json_error() {
+    let secret = b"mock-routes-secret";
+    let (status, json) = json_response(
+        app(secret),
+        Request::builder()
+            .method("GET")
+            .uri(api_path("/unknown-route"))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(json["status"], StatusCode::NOT_FOUND.as_u16());
+    assert!(!json["message"].as_str().unwrap_or("").is_empty());
+}
+
+#[tokio::test]
+async fn panic_route_is_caught_and_returned_as_json() {
+    let secret = b"mock-routes-secret";
+    let (status, json) = json_response(
+        app(secret),
+        Request::builder()
+            .method("GET")
+            .uri(api_path("/todo/panic"))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(json["status"], StatusCode::INTERNAL_SERVER_ERROR.as_u16());
+    assert!(
+        json["message"]
+            .as_str()
+            .unwrap_or("")
+            .starts_with("internal server error"),
+        "unexpected message: {}",
+        json["message"]
+    );
+}
+
+#[tokio::test]
+async fn unsupported_method_returns_json_405_with_allow_header() {
+    let secret = b"mock-routes-secret";
+    let response = app(secret)
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(api_path(&format!("/todo-crud/{}", Uuid::new_v4())))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("request should succeed");
+    let status = response.status();
+    let allow = response
+        .headers()
+        .get("allow")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body should read");
+    let json: serde_json::Value = serde_json::from_slice(&body).expect("body should be json");
+
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(allow.as_deref(), Some("GET,HEAD,PATCH,DELETE"));
+    assert_eq!(json["status"], 405);
+    assert_eq!(json["message"], "Method Not Allowed");
+}
+
+#[tokio::test]
+async fn options_describes_crud_resource_operations() {
+    let secret = b"mock-routes-secret";
+    let response = app(secret)
+        .oneshot(
+            Request::builder()
+                .method("OPTIONS")
+                .uri(api_path("/todo-crud"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("request should succeed");
+    let status = response.status();
+    let allow = response
+        .headers()
+        .get("allow")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body should read");
+    let json: serde_json::Value = serde_json::from_slice(&body).expect("body should be json");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(allow.as_deref(), Some("GET,HEAD,POST,OPTIONS"));
+    assert_eq!(json["data"]["path"], api_path("/todo-crud"));
+    let operations = json["data"]["operations"]
+        .as_array()
+        .expect("operations array");
+    let methods: Vec<&str> = operations
+        .iter()
+        .filter_map(|operation| operation["method"].as_str())
+        .collect();
+    assert_eq!(methods, ["GET", "POST"]);
+    assert!(operations.iter().all(|operation| {
+        !operation["response"]
+            .as_str()
+            .unwrap_or_default()
+            .is_empty()
+    }));
+}
+
+#[tokio::test]
+async fn docs_view_returns_304_when_not_modified_since_build() {
+    let secret = b"mock-routes-secret";
+    let first = app(secret)
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/docs")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("request should succeed");
+    assert_eq!(first.status(), StatusCode::OK);
+    let last_modified = first
+        .headers()
+        .get("last-modified")
+        .expect("docs view should send Last-Modified")
+        .clone();
+
+    let second = app(secret)
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/docs")
+                .header("if-modified-since", last_modified)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("request should succeed");
+    assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+    let body = body::to_bytes(second.into_body(), usize::MAX)
+        .await
+        .expect("body should read");
+    assert!(body.is_empty());
+}
+
+#[tokio::test]
+async fn openapi_json_lists_every_catalogued_api_path() {
+    let response = app(b"mock-routes-secret")
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/openapi.json")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("request should succeed");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body should read");
+    let spec: serde_json::Value = serde_json::from_slice(&body).expect("spec should parse");
+
+    let mut api_paths: Vec<&str> = routes()
+        .iter()
+        .filter(|route| route.source.starts_with("src/routes/api/"))
+        .map(|route| route.path)
+        .collect();
+    api_paths.sort_unstable();
+    api_paths.dedup();
+    let spec_paths = spec["paths"].as_object().expect("paths object");
+    assert_eq!(spec_paths.len(), api_paths.len());
+    assert!(api_paths.iter().all(|path| spec_paths.contains_key(*path)));
+}
+
+#[tokio::test]
+async fn auth_me_returns_stored_profile_without_password_hash() {
+    let secret = b"mock-routes-secret";
+    let user_id = Uuid::new_v4();
+    let now = chrono::Utc::now().fixed_offset();
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([vec![user::Model {
+            id: user_id,
+            created_at: now,
+            updated_at: now,
+            email: "ada@example.com".to_string(),
+            password_hash: "secret-hash".to_string(),
+            role: "admin".to_string(),
+            last_login_at: Some(now),
+        }]])
+        .into_connection();
+
+    let (status, json) = json_response(
+        app_with_db(secret, db, |_| {}),
+        Request::builder()
+            .method("GET")
+            .uri(api_path("/auth/me"))
+            .header(
+                "Authorization",
+                auth_header_for(secret, user_id, vec![Role::Admin, Role::User]),
+            )
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let profile = &json["data"];
+    assert_eq!(profile["id"], user_id.to_string());
+    assert_eq!(profile["email"], "ada@example.com");
+    assert_eq!(profile["roles"], json!(["admin", "user"]));
+    assert!(profile["last_login_at"].is_string());
+    assert!(profile.get("password_hash").is_none());
+}
+
+#[tokio::test]
+async fn auth_permissions_expand_admin_roles_from_the_token() {
+    let secret = b"mock-routes-secret";
+    let user_id = Uuid::new_v4();
+    let mut claims = make_access_claims(&user_id, vec![Role::Admin], 3600);
+    claims.scopes = vec!["todos:write".to_string()];
+    let token = encode_token(&JwtKeys::from_secret(secret), &claims).expect("encode token");
+
+    // The mock database has no results queued, so any lookup would fail.
+    let (status, json) = json_response(
+        app(secret),
+        Request::builder()
+            .method("GET")
+            .uri(api_path("/auth/permissions"))
+            .header("Authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        json["data"],
+        json!({
+            "sub": user_id.to_string(),
+            "roles": ["admin"],
+            "effective_roles": ["admin", "user"],
+            "scopes": ["todos:write"],
+        })
+    );
+}
+
+#[tokio::test]
+async fn auth_me_rejects_token_for_deleted_user() {
+    let secret = b"mock-routes-secret";
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([Vec::<user::Model>::new()])
+        .into_connection();
+
+    let (status, json) = json_response(
+        app_with_db(secret, db, |_| {}),
+        Request::builder()
+            .method("GET")
+            .uri(api_path("/auth/me"))
+            .header("Authorization", auth_header(secret, vec![Role::User]))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["message"], "User no longer exists");
+}
+
+async fn sqlite_job_db() -> DatabaseConnection {
+    // A single connection keeps every query on the same in-memory database.
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options.max_connections(1).sqlx_logging(false);
+    let db = Database::connect(options)
+        .await
+        .expect("sqlite should open");
+    db.get_schema_builder()
+        .register(job::Entity)
+        .sync(&db)
+        .await
+        .expect("job table should be created");
+    db
+}
+
+#[tokio::test]
+async fn submitted_job_is_queued_and_reports_completion_after_worker_runs() {
+    let secret = b"mock-routes-secret";
+    let user_id = Uuid::new_v4();
+    let db = sqlite_job_db().await;
+    let app = app_with_db(secret, db.clone(), |_| {});
+    let auth = auth_header_for(secret, user_id, vec![Role::User]);
+
+    let (status, json) = json_response(
+        app.clone(),
+        Request::builder()
+            .method("POST")
+            .uri(api_path("/jobs"))
+            .header("Authorization", auth.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({ "type": "echo", "params": { "n": 7 } }).to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(json["data"]["status"], "queued");
+    let id: Uuid = json["data"]["id"].as_str().unwrap().parse().unwrap();
+    let row = job::Entity::find_by_id(id)
+        .one(&db)
+        .await
+        .unwrap()
+        .expect("job row should exist");
+    assert_eq!(row.status, job::STATUS_QUEUED);
+    assert_eq!(row.submitted_by, Some(user_id));
+
+    let ran = run_jobs_once(&JobDao::new(&db), &job_registry())
+        .await
+        .expect("worker pass should succeed");
+    assert_eq!(ran, 1);
+
+    let (status, json) = json_response(
+        app,
+        Request::builder()
+            .method("GET")
+            .uri(api_path(&format!("/jobs/{id}")))
+            .header("Authorization", auth)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["status"], "completed");
+    assert_eq!(json["data"]["result"], json!({ "n": 7 }));
+    assert!(json["data"]["finished_at"].is_string());
+}
+
+#[tokio::test]
+async fn job_status_is_hidden_from_other_users() {
+    let secret = b"mock-routes-secret";
+    let db = sqlite_job_db().await;
+    let job = JobDao::new(&db)
+        .enqueue("echo", json!({}), Some(Uuid::new_v4()))
+        .await
+        .expect("job should enqueue");
+
+    let (status, _) = json_response(
+        app_with_db(secret, db, |_| {}),
+        Request::builder()
+            .method("GET")
+            .uri(api_path(&format!("/jobs/{}", job.id)))
+            .header("Authorization", auth_header(secret, vec![Role::User]))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn submit_job_rejects_unknown_type() {
+    let secret = b"mock-routes-secret";
+    let (status, json) = json_response(
+        app(secret),
+        Request::builder()
+            .method("POST")
+            .uri(api_path("/jobs"))
+            .header("Authorization", auth_header(secret, vec![Role::User]))
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ "type": "nope" }).to_string()))
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["message"], "Unknown job type `nope`");
+}
+
+async fn public_status(app: &Router, auth: Option<&str>) -> StatusCode {
+    let mut request = Request::builder().uri(api_path("/public"));
+    if let Some(auth) = auth {
+        request = request.header("authorization", auth);
+    }
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .expect("request should succeed")
+        .status()
+}
+
+#[tokio::test]
+async fn rate_limit_throttles_users_at_the_threshold_but_not_admins() {
+    let secret = b"mock-routes-secret";
+    let app = app_with_config(secret, |cfg| cfg.general.rate_limit.max_requests = 3);
+    let user = auth_header(secret, vec![Role::User]);
+    let admin = auth_header(secret, vec![Role::Admin, Role::User]);
+
+    for _ in 0..3 {
+        assert_eq!(public_status(&app, Some(&user)).await, StatusCode::OK);
+    }
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(api_path("/public"))
+                .header("authorization", &user)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key("retry-after"));
+
+    for _ in 0..10 {
+        assert_eq!(public_status(&app, Some(&admin)).await, StatusCode::OK);
+    }
+    assert_eq!(public_status(&app, None).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rate_limited_requests_still_pass_route_role_checks() {
+    let secret = b"mock-routes-secret";
+    let app = app_with_config(secret, |cfg| {
+        cfg.general.rate_limit.max_requests = 10;
+        cfg.general.rate_limit.exempt_roles.clear();
+    });
+    let stats = |auth: String| {
+        Request::builder()
+            .uri(api_path("/admin/stats"))
+            .header("authorization", auth)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let (status, _) =
+        json_response(app.clone(), stats(auth_header(secret, vec![Role::Admin]))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, json) = json_response(app, stats(auth_header(secret, vec![Role::User]))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["message"], "Missing required role");
+}
+
+async fn anonymous_status_from(app: &Router, ip: [u8; 4]) -> StatusCode {
+    let addr = std::net::SocketAddr::from((ip, 4000));
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .uri(api_path("/public"))
+                .extension(axum::extract::ConnectInfo(addr))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .expect("request should succeed")
+        .status()
+}
+
+#[tokio::test]
+async fn rate_limit_counts_anonymous_clients_per_ip_and_skips_unknown_addresses() {
+    let secret = b"mock-routes-secret";
+    let app = app_with_config(secret, |cfg| cfg.general.rate_limit.max_requests = 1);
+
+    assert_eq!(
+        anonymous_status_from(&app, [10, 0, 0, 1]).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        anonymous_status_from(&app, [10, 0, 0, 1]).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        anonymous_status_from(&app, [10, 0, 0, 2]).await,
+        StatusCode::OK
+    );
+    for _ in 0..3 {
+        assert_eq!(public_status(&app, None).await, StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn rate_limit_gives_exempt_scopes_their_own_higher_limit() {
+    let secret = b"mock-routes-secret";
+    let app = app_with_config(secret, |cfg| {
+        let limit = &mut cfg.general.rate_limit;
+        limit.max_requests = 1;
+        limit.exempt_roles.clear();
+        limit.exempt_scopes = vec!["bulk:write".to_string()];
+        limit.exempt_max_requests = 3;
+    });
+    let mut claims = make_access_claims(&Uuid::new_v4(), vec![Role::Admin], 3600);
+    claims.scopes = vec!["bulk:write".to_string()];
+    let bulk = format!(
+        "Bearer {}",
+        encode_token(&JwtKeys::from_secret(secret), &claims).expect("encode token")
+    );
+    let admin = auth_header(secret, vec![Role::Admin]);
+
+    for _ in 0..3 {
+        assert_eq!(public_status(&app, Some(&bulk)).await, StatusCode::OK);
+    }
+    assert_eq!(
+        public_status(&app, Some(&bulk)).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(public_status(&app, Some(&admin)).await, StatusCode::OK);
+    assert_eq!(
+        public_status(&app, Some(&admin)).await,
+        StatusCode::TOO_MANY_REQUESTS
     );
 }
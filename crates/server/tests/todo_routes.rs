@@ -11,13 +11,14 @@ use uuid::Uuid;
 
 use rust_oxide::{
     auth::{
-        Role,
+        RegistrationMode, Role,
         bootstrap::build_providers,
         jwt::{JwtKeys, encode_token, make_access_claims},
+        password::PasswordKdf,
         providers::AuthProviderId,
     },
-    config::{AppConfig, AuthConfig},
-    realtime::{AppRealtimeVerifier, SocketAppState, SocketServerHandle},
+    config::{AppConfig, AuthConfig, LockoutConfig, TokenSourceConfig},
+    realtime::{SocketAppState, spawn_realtime_runtime},
     routes::{API_PREFIX, router},
     services::ServiceContext,
     state::AppState,
@@ -56,12 +57,10 @@ fn build_state(cfg: AppConfig, db: DatabaseConnection) -> std::sync::Arc<AppStat
     AppState::new(cfg, db, providers)
 }
 
-fn realtime_runtime_for_state(state: &std::sync::Arc<AppState>) -> std::sync::Arc<SocketAppState> {
-    let realtime = SocketServerHandle::spawn(state.config.realtime.clone());
-    std::sync::Arc::new(SocketAppState::new(
-        realtime,
-        AppRealtimeVerifier::new(state.auth_providers.clone()),
-    ))
+fn realtime_runtime_for_state(
+    state: &std::sync::Arc<AppState>,
+) -> Option<std::sync::Arc<SocketAppState>> {
+    spawn_realtime_runtime(&state.config.realtime, state.auth_providers.clone())
 }
 
 async fn send(
@@ -115,10 +114,20 @@ fn auth_header(state: &std::sync::Arc<AppState>) -> String {
 
 fn test_auth_config(jwt_secret: String) -> AuthConfig {
     AuthConfig {
+        enabled: true,
         provider: AuthProviderId::Local,
         jwt_secret,
+        jwt_kid: None,
+        jwt_verification_keys: Vec::new(),
         admin_email: "admin@example.com".to_string(),
         admin_password: "adminpassword".to_string(),
+        registration_mode: RegistrationMode::Open,
+        single_session: false,
+        bind_refresh_tokens: false,
+        password_kdf: PasswordKdf::Argon2id,
+        password_history: 0,
+        lockout: LockoutConfig::default(),
+        token: TokenSourceConfig::default(),
     }
 }
 
@@ -734,7 +743,10 @@ async fn todo_get_list_not_found() {
     .await;
 
     assert_eq!(status, StatusCode::NOT_FOUND);
-    assert_eq!(json_message(&response), Some("Resource not found"));
+    assert_eq!(
+        json_message(&response),
+        Some(format!("TodoList {missing_id} not found").as_str())
+    );
 }
 
 #[tokio::test]